@@ -0,0 +1,70 @@
+#ifndef VOICECODE_H
+#define VOICECODE_H
+
+/* Voice code calculator for Produce Traceability Initiative labels, generated by cbindgen, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of a C API call, 0 is success
+ */
+typedef enum VoiceCodeStatus {
+  VOICE_CODE_STATUS_OK = 0,
+  /**
+   * An argument was NULL
+   */
+  VOICE_CODE_STATUS_NULL_ARGUMENT = 1,
+  /**
+   * A string argument was not valid UTF-8
+   */
+  VOICE_CODE_STATUS_INVALID_UTF8 = 2,
+  /**
+   * GTIN is not 8, 12, 13 or 14 numeric digits
+   */
+  VOICE_CODE_STATUS_INVALID_GTIN = 3,
+  /**
+   * LOT is empty, too long or contains a character outside the PTI set
+   */
+  VOICE_CODE_STATUS_INVALID_LOT = 4,
+  /**
+   * Pack date is not six numeric digits
+   */
+  VOICE_CODE_STATUS_INVALID_DATE = 5,
+  /**
+   * Any other validation failure
+   */
+  VOICE_CODE_STATUS_OTHER = 6,
+} VoiceCodeStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Compute the voice code for a GTIN, LOT and YYMMDD pack date
+ *
+ * On success `out` holds the four digits and a terminating NUL, on failure `out` holds
+ * an empty string when it is not NULL.
+ *
+ * # Safety
+ * `gtin`, `lot` and `yymmdd` must be NULL or point to NUL terminated strings, `out` must be
+ * NULL or point to at least 5 writable bytes.
+ */
+enum VoiceCodeStatus voicecode_compute(const char *gtin,
+                                       const char *lot,
+                                       const char *yymmdd,
+                                       char *out);
+
+/**
+ * Static description of a status, never NULL and never freed
+ */
+const char *voicecode_status_message(enum VoiceCodeStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* VOICECODE_H */
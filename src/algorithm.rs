@@ -0,0 +1,92 @@
+//! The voice code algorithm as a trait, so a later PTI revision can sit next to the current
+//! one without changing every call site
+//!
+//! `HashVoiceCode::new` and friends always use `PtiV1`, the builder takes any algorithm
+//! through `HashVoiceCodeBuilder::algorithm`.
+
+use std::fmt;
+
+use crate::calculator::VoiceCodeCalculator;
+use crate::gtin::Gtin;
+use crate::hash::VoiceCodeHasher;
+use crate::lot::Lot;
+use crate::pack_date::{DatePadding, PackDate};
+
+/// Computes a voice code from validated parts
+///
+/// # Example
+/// ```
+/// use voicecode::{ Gtin, HashVoiceCode, Lot, PackDate };
+/// use voicecode::algorithm::{ PtiV1, VoiceCodeAlgorithm };
+///
+/// /// GTIN and LOT only, ignoring the date
+/// #[derive(Debug)]
+/// struct NoDate;
+///
+/// impl VoiceCodeAlgorithm for NoDate {
+///     fn code(&self, gtin: &Gtin, lot: &Lot, _date: &PackDate) -> u16 {
+///         voicecode::hash_u16(format!("{}{}", gtin.as_str(), lot.as_str()).as_bytes())
+///     }
+/// }
+///
+/// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+/// let lot = Lot::parse("LOT123").unwrap();
+/// assert_eq!(PtiV1.code(&gtin, &lot, &PackDate::from_parts("03", "01", "02").unwrap()), 6991);
+///
+/// let voice_code = HashVoiceCode::builder()
+///     .gtin("12345678901244")
+///     .lot("LOT123")
+///     .pack_date(("03", "01", "02"))
+///     .algorithm(NoDate)
+///     .build()
+///     .unwrap();
+/// assert_eq!(voice_code.voice_code, format!("{:04}", voicecode::hash_u16(b"12345678901244LOT123")));
+/// ```
+pub trait VoiceCodeAlgorithm: fmt::Debug + Send + Sync {
+    /// Voice code from 0 to 9999, the date parts are hashed as the `PackDate` holds them
+    fn code(&self, gtin: &Gtin, lot: &Lot, date: &PackDate) -> u16;
+}
+
+/// The current PTI voice code, CRC-16/ARC of GTIN, LOT and YYMMDD reduced to four digits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PtiV1;
+
+impl VoiceCodeAlgorithm for PtiV1 {
+    fn code(&self, gtin: &Gtin, lot: &Lot, date: &PackDate) -> u16 {
+        let mut hasher = VoiceCodeHasher::new();
+        hasher.update(gtin.as_str().as_bytes());
+        hasher.update(lot.as_str().as_bytes());
+        hasher.update(date.hash_text(DatePadding::Raw).as_bytes());
+        hasher.finalize()
+    }
+}
+
+/// Same text as `PtiV1`, hashed with the calculator's polynomial
+impl VoiceCodeAlgorithm for VoiceCodeCalculator {
+    fn code(&self, gtin: &Gtin, lot: &Lot, date: &PackDate) -> u16 {
+        let text = format!("{}{}{}", gtin.as_str(), lot.as_str(), date.hash_text(DatePadding::Raw));
+        self.hash_u16(text.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+    use crate::voicecode::HashVoiceCode;
+
+    #[test]
+    fn test_pti_v1_matches_new() {
+        for (gtin, lot, yy, mm, dd) in [
+            ("12345678901244", "LOT123", "03", "01", "02"),
+            ("61414100734933", "32abcd", "03", "01", "02"),
+            ("61414100734933", "LOT123", "3", "1", "10"),
+        ] {
+            let expected = HashVoiceCode::new(Gtin::parse_unchecked(gtin).unwrap(), lot, yy, mm, dd).unwrap();
+            let pack_date = expected.pack_date().padded(DatePadding::default());
+            let code = PtiV1.code(&expected.gtin, &expected.lot, &pack_date);
+            assert_eq!(format!("{:04}", code), expected.voice_code);
+            assert_eq!(VoiceCodeCalculator::pti().code(&expected.gtin, &expected.lot, &pack_date), code);
+        }
+    }
+}
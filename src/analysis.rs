@@ -0,0 +1,273 @@
+//! How evenly voice codes spread across 0000 to 9999
+//!
+//! A voice code is CRC-16/ARC mod 10000. With a uniform CRC the 65536 values fold onto codes
+//! 0000 to 5535 seven times and 5536 to 9999 six times, so the low codes are 7/6 as likely as
+//! the high ones. `Histogram` compares the observed spread with that model, distinct codes and
+//! colliding pairs against their expected values and a chi-squared statistic over all ten
+//! thousand codes.
+//!
+//! Random LOTs follow the model closely. Runs of consecutive pack dates or sequentially
+//! numbered LOTs change only the low bits of a few characters, and the CRC is linear, so they
+//! fold onto fewer codes with more collisions than random draws, which this measures.
+
+use std::borrow::Borrow;
+use std::fmt;
+
+use crate::voicecode::HashVoiceCode;
+
+/// Number of possible voice codes
+pub const CODES: usize = 10_000;
+
+/// Codes below this are reached by seven CRC values, the rest by six
+const SEVEN_WAY_CODES: usize = 65_536 - 6 * CODES;
+
+/// Probability of `voice_code` when the CRC is uniform
+///
+/// # Example
+/// ```
+/// use voicecode::analysis::probability;
+/// assert_eq!(probability(5535), 7.0 / 65536.0);
+/// assert_eq!(probability(5536), 6.0 / 65536.0);
+/// ```
+pub fn probability(voice_code: u16) -> f64 {
+    let ways = if (voice_code as usize) < SEVEN_WAY_CODES { 7.0 } else { 6.0 };
+    ways / 65_536.0
+}
+
+/// Count of each voice code
+///
+/// # Example
+/// ```
+/// use voicecode::analysis::Histogram;
+/// let histogram: Histogram = [6991, 6991, 8079].into_iter().collect();
+/// assert_eq!(histogram.total(), 3);
+/// assert_eq!(histogram.distinct(), 2);
+/// assert_eq!(histogram.count(6991), 2);
+/// assert_eq!(histogram.most_common(), Some((6991, 2)));
+/// assert_eq!(histogram.collision_pairs(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    counts: Vec<u32>,
+    total: u64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Histogram { counts: vec![0; CODES], total: 0 }
+    }
+
+    /// Count one occurrence of `voice_code`, which must be below `CODES`
+    pub fn add(&mut self, voice_code: u16) {
+        self.counts[voice_code as usize] += 1;
+        self.total += 1;
+    }
+
+    /// Occurrences of `voice_code`
+    pub fn count(&self, voice_code: u16) -> u32 {
+        self.counts.get(voice_code as usize).copied().unwrap_or(0)
+    }
+
+    /// Occurrences of every code, indexed by code
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Codes counted
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Codes seen at least once
+    pub fn distinct(&self) -> usize {
+        self.counts.iter().filter(|count| **count > 0).count()
+    }
+
+    /// Occurrences of `voice_code` expected from `total` codes, see `probability`
+    pub fn expected(&self, voice_code: u16) -> f64 {
+        self.total as f64 * probability(voice_code)
+    }
+
+    /// Distinct codes expected from `total` codes
+    pub fn expected_distinct(&self) -> f64 {
+        (0..CODES as u16).map(|code| 1.0 - (1.0 - probability(code)).powf(self.total as f64)).sum()
+    }
+
+    /// Most frequent code and its count, the lowest code on a tie
+    pub fn most_common(&self) -> Option<(u16, u32)> {
+        let (code, count) = self.counts.iter().enumerate().rev().max_by_key(|(_, count)| **count)?;
+        (*count > 0).then_some((code as u16, *count))
+    }
+
+    /// Pairs of counted codes that are equal, what a picker sees as a duplicate
+    pub fn collision_pairs(&self) -> u64 {
+        self.counts.iter().map(|count| *count as u64 * count.saturating_sub(1) as u64 / 2).sum()
+    }
+
+    /// Equal pairs expected from `total` codes
+    pub fn expected_collision_pairs(&self) -> f64 {
+        let total = self.total as f64;
+        let same: f64 = (0..CODES as u16).map(|code| probability(code).powi(2)).sum();
+        total * (total - 1.0) / 2.0 * same
+    }
+
+    /// Pearson chi-squared against `probability`, with `CODES - 1` degrees of freedom
+    ///
+    /// Close to 9999 when codes follow the model and there are several per code,
+    /// `chi_squared_z` puts it on a standard normal scale.
+    pub fn chi_squared(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (0..CODES as u16)
+            .map(|code| {
+                let expected = self.expected(code);
+                (self.count(code) as f64 - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    /// `chi_squared` as standard deviations from its mean, within about ±3 when codes follow
+    /// the model
+    pub fn chi_squared_z(&self) -> f64 {
+        let freedom = (CODES - 1) as f64;
+        (self.chi_squared() - freedom) / (2.0 * freedom).sqrt()
+    }
+
+    /// Counts summed over runs of `width` consecutive codes, `buckets(1000)` gives ten buckets
+    /// 0000-0999, 1000-1999 and so on
+    pub fn buckets(&self, width: usize) -> Vec<u64> {
+        self.counts.chunks(width.max(1)).map(|chunk| chunk.iter().map(|count| *count as u64).sum()).collect()
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Summary and a bar per thousand codes
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "codes            {}", self.total)?;
+        writeln!(f, "distinct         {}, expected {:.0}", self.distinct(), self.expected_distinct())?;
+        writeln!(f, "colliding pairs  {}, expected {:.0}", self.collision_pairs(), self.expected_collision_pairs())?;
+        if let Some((code, count)) = self.most_common() {
+            writeln!(f, "most common      {:04} x {}", code, count)?;
+        }
+        writeln!(f, "chi-squared      {:.1}, z {:.2} over {} degrees of freedom", self.chi_squared(), self.chi_squared_z(), CODES - 1)?;
+
+        let buckets = self.buckets(1000);
+        let max = buckets.iter().copied().max().unwrap_or(0).max(1);
+        for (i, count) in buckets.iter().enumerate() {
+            let bar = "#".repeat((count * 50 / max) as usize);
+            write!(f, "\n{:04}-{:04} {:>8} {}", i * 1000, i * 1000 + 999, count, bar)?;
+        }
+        Ok(())
+    }
+}
+
+impl Extend<u16> for Histogram {
+    fn extend<I: IntoIterator<Item = u16>>(&mut self, iter: I) {
+        for voice_code in iter {
+            self.add(voice_code);
+        }
+    }
+}
+
+impl FromIterator<u16> for Histogram {
+    fn from_iter<I: IntoIterator<Item = u16>>(iter: I) -> Self {
+        let mut histogram = Histogram::new();
+        histogram.extend(iter);
+        histogram
+    }
+}
+
+/// Histogram of the voice codes of `codes`, owned or borrowed
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "chrono")] {
+/// use voicecode::{ Gtin, Lot, NaiveDate };
+/// use voicecode::analysis::distribution;
+/// use voicecode::series::VoiceCodeSeries;
+/// let gtin = Gtin::parse("10614141007346").unwrap();
+/// let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+/// let codes = ["A1", "B2", "C3"].into_iter().flat_map(|lot| VoiceCodeSeries::new(gtin.clone(), Lot::parse(lot).unwrap(), start..=end));
+/// let histogram = distribution(codes);
+/// assert_eq!(histogram.total(), 3 * 1827);
+/// println!("{}", histogram);
+/// # }
+/// ```
+pub fn distribution<I>(codes: I) -> Histogram
+where
+    I: IntoIterator,
+    I::Item: Borrow<HashVoiceCode>,
+{
+    codes.into_iter().map(|voice_code| voice_code.borrow().voice_code_u16()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+    use crate::lot::Lot;
+    use crate::pack_date::PackDate;
+
+    #[test]
+    fn test_uniform() {
+        let crc_values: Histogram = (0..=u16::MAX).map(|crc| crc % CODES as u16).collect();
+        assert!(crc_values.chi_squared() < 1e-9);
+        assert_eq!((crc_values.count(5535), crc_values.count(5536)), (7, 6));
+
+        // every code exactly twice is far more even than random codes would be
+        let histogram: Histogram = (0..CODES as u16).chain(0..CODES as u16).collect();
+        assert_eq!(histogram.distinct(), CODES);
+        assert!(histogram.chi_squared_z() < -3.0);
+        assert_eq!(histogram.collision_pairs(), CODES as u64);
+        assert_eq!(histogram.most_common(), Some((0, 2)));
+        assert_eq!(histogram.buckets(1000), vec![2000; 10]);
+        assert_eq!(histogram.buckets(3000), vec![6000, 6000, 6000, 2000]);
+        let report = histogram.to_string();
+        assert!(report.starts_with("codes            20000\ndistinct         10000, expected 8631\n"), "{}", report);
+        assert!(report.ends_with(&format!("9000-9999     2000 {}", "#".repeat(50))));
+
+        let empty = Histogram::new();
+        assert_eq!((empty.most_common(), empty.chi_squared(), empty.expected_distinct()), (None, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_lots() {
+        let gtin = Gtin::parse("10614141007346").unwrap();
+        let pack_date = PackDate::from_parts("24", "06", "15").unwrap();
+        let voice_code = |lot: &str| HashVoiceCode::from_parts(gtin.clone(), Lot::parse(lot).unwrap(), pack_date.clone());
+
+        // pseudo random eight character LOTs follow the model
+        let mut state = 1u64;
+        let random: Vec<HashVoiceCode> = (0..50_000)
+            .map(|_| {
+                let lot: String = (0..8)
+                    .map(|_| {
+                        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                        char::from(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ"[(state >> 33) as usize % 36])
+                    })
+                    .collect();
+                voice_code(&lot)
+            })
+            .collect();
+        let histogram = distribution(&random);
+        assert_eq!(histogram.total(), 50_000);
+        assert!(histogram.chi_squared_z().abs() < 4.0, "{}", histogram.chi_squared_z());
+        assert!((histogram.distinct() as f64 - histogram.expected_distinct()).abs() < 100.0);
+        let ratio = histogram.collision_pairs() as f64 / histogram.expected_collision_pairs();
+        assert!((0.95..1.05).contains(&ratio), "{}", ratio);
+
+        // sequential numeric LOTs only vary the low bits of a few bytes and the linear CRC
+        // folds them onto fewer codes
+        let sequential = distribution((0..50_000).map(|n| voice_code(&format!("L{:05}", n))));
+        assert!(sequential.chi_squared_z() > 50.0);
+        assert!(sequential.collision_pairs() as f64 > 1.2 * sequential.expected_collision_pairs());
+    }
+}
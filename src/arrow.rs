@@ -0,0 +1,107 @@
+//! Columnar voice codes over Apache Arrow arrays, for pack-out records read from Parquet
+//!
+//! Each row is validated and hashed straight from the array buffers, the only allocation is
+//! the output array.
+
+use arrow_array::builder::StringBuilder;
+use arrow_array::{Array, Date32Array, StringArray};
+use chrono::Datelike;
+
+use crate::gtin::is_valid_gtin;
+use crate::hash::VoiceCodeHasher;
+use crate::lot::is_valid_lot;
+
+/// Zero padded YYMMDD for a Date32 value, None when it is outside chrono's range
+fn yymmdd(pack_date: &Date32Array, i: usize) -> Option<[u8; 6]> {
+    let date = pack_date.value_as_date(i)?;
+    let mut out = [0u8; 6];
+    for (pair, value) in out.chunks_exact_mut(2).zip([date.year().rem_euclid(100) as u32, date.month(), date.day()]) {
+        pair[0] = b'0' + (value / 10) as u8;
+        pair[1] = b'0' + (value % 10) as u8;
+    }
+    Some(out)
+}
+
+/// Voice code for every row, null where any input is null or fails validation
+///
+/// GTINs are checked like `Gtin::parse_unchecked` and LOTs like `Lot::parse`, use
+/// `batch::compute_all` when the reason for a failure matters.
+///
+/// # Panics
+/// When the arrays have different lengths
+///
+/// # Example
+/// ```
+/// use voicecode::arrow::compute_arrow;
+/// use arrow_array::{ Array, Date32Array, StringArray };
+/// use voicecode::NaiveDate;
+///
+/// let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+/// let days = (NaiveDate::from_ymd_opt(2003, 1, 2).unwrap() - epoch).num_days() as i32;
+/// let gtin = StringArray::from(vec![Some("12345678901244"), Some("12345678901244"), None]);
+/// let lot = StringArray::from(vec!["LOT123", "LOT 123", "LOT123"]);
+/// let pack_date = Date32Array::from(vec![days; 3]);
+///
+/// let codes = compute_arrow(&gtin, &lot, &pack_date);
+/// assert_eq!(codes.value(0), "6991");
+/// assert!(codes.is_null(1));
+/// assert!(codes.is_null(2));
+/// ```
+pub fn compute_arrow(gtin: &StringArray, lot: &StringArray, pack_date: &Date32Array) -> StringArray {
+    assert_eq!(gtin.len(), lot.len(), "gtin and lot arrays differ in length");
+    assert_eq!(gtin.len(), pack_date.len(), "gtin and pack_date arrays differ in length");
+
+    let mut builder = StringBuilder::with_capacity(gtin.len(), gtin.len() * 4);
+    let mut code = [0u8; 4];
+    for i in 0..gtin.len() {
+        if gtin.is_null(i) || lot.is_null(i) || pack_date.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+        let (gtin, lot) = (gtin.value(i).as_bytes(), lot.value(i).as_bytes());
+        let date = match yymmdd(pack_date, i) {
+            Some(date) if is_valid_gtin(gtin) && is_valid_lot(lot) => date,
+            _ => {
+                builder.append_null();
+                continue;
+            }
+        };
+
+        let mut hasher = VoiceCodeHasher::new();
+        hasher.update(gtin);
+        hasher.update(lot);
+        hasher.update(&date);
+        let mut value = hasher.finalize();
+        for digit in code.iter_mut().rev() {
+            *digit = b'0' + (value % 10) as u8;
+            value /= 10;
+        }
+        builder.append_value(std::str::from_utf8(&code).expect("digits are ASCII"));
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::{compute_all, BatchRecord};
+
+    #[test]
+    fn test_matches_row_by_row() {
+        let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+        let dates: Vec<chrono::NaiveDate> = (0..400).map(|i| chrono::NaiveDate::from_ymd_opt(1999, 12, 1).unwrap() + chrono::Days::new(i)).collect();
+        let lots: Vec<String> = (0..400).map(|i| if i % 37 == 0 { format!("L {}", i) } else { format!("L{}", i) }).collect();
+        let gtin = StringArray::from(vec!["61414100734933"; 400]);
+        let lot = StringArray::from(lots.clone());
+        let pack_date = Date32Array::from(dates.iter().map(|date| (*date - epoch).num_days() as i32).collect::<Vec<_>>());
+
+        let codes = compute_arrow(&gtin, &lot, &pack_date);
+        let records = dates.iter().zip(&lots).map(|(date, lot)| BatchRecord::new("61414100734933", lot, &date.format("%y%m%d").to_string()));
+        for (i, expected) in compute_all(records).into_iter().enumerate() {
+            match expected {
+                Ok(voice_code) => assert_eq!(codes.value(i), voice_code.voice_code),
+                Err(_) => assert!(codes.is_null(i)),
+            }
+        }
+    }
+}
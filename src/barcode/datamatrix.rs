@@ -0,0 +1,79 @@
+//! GS1 DataMatrix, ECC 200 with FNC1 in first position
+
+use ::datamatrix::{DataMatrix, SymbolList};
+
+use crate::gs1::Gs1ElementString;
+use crate::voicecode::HashVoiceCode;
+
+/// A square GS1 DataMatrix symbol
+///
+/// Separators after variable length elements are encoded as the GS character, which GS1
+/// scanners transmit the same way as FNC1.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode };
+/// use voicecode::barcode::Gs1DataMatrix;
+/// let voice_code = HashVoiceCode::new("12345678901244", "LOT123", "03", "01", "02").unwrap();
+/// let symbol = Gs1DataMatrix::from(&voice_code);
+/// assert_eq!((symbol.width(), symbol.height()), (20, 20));
+/// // solid finder pattern along the left edge
+/// assert!((0..symbol.height()).all(|y| symbol.is_dark(0, y)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gs1DataMatrix {
+    width: usize,
+    modules: Vec<bool>,
+}
+
+impl Gs1DataMatrix {
+    /// Encode an element string in the smallest square symbol, None when it does not fit in 144x144
+    pub fn encode(elements: &Gs1ElementString) -> Option<Self> {
+        let data = elements.to_unbracketed();
+        let symbol = DataMatrix::encode_gs1(data.as_bytes(), SymbolList::default().enforce_square()).ok()?;
+        let bitmap = symbol.bitmap();
+        Some(Gs1DataMatrix { width: bitmap.width(), modules: bitmap.bits().to_vec() })
+    }
+
+    /// Modules per row, no quiet zone
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Modules per column, no quiet zone
+    pub fn height(&self) -> usize {
+        self.modules.len() / self.width
+    }
+
+    /// True when the module at column `x`, row `y` is dark, row 0 is the top
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.width + x]
+    }
+
+    /// All modules row by row from the top, true for dark
+    pub fn modules(&self) -> &[bool] {
+        &self.modules
+    }
+}
+
+impl From<&HashVoiceCode> for Gs1DataMatrix {
+    fn from(value: &HashVoiceCode) -> Self {
+        // (01), (13) and a LOT of at most 20 characters always fit
+        Gs1DataMatrix::encode(&Gs1ElementString::from(value)).expect("voice code element string fits a DataMatrix")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for input in ["(01)12345678901244(13)030102(10)LOT123", "(01)10614141007346(10)ABCDEFGHIJKLMNOPQRST(13)030102"] {
+            let elements = Gs1ElementString::parse(input).unwrap();
+            let symbol = Gs1DataMatrix::encode(&elements).unwrap();
+            let decoded = DataMatrix::decode(symbol.modules(), symbol.width()).unwrap();
+            assert_eq!(decoded, elements.to_unbracketed().into_bytes(), "{}", input);
+        }
+    }
+}
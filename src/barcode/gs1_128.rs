@@ -0,0 +1,225 @@
+//! GS1-128, Code 128 with FNC1 in first position
+
+use crate::gs1::{Gs1ElementString, GS};
+use crate::voicecode::HashVoiceCode;
+
+/// Bar and space widths for Code 128 values 0 to 105, each sums to 11 modules
+const PATTERNS: [[u8; 6]; 106] = [
+    [2, 1, 2, 2, 2, 2], [2, 2, 2, 1, 2, 2], [2, 2, 2, 2, 2, 1], [1, 2, 1, 2, 2, 3], [1, 2, 1, 3, 2, 2],
+    [1, 3, 1, 2, 2, 2], [1, 2, 2, 2, 1, 3], [1, 2, 2, 3, 1, 2], [1, 3, 2, 2, 1, 2], [2, 2, 1, 2, 1, 3],
+    [2, 2, 1, 3, 1, 2], [2, 3, 1, 2, 1, 2], [1, 1, 2, 2, 3, 2], [1, 2, 2, 1, 3, 2], [1, 2, 2, 2, 3, 1],
+    [1, 1, 3, 2, 2, 2], [1, 2, 3, 1, 2, 2], [1, 2, 3, 2, 2, 1], [2, 2, 3, 2, 1, 1], [2, 2, 1, 1, 3, 2],
+    [2, 2, 1, 2, 3, 1], [2, 1, 3, 2, 1, 2], [2, 2, 3, 1, 1, 2], [3, 1, 2, 1, 3, 1], [3, 1, 1, 2, 2, 2],
+    [3, 2, 1, 1, 2, 2], [3, 2, 1, 2, 2, 1], [3, 1, 2, 2, 1, 2], [3, 2, 2, 1, 1, 2], [3, 2, 2, 2, 1, 1],
+    [2, 1, 2, 1, 2, 3], [2, 1, 2, 3, 2, 1], [2, 3, 2, 1, 2, 1], [1, 1, 1, 3, 2, 3], [1, 3, 1, 1, 2, 3],
+    [1, 3, 1, 3, 2, 1], [1, 1, 2, 3, 1, 3], [1, 3, 2, 1, 1, 3], [1, 3, 2, 3, 1, 1], [2, 1, 1, 3, 1, 3],
+    [2, 3, 1, 1, 1, 3], [2, 3, 1, 3, 1, 1], [1, 1, 2, 1, 3, 3], [1, 1, 2, 3, 3, 1], [1, 3, 2, 1, 3, 1],
+    [1, 1, 3, 1, 2, 3], [1, 1, 3, 3, 2, 1], [1, 3, 3, 1, 2, 1], [3, 1, 3, 1, 2, 1], [2, 1, 1, 3, 3, 1],
+    [2, 3, 1, 1, 3, 1], [2, 1, 3, 1, 1, 3], [2, 1, 3, 3, 1, 1], [2, 1, 3, 1, 3, 1], [3, 1, 1, 1, 2, 3],
+    [3, 1, 1, 3, 2, 1], [3, 3, 1, 1, 2, 1], [3, 1, 2, 1, 1, 3], [3, 1, 2, 3, 1, 1], [3, 3, 2, 1, 1, 1],
+    [3, 1, 4, 1, 1, 1], [2, 2, 1, 4, 1, 1], [4, 3, 1, 1, 1, 1], [1, 1, 1, 2, 2, 4], [1, 1, 1, 4, 2, 2],
+    [1, 2, 1, 1, 2, 4], [1, 2, 1, 4, 2, 1], [1, 4, 1, 1, 2, 2], [1, 4, 1, 2, 2, 1], [1, 1, 2, 2, 1, 4],
+    [1, 1, 2, 4, 1, 2], [1, 2, 2, 1, 1, 4], [1, 2, 2, 4, 1, 1], [1, 4, 2, 1, 1, 2], [1, 4, 2, 2, 1, 1],
+    [2, 4, 1, 2, 1, 1], [2, 2, 1, 1, 1, 4], [4, 1, 3, 1, 1, 1], [2, 4, 1, 1, 1, 2], [1, 3, 4, 1, 1, 1],
+    [1, 1, 1, 2, 4, 2], [1, 2, 1, 1, 4, 2], [1, 2, 1, 2, 4, 1], [1, 1, 4, 2, 1, 2], [1, 2, 4, 1, 1, 2],
+    [1, 2, 4, 2, 1, 1], [4, 1, 1, 2, 1, 2], [4, 2, 1, 1, 1, 2], [4, 2, 1, 2, 1, 1], [2, 1, 2, 1, 4, 1],
+    [2, 1, 4, 1, 2, 1], [4, 1, 2, 1, 2, 1], [1, 1, 1, 1, 4, 3], [1, 1, 1, 3, 4, 1], [1, 3, 1, 1, 4, 1],
+    [1, 1, 4, 1, 1, 3], [1, 1, 4, 3, 1, 1], [4, 1, 1, 1, 1, 3], [4, 1, 1, 3, 1, 1], [1, 1, 3, 1, 4, 1],
+    [1, 1, 4, 1, 3, 1], [3, 1, 1, 1, 4, 1], [4, 1, 1, 1, 3, 1], [2, 1, 1, 4, 1, 2], [2, 1, 1, 2, 1, 4],
+    [2, 1, 1, 2, 3, 2],
+];
+
+/// Stop pattern including the final bar, 13 modules
+const STOP: [u8; 7] = [2, 3, 3, 1, 1, 1, 2];
+
+const CODE_C: u8 = 99;
+const CODE_B: u8 = 100;
+const FNC1: u8 = 102;
+const START_B: u8 = 104;
+const START_C: u8 = 105;
+const STOP_VALUE: u8 = 106;
+
+/// A GS1-128 symbol
+///
+/// Code set C packs digit pairs, code set B carries everything else, FNC1 follows the start
+/// character and stands in for the group separator after variable length elements.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::barcode::Gs1_128;
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let symbol = Gs1_128::from(&voice_code);
+/// // start C, FNC1, then 01 12 34 ...
+/// assert_eq!(&symbol.codewords()[..4], &[105, 102, 1, 12]);
+/// assert_eq!(symbol.width(), symbol.modules().len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gs1_128 {
+    codewords: Vec<u8>,
+}
+
+impl Gs1_128 {
+    /// Encode an element string, elements after variable length AIs are separated with FNC1
+    ///
+    /// `Gs1ElementString::push` only takes printable ASCII, which code set B covers, so every
+    /// element string can be encoded.
+    pub fn encode(elements: &Gs1ElementString) -> Self {
+        let data = elements.to_unbracketed();
+        let mut codewords = encode_data(data.as_bytes());
+        let checksum = codewords
+            .iter()
+            .enumerate()
+            .map(|(i, &cw)| i.max(1) as u32 * u32::from(cw))
+            .sum::<u32>()
+            % 103;
+        codewords.push(checksum as u8);
+        codewords.push(STOP_VALUE);
+        Gs1_128 { codewords }
+    }
+
+    /// Code 128 values from start character to stop character, including FNC1 and the check character
+    pub fn codewords(&self) -> &[u8] {
+        &self.codewords
+    }
+
+    /// Alternating bar and space widths in modules, starting and ending with a bar, no quiet zones
+    pub fn widths(&self) -> Vec<u8> {
+        let data = self.codewords.strip_suffix(&[STOP_VALUE]).unwrap_or(&self.codewords);
+        let mut widths: Vec<u8> = data.iter().filter_map(|&cw| PATTERNS.get(usize::from(cw))).flatten().copied().collect();
+        widths.extend_from_slice(&STOP);
+        widths
+    }
+
+    /// One entry per module, true for bar, no quiet zones
+    pub fn modules(&self) -> Vec<bool> {
+        self.widths()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &w)| std::iter::repeat_n(i % 2 == 0, w as usize))
+            .collect()
+    }
+
+    /// Symbol width in modules, no quiet zones
+    pub fn width(&self) -> usize {
+        (self.codewords.len() - 1) * 11 + 13
+    }
+}
+
+impl From<&Gs1ElementString> for Gs1_128 {
+    fn from(value: &Gs1ElementString) -> Self {
+        Gs1_128::encode(value)
+    }
+}
+
+impl From<&HashVoiceCode> for Gs1_128 {
+    fn from(value: &HashVoiceCode) -> Self {
+        Gs1_128::encode(&Gs1ElementString::from(value))
+    }
+}
+
+/// Start character, FNC1 and data codewords, switching to code set C for runs of digits
+fn encode_data(data: &[u8]) -> Vec<u8> {
+    let digit_run = |from: usize| data[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut in_c = digit_run(0) >= 4 || (digit_run(0) == data.len() && data.len().is_multiple_of(2));
+    let mut codewords = vec![if in_c { START_C } else { START_B }, FNC1];
+
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i];
+        if b == GS as u8 {
+            codewords.push(FNC1);
+            i += 1;
+            continue;
+        }
+        let run = digit_run(i);
+        if in_c {
+            if run >= 2 {
+                codewords.push((data[i] - b'0') * 10 + (data[i + 1] - b'0'));
+                i += 2;
+                continue;
+            }
+            codewords.push(CODE_B);
+            in_c = false;
+        }
+        // worth switching when the run fills whole digit pairs, an odd leading digit stays in B
+        let reaches_end = i + run == data.len();
+        if run >= 6 || (run >= 4 && reaches_end) {
+            if run % 2 == 1 {
+                codewords.push(code_b(b));
+                i += 1;
+            }
+            codewords.push(CODE_C);
+            in_c = true;
+            continue;
+        }
+        codewords.push(code_b(b));
+        i += 1;
+    }
+    codewords
+}
+
+/// Code set B value for an ASCII character, anything outside the set is clamped into it
+/// rather than indexing past `PATTERNS`
+fn code_b(b: u8) -> u8 {
+    debug_assert!((b' '..=0x7f).contains(&b), "GS1 data is printable ASCII");
+    b.saturating_sub(b' ').min(0x7f - b' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode data codewords back to text, FNC1 after the first becomes GS
+    fn decode(codewords: &[u8]) -> String {
+        let mut in_c = codewords[0] == START_C;
+        assert_eq!(codewords[1], FNC1);
+        let mut out = String::new();
+        for &cw in &codewords[2..codewords.len() - 2] {
+            match (cw, in_c) {
+                (FNC1, _) => out.push(GS),
+                (CODE_B, true) => in_c = false,
+                (CODE_C, false) => in_c = true,
+                (cw, true) => out.push_str(&format!("{:02}", cw)),
+                (cw, false) => out.push((cw + b' ') as char),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert!(PATTERNS.iter().all(|p| p.iter().sum::<u8>() == 11));
+        assert_eq!(STOP.iter().sum::<u8>(), 13);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in [
+            "(01)12345678901244(13)030102(10)LOT123",
+            "(01)10614141007346(10)A1(13)030102",
+            "(01)10614141007346(10)123456789(13)030102",
+            "(10)ABC12345(01)10614141007346",
+            "(10)1(01)10614141007346",
+        ] {
+            let elements = Gs1ElementString::parse(input).unwrap();
+            let symbol = Gs1_128::encode(&elements);
+            assert_eq!(decode(symbol.codewords()), elements.to_unbracketed(), "{}", input);
+            assert_eq!(symbol.width(), symbol.modules().len());
+        }
+    }
+
+    #[test]
+    fn test_known_symbol() {
+        let elements = Gs1ElementString::parse("(01)12345678901244(13)030102(10)LOT123").unwrap();
+        let symbol = Gs1_128::encode(&elements);
+        // start C, FNC1, 01 12 34 56 78 90 12 44 13 03 01 02 10, code B, L O T 1 2 3, check, stop
+        assert_eq!(
+            symbol.codewords(),
+            &[105, 102, 1, 12, 34, 56, 78, 90, 12, 44, 13, 3, 1, 2, 10, 100, 44, 47, 52, 17, 18, 19, 94, 106]
+        );
+        let modules = symbol.modules();
+        assert!(modules[0] && modules[modules.len() - 1]);
+    }
+}
@@ -0,0 +1,12 @@
+//! Barcode symbol encoders shared by the label renderers
+//!
+//! Encoders produce the symbol itself, codewords and modules, renderers decide on
+//! dimensions and quiet zones.
+
+#[cfg(feature = "datamatrix")]
+pub mod datamatrix;
+pub mod gs1_128;
+
+#[cfg(feature = "datamatrix")]
+pub use datamatrix::Gs1DataMatrix;
+pub use gs1_128::Gs1_128;
@@ -0,0 +1,116 @@
+//! CSV in, CSV out
+//!
+//! Input needs `gtin`, `lot` and `pack_date` columns, matched case insensitively, and may
+//! carry any other columns. Output repeats every input column and appends `voice_code`,
+//! `major`, `minor` and `error`.
+
+use std::io;
+
+use ::csv::{ReaderBuilder, StringRecord, Writer};
+
+use crate::batch::{BatchError, BatchRecord, BatchSummary};
+
+impl From<::csv::Error> for BatchError {
+    fn from(value: ::csv::Error) -> Self {
+        BatchError::Csv(value)
+    }
+}
+
+pub(crate) fn column(headers: &StringRecord, name: &'static str) -> Result<usize, BatchError> {
+    headers
+        .iter()
+        .position(|header| header.trim().eq_ignore_ascii_case(name))
+        .ok_or(BatchError::MissingColumn(name))
+}
+
+/// Read records from `input`, write the enriched rows to `output`
+///
+/// # Example
+/// ```
+/// use voicecode::batch::csv::process_csv;
+/// let input = "sku,gtin,lot,pack_date\nA,12345678901244,LOT123,030102\nB,12345678901244,LOT 123,030102\n";
+/// let mut output = Vec::new();
+/// let summary = process_csv(input.as_bytes(), &mut output).unwrap();
+/// assert_eq!(summary.rows, 2);
+/// assert_eq!(summary.errors[0].row, 2);
+///
+/// let output = String::from_utf8(output).unwrap();
+/// let mut lines = output.lines();
+/// assert_eq!(lines.next(), Some("sku,gtin,lot,pack_date,voice_code,major,minor,error"));
+/// assert_eq!(lines.next(), Some("A,12345678901244,LOT123,030102,6991,91,69,"));
+/// ```
+pub fn process_csv<R: io::Read, W: io::Write>(input: R, output: W) -> Result<BatchSummary, BatchError> {
+    let _span = span!("batch", format = "csv");
+    #[cfg(feature = "metrics")]
+    let _timer = crate::metrics::BatchTimer::start("csv");
+    let mut reader = ReaderBuilder::new().flexible(true).from_reader(input);
+    let mut writer = Writer::from_writer(output);
+
+    let headers = reader.headers()?.clone();
+    let gtin = column(&headers, "gtin")?;
+    let lot = column(&headers, "lot")?;
+    let pack_date = column(&headers, "pack_date")?;
+
+    let mut out_headers = headers.clone();
+    for name in ["voice_code", "major", "minor", "error"] {
+        out_headers.push_field(name);
+    }
+    writer.write_record(&out_headers)?;
+
+    let mut summary = BatchSummary::default();
+    for (index, row) in reader.records().enumerate() {
+        let row = row?;
+        let field = |i: usize| row.get(i).unwrap_or_default().trim();
+        let record = BatchRecord::new(field(gtin), field(lot), field(pack_date));
+
+        let mut out_row = row.clone();
+        match record.compute() {
+            Ok(voice_code) => {
+                out_row.push_field(&voice_code.voice_code);
+                out_row.push_field(&voice_code.voice_code_major);
+                out_row.push_field(&voice_code.voice_code_minor);
+                out_row.push_field("");
+            }
+            Err(error) => {
+                for _ in 0..3 {
+                    out_row.push_field("");
+                }
+                out_row.push_field(&error.to_string());
+                summary.row_failed(index + 1, error);
+            }
+        }
+        writer.write_record(&out_row)?;
+        summary.row_read();
+    }
+    writer.flush()?;
+    Ok(summary.finished())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::RowError;
+    use crate::error::VoiceCodeError;
+    use crate::gtin::GtinError;
+
+    #[test]
+    fn test_missing_column() {
+        let result = process_csv("gtin,lot\n1,2\n".as_bytes(), Vec::new());
+        assert!(matches!(result, Err(BatchError::MissingColumn("pack_date"))));
+    }
+
+    #[test]
+    fn test_collects_errors() {
+        let input = "GTIN,Lot,Pack_Date\n61414100734933,32abcd,030102\n1,32abcd,030102\n61414100734933,32abcd,03mm02\n";
+        let mut output = Vec::new();
+        let summary = process_csv(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(summary.rows, 3);
+        assert_eq!(summary.succeeded(), 1);
+        assert_eq!(summary.errors[0], RowError { row: 2, error: VoiceCodeError::InvalidGtin { reason: GtinError::Length { len: 1 } } });
+        assert_eq!(summary.errors[1].row, 3);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.lines().nth(1).unwrap().ends_with(",8079,79,80,"));
+        assert!(output.lines().nth(2).unwrap().ends_with(",,,,\"GTIN must be 8, 12, 13 or 14 digits, got 1\""));
+    }
+}
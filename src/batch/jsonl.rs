@@ -0,0 +1,115 @@
+//! JSON Lines in, JSON Lines out, for Unix pipelines
+//!
+//! Every input line is an object with string `gtin`, `lot` and `pack_date` fields and may
+//! carry any other fields. Every output line repeats the input object with `voice_code`,
+//! `major` and `minor` added, or is `{"error":…}` when the line is not a JSON object or the
+//! record fails validation. Blank lines are skipped so output lines match input records.
+
+use std::io::{self, BufRead};
+
+use serde_json::{json, Map, Value};
+
+use crate::batch::{BatchError, BatchRecord, BatchSummary};
+use crate::error::VoiceCodeError;
+
+fn field(object: &Map<String, Value>, name: &'static str) -> Result<String, VoiceCodeError> {
+    match object.get(name) {
+        Some(Value::String(value)) => Ok(value.trim().to_string()),
+        _ => Err(VoiceCodeError::MissingField { field: name }),
+    }
+}
+
+fn record(object: &Map<String, Value>) -> Result<BatchRecord, VoiceCodeError> {
+    Ok(BatchRecord { gtin: field(object, "gtin")?, lot: field(object, "lot")?, pack_date: field(object, "pack_date")? })
+}
+
+/// Add `voice_code`, `major` and `minor` to a record object, unchanged when it fails validation
+pub(crate) fn enrich(object: &mut Map<String, Value>) -> Result<(), VoiceCodeError> {
+    let voice_code = record(object)?.compute()?;
+    object.insert("voice_code".to_string(), voice_code.voice_code.into());
+    object.insert("major".to_string(), voice_code.voice_code_major.into());
+    object.insert("minor".to_string(), voice_code.voice_code_minor.into());
+    Ok(())
+}
+
+/// Read records from `input`, write one enriched record or error per record to `output`
+///
+/// Lines are numbered from 1 in the summary, counting blank lines.
+///
+/// # Example
+/// ```
+/// use voicecode::batch::jsonl::process_jsonl;
+/// let input = r#"{"sku":"A","gtin":"12345678901244","lot":"LOT123","pack_date":"030102"}
+/// {"gtin":"12345678901244","lot":"LOT 123","pack_date":"030102"}
+/// "#;
+/// let mut output = Vec::new();
+/// let summary = process_jsonl(input.as_bytes(), &mut output).unwrap();
+/// assert_eq!(summary.rows, 2);
+/// assert_eq!(summary.errors[0].row, 2);
+///
+/// let output = String::from_utf8(output).unwrap();
+/// let mut lines = output.lines();
+/// assert_eq!(
+///     lines.next(),
+///     Some(r#"{"sku":"A","gtin":"12345678901244","lot":"LOT123","pack_date":"030102","voice_code":"6991","major":"91","minor":"69"}"#)
+/// );
+/// assert!(lines.next().unwrap().starts_with(r#"{"error":"#));
+/// ```
+pub fn process_jsonl<R: BufRead, W: io::Write>(input: R, mut output: W) -> Result<BatchSummary, BatchError> {
+    let _span = span!("batch", format = "jsonl");
+    #[cfg(feature = "metrics")]
+    let _timer = crate::metrics::BatchTimer::start("jsonl");
+    let mut summary = BatchSummary::default();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        summary.row_read();
+
+        let out = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Object(mut object)) => match enrich(&mut object) {
+                Ok(()) => Value::Object(object),
+                Err(error) => {
+                    let out = json!({ "error": error.to_string() });
+                    summary.row_failed(index + 1, error);
+                    out
+                }
+            },
+            Ok(_) => {
+                summary.row_malformed(index + 1);
+                json!({ "error": "line is not a JSON object" })
+            }
+            Err(e) => {
+                summary.row_malformed(index + 1);
+                json!({ "error": e.to_string() })
+            }
+        };
+        writeln!(output, "{}", out)?;
+    }
+    output.flush()?;
+    Ok(summary.finished())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::RowError;
+
+    #[test]
+    fn test_collects_errors() {
+        let input = "{\"gtin\":\"61414100734933\",\"lot\":\"32abcd\",\"pack_date\":\"030102\"}\n\nnot json\n[1]\n{\"gtin\":\"61414100734933\",\"lot\":\"32abcd\"}\n";
+        let mut output = Vec::new();
+        let summary = process_jsonl(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(summary.rows, 4);
+        assert_eq!(summary.succeeded(), 1);
+        assert_eq!(summary.malformed, vec![3, 4]);
+        assert_eq!(summary.errors, vec![RowError { row: 5, error: VoiceCodeError::MissingField { field: "pack_date" } }]);
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["voice_code"], "8079");
+        assert!(lines[1..].iter().all(|line| line["error"].is_string()));
+    }
+}
@@ -0,0 +1,218 @@
+//! Computing voice codes for many records at once
+//!
+//! Row level validation failures are collected alongside the successful rows so one bad
+//! row never aborts a run.
+
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "jsonl")]
+pub mod jsonl;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "watch")]
+pub mod watch;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
+
+use std::fmt;
+use std::io;
+
+use crate::error::VoiceCodeError;
+use crate::pack_date::PackDate;
+use crate::voicecode::HashVoiceCode;
+
+/// Unvalidated input row
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serve", derive(utoipa::ToSchema))]
+pub struct BatchRecord {
+    pub gtin: String,
+    pub lot: String,
+    /// YYMMDD or YYYY-MM-DD
+    pub pack_date: String,
+}
+
+impl BatchRecord {
+    pub fn new(gtin: &str, lot: &str, pack_date: &str) -> Self {
+        BatchRecord { gtin: gtin.to_string(), lot: lot.to_string(), pack_date: pack_date.to_string() }
+    }
+
+    /// Validate the row and compute its voice code
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::batch::BatchRecord;
+    /// let record = BatchRecord::new("12345678901244", "LOT123", "2003-01-02");
+    /// assert_eq!(record.compute().unwrap().voice_code, "6991");
+    /// ```
+    pub fn compute(&self) -> Result<HashVoiceCode, VoiceCodeError> {
+        let pack_date: PackDate = self.pack_date.parse()?;
+        HashVoiceCode::builder().gtin(&self.gtin).lot(&self.lot).pack_date(pack_date).build()
+    }
+}
+
+/// Validation failure for one row, rows are numbered from 1 excluding any header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowError {
+    pub row: usize,
+    pub error: VoiceCodeError,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.error)
+    }
+}
+
+/// Outcome of a batch run
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Rows read
+    pub rows: usize,
+    /// Rows that failed validation
+    pub errors: Vec<RowError>,
+    /// Rows that could not be read as a record at all, only JSON Lines input skips these
+    /// instead of stopping
+    pub malformed: Vec<usize>,
+}
+
+impl BatchSummary {
+    /// Rows that produced a voice code
+    pub fn succeeded(&self) -> usize {
+        self.rows - self.errors.len() - self.malformed.len()
+    }
+}
+
+/// Bookkeeping shared by the file processors
+#[cfg(any(feature = "csv", feature = "jsonl", feature = "xlsx"))]
+impl BatchSummary {
+    /// Count a row that was read, logging progress every `PROGRESS_ROWS` rows
+    pub(crate) fn row_read(&mut self) {
+        self.rows += 1;
+        if self.rows.is_multiple_of(PROGRESS_ROWS) {
+            event!(info, "batch progress", rows = self.rows, failed = self.errors.len() + self.malformed.len());
+        }
+    }
+
+    pub(crate) fn row_failed(&mut self, row: usize, error: VoiceCodeError) {
+        event!(warn, "row failed validation", row = row, error = error);
+        self.errors.push(RowError { row, error });
+    }
+
+    #[cfg(feature = "jsonl")]
+    pub(crate) fn row_malformed(&mut self, row: usize) {
+        event!(warn, "row is not a record", row = row);
+        self.malformed.push(row);
+    }
+
+    /// Log the totals once a run has finished
+    pub(crate) fn finished(self) -> Self {
+        event!(info, "batch finished", rows = self.rows, succeeded = self.succeeded(), failed = self.rows - self.succeeded());
+        self
+    }
+}
+
+/// Rows between batch progress events
+#[cfg(any(feature = "csv", feature = "jsonl", feature = "xlsx"))]
+const PROGRESS_ROWS: usize = 10_000;
+
+/// Failure that stops a batch run, unlike `RowError` which is collected
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BatchError {
+    Io(io::Error),
+    #[cfg(feature = "csv")]
+    Csv(::csv::Error),
+    #[cfg(feature = "xlsx")]
+    Xlsx(xlsx::XlsxError),
+    /// Input has no column with this name
+    MissingColumn(&'static str),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Io(e) => write!(f, "{}", e),
+            #[cfg(feature = "csv")]
+            BatchError::Csv(e) => write!(f, "{}", e),
+            #[cfg(feature = "xlsx")]
+            BatchError::Xlsx(e) => write!(f, "{}", e),
+            BatchError::MissingColumn(column) => write!(f, "input has no {} column", column),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchError::Io(e) => Some(e),
+            #[cfg(feature = "csv")]
+            BatchError::Csv(e) => Some(e),
+            #[cfg(feature = "xlsx")]
+            BatchError::Xlsx(e) => Some(e),
+            BatchError::MissingColumn(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for BatchError {
+    fn from(value: io::Error) -> Self {
+        BatchError::Io(value)
+    }
+}
+
+/// Compute every record in order
+///
+/// # Example
+/// ```
+/// use voicecode::batch::{ compute_all, BatchRecord };
+/// let results = compute_all(vec![
+///     BatchRecord::new("12345678901244", "LOT123", "030102"),
+///     BatchRecord::new("12345678901244", "LOT 123", "030102"),
+/// ]);
+/// assert_eq!(results[0].as_ref().unwrap().voice_code, "6991");
+/// assert!(results[1].is_err());
+/// ```
+pub fn compute_all<I>(records: I) -> Vec<Result<HashVoiceCode, VoiceCodeError>>
+where
+    I: IntoIterator<Item = BatchRecord>,
+{
+    records.into_iter().map(|record| record.compute()).collect()
+}
+
+/// Compute every record across the rayon thread pool, results keep the input order
+///
+/// # Example
+/// ```
+/// use voicecode::batch::{ compute_parallel, BatchRecord };
+/// let records = (1..=28).map(|day| BatchRecord::new("12345678901244", "LOT123", &format!("0301{:02}", day)));
+/// let results = compute_parallel(records);
+/// assert_eq!(results.len(), 28);
+/// assert_eq!(results[1].as_ref().unwrap().voice_code, "6991");
+/// ```
+#[cfg(feature = "rayon")]
+pub fn compute_parallel<I>(records: I) -> Vec<Result<HashVoiceCode, VoiceCodeError>>
+where
+    I: IntoIterator<Item = BatchRecord>,
+{
+    use rayon::prelude::*;
+
+    let records: Vec<BatchRecord> = records.into_iter().collect();
+    records.par_iter().map(BatchRecord::compute).collect()
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let records: Vec<BatchRecord> = (0..2000)
+            .map(|i| BatchRecord::new("61414100734933", &format!("L{}", i), if i % 97 == 0 { "bad" } else { "030102" }))
+            .collect();
+        let codes = |results: Vec<Result<HashVoiceCode, VoiceCodeError>>| -> Vec<Result<String, VoiceCodeError>> {
+            results.into_iter().map(|result| result.map(|voice_code| voice_code.voice_code)).collect()
+        };
+        assert_eq!(codes(compute_parallel(records.clone())), codes(compute_all(records)));
+    }
+}
@@ -0,0 +1,80 @@
+//! Computing voice codes over async streams
+//!
+//! The adapters are lazy, a record is only pulled from the source when the consumer polls
+//! for the next result, so a slow sink holds back a fast source instead of buffering.
+
+use futures_core::Stream;
+use futures_util::StreamExt;
+
+use crate::batch::BatchRecord;
+use crate::error::VoiceCodeError;
+use crate::voicecode::HashVoiceCode;
+
+/// Compute each record as it arrives, results come out in input order
+///
+/// # Example
+/// ```
+/// use futures_util::{ stream, FutureExt, StreamExt };
+/// use voicecode::batch::{ stream::compute_stream, BatchRecord };
+/// let records = stream::iter(vec![
+///     BatchRecord::new("12345678901244", "LOT123", "030102"),
+///     BatchRecord::new("12345678901244", "LOT 123", "030102"),
+/// ]);
+/// let results: Vec<_> = compute_stream(records).collect().now_or_never().unwrap();
+/// assert_eq!(results[0].as_ref().unwrap().voice_code, "6991");
+/// assert!(results[1].is_err());
+/// ```
+pub fn compute_stream<S>(records: S) -> impl Stream<Item = Result<HashVoiceCode, VoiceCodeError>>
+where
+    S: Stream<Item = BatchRecord>,
+{
+    records.map(|record| record.compute())
+}
+
+/// `compute_stream` for sources that can fail, such as a network stream or a message
+/// consumer, source errors are passed through as the outer `Err` without ending the stream
+///
+/// # Example
+/// ```
+/// use futures_util::{ stream, FutureExt, StreamExt };
+/// use voicecode::batch::{ stream::try_compute_stream, BatchRecord };
+/// let records = stream::iter(vec![
+///     Ok(BatchRecord::new("12345678901244", "LOT123", "030102")),
+///     Err("connection reset"),
+/// ]);
+/// let results: Vec<_> = try_compute_stream(records).collect().now_or_never().unwrap();
+/// assert_eq!(results[0].as_ref().unwrap().as_ref().unwrap().voice_code, "6991");
+/// assert_eq!(results[1].as_ref().unwrap_err(), &"connection reset");
+/// ```
+pub fn try_compute_stream<S, E>(records: S) -> impl Stream<Item = Result<Result<HashVoiceCode, VoiceCodeError>, E>>
+where
+    S: Stream<Item = Result<BatchRecord, E>>,
+{
+    records.map(|record| record.map(|record| record.compute()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::pin::pin;
+    use std::task::Poll;
+
+    use futures_util::{stream, FutureExt};
+
+    use super::*;
+
+    #[test]
+    fn test_pulls_on_demand() {
+        let pulled = Cell::new(0);
+        let records = stream::poll_fn(|_| {
+            pulled.set(pulled.get() + 1);
+            Poll::Ready(Some(BatchRecord::new("61414100734933", "32abcd", "030102")))
+        });
+        let mut results = pin!(compute_stream(records));
+        for _ in 0..3 {
+            assert_eq!(results.next().now_or_never().unwrap().unwrap().unwrap().voice_code, "8079");
+            assert_eq!(pulled.get(), 1);
+            pulled.set(0);
+        }
+    }
+}
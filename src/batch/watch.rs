@@ -0,0 +1,300 @@
+//! Drop folder processing for ERP integrations
+//!
+//! [`DropFolder`] watches an input directory for `.csv` files, and `.xlsx` workbooks with the
+//! `xlsx` feature, and writes each file's results under the same name to the output directory.
+//! [`DropFolder::run`] is woken by file system events through notify, debounced, and then
+//! checks the folder with [`DropFolder::poll`]. A file is only picked up once its size and
+//! modification time were the same on two checks in a row, so files the ERP is still writing
+//! are left alone. Results are written to a
+//! hidden `.part` file and renamed into place, so readers of the output directory never see
+//! half a file.
+//!
+//! A processed input is removed. An input that cannot be processed, such as one without a
+//! `gtin` column, is moved to the error directory with a `<name>.error.txt` next to it saying
+//! why. With `fail_on_error` that also happens to files with rows failing validation, and the
+//! text file lists the failed rows.
+//!
+//! Hidden files and Excel `~$` lock files are ignored, as are other extensions.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+use notify_debouncer_full::new_debouncer;
+use notify_debouncer_full::notify::RecursiveMode;
+
+use crate::batch::{BatchError, BatchSummary};
+
+/// Result of processing one dropped file
+#[derive(Debug)]
+pub struct Processed {
+    /// The file as it was found in the input directory
+    pub input: PathBuf,
+    /// Where the file's results or the file itself went
+    pub moved_to: PathBuf,
+    pub outcome: Result<BatchSummary, BatchError>,
+}
+
+impl Processed {
+    /// The file could not be processed or has rows failing validation
+    pub fn failed(&self) -> bool {
+        match &self.outcome {
+            Ok(summary) => summary.succeeded() != summary.rows,
+            Err(_) => true,
+        }
+    }
+}
+
+/// Input, output and error directories polled for dropped files
+#[derive(Debug, Clone)]
+pub struct DropFolder {
+    input: PathBuf,
+    output: PathBuf,
+    errors: PathBuf,
+    fail_on_error: bool,
+    /// Size and modification time of files seen on the last poll
+    seen: HashMap<PathBuf, (u64, SystemTime)>,
+}
+
+impl DropFolder {
+    pub fn new(input: impl Into<PathBuf>, output: impl Into<PathBuf>, errors: impl Into<PathBuf>) -> Self {
+        DropFolder {
+            input: input.into(),
+            output: output.into(),
+            errors: errors.into(),
+            fail_on_error: false,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Also move files with rows failing validation to the error directory
+    pub fn fail_on_error(mut self, fail_on_error: bool) -> Self {
+        self.fail_on_error = fail_on_error;
+        self
+    }
+
+    /// Process every file that stopped changing since the last poll, in name order
+    ///
+    /// # Example
+    /// ```no_run
+    /// use voicecode::batch::watch::DropFolder;
+    /// let mut folder = DropFolder::new("inbox", "outbox", "errors");
+    /// loop {
+    ///     for processed in folder.poll().unwrap() {
+    ///         println!("{} -> {}", processed.input.display(), processed.moved_to.display());
+    ///     }
+    ///     std::thread::sleep(std::time::Duration::from_secs(2));
+    /// }
+    /// ```
+    pub fn poll(&mut self) -> io::Result<Vec<Processed>> {
+        fs::create_dir_all(&self.output)?;
+        fs::create_dir_all(&self.errors)?;
+        let mut current = HashMap::new();
+        for entry in fs::read_dir(&self.input)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() && kind(&entry.path()).is_some() {
+                current.insert(entry.path(), (metadata.len(), metadata.modified()?));
+            }
+        }
+        let mut ready: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, state)| self.seen.get(*path) == Some(state))
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+        for path in &ready {
+            current.remove(path);
+        }
+        self.seen = current;
+        ready.into_iter().map(|path| self.process(path)).collect()
+    }
+
+    /// Watch the input directory forever, handing each processed file to `on_processed`
+    ///
+    /// The folder is polled once file system events have been quiet for `interval`, and again
+    /// every `interval` while files it has seen have not settled yet.
+    pub fn run(&mut self, interval: Duration, mut on_processed: impl FnMut(&Processed)) -> io::Result<()> {
+        let (events, changed) = mpsc::channel();
+        let mut debouncer = new_debouncer(interval, None, events).map_err(io::Error::other)?;
+        debouncer.watch(&self.input, RecursiveMode::NonRecursive).map_err(io::Error::other)?;
+        loop {
+            for processed in self.poll()? {
+                on_processed(&processed);
+            }
+            let woken = match self.seen.is_empty() {
+                true => changed.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                false => changed.recv_timeout(interval),
+            };
+            match woken {
+                Ok(Ok(_)) | Err(RecvTimeoutError::Timeout) => {}
+                Ok(Err(errors)) => {
+                    if let Some(error) = errors.into_iter().next() {
+                        return Err(io::Error::other(error));
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(io::Error::other("file watcher stopped")),
+            }
+        }
+    }
+
+    fn process(&self, input: PathBuf) -> io::Result<Processed> {
+        let name = input.file_name().expect("read_dir entries have a name").to_os_string();
+        let part = self.output.join(format!(".{}.part", name.to_string_lossy()));
+        let outcome = match kind(&input) {
+            Some(Kind::Csv) => process_csv(&input, &part),
+            #[cfg(feature = "xlsx")]
+            Some(Kind::Xlsx) => process_xlsx(&input, &part),
+            None => unreachable!("poll only returns known kinds"),
+        };
+        let processed = Processed { input, moved_to: PathBuf::new(), outcome };
+        let moved_to = if processed.failed() && (processed.outcome.is_err() || self.fail_on_error) {
+            let _ = fs::remove_file(&part);
+            let moved_to = self.errors.join(&name);
+            move_file(&processed.input, &moved_to)?;
+            let mut reason = moved_to.clone().into_os_string();
+            reason.push(".error.txt");
+            fs::write(reason, error_text(&processed.outcome))?;
+            moved_to
+        } else {
+            let moved_to = self.output.join(&name);
+            fs::rename(&part, &moved_to)?;
+            fs::remove_file(&processed.input)?;
+            moved_to
+        };
+        Ok(Processed { moved_to, ..processed })
+    }
+}
+
+enum Kind {
+    Csv,
+    #[cfg(feature = "xlsx")]
+    Xlsx,
+}
+
+fn kind(path: &Path) -> Option<Kind> {
+    let name = path.file_name()?.to_str()?;
+    if name.starts_with('.') || name.starts_with("~$") {
+        return None;
+    }
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "csv" => Some(Kind::Csv),
+        #[cfg(feature = "xlsx")]
+        "xlsx" => Some(Kind::Xlsx),
+        _ => None,
+    }
+}
+
+fn process_csv(input: &Path, output: &Path) -> Result<BatchSummary, BatchError> {
+    let mut writer = BufWriter::new(File::create(output)?);
+    let summary = crate::batch::csv::process_csv(BufReader::new(File::open(input)?), &mut writer)?;
+    writer.flush()?;
+    Ok(summary)
+}
+
+#[cfg(feature = "xlsx")]
+fn process_xlsx(input: &Path, output: &Path) -> Result<BatchSummary, BatchError> {
+    let mut writer = BufWriter::new(File::create(output)?);
+    let summary = crate::batch::xlsx::process_xlsx(&fs::read(input)?, &mut writer)?;
+    writer.flush()?;
+    Ok(summary)
+}
+
+/// Rename, or copy and remove when `to` is on another file system
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+fn error_text(outcome: &Result<BatchSummary, BatchError>) -> String {
+    match outcome {
+        Ok(summary) => {
+            let mut text = String::new();
+            for error in &summary.errors {
+                text.push_str(&format!("{}\n", error));
+            }
+            text
+        }
+        Err(e) => format!("{}\n", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("voicecode-watch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("in")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_poll() {
+        let dir = scratch("poll");
+        let mut folder = DropFolder::new(dir.join("in"), dir.join("out"), dir.join("errors")).fail_on_error(true);
+        fs::write(dir.join("in/good.csv"), "gtin,lot,pack_date\n12345678901244,LOT123,030102\n").unwrap();
+        fs::write(dir.join("in/bad row.CSV"), "gtin,lot,pack_date\n12345678901244,LOT 123,030102\n").unwrap();
+        fs::write(dir.join("in/no-columns.csv"), "sku\nA\n").unwrap();
+        fs::write(dir.join("in/notes.txt"), "not a batch").unwrap();
+        fs::write(dir.join("in/.hidden.csv"), "").unwrap();
+
+        // first sighting only records the files
+        assert!(folder.poll().unwrap().is_empty());
+        let processed = folder.poll().unwrap();
+        let names: Vec<_> = processed.iter().map(|p| p.moved_to.strip_prefix(&dir).unwrap().to_path_buf()).collect();
+        assert_eq!(
+            names,
+            [PathBuf::from("errors/bad row.CSV"), PathBuf::from("out/good.csv"), PathBuf::from("errors/no-columns.csv")]
+        );
+        assert!(processed[0].failed() && !processed[1].failed());
+
+        let output = fs::read_to_string(dir.join("out/good.csv")).unwrap();
+        assert_eq!(output.lines().nth(1), Some("12345678901244,LOT123,030102,6991,91,69,"));
+        assert!(fs::read_to_string(dir.join("errors/bad row.CSV.error.txt")).unwrap().starts_with("row 1: "));
+        assert_eq!(fs::read_to_string(dir.join("errors/no-columns.csv.error.txt")).unwrap(), "input has no gtin column\n");
+
+        let mut left: Vec<_> = fs::read_dir(dir.join("in")).unwrap().map(|e| e.unwrap().file_name()).collect();
+        left.sort();
+        assert_eq!(left, [".hidden.csv", "notes.txt"]);
+        assert_eq!(fs::read_dir(dir.join("out")).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_waits_for_writes() {
+        let dir = scratch("writes");
+        let mut folder = DropFolder::new(dir.join("in"), dir.join("out"), dir.join("errors"));
+        fs::write(dir.join("in/a.csv"), "gtin,lot,pack_date\n").unwrap();
+        assert!(folder.poll().unwrap().is_empty());
+        fs::write(dir.join("in/a.csv"), "gtin,lot,pack_date\n12345678901244,LOT 123,030102\n").unwrap();
+        assert!(folder.poll().unwrap().is_empty());
+        let processed = folder.poll().unwrap();
+        // row failures stay in the output without fail_on_error
+        assert_eq!(processed[0].moved_to, dir.join("out/a.csv"));
+        assert_eq!(processed[0].outcome.as_ref().unwrap().errors.len(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = scratch("run");
+        let mut folder = DropFolder::new(dir.join("in"), dir.join("out"), dir.join("errors"));
+        let (sender, processed) = mpsc::channel();
+        std::thread::spawn(move || {
+            folder.run(Duration::from_millis(50), |p| sender.send(p.moved_to.clone()).unwrap())
+        });
+        std::thread::sleep(Duration::from_millis(200));
+        fs::write(dir.join("in/a.csv"), "gtin,lot,pack_date\n12345678901244,LOT123,030102\n").unwrap();
+        assert_eq!(processed.recv_timeout(Duration::from_secs(10)), Ok(dir.join("out/a.csv")));
+        // the watcher thread may still be polling, it stops once the input directory is gone
+        let _ = fs::remove_dir_all(dir);
+    }
+}
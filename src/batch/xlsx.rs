@@ -0,0 +1,272 @@
+//! Excel workbook in, Excel workbook out
+//!
+//! The first worksheet needs `gtin`, `lot` and `pack_date` (or `date`) header cells, matched
+//! case insensitively, and may carry any other columns. Pack dates can be real Excel dates
+//! or YYMMDD and YYYY-MM-DD text. The output workbook has one `Results` sheet repeating
+//! every input column with `voice_code`, `major`, `minor` and `error` appended, rows that
+//! failed validation are filled red.
+//!
+//! Excel drops leading zeros from numbers, so a GTIN column typed as a number turns
+//! 00614141007349 into 614141007349, which hashes differently. Format GTIN columns as Text.
+
+use std::fmt;
+use std::io::{self, Cursor};
+
+use calamine::{Data, Reader, Xlsx};
+use chrono::NaiveDate;
+use rust_xlsxwriter::{Color, Format, Workbook};
+
+use crate::batch::{BatchError, BatchRecord, BatchSummary};
+
+/// Why a workbook could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XlsxError {
+    pub reason: String,
+}
+
+impl XlsxError {
+    fn new(reason: impl Into<String>) -> Self {
+        XlsxError { reason: reason.into() }
+    }
+}
+
+impl fmt::Display for XlsxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "xlsx: {}", self.reason)
+    }
+}
+
+impl std::error::Error for XlsxError {}
+
+impl From<XlsxError> for BatchError {
+    fn from(value: XlsxError) -> Self {
+        BatchError::Xlsx(value)
+    }
+}
+
+/// Read the first sheet of the workbook in `input`, write the results workbook to `output`
+///
+/// # Example
+/// ```
+/// use voicecode::batch::xlsx::{ process_xlsx, write_workbook };
+/// let mut input = Vec::new();
+/// write_workbook(&[
+///     vec!["gtin".to_string(), "lot".to_string(), "pack_date".to_string()],
+///     vec!["12345678901244".to_string(), "LOT123".to_string(), "030102".to_string()],
+/// ], &mut input).unwrap();
+///
+/// let mut output = Vec::new();
+/// let summary = process_xlsx(&input, &mut output).unwrap();
+/// assert_eq!(summary.succeeded(), 1);
+/// ```
+pub fn process_xlsx<W: io::Write>(input: &[u8], output: W) -> Result<BatchSummary, BatchError> {
+    let _span = span!("batch", format = "xlsx");
+    #[cfg(feature = "metrics")]
+    let _timer = crate::metrics::BatchTimer::start("xlsx");
+    let rows = read_first_sheet(input)?;
+    let mut rows = rows.into_iter();
+    let headers = rows.next().unwrap_or_default();
+    let column = |names: &[&'static str]| {
+        names
+            .iter()
+            .find_map(|name| headers.iter().position(|header| header.text.trim().eq_ignore_ascii_case(name)))
+            .ok_or(BatchError::MissingColumn(names[0]))
+    };
+    let gtin = column(&["gtin"])?;
+    let lot = column(&["lot"])?;
+    let pack_date = column(&["pack_date", "date"])?;
+
+    let mut out_rows = Vec::new();
+    let mut header_row: Vec<String> = headers.iter().map(|cell| cell.text.clone()).collect();
+    header_row.extend(["voice_code", "major", "minor", "error"].map(String::from));
+    out_rows.push(header_row);
+
+    let mut summary = BatchSummary::default();
+    let mut failed = Vec::new();
+    for (index, row) in rows.enumerate() {
+        // date cells as YYYY-MM-DD rather than the serial number behind them
+        let mut out_row: Vec<String> = row
+            .iter()
+            .map(|cell| cell.date.map_or_else(|| cell.text.clone(), |date| date.format("%Y-%m-%d").to_string()))
+            .collect();
+        let text = |i: usize| out_row.get(i).map_or("", |text| text.trim());
+        // YYMMDD typed as a number loses the leading zero of years 2000 to 2009
+        let date = match text(pack_date) {
+            date if date.len() == 5 && date.bytes().all(|b| b.is_ascii_digit()) => format!("0{}", date),
+            date => date.to_string(),
+        };
+        let record = BatchRecord::new(text(gtin), text(lot), &date);
+
+        out_row.resize(headers.len().max(out_row.len()), String::new());
+        match record.compute() {
+            Ok(code) => out_row.extend([code.voice_code, code.voice_code_major, code.voice_code_minor, String::new()]),
+            Err(error) => {
+                out_row.extend([String::new(), String::new(), String::new(), error.to_string()]);
+                summary.row_failed(index + 1, error);
+                failed.push(index + 1);
+            }
+        }
+        out_rows.push(out_row);
+        summary.row_read();
+    }
+
+    write_workbook_highlighting(&out_rows, &failed, output)?;
+    Ok(summary.finished())
+}
+
+/// One worksheet cell, `date` is set for numbers formatted as a date
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Cell {
+    text: String,
+    date: Option<NaiveDate>,
+}
+
+/// Non-empty rows of the first worksheet, cells by column
+fn read_first_sheet(input: &[u8]) -> Result<Vec<Vec<Cell>>, XlsxError> {
+    let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(input)).map_err(|error| XlsxError::new(error.to_string()))?;
+    let sheet = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| XlsxError::new("workbook has no sheets"))?
+        .map_err(|error| XlsxError::new(error.to_string()))?;
+    // the range starts at the first used cell, pad so cells keep their column
+    let (_, first_column) = sheet.start().unwrap_or_default();
+    let mut rows = Vec::new();
+    for row in sheet.rows() {
+        let mut cells = vec![Cell::default(); first_column as usize];
+        cells.extend(row.iter().map(read_cell));
+        if cells.iter().any(|cell| !cell.text.trim().is_empty()) {
+            rows.push(cells);
+        }
+    }
+    Ok(rows)
+}
+
+fn read_cell(data: &Data) -> Cell {
+    match data {
+        Data::DateTime(datetime) if datetime.is_datetime() => {
+            Cell { text: data.to_string(), date: datetime.as_datetime().map(|datetime| datetime.date()) }
+        }
+        // whole numbers without Excel's float formatting, GTINs and YYMMDD typed as numbers
+        Data::Float(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+            Cell { text: format!("{}", *number as i64), date: None }
+        }
+        _ => Cell { text: data.to_string(), date: None },
+    }
+}
+
+/// Write `rows` as a one sheet workbook, every cell as text so leading zeros survive
+pub fn write_workbook<W: io::Write>(rows: &[Vec<String>], output: W) -> io::Result<()> {
+    write_workbook_highlighting(rows, &[], output)
+}
+
+/// `write_workbook` with the first row bold and the rows at `highlighted` filled red
+fn write_workbook_highlighting<W: io::Write>(rows: &[Vec<String>], highlighted: &[usize], mut output: W) -> io::Result<()> {
+    let plain = Format::new();
+    let bold = Format::new().set_bold();
+    let failed = Format::new().set_background_color(Color::RGB(0xFFC7CE)).set_font_color(Color::RGB(0x9C0006));
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Results").map_err(io::Error::other)?;
+    for (index, row) in rows.iter().enumerate() {
+        let format = match index {
+            0 => &bold,
+            _ if highlighted.contains(&index) => &failed,
+            _ => &plain,
+        };
+        let index = u32::try_from(index).map_err(io::Error::other)?;
+        for (column, value) in row.iter().enumerate() {
+            let column = u16::try_from(column).map_err(io::Error::other)?;
+            sheet.write_string_with_format(index, column, value, format).map_err(io::Error::other)?;
+        }
+    }
+    output.write_all(&workbook.save_to_buffer().map_err(io::Error::other)?)?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::RowError;
+    use crate::error::VoiceCodeError;
+    use crate::gtin::GtinError;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rows = vec![row(&["sku", "GTIN", "Lot", "Date"]), row(&["A", "00614141007349", "32<&>", "2003-01-02"])];
+        let mut workbook = Vec::new();
+        write_workbook(&rows, &mut workbook).unwrap();
+        let read: Vec<Vec<String>> = read_first_sheet(&workbook)
+            .unwrap()
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.text).collect())
+            .collect();
+        assert_eq!(read, rows);
+    }
+
+    #[test]
+    fn test_process() {
+        let rows = vec![
+            row(&["gtin", "lot", "pack_date", "note"]),
+            row(&["61414100734933", "32abcd", "030102"]),
+            row(&["1", "32abcd", "030102", "typo"]),
+            row(&["61414100734933", "32abcd", "30102"]),
+        ];
+        let mut input = Vec::new();
+        write_workbook(&rows, &mut input).unwrap();
+        let mut output = Vec::new();
+        let summary = process_xlsx(&input, &mut output).unwrap();
+        assert_eq!(summary.rows, 3);
+        assert_eq!(summary.errors, [RowError { row: 2, error: VoiceCodeError::InvalidGtin { reason: GtinError::Length { len: 1 } } }]);
+
+        let read = read_first_sheet(&output).unwrap();
+        let headers: Vec<&str> = read[0].iter().map(|cell| cell.text.as_str()).collect();
+        assert_eq!(headers, ["gtin", "lot", "pack_date", "note", "voice_code", "major", "minor", "error"]);
+        assert_eq!(read[2][7].text, "GTIN must be 8, 12, 13 or 14 digits, got 1");
+        assert_eq!(read[3][4].text, "8079");
+
+        let mut input = Vec::new();
+        write_workbook(&[row(&["gtin", "lot"])], &mut input).unwrap();
+        assert!(matches!(process_xlsx(&input, &mut output), Err(BatchError::MissingColumn(_))));
+    }
+
+    #[test]
+    fn test_excel_cells() {
+        // what Excel writes for a GTIN and YYMMDD typed as numbers and real date cells
+        let date_format = Format::new().set_num_format("yyyy-mm-dd");
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_number(0, 1, 12345678901244.0).unwrap();
+        let date = rust_xlsxwriter::ExcelDateTime::from_ymd(2003, 1, 2).unwrap();
+        sheet.write_datetime_with_format(0, 2, &date, &date_format).unwrap();
+        sheet.write_number_with_format(0, 3, 37623.5, &date_format).unwrap();
+        sheet.write_number(0, 4, 30102.0).unwrap();
+        sheet.write_string(0, 5, "LOT123").unwrap();
+        sheet.write_number(0, 6, 1.5).unwrap();
+        let workbook = workbook.save_to_buffer().unwrap();
+
+        let cells = read_first_sheet(&workbook).unwrap().remove(0);
+        let date = NaiveDate::from_ymd_opt(2003, 1, 2);
+        assert_eq!(cells[0], Cell::default());
+        assert_eq!(cells[1], Cell { text: "12345678901244".to_string(), date: None });
+        assert_eq!(cells[2].date, date);
+        assert_eq!(cells[3].date, date);
+        assert_eq!(cells[4].text, "30102");
+        assert_eq!(cells[5].text, "LOT123");
+        assert_eq!(cells[6].text, "1.5");
+    }
+
+    #[test]
+    fn test_not_a_workbook() {
+        assert!(read_first_sheet(b"gtin,lot,pack_date\n").is_err());
+        assert!(read_first_sheet(&[]).is_err());
+        let mut workbook = Vec::new();
+        write_workbook(&[row(&["gtin"])], &mut workbook).unwrap();
+        workbook.truncate(workbook.len() - 30);
+        assert!(read_first_sheet(&workbook).is_err());
+    }
+}
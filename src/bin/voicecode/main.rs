@@ -0,0 +1,806 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use voicecode::batch::watch::DropFolder;
+use voicecode::batch::{BatchError, BatchSummary};
+use voicecode::conformance::ConformanceReport;
+use voicecode::create_crc_lut::{lut_source, LutLanguage};
+use voicecode::gs1::DateAi;
+use voicecode::label::epl::{self, EplOptions};
+use voicecode::label::zpl::{self, ZplOptions};
+use voicecode::print::RawSocketPrinter;
+use voicecode::scan::WedgeConfig;
+#[cfg(feature = "chrono")]
+use voicecode::series::VoiceCodeSeries;
+use voicecode::{Gtin, HashVoiceCode, Lot, NonAsciiPolicy, PackDate, VoiceCodeError};
+
+/// Voice code calculator for Produce Traceability Initiative labels
+#[derive(Parser)]
+#[command(name = "voicecode", version)]
+struct Cli {
+    /// Output format
+    #[arg(long, value_enum, global = true, default_value_t = Format::Human)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+    /// One JSON object per line, `batch` reads and writes JSON Lines instead of CSV
+    Jsonl,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Language {
+    Zpl,
+    Epl,
+}
+
+#[cfg(feature = "template")]
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TemplateLanguage {
+    Zpl,
+    Epl,
+    Svg,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DateAiArg {
+    /// AI(13)
+    Pack,
+    /// AI(15)
+    BestBefore,
+    /// AI(17)
+    Expiry,
+    /// AI(7007)
+    Harvest,
+}
+
+impl From<DateAiArg> for DateAi {
+    fn from(value: DateAiArg) -> Self {
+        match value {
+            DateAiArg::Pack => DateAi::PackDate,
+            DateAiArg::BestBefore => DateAi::BestBefore,
+            DateAiArg::Expiry => DateAi::Expiry,
+            DateAiArg::Harvest => DateAi::Harvest,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum NonAscii {
+    Reject,
+    /// UTF-16 code units, same as the JavaScript widget
+    Utf16,
+    /// One step per Unicode scalar value
+    Scalar,
+}
+
+impl From<NonAscii> for NonAsciiPolicy {
+    fn from(value: NonAscii) -> Self {
+        match value {
+            NonAscii::Reject => NonAsciiPolicy::Reject,
+            NonAscii::Utf16 => NonAsciiPolicy::JsCompatUtf16,
+            NonAscii::Scalar => NonAsciiPolicy::RawScalar,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compute the voice code for a GTIN, lot and pack date
+    Compute {
+        #[arg(long)]
+        gtin: String,
+        #[arg(long)]
+        lot: String,
+        /// Pack date as YYMMDD or YYYY-MM-DD
+        #[arg(long)]
+        date: String,
+    },
+    /// Validate a GTIN, lot and pack date without computing a code
+    Validate {
+        #[arg(long)]
+        gtin: Option<String>,
+        #[arg(long)]
+        lot: Option<String>,
+        /// Pack date as YYMMDD or YYYY-MM-DD
+        #[arg(long)]
+        date: Option<String>,
+        /// Also verify the GTIN check digit
+        #[arg(long)]
+        check_digit: bool,
+    },
+    /// Hash raw text exactly as given, no validation or padding
+    Hash {
+        text: String,
+        /// Characters outside ASCII, rejected by default
+        #[arg(long, value_enum, default_value_t = NonAscii::Reject)]
+        non_ascii: NonAscii,
+    },
+    /// Compute codes for a CSV with gtin, lot and pack_date columns, or JSON Lines with
+    /// `--format jsonl`, or an Excel workbook with `--xlsx`
+    Batch {
+        /// Input file, `-` for stdin
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// Output file, `-` for stdout
+        #[arg(default_value = "-")]
+        output: PathBuf,
+        /// Exit with failure when any row fails validation
+        #[arg(long)]
+        fail_on_error: bool,
+        /// Read an xlsx workbook and write a results workbook with failed rows highlighted
+        #[cfg(feature = "xlsx")]
+        #[arg(long)]
+        xlsx: bool,
+    },
+    /// Process CSV files, and xlsx workbooks with the `xlsx` feature, dropped into a directory
+    Watch {
+        /// Directory the files are dropped into
+        input: PathBuf,
+        /// Directory results are written to under the input file's name
+        #[arg(long)]
+        output: PathBuf,
+        /// Directory files that cannot be processed are moved to
+        #[arg(long)]
+        errors: PathBuf,
+        /// Seconds file events must be quiet before the folder is checked, a file is processed once it is unchanged for one interval
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Also move files with rows failing validation to the error directory
+        #[arg(long)]
+        fail_on_error: bool,
+    },
+    /// Read barcodes typed by a keyboard wedge scanner, one per line on stdin, and print
+    /// their voice codes
+    Scan {
+        /// Text the scanner types before each barcode
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Text the scanner types after each barcode
+        #[arg(long, default_value = "")]
+        suffix: String,
+        /// Text the scanner types in place of GS (ASCII 29), such as `~`
+        #[arg(long)]
+        gs: Option<String>,
+        /// Voice code every scanned case should carry, each scan is reported as a match or not
+        #[arg(long)]
+        expect: Option<String>,
+    },
+    /// QA station: scan a case's barcode, key the voice code printed on it and see whether
+    /// they match, an empty scan ends the session
+    #[cfg(feature = "tui")]
+    Station {
+        /// CSV log of every check, appended to when it exists
+        #[arg(long)]
+        log: PathBuf,
+    },
+    /// Run the bundled known answer vectors, and any given CSV files of vectors, and report
+    /// pass or fail
+    Conformance {
+        /// CSV files with gtin, lot, pack_date, voice_code and optional source columns
+        vectors: Vec<PathBuf>,
+        /// Run only the given files
+        #[arg(long)]
+        no_bundled: bool,
+    },
+    /// Report how evenly the voice codes of one GTIN spread across 0000-9999 over a range of
+    /// pack dates and LOTs
+    #[cfg(feature = "chrono")]
+    Distribution {
+        #[arg(long)]
+        gtin: String,
+        /// First pack date as YYMMDD or YYYY-MM-DD
+        #[arg(long)]
+        from: String,
+        /// Last pack date, the first date alone when left out
+        #[arg(long)]
+        to: Option<String>,
+        /// LOT to combine with every date, repeat for more
+        #[arg(long, required = true)]
+        lot: Vec<String>,
+        /// Use each `--lot` as a prefix numbered from 1 to this, zero padded
+        #[arg(long)]
+        lot_count: Option<u32>,
+    },
+    /// CRC look up table tools
+    Lut {
+        #[command(subcommand)]
+        command: LutCommand,
+    },
+    /// Print a label to a network printer on port 9100
+    Print {
+        /// Printer host or host:port
+        #[arg(long)]
+        printer: String,
+        #[arg(long)]
+        gtin: String,
+        #[arg(long)]
+        lot: String,
+        /// Pack date as YYMMDD or YYYY-MM-DD
+        #[arg(long)]
+        date: String,
+        /// Connect, read and write timeout in seconds
+        #[arg(long, default_value_t = 5)]
+        timeout: u64,
+        /// Printer language, EPL2 for older Eltron printers
+        #[arg(long, value_enum, default_value_t = Language::Zpl)]
+        language: Language,
+        /// AI the barcode carries the date under, the voice code is the same for all of them
+        #[arg(long, value_enum, default_value_t = DateAiArg::Pack)]
+        date_ai: DateAiArg,
+    },
+    /// Render a label from a TOML or JSON template to stdout
+    #[cfg(feature = "template")]
+    Label {
+        /// Template file, read as JSON when it ends in `.json` and TOML otherwise
+        #[arg(long)]
+        template: PathBuf,
+        #[arg(long)]
+        gtin: String,
+        #[arg(long)]
+        lot: String,
+        /// Pack date as YYMMDD or YYYY-MM-DD
+        #[arg(long)]
+        date: String,
+        #[arg(long, value_enum, default_value_t = TemplateLanguage::Zpl)]
+        language: TemplateLanguage,
+    },
+    /// Serve the HTTP API, see the library `serve` module for the routes
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0:8080")]
+        listen: String,
+    },
+    /// Print the OpenAPI 3 document for the HTTP API
+    #[cfg(feature = "serve")]
+    Openapi,
+    /// Serve the gRPC API, see `proto/voicecode/v1/voicecode.proto`
+    #[cfg(feature = "grpc")]
+    Grpc {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0:50051")]
+        listen: std::net::SocketAddr,
+    },
+}
+
+#[derive(Subcommand)]
+enum LutCommand {
+    /// Print the 256 entry table as source code
+    Export {
+        #[arg(long, value_enum)]
+        lang: LutLang,
+        /// Reflected polynomial, decimal or 0x hex
+        #[arg(long, default_value = "40961", value_parser = parse_polynomial)]
+        poly: u16,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LutLang {
+    C,
+    Js,
+    Python,
+    Rust,
+}
+
+impl From<LutLang> for LutLanguage {
+    fn from(value: LutLang) -> Self {
+        match value {
+            LutLang::C => LutLanguage::C,
+            LutLang::Js => LutLanguage::JavaScript,
+            LutLang::Python => LutLanguage::Python,
+            LutLang::Rust => LutLanguage::Rust,
+        }
+    }
+}
+
+fn parse_polynomial(value: &str) -> Result<u16, String> {
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|e| format!("not a 16 bit polynomial: {}", e))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Compute { gtin, lot, date } => compute(cli.format, &gtin, &lot, &date),
+        Command::Validate { gtin, lot, date, check_digit } => validate(cli.format, gtin, lot, date, check_digit),
+        Command::Hash { text, non_ascii } => hash(cli.format, &text, non_ascii),
+        Command::Batch {
+            input,
+            output,
+            fail_on_error,
+            #[cfg(feature = "xlsx")]
+            xlsx,
+        } => {
+            #[cfg(feature = "xlsx")]
+            if xlsx {
+                return batch(cli.format, fail_on_error, || batch_xlsx(&input, &output));
+            }
+            batch(cli.format, fail_on_error, || batch_stream(cli.format, &input, &output))
+        }
+        Command::Watch { input, output, errors, interval, fail_on_error } => {
+            watch(cli.format, DropFolder::new(input, output, errors).fail_on_error(fail_on_error), interval)
+        }
+        Command::Scan { prefix, suffix, gs, expect } => {
+            scan(cli.format, &WedgeConfig { prefix, suffix, gs_substitute: gs }, expect.as_deref())
+        }
+        #[cfg(feature = "tui")]
+        Command::Station { log } => station(cli.format, &log),
+        Command::Lut { command: LutCommand::Export { lang, poly } } => {
+            print!("{}", lut_source(poly, lang.into()));
+            ExitCode::SUCCESS
+        }
+        Command::Conformance { vectors, no_bundled } => conformance(cli.format, &vectors, no_bundled),
+        #[cfg(feature = "chrono")]
+        Command::Distribution { gtin, from, to, lot, lot_count } => {
+            distribution(cli.format, &gtin, &from, to.as_deref(), &lot, lot_count)
+        }
+        Command::Print { printer, gtin, lot, date, timeout, language, date_ai } => {
+            print(cli.format, &printer, &gtin, &lot, &date, timeout, language, date_ai.into())
+        }
+        #[cfg(feature = "template")]
+        Command::Label { template, gtin, lot, date, language } => label(cli.format, &template, &gtin, &lot, &date, language),
+        #[cfg(feature = "serve")]
+        Command::Serve { listen } => serve(cli.format, &listen),
+        #[cfg(feature = "serve")]
+        Command::Openapi => {
+            println!("{}", voicecode::serve::openapi().to_pretty_json().expect("OpenAPI document serializes"));
+            ExitCode::SUCCESS
+        }
+        #[cfg(feature = "grpc")]
+        Command::Grpc { listen } => grpc(cli.format, listen),
+    }
+}
+
+fn compute(format: Format, gtin: &str, lot: &str, date: &str) -> ExitCode {
+    let result = date
+        .parse::<PackDate>()
+        .and_then(|pack_date| HashVoiceCode::builder().gtin(gtin).lot(lot).pack_date(pack_date).build());
+
+    match (result, format) {
+        (Ok(voice_code), Format::Human) => {
+            println!("GTIN:       {}", voice_code.gtin);
+            println!("Lot:        {}", voice_code.lot);
+            println!("Pack date:  {}", voice_code.pack_date());
+            println!("Voice code: {} ({} {})", voice_code.voice_code, voice_code.voice_code_minor, voice_code.voice_code_major);
+            ExitCode::SUCCESS
+        }
+        (Ok(voice_code), Format::Json | Format::Jsonl) => {
+            println!("{}", serde_json::to_string(&voice_code).expect("voice code serializes"));
+            ExitCode::SUCCESS
+        }
+        (Err(e), format) => report_error(format, &e),
+    }
+}
+
+fn validate(format: Format, gtin: Option<String>, lot: Option<String>, date: Option<String>, check_digit: bool) -> ExitCode {
+    let mut results: Vec<(&str, Result<(), VoiceCodeError>)> = Vec::new();
+    if let Some(gtin) = gtin {
+        let parsed = if check_digit { Gtin::parse(&gtin) } else { Gtin::parse_unchecked(&gtin) };
+        results.push(("gtin", parsed.map(|_| ())));
+    }
+    if let Some(lot) = lot {
+        results.push(("lot", Lot::parse(&lot).map(|_| ())));
+    }
+    if let Some(date) = date {
+        results.push(("pack_date", date.parse::<PackDate>().map(|_| ())));
+    }
+
+    match format {
+        Format::Human => {
+            for (field, result) in &results {
+                match result {
+                    Ok(()) => println!("{}: ok", field),
+                    Err(e) => println!("{}: {}", field, e),
+                }
+            }
+        }
+        Format::Json | Format::Jsonl => {
+            let report: serde_json::Map<String, serde_json::Value> = results
+                .iter()
+                .map(|(field, result)| {
+                    let value = match result {
+                        Ok(()) => json!({ "valid": true }),
+                        Err(e) => json!({ "valid": false, "error": e.to_string() }),
+                    };
+                    (field.to_string(), value)
+                })
+                .collect();
+            println!("{}", serde_json::Value::Object(report));
+        }
+    }
+
+    if results.iter().all(|(_, result)| result.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn hash(format: Format, text: &str, non_ascii: NonAscii) -> ExitCode {
+    let voice_code = match HashVoiceCode::generate_voice_code_hash_with(text, non_ascii.into()) {
+        Ok(voice_code) => voice_code,
+        Err(e) => return report_error(format, &e),
+    };
+    match format {
+        Format::Human => println!("{}", voice_code),
+        Format::Json | Format::Jsonl => println!("{}", json!({ "hash_text": text, "voice_code": voice_code })),
+    }
+    ExitCode::SUCCESS
+}
+
+fn open_input(input: &Path) -> io::Result<Box<dyn BufRead>> {
+    Ok(if input == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(input)?))
+    })
+}
+
+fn create_output(output: &Path) -> io::Result<Box<dyn Write>> {
+    Ok(if output == Path::new("-") {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(BufWriter::new(File::create(output)?))
+    })
+}
+
+fn batch_stream(format: Format, input: &Path, output: &Path) -> Result<BatchSummary, BatchError> {
+    let reader = open_input(input)?;
+    let writer = create_output(output)?;
+    match format {
+        Format::Jsonl => voicecode::batch::jsonl::process_jsonl(reader, writer),
+        Format::Human | Format::Json => voicecode::batch::csv::process_csv(reader, writer),
+    }
+}
+
+#[cfg(feature = "xlsx")]
+fn batch_xlsx(input: &Path, output: &Path) -> Result<BatchSummary, BatchError> {
+    let mut workbook = Vec::new();
+    io::copy(&mut open_input(input)?, &mut workbook)?;
+    voicecode::batch::xlsx::process_xlsx(&workbook, create_output(output)?)
+}
+
+fn watch(format: Format, mut folder: DropFolder, interval: u64) -> ExitCode {
+    let result = folder.run(Duration::from_secs(interval), |processed| {
+        let (input, moved_to) = (processed.input.display().to_string(), processed.moved_to.display().to_string());
+        match (format, &processed.outcome) {
+            (Format::Human, Ok(summary)) => {
+                let failed = summary.rows - summary.succeeded();
+                println!("{} -> {}: {} rows, {} failed", input, moved_to, summary.rows, failed)
+            }
+            (Format::Human, Err(e)) => println!("{} -> {}: {}", input, moved_to, e),
+            (Format::Json | Format::Jsonl, Ok(summary)) => {
+                let report = json!({ "input": input, "moved_to": moved_to, "rows": summary.rows, "succeeded": summary.succeeded() });
+                println!("{}", report)
+            }
+            (Format::Json | Format::Jsonl, Err(e)) => {
+                println!("{}", json!({ "input": input, "moved_to": moved_to, "error": e.to_string() }))
+            }
+        }
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(format, &e),
+    }
+}
+
+fn scan(format: Format, wedge: &WedgeConfig, expect: Option<&str>) -> ExitCode {
+    let expect: Option<String> = expect.map(|code| code.chars().filter(|ch| !ch.is_whitespace()).collect());
+    let mut all_ok = true;
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return report_error(format, &e),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let voice_code = match wedge.parse(&line).and_then(|scan| scan.voice_code()) {
+            Ok(voice_code) => voice_code,
+            Err(e) => {
+                all_ok = false;
+                report_error(format, &e);
+                continue;
+            }
+        };
+        let matched = expect.as_ref().map(|expect| *expect == voice_code.voice_code);
+        all_ok &= matched != Some(false);
+        match (format, matched) {
+            (Format::Human, None) => {
+                println!("{}  {} {} {}", voice_code.voice_code, voice_code.gtin, voice_code.lot, voice_code.pack_date())
+            }
+            (Format::Human, Some(true)) => println!("MATCH {}", voice_code.voice_code),
+            (Format::Human, Some(false)) => {
+                println!("MISMATCH {}, expected {}", voice_code.voice_code, expect.as_deref().unwrap_or_default())
+            }
+            (Format::Json | Format::Jsonl, _) => {
+                let mut value = serde_json::to_value(&voice_code).expect("voice code serializes");
+                if let Some(matched) = matched {
+                    value["match"] = matched.into();
+                }
+                println!("{}", value);
+            }
+        }
+    }
+    if all_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(feature = "tui")]
+fn station(format: Format, log: &Path) -> ExitCode {
+    let run = || -> io::Result<()> {
+        let append = std::fs::metadata(log).is_ok_and(|metadata| metadata.len() > 0);
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(log)?;
+        let mut screen = voicecode::station::Screen::new(voicecode::station::Station::new(file, append)?);
+        screen.run()?;
+        println!("{}", screen.station().tally());
+        Ok(())
+    };
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(format, &e),
+    }
+}
+
+fn batch(format: Format, fail_on_error: bool, run: impl FnOnce() -> Result<BatchSummary, BatchError>) -> ExitCode {
+    let summary = match run() {
+        Ok(summary) => summary,
+        Err(e) => return report_error(format, &e),
+    };
+
+    match format {
+        Format::Human => {
+            for error in &summary.errors {
+                eprintln!("{}", error);
+            }
+            eprintln!("{} rows, {} ok, {} failed", summary.rows, summary.succeeded(), summary.errors.len());
+        }
+        Format::Json => {
+            let errors: Vec<_> = summary
+                .errors
+                .iter()
+                .map(|error| json!({ "row": error.row, "error": error.error.to_string() }))
+                .collect();
+            println!("{}", json!({ "rows": summary.rows, "succeeded": summary.succeeded(), "errors": errors }));
+        }
+        // Errors are already in the output stream
+        Format::Jsonl => {}
+    }
+
+    if fail_on_error && summary.succeeded() != summary.rows {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn conformance(format: Format, files: &[PathBuf], no_bundled: bool) -> ExitCode {
+    let mut vectors = if no_bundled { Vec::new() } else { voicecode::conformance::bundled() };
+    for file in files {
+        match File::open(file).map_err(BatchError::from).and_then(voicecode::conformance::load_csv) {
+            Ok(loaded) => vectors.extend(loaded),
+            Err(e) => return report_error(format, &format!("{}: {}", file.display(), e)),
+        }
+    }
+    let report = ConformanceReport::run(&vectors);
+
+    match format {
+        Format::Human => println!("{}", report),
+        Format::Json | Format::Jsonl => {
+            let results: Vec<_> = report
+                .results
+                .iter()
+                .map(|result| {
+                    let vector = &result.vector;
+                    let (actual, error) = match &result.actual {
+                        Ok(code) => (code.clone(), None),
+                        Err(e) => (voicecode::conformance::INVALID.to_string(), Some(e.to_string())),
+                    };
+                    json!({
+                        "gtin": vector.gtin,
+                        "lot": vector.lot,
+                        "pack_date": vector.pack_date,
+                        "expected": vector.voice_code,
+                        "actual": actual,
+                        "error": error,
+                        "source": vector.source,
+                        "passed": result.passed(),
+                    })
+                })
+                .collect();
+            let failed = report.results.len() - report.passed();
+            println!("{}", json!({ "vectors": report.results.len(), "passed": report.passed(), "failed": failed, "results": results }));
+        }
+    }
+
+    if report.is_pass() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn distribution(format: Format, gtin: &str, from: &str, to: Option<&str>, lots: &[String], lot_count: Option<u32>) -> ExitCode {
+    let parse_date = |date: &str| date.parse::<PackDate>().map(|pack_date| pack_date.date());
+    let dates = match (parse_date(from), parse_date(to.unwrap_or(from))) {
+        (Ok(Some(start)), Ok(Some(end))) if start <= end => start..=end,
+        (Err(e), _) | (_, Err(e)) => return report_error(format, &e),
+        _ => return report_error(format, &"--from and --to must be calendar dates with --from first"),
+    };
+    let gtin = match Gtin::parse_unchecked(gtin) {
+        Ok(gtin) => gtin,
+        Err(e) => return report_error(format, &e),
+    };
+    let lots: Vec<String> = match lot_count {
+        Some(count) => {
+            let width = count.to_string().len();
+            lots.iter().flat_map(|prefix| (1..=count).map(move |n| format!("{}{:0width$}", prefix, n, width = width))).collect()
+        }
+        None => lots.to_vec(),
+    };
+    let lots = match lots.iter().map(|lot| Lot::parse(lot)).collect::<Result<Vec<_>, _>>() {
+        Ok(lots) => lots,
+        Err(e) => return report_error(format, &e),
+    };
+
+    let codes = lots.into_iter().flat_map(|lot| VoiceCodeSeries::new(gtin.clone(), lot, dates.clone()));
+    let histogram = voicecode::analysis::distribution(codes);
+    match format {
+        Format::Human => println!("{}", histogram),
+        Format::Json | Format::Jsonl => {
+            let most_common = histogram.most_common().map(|(code, count)| json!({ "voice_code": format!("{:04}", code), "count": count }));
+            println!(
+                "{}",
+                json!({
+                    "codes": histogram.total(),
+                    "distinct": histogram.distinct(),
+                    "expected_distinct": histogram.expected_distinct(),
+                    "collision_pairs": histogram.collision_pairs(),
+                    "expected_collision_pairs": histogram.expected_collision_pairs(),
+                    "most_common": most_common,
+                    "chi_squared": histogram.chi_squared(),
+                    "chi_squared_z": histogram.chi_squared_z(),
+                    "buckets": histogram.buckets(100),
+                })
+            );
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[allow(clippy::too_many_arguments)]
+fn print(
+    format: Format,
+    printer: &str,
+    gtin: &str,
+    lot: &str,
+    date: &str,
+    timeout: u64,
+    language: Language,
+    date_ai: DateAi,
+) -> ExitCode {
+    let voice_code = match date
+        .parse::<PackDate>()
+        .and_then(|pack_date| HashVoiceCode::builder().gtin(gtin).lot(lot).pack_date(pack_date).build())
+    {
+        Ok(voice_code) => voice_code,
+        Err(e) => return report_error(format, &e),
+    };
+
+    let job = match language {
+        Language::Zpl => zpl::render(&voice_code, &ZplOptions { date_ai, ..ZplOptions::default() }),
+        Language::Epl => epl::render(&voice_code, &EplOptions { date_ai, ..EplOptions::default() }),
+    };
+    let mut printer = RawSocketPrinter::new(printer).timeout(Duration::from_secs(timeout));
+    if let Err(e) = printer.send(job.as_bytes()) {
+        return report_error(format, &e);
+    }
+
+    match format {
+        Format::Human => println!("Printed voice code {} to {}", voice_code.voice_code, printer.address()),
+        Format::Json | Format::Jsonl => println!("{}", json!({ "printer": printer.address(), "voice_code": voice_code.voice_code })),
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(feature = "template")]
+fn label(format: Format, template: &Path, gtin: &str, lot: &str, date: &str, language: TemplateLanguage) -> ExitCode {
+    use voicecode::label::template::Template;
+
+    let source = match std::fs::read_to_string(template) {
+        Ok(source) => source,
+        Err(e) => return report_error(format, &e),
+    };
+    let template = if template.extension().is_some_and(|extension| extension == "json") {
+        Template::from_json(&source)
+    } else {
+        Template::from_toml(&source)
+    };
+    let template = match template {
+        Ok(template) => template,
+        Err(e) => return report_error(format, &e),
+    };
+    let voice_code = match date
+        .parse::<PackDate>()
+        .and_then(|pack_date| HashVoiceCode::builder().gtin(gtin).lot(lot).pack_date(pack_date).build())
+    {
+        Ok(voice_code) => voice_code,
+        Err(e) => return report_error(format, &e),
+    };
+    let label = match language {
+        TemplateLanguage::Zpl => template.render_zpl(&voice_code),
+        TemplateLanguage::Epl => template.render_epl(&voice_code),
+        TemplateLanguage::Svg => template.render_svg(&voice_code),
+    };
+    match label {
+        Ok(label) => {
+            print!("{}", label);
+            ExitCode::SUCCESS
+        }
+        Err(e) => report_error(format, &e),
+    }
+}
+
+#[cfg(feature = "serve")]
+fn serve(format: Format, listen: &str) -> ExitCode {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => return report_error(format, &e),
+    };
+    let result = runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(listen).await?;
+        if format == Format::Human {
+            eprintln!("Listening on {}", listener.local_addr()?);
+        }
+        voicecode::serve::serve(listener).await
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(format, &e),
+    }
+}
+
+#[cfg(feature = "grpc")]
+fn grpc(format: Format, listen: std::net::SocketAddr) -> ExitCode {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => return report_error(format, &e),
+    };
+    if format == Format::Human {
+        eprintln!("Listening on {}", listen);
+    }
+    let result = runtime.block_on(tonic::transport::Server::builder().add_service(voicecode::grpc::server()).serve(listen));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(format, &e),
+    }
+}
+
+fn report_error(format: Format, error: &impl Display) -> ExitCode {
+    match format {
+        Format::Human => eprintln!("error: {}", error),
+        Format::Json | Format::Jsonl => println!("{}", json!({ "error": error.to_string() })),
+    }
+    ExitCode::FAILURE
+}
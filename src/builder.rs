@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+#[cfg(feature = "chrono")]
+use chrono::NaiveDate;
+
+use crate::algorithm::VoiceCodeAlgorithm;
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::lot::{Lot, LotCase};
+use crate::pack_date::{DatePadding, PackDate, YearWindow};
+use crate::voicecode::HashVoiceCode;
+
+/// Pack date accepted by `HashVoiceCodeBuilder::pack_date`
+///
+/// Built from a `NaiveDate`, a `PackDate` or a `(yy, mm, dd)` tuple of strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PackDateInput {
+    #[cfg(feature = "chrono")]
+    Date(NaiveDate),
+    PackDate(PackDate),
+    Parts { yy: String, mm: String, dd: String },
+}
+
+impl PackDateInput {
+    /// Validated pack date, YY string parts are read with `window`
+    pub(crate) fn resolve(&self, window: YearWindow) -> Result<PackDate, VoiceCodeError> {
+        match self {
+            #[cfg(feature = "chrono")]
+            PackDateInput::Date(date) => Ok(PackDate::from_naive(*date)),
+            PackDateInput::PackDate(pack_date) => Ok(pack_date.clone()),
+            PackDateInput::Parts { yy, mm, dd } => PackDate::from_parts_with_window(yy, mm, dd, window),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveDate> for PackDateInput {
+    fn from(value: NaiveDate) -> Self {
+        PackDateInput::Date(value)
+    }
+}
+
+impl From<PackDate> for PackDateInput {
+    fn from(value: PackDate) -> Self {
+        PackDateInput::PackDate(value)
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Date> for PackDateInput {
+    fn from(value: time::Date) -> Self {
+        PackDateInput::PackDate(PackDate::from_time_date(value))
+    }
+}
+
+impl From<(&str, &str, &str)> for PackDateInput {
+    fn from((yy, mm, dd): (&str, &str, &str)) -> Self {
+        PackDateInput::Parts { yy: yy.to_string(), mm: mm.to_string(), dd: dd.to_string() }
+    }
+}
+
+/// Named-field alternative to the positional `HashVoiceCode::new`
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, LotCase };
+/// let voice_code = HashVoiceCode::builder()
+///     .gtin("12345678901244")
+///     .lot("lot123")
+///     .lot_case(LotCase::Upper)
+///     .pack_date(("03", "01", "02"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(voice_code.voice_code, "6991");
+///
+/// # #[cfg(feature = "chrono")] {
+/// let voice_code = HashVoiceCode::builder()
+///     .gtin("12345678901244")
+///     .lot("LOT123")
+///     .pack_date(voicecode::NaiveDate::from_ymd_opt(2003, 1, 2).unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(voice_code.voice_code, "6991");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HashVoiceCodeBuilder {
+    gtin: Option<String>,
+    lot: Option<String>,
+    pack_date: Option<PackDateInput>,
+    strict_dates: bool,
+    lot_case: LotCase,
+    year_window: YearWindow,
+    date_padding: DatePadding,
+    algorithm: Option<Arc<dyn VoiceCodeAlgorithm>>,
+}
+
+impl HashVoiceCodeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// GTIN, checked for length and digits only
+    pub fn gtin(mut self, gtin: impl AsRef<str>) -> Self {
+        self.gtin = Some(gtin.as_ref().to_string());
+        self
+    }
+
+    /// LOT code
+    pub fn lot(mut self, lot: impl AsRef<str>) -> Self {
+        self.lot = Some(lot.as_ref().to_string());
+        self
+    }
+
+    /// Pack date from a `NaiveDate`, a `PackDate` or `(yy, mm, dd)` strings
+    pub fn pack_date(mut self, pack_date: impl Into<PackDateInput>) -> Self {
+        self.pack_date = Some(pack_date.into());
+        self
+    }
+
+    /// Reject date parts that are not a calendar date, such as month 13 or Feb 30
+    pub fn strict_dates(mut self, strict: bool) -> Self {
+        self.strict_dates = strict;
+        self
+    }
+
+    /// Case normalization applied to the LOT before validating and hashing, recorded in
+    /// `HashVoiceCode::lot_case`
+    pub fn lot_case(mut self, lot_case: LotCase) -> Self {
+        self.lot_case = lot_case;
+        self
+    }
+
+    /// Shorthand for `lot_case(LotCase::Upper)` when true and `LotCase::Preserve` when false
+    pub fn normalize_lot_case(self, normalize: bool) -> Self {
+        self.lot_case(if normalize { LotCase::Upper } else { LotCase::Preserve })
+    }
+
+    /// Century window used to interpret YY string parts
+    pub fn year_window(mut self, window: YearWindow) -> Self {
+        self.year_window = window;
+        self
+    }
+
+    /// How the date parts are written into the hashed text, `DatePadding::ZeroPad` by default
+    pub fn date_padding(mut self, padding: DatePadding) -> Self {
+        self.date_padding = padding;
+        self
+    }
+
+    /// Algorithm computing the code, `PtiV1` unless set
+    ///
+    /// The date passed to it is already padded per `date_padding`.
+    pub fn algorithm(mut self, algorithm: impl VoiceCodeAlgorithm + 'static) -> Self {
+        self.algorithm = Some(Arc::new(algorithm));
+        self
+    }
+
+    /// Validate the inputs and compute the voice code
+    pub fn build(&self) -> Result<HashVoiceCode, VoiceCodeError> {
+        let result = self.try_build();
+        if let Err(error) = &result {
+            event!(debug, "voice code validation failed", error = error);
+        }
+        #[cfg(feature = "metrics")]
+        crate::metrics::computed(&result);
+        result
+    }
+
+    fn try_build(&self) -> Result<HashVoiceCode, VoiceCodeError> {
+        let pack_date = match &self.pack_date {
+            Some(input) => input.resolve(self.year_window)?,
+            None => return Err(VoiceCodeError::MissingField { field: "pack_date" }),
+        };
+        if self.strict_dates && pack_date.calendar_date().is_none() {
+            return Err(VoiceCodeError::InvalidCalendarDate { yymmdd: pack_date.yymmdd() });
+        }
+
+        let lot = match &self.lot {
+            Some(lot) => Lot::parse(&self.lot_case.apply(lot))?,
+            None => return Err(VoiceCodeError::MissingField { field: "lot" }),
+        };
+
+        let gtin = match &self.gtin {
+            Some(gtin) => Gtin::parse_unchecked(gtin)?,
+            None => return Err(VoiceCodeError::MissingField { field: "gtin" }),
+        };
+
+        let voice_code = match &self.algorithm {
+            Some(algorithm) => HashVoiceCode::from_parts_with_algorithm(gtin, lot, pack_date, self.date_padding, algorithm.clone()),
+            None => HashVoiceCode::from_parts_with_padding(gtin, lot, pack_date, self.date_padding),
+        };
+        Ok(voice_code.recorded_lot_case(self.lot_case))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_new() {
+        let expected = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "32abcd", "03", "01", "02").unwrap();
+        let built = HashVoiceCodeBuilder::new()
+            .gtin("61414100734933")
+            .lot("32abcd")
+            .pack_date(("03", "01", "02"))
+            .build()
+            .unwrap();
+        assert_eq!(built.voice_code, expected.voice_code);
+        assert_eq!(built.voice_code, "8079");
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").lot("32abcd");
+        assert_eq!(builder.build().unwrap_err(), VoiceCodeError::MissingField { field: "pack_date" });
+
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").pack_date(("03", "01", "02"));
+        assert_eq!(builder.build().unwrap_err(), VoiceCodeError::MissingField { field: "lot" });
+    }
+
+    #[test]
+    fn test_strict_dates() {
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").lot("32abcd").pack_date(("03", "02", "30"));
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            builder.strict_dates(true).build().unwrap_err(),
+            VoiceCodeError::InvalidCalendarDate { yymmdd: "030230".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_date_padding() {
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").lot("32abcd").pack_date(("3", "1", "2"));
+        assert_eq!(builder.clone().build().unwrap().hash_text, "6141410073493332abcd030102");
+        let raw = builder.date_padding(DatePadding::Raw).build().unwrap();
+        assert_eq!(raw.hash_text, "6141410073493332abcd312");
+    }
+
+    #[test]
+    fn test_normalize_lot_case() {
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").lot("32abcd").pack_date(("03", "01", "02"));
+        let preserved = builder.clone().build().unwrap();
+        let upper = builder.normalize_lot_case(true).build().unwrap();
+        assert_eq!(preserved.lot, "32abcd");
+        assert_eq!(upper.lot, "32ABCD");
+        assert_ne!(preserved.voice_code, upper.voice_code);
+    }
+
+    #[test]
+    fn test_lot_case_recorded() {
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").lot("32AbCd").pack_date(("03", "01", "02"));
+        let lower = builder.clone().lot_case(LotCase::Lower).build().unwrap();
+        assert_eq!(lower.lot, "32abcd");
+        assert_eq!(lower.lot_case(), LotCase::Lower);
+        assert_eq!(lower.voice_code, "8079");
+        assert_eq!(lower.with_date(PackDate::from_parts("03", "01", "03").unwrap()).lot_case(), LotCase::Lower);
+        assert_eq!(lower.with_lot(Lot::parse("32abce").unwrap()).lot_case(), LotCase::Preserve);
+
+        assert_eq!(builder.clone().build().unwrap().lot_case(), LotCase::Preserve);
+        assert_eq!(builder.normalize_lot_case(true).build().unwrap().lot_case(), LotCase::Upper);
+    }
+
+    #[test]
+    fn test_algorithm() {
+        use crate::algorithm::PtiV1;
+        use crate::calculator::VoiceCodeCalculator;
+
+        let builder = HashVoiceCodeBuilder::new().gtin("61414100734933").lot("32abcd").pack_date(("3", "1", "2"));
+        for padding in [DatePadding::Raw, DatePadding::ZeroPad] {
+            let builder = builder.clone().date_padding(padding);
+            assert_eq!(builder.clone().algorithm(PtiV1).build().unwrap(), builder.build().unwrap());
+        }
+
+        let kermit = builder.date_padding(DatePadding::ZeroPad).algorithm(VoiceCodeCalculator::new(0x8408)).build().unwrap();
+        assert_eq!(kermit.hash_text, "6141410073493332abcd030102");
+        assert_eq!(kermit.voice_code, format!("{:04}", VoiceCodeCalculator::new(0x8408).hash_u16(kermit.hash_text.as_bytes())));
+
+        // rebuilt codes keep the algorithm
+        let relot = kermit.with_lot(Lot::parse("32abce").unwrap());
+        assert_eq!(relot.hash_text, "6141410073493332abce030102");
+        assert_eq!(relot.voice_code_u16(), VoiceCodeCalculator::new(0x8408).hash_u16(relot.hash_text.as_bytes()));
+        assert_ne!(relot.voice_code, HashVoiceCode::generate_voice_code_hash(&relot.hash_text));
+    }
+}
@@ -0,0 +1,184 @@
+//! Memoized voice codes for servers that see the same GTIN, LOT and pack date over and over
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::error::VoiceCodeError;
+use crate::voicecode::HashVoiceCode;
+
+/// Separates the inputs in a cache key, no valid GTIN, LOT or date part contains it
+const KEY_SEPARATOR: char = '\u{1f}';
+
+/// Hit, miss and size counters of a `CachedVoiceCode`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries currently cached
+    pub len: usize,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache, 0 before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 { 0.0 } else { self.hits as f64 / total as f64 }
+    }
+}
+
+/// `HashVoiceCode::new` behind a bounded least recently used cache, safe to share between threads
+///
+/// Inputs are cached as given, "3" and "03" are different entries like they are different
+/// hashes. Errors are returned without being cached.
+///
+/// # Example
+/// ```
+/// use voicecode::cache::CachedVoiceCode;
+/// let cache = CachedVoiceCode::new(10_000);
+/// assert_eq!(cache.get("00614141007349", "LOT123", "03", "01", "02").unwrap().voice_code, "6307");
+/// assert_eq!(cache.get("00614141007349", "LOT123", "03", "01", "02").unwrap().voice_code, "6307");
+/// let stats = cache.stats();
+/// assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 1));
+/// ```
+pub struct CachedVoiceCode {
+    capacity: usize,
+    lru: Mutex<Lru>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Entries with the tick they were last used at, `order` maps ticks back to keys so the
+/// oldest is the first entry
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, (u64, HashVoiceCode)>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn get(&mut self, key: &str) -> Option<HashVoiceCode> {
+        self.tick += 1;
+        let (used, voice_code) = self.entries.get_mut(key)?;
+        let key = self.order.remove(used).expect("every entry is ordered");
+        *used = self.tick;
+        let voice_code = voice_code.clone();
+        self.order.insert(self.tick, key);
+        Some(voice_code)
+    }
+
+    fn insert(&mut self, key: String, voice_code: HashVoiceCode, capacity: usize) {
+        self.tick += 1;
+        if let Some((used, _)) = self.entries.insert(key.clone(), (self.tick, voice_code)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.tick, key);
+        while self.entries.len() > capacity {
+            let (_, oldest) = self.order.pop_first().expect("every entry is ordered");
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+impl CachedVoiceCode {
+    /// Cache holding at most `capacity` voice codes, 0 disables caching but still counts misses
+    pub fn new(capacity: usize) -> Self {
+        CachedVoiceCode { capacity, lru: Mutex::new(Lru::default()), hits: AtomicU64::new(0), misses: AtomicU64::new(0) }
+    }
+
+    /// Voice code for the inputs, from the cache when they were seen recently
+    pub fn get(&self, gtin: &str, lot: &str, yy: &str, mm: &str, dd: &str) -> Result<HashVoiceCode, VoiceCodeError> {
+        let key = [gtin, lot, yy, mm, dd].join(&KEY_SEPARATOR.to_string());
+        if let Some(voice_code) = self.lock().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(voice_code);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // hashed outside the lock, two threads missing the same key both compute it
+        let voice_code = HashVoiceCode::new(gtin, lot, yy, mm, dd)?;
+        if self.capacity > 0 {
+            self.lock().insert(key, voice_code.clone(), self.capacity);
+        }
+        Ok(voice_code)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Counters and current size
+    pub fn stats(&self) -> CacheStats {
+        CacheStats { hits: self.hits(), misses: self.misses(), len: self.lock().entries.len() }
+    }
+
+    /// Drop every entry, the counters keep running
+    pub fn clear(&self) {
+        *self.lock() = Lru::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // the cache holds no invariant a panicking thread could break halfway
+        self.lru.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = CachedVoiceCode::new(2);
+        cache.get("00614141007349", "A", "03", "01", "02").unwrap();
+        cache.get("00614141007349", "B", "03", "01", "02").unwrap();
+        // A is now more recent than B
+        cache.get("00614141007349", "A", "03", "01", "02").unwrap();
+        cache.get("00614141007349", "C", "03", "01", "02").unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3, len: 2 });
+
+        cache.get("00614141007349", "A", "03", "01", "02").unwrap();
+        assert_eq!(cache.hits(), 2);
+        cache.get("00614141007349", "B", "03", "01", "02").unwrap();
+        assert_eq!(cache.misses(), 4);
+    }
+
+    #[test]
+    fn test_errors_not_cached() {
+        let cache = CachedVoiceCode::new(2);
+        assert!(cache.get("1234", "A", "03", "01", "02").is_err());
+        assert!(cache.get("1234", "A", "03", "01", "02").is_err());
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2, len: 0 });
+
+        let disabled = CachedVoiceCode::new(0);
+        assert_eq!(disabled.get("00614141007349", "LOT123", "03", "01", "02").unwrap().voice_code, "6307");
+        assert_eq!(disabled.stats().len, 0);
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let cache = CachedVoiceCode::new(16);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        assert_eq!(cache.get("00614141007349", "LOT123", "03", "01", "02").unwrap().voice_code, "6307");
+                    }
+                });
+            }
+        });
+        let stats = cache.stats();
+        assert_eq!(stats.hits + stats.misses, 400);
+        assert!(stats.hit_rate() > 0.9);
+        assert_eq!(stats.len, 1);
+    }
+}
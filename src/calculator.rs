@@ -0,0 +1,138 @@
+//! Voice code calculator that owns its look up table
+//!
+//! `HashVoiceCode::new` and the `hash` functions share one table built at compile time.
+//! A `VoiceCodeCalculator` carries its own, so it can be built from another polynomial to
+//! try alternatives, and nothing it computes touches a static.
+
+use std::sync::Arc;
+
+use crate::create_crc_lut::create_crc_lut;
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::hash::VOICE_CODE_MODULUS;
+use crate::lot::Lot;
+use crate::pack_date::{DatePadding, PackDate};
+use crate::voicecode::HashVoiceCode;
+
+/// Reflected polynomial of the PTI voice code, CRC-16/ARC
+pub const PTI_POLYNOMIAL: u16 = 40961;
+
+/// Voice code hashing with a table built from any reflected 16 bit polynomial
+///
+/// It holds no interior state, so one calculator can be shared by reference between threads.
+///
+/// # Example
+/// ```
+/// use voicecode::{ VoiceCodeCalculator, Gtin };
+/// let calculator = VoiceCodeCalculator::pti();
+/// assert_eq!(calculator.compute(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap().voice_code, "6991");
+///
+/// // CRC-16/KERMIT, reflected 0x1021
+/// let kermit = VoiceCodeCalculator::new(0x8408);
+/// assert_eq!(kermit.crc(b"123456789"), 0x2189);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VoiceCodeCalculator {
+    polynomial: u16,
+    lut: [u16; 256],
+}
+
+impl VoiceCodeCalculator {
+    /// Calculator for `polynomial` in reflected form, as `create_crc_lut` takes it
+    pub const fn new(polynomial: u16) -> Self {
+        VoiceCodeCalculator { polynomial, lut: create_crc_lut(polynomial) }
+    }
+
+    /// Calculator for the PTI voice code, agrees with `HashVoiceCode::new`
+    pub const fn pti() -> Self {
+        Self::new(PTI_POLYNOMIAL)
+    }
+
+    pub fn polynomial(&self) -> u16 {
+        self.polynomial
+    }
+
+    /// CRC of `input`, zero init and no final XOR, before it is reduced to a voice code
+    pub fn crc(&self, input: &[u8]) -> u16 {
+        input.iter().fold(0u16, |crc, &b| (crc >> 8) ^ self.lut[((crc ^ u16::from(b)) & 0xff) as usize])
+    }
+
+    /// Voice code for `input` as a number from 0 to 9999
+    pub fn hash_u16(&self, input: &[u8]) -> u16 {
+        self.crc(input) % VOICE_CODE_MODULUS
+    }
+
+    /// Validate the inputs like `HashVoiceCode::new` and hash them with this calculator
+    ///
+    /// A code from any polynomial other than `PTI_POLYNOMIAL` will not match a PTI label,
+    /// `HashVoiceCode` methods that hash again, like `with_lot`, keep using this calculator.
+    pub fn compute<G, L>(&self, gtin: G, lot: L, yy: &str, mm: &str, dd: &str) -> Result<HashVoiceCode, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        L: TryInto<Lot>,
+        VoiceCodeError: From<G::Error> + From<L::Error>,
+    {
+        let pack_date = PackDate::from_parts(yy, mm, dd)?;
+        let lot: Lot = lot.try_into()?;
+        let gtin: Gtin = gtin.try_into()?;
+        Ok(self.from_parts(gtin, lot, pack_date))
+    }
+
+    /// Hash already validated parts with this calculator
+    pub fn from_parts(&self, gtin: Gtin, lot: Lot, pack_date: PackDate) -> HashVoiceCode {
+        HashVoiceCode::from_parts_with_algorithm(gtin, lot, pack_date, DatePadding::default(), Arc::new(self.clone()))
+    }
+}
+
+impl Default for VoiceCodeCalculator {
+    fn default() -> Self {
+        Self::pti()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::{Crc, CrcModel};
+
+    #[test]
+    fn test_pti_matches_new() {
+        let calculator = VoiceCodeCalculator::default();
+        for (gtin, lot, yy, mm, dd) in [
+            ("12345678901244", "LOT123", "03", "01", "02"),
+            ("61414100734933", "32abcd", "03", "01", "02"),
+            ("61414100734933", "LOT123", "3", "1", "10"),
+        ] {
+            let gtin = Gtin::parse_unchecked(gtin).unwrap();
+            let expected = HashVoiceCode::new(&gtin, lot, yy, mm, dd).unwrap();
+            assert_eq!(calculator.compute(&gtin, lot, yy, mm, dd).unwrap(), expected);
+        }
+        assert!(calculator.compute("1234", "LOT123", "03", "01", "02").is_err());
+    }
+
+    #[test]
+    fn test_other_polynomial() {
+        // CRC-16/KERMIT, reflected 0x1021
+        let model = CrcModel { width: 16, poly: 0x1021, init: 0, refin: true, refout: true, xorout: 0 };
+        let calculator = VoiceCodeCalculator::new(0x8408);
+        let voice_code = calculator.compute(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        let expected = Crc::new(model).checksum(voice_code.hash_text.as_bytes()) % 10000;
+        assert_eq!(voice_code.voice_code, format!("{:04}", expected));
+        assert_ne!(voice_code.voice_code, "6991");
+
+        let next_day = voice_code.with_date(PackDate::from_parts("03", "01", "03").unwrap());
+        let expected = calculator.compute(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "03").unwrap();
+        assert_eq!(next_day.voice_code, expected.voice_code);
+        assert_eq!(u32::from(next_day.voice_code_u16()), Crc::new(model).checksum(next_day.hash_text.as_bytes()) % 10000);
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let calculator = VoiceCodeCalculator::pti();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(calculator.hash_u16(b"12345678901244LOT123030102"), 6991));
+            }
+        });
+    }
+}
@@ -0,0 +1,207 @@
+//! Known answer vectors, to certify that a deployment computes the same codes as the PTI
+//! calculator and the voicecodewidget.js widget
+//!
+//! The bundled vectors are `data/conformance.csv`, rows tagged `pti-calculator` come from
+//! the PTI calculator, `crc16-arc` rows were checked against an independent CRC-16/ARC and
+//! `validation` rows must be rejected. Vectors in the same CSV layout can be added:
+//!
+//! ```text
+//! gtin,lot,pack_date,voice_code,source
+//! 12345678901244,LOT123,030102,6991,pti-calculator
+//! 1234,LOT123,030102,invalid,validation
+//! ```
+
+use std::fmt;
+use std::io;
+
+use ::csv::ReaderBuilder;
+
+use crate::batch::csv::column;
+use crate::batch::{BatchError, BatchRecord};
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::pack_date::PackDate;
+use crate::voicecode::HashVoiceCode;
+
+const BUNDLED: &str = include_str!("../data/conformance.csv");
+
+/// `voice_code` of a vector the inputs of which must be rejected
+pub const INVALID: &str = "invalid";
+
+/// One known answer, `pack_date` is YYMMDD or YYYY-MM-DD and an empty `lot` means no LOT
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vector {
+    pub gtin: String,
+    pub lot: String,
+    pub pack_date: String,
+    /// Four digits, or `INVALID`
+    pub voice_code: String,
+    /// Where the expected answer comes from
+    pub source: String,
+}
+
+impl Vector {
+    /// What this crate computes for the vector's inputs, like the PTI calculator the GTIN
+    /// check digit is not verified
+    pub fn compute(&self) -> Result<HashVoiceCode, VoiceCodeError> {
+        if self.lot.is_empty() {
+            let pack_date: PackDate = self.pack_date.parse()?;
+            return HashVoiceCode::new_no_lot(Gtin::parse_unchecked(&self.gtin)?, pack_date);
+        }
+        BatchRecord::new(&self.gtin, &self.lot, &self.pack_date).compute()
+    }
+}
+
+/// The vectors shipped with the crate
+pub fn bundled() -> Vec<Vector> {
+    load_csv(BUNDLED.as_bytes()).expect("bundled vectors parse")
+}
+
+/// Read vectors from a CSV with gtin, lot, pack_date, voice_code and optional source columns
+pub fn load_csv<R: io::Read>(input: R) -> Result<Vec<Vector>, BatchError> {
+    let mut reader = ReaderBuilder::new().flexible(true).from_reader(input);
+    let headers = reader.headers()?.clone();
+    let gtin = column(&headers, "gtin")?;
+    let lot = column(&headers, "lot")?;
+    let pack_date = column(&headers, "pack_date")?;
+    let voice_code = column(&headers, "voice_code")?;
+    let source = column(&headers, "source").ok();
+
+    let mut vectors = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        let field = |i: usize| row.get(i).unwrap_or_default().trim().to_string();
+        vectors.push(Vector {
+            gtin: field(gtin),
+            lot: field(lot),
+            pack_date: field(pack_date),
+            voice_code: field(voice_code),
+            source: source.map(field).unwrap_or_default(),
+        });
+    }
+    Ok(vectors)
+}
+
+/// Result of one vector
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VectorResult {
+    pub vector: Vector,
+    /// The computed code, or the error for rejected inputs
+    pub actual: Result<String, VoiceCodeError>,
+}
+
+impl VectorResult {
+    pub fn passed(&self) -> bool {
+        match &self.actual {
+            Ok(code) => *code == self.vector.voice_code,
+            Err(_) => self.vector.voice_code == INVALID,
+        }
+    }
+}
+
+impl fmt::Display for VectorResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let vector = &self.vector;
+        let status = if self.passed() { "pass" } else { "FAIL" };
+        write!(f, "{} GTIN {} LOT {} pack date {} expected {} got ", status, vector.gtin, vector.lot, vector.pack_date, vector.voice_code)?;
+        match &self.actual {
+            Ok(code) => write!(f, "{}", code)?,
+            Err(e) => write!(f, "{} ({})", INVALID, e)?,
+        }
+        if !vector.source.is_empty() {
+            write!(f, " [{}]", vector.source)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pass/fail of every vector in a run
+///
+/// # Example
+/// ```
+/// use voicecode::conformance::{ self, ConformanceReport };
+/// let mut vectors = conformance::bundled();
+/// vectors.extend(conformance::load_csv("gtin,lot,pack_date,voice_code\n61414100734933,LOT123,030102,1234\n".as_bytes()).unwrap());
+/// let report = ConformanceReport::run(&vectors);
+/// assert_eq!(report.failures().count(), 1);
+/// assert!(!report.is_pass());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub results: Vec<VectorResult>,
+}
+
+impl ConformanceReport {
+    pub fn run<'a, I>(vectors: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Vector>,
+    {
+        let results = vectors
+            .into_iter()
+            .map(|vector| VectorResult { vector: vector.clone(), actual: vector.compute().map(|voice_code| voice_code.voice_code) })
+            .collect();
+        ConformanceReport { results }
+    }
+
+    /// True when every vector passed
+    pub fn is_pass(&self) -> bool {
+        self.results.iter().all(VectorResult::passed)
+    }
+
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|result| result.passed()).count()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &VectorResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+}
+
+/// Failures one per line, then the totals
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for failure in self.failures() {
+            writeln!(f, "{}", failure)?;
+        }
+        write!(f, "{} vectors, {} passed, {} failed", self.results.len(), self.passed(), self.results.len() - self.passed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::PTI;
+
+    #[test]
+    fn test_bundled_pass() {
+        let vectors = bundled();
+        assert!(vectors.len() >= 10);
+        let report = ConformanceReport::run(&vectors);
+        assert!(report.is_pass(), "{}", report);
+    }
+
+    #[test]
+    fn test_bundled_agree_with_crc() {
+        for vector in bundled().iter().filter(|vector| vector.voice_code != INVALID) {
+            let text = format!("{}{}{}", vector.gtin, vector.lot, vector.pack_date);
+            assert_eq!(format!("{:04}", PTI.checksum(text.as_bytes()) % 10000), vector.voice_code, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_failures_reported() {
+        let vectors = load_csv(
+            "gtin,lot,pack_date,voice_code,source\n12345678901244,LOT123,030102,6992,mine\n1234,LOT123,030102,6991,mine\n".as_bytes(),
+        )
+        .unwrap();
+        let report = ConformanceReport::run(&vectors);
+        assert_eq!(report.passed(), 0);
+        assert_eq!(
+            report.to_string(),
+            "FAIL GTIN 12345678901244 LOT LOT123 pack date 030102 expected 6992 got 6991 [mine]\n\
+             FAIL GTIN 1234 LOT LOT123 pack date 030102 expected 6991 got invalid (GTIN must be 8, 12, 13 or 14 digits, got 4) [mine]\n\
+             2 vectors, 0 passed, 2 failed"
+        );
+        assert!(load_csv("gtin,lot\n1,2\n".as_bytes()).is_err());
+    }
+}
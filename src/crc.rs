@@ -0,0 +1,266 @@
+//! Table driven CRC for any Rocksoft model from 8 to 32 bits wide
+//!
+//! The voice code is CRC-16/ARC, `CrcModel::PTI`, which the PTI reference implementation
+//! writes as the reflected polynomial 0xA001 (40961) passed to `create_crc_lut`.
+//!
+//! # The voice code as CRC-16/ARC
+//!
+//! The voice code is `CRC-16/ARC(GTIN + LOT + YYMMDD) % 10000`, zero padded to four digits,
+//! over the ASCII text with nothing between the parts. CRC-16/ARC is width 16, polynomial
+//! 0x8005 (0xA001 reflected), init 0, input and output reflected, no final XOR, check value
+//! 0xBB3D. Any library that implements CRC-16/ARC, sometimes listed as CRC-16, CRC-IBM or
+//! CRC-16/LHA, computes the same CRC. MODBUS uses the same polynomial but starts from 0xFFFF
+//! and gives different codes.
+//!
+//! ```
+//! use voicecode::crc::{ Crc, CrcModel };
+//! const CRC_32: Crc = Crc::new(CrcModel::CRC_32);
+//! assert_eq!(CRC_32.checksum(b"123456789"), 0xcbf43926);
+//!
+//! let mut digest = voicecode::crc::PTI.digest();
+//! digest.update(b"12345678901244");
+//! digest.update(b"LOT123030102");
+//! assert_eq!(digest.finalize() % 10000, 6991);
+//! ```
+
+/// Rocksoft model parameters, `poly` in normal (not reflected) form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CrcModel {
+    /// Register width in bits, 8 to 32
+    pub width: u8,
+    pub poly: u32,
+    /// Register value before the first byte, not reflected
+    pub init: u32,
+    /// Feed each input byte least significant bit first
+    pub refin: bool,
+    /// Reflect the register before `xorout`
+    pub refout: bool,
+    pub xorout: u32,
+}
+
+impl CrcModel {
+    /// CRC-16/ARC, the voice code checksum, reflected polynomial 0xA001
+    pub const PTI: CrcModel = CrcModel { width: 16, poly: 0x8005, init: 0, refin: true, refout: true, xorout: 0 };
+    /// CRC-8/SMBUS
+    pub const CRC_8: CrcModel = CrcModel { width: 8, poly: 0x07, init: 0, refin: false, refout: false, xorout: 0 };
+    /// CRC-16/IBM-3740, often called CRC-16/CCITT-FALSE
+    pub const CRC_16_IBM_3740: CrcModel =
+        CrcModel { width: 16, poly: 0x1021, init: 0xffff, refin: false, refout: false, xorout: 0 };
+    /// CRC-16/ARC, the same model as `PTI`
+    pub const CRC_16_ARC: CrcModel = CrcModel::PTI;
+    /// CRC-16/KERMIT, also called CRC-16/CCITT or CRC-CCITT (true)
+    pub const CRC_16_KERMIT: CrcModel =
+        CrcModel { width: 16, poly: 0x1021, init: 0, refin: true, refout: true, xorout: 0 };
+    /// CRC-16/MODBUS
+    pub const CRC_16_MODBUS: CrcModel =
+        CrcModel { width: 16, poly: 0x8005, init: 0xffff, refin: true, refout: true, xorout: 0 };
+    /// CRC-16/XMODEM, also called CRC-16/ACORN or CRC-16/LTE
+    pub const CRC_16_XMODEM: CrcModel =
+        CrcModel { width: 16, poly: 0x1021, init: 0, refin: false, refout: false, xorout: 0 };
+    /// CRC-16/USB
+    pub const CRC_16_USB: CrcModel =
+        CrcModel { width: 16, poly: 0x8005, init: 0xffff, refin: true, refout: true, xorout: 0xffff };
+    /// CRC-16/MAXIM-DOW
+    pub const CRC_16_MAXIM_DOW: CrcModel =
+        CrcModel { width: 16, poly: 0x8005, init: 0, refin: true, refout: true, xorout: 0xffff };
+    /// CRC-32/ISO-HDLC, the zlib and Ethernet CRC
+    pub const CRC_32: CrcModel =
+        CrcModel { width: 32, poly: 0x04c1_1db7, init: 0xffff_ffff, refin: true, refout: true, xorout: 0xffff_ffff };
+
+    /// Mask covering the `width` low bits
+    const fn mask(&self) -> u32 {
+        u32::MAX >> (32 - self.width as u32)
+    }
+}
+
+/// A named model with its published check value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Preset {
+    /// Name in the CRC catalogue
+    pub name: &'static str,
+    pub model: CrcModel,
+    /// CRC of the ASCII text "123456789"
+    pub check: u32,
+}
+
+/// Common models with the check values the CRC catalogue publishes for them
+pub const PRESETS: &[Preset] = &[
+    Preset { name: "CRC-8/SMBUS", model: CrcModel::CRC_8, check: 0xf4 },
+    Preset { name: "CRC-16/ARC", model: CrcModel::CRC_16_ARC, check: 0xbb3d },
+    Preset { name: "CRC-16/IBM-3740", model: CrcModel::CRC_16_IBM_3740, check: 0x29b1 },
+    Preset { name: "CRC-16/KERMIT", model: CrcModel::CRC_16_KERMIT, check: 0x2189 },
+    Preset { name: "CRC-16/MAXIM-DOW", model: CrcModel::CRC_16_MAXIM_DOW, check: 0x44c2 },
+    Preset { name: "CRC-16/MODBUS", model: CrcModel::CRC_16_MODBUS, check: 0x4b37 },
+    Preset { name: "CRC-16/USB", model: CrcModel::CRC_16_USB, check: 0xb4c8 },
+    Preset { name: "CRC-16/XMODEM", model: CrcModel::CRC_16_XMODEM, check: 0x31c3 },
+    Preset { name: "CRC-32/ISO-HDLC", model: CrcModel::CRC_32, check: 0xcbf4_3926 },
+];
+
+/// Preset by catalogue name, case insensitive
+///
+/// # Example
+/// ```
+/// use voicecode::crc::{ preset, Crc };
+/// let modbus = preset("crc-16/modbus").unwrap();
+/// assert_eq!(Crc::new(modbus.model).checksum(b"123456789"), modbus.check);
+/// assert!(preset("CRC-16/NOPE").is_none());
+/// ```
+pub fn preset(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
+
+/// Voice code CRC, `PTI.checksum(text) % 10000` is the voice code
+pub static PTI: Crc = Crc::new(CrcModel::PTI);
+
+/// A `CrcModel` with its look up table
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Crc {
+    model: CrcModel,
+    table: [u32; 256],
+}
+
+impl Crc {
+    /// Build the table for `model`, usable in const context
+    ///
+    /// # Panics
+    /// When `model.width` is outside 8 to 32
+    pub const fn new(model: CrcModel) -> Self {
+        assert!(model.width >= 8 && model.width <= 32, "CRC width must be 8 to 32 bits");
+        Crc { model, table: create_table(&model) }
+    }
+
+    pub fn model(&self) -> &CrcModel {
+        &self.model
+    }
+
+    /// Look up table, reflected when `refin` is set
+    pub fn table(&self) -> &[u32; 256] {
+        &self.table
+    }
+
+    /// CRC of `bytes` in one call
+    pub fn checksum(&self, bytes: &[u8]) -> u32 {
+        let mut digest = self.digest();
+        digest.update(bytes);
+        digest.finalize()
+    }
+
+    /// Start an incremental CRC
+    pub fn digest(&self) -> Digest<'_> {
+        let value = if self.model.refin { reflect(self.model.init, self.model.width) } else { self.model.init };
+        Digest { crc: self, value }
+    }
+}
+
+/// Running CRC, clone it to branch off a shared prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digest<'a> {
+    crc: &'a Crc,
+    value: u32,
+}
+
+impl Digest<'_> {
+    pub fn update(&mut self, bytes: &[u8]) {
+        let model = &self.crc.model;
+        let table = &self.crc.table;
+        let mut value = self.value;
+        if model.refin {
+            for &b in bytes {
+                value = (value >> 8) ^ table[((value ^ u32::from(b)) & 0xff) as usize];
+            }
+        } else {
+            let shift = model.width as u32 - 8;
+            let mask = model.mask();
+            for &b in bytes {
+                value = ((value << 8) ^ table[(((value >> shift) ^ u32::from(b)) & 0xff) as usize]) & mask;
+            }
+        }
+        self.value = value;
+    }
+
+    /// CRC of everything passed to `update` so far, the digest can keep going afterwards
+    pub fn finalize(&self) -> u32 {
+        let model = &self.crc.model;
+        let value = if model.refin != model.refout { reflect(self.value, model.width) } else { self.value };
+        (value ^ model.xorout) & model.mask()
+    }
+}
+
+/// Look up table for `model`, entries are `width` bits
+const fn create_table(model: &CrcModel) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mask = model.mask();
+
+    // const fn, so while loops instead of iterators
+    let mut i = 0;
+    while i < 256 {
+        let mut value: u32;
+        let mut bit = 0;
+        if model.refin {
+            let poly = reflect(model.poly, model.width);
+            value = i as u32;
+            while bit < 8 {
+                value = if value & 1 != 0 { (value >> 1) ^ poly } else { value >> 1 };
+                bit += 1;
+            }
+        } else {
+            let top = 1u32 << (model.width - 1);
+            value = (i as u32) << (model.width - 8);
+            while bit < 8 {
+                value = if value & top != 0 { (value << 1) ^ model.poly } else { value << 1 };
+                bit += 1;
+            }
+        }
+        table[i] = value & mask;
+        i += 1;
+    }
+
+    table
+}
+
+/// Reverse the low `width` bits of `value`
+const fn reflect(value: u32, width: u8) -> u32 {
+    value.reverse_bits() >> (32 - width as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_crc_lut::create_crc_lut;
+
+    #[test]
+    fn test_check_values() {
+        // CRC catalogue check values, the CRC of "123456789"
+        for preset in PRESETS {
+            assert_eq!(Crc::new(preset.model).checksum(b"123456789"), preset.check, "{}", preset.name);
+        }
+        assert_eq!(Crc::new(CrcModel::PTI).checksum(b"123456789"), 0xbb3d);
+    }
+
+    #[test]
+    fn test_voice_code_is_arc_only() {
+        let text = b"12345678901244LOT123030102";
+        assert_eq!(Crc::new(preset("CRC-16/ARC").unwrap().model).checksum(text) % 10000, 6991);
+        assert_ne!(Crc::new(CrcModel::CRC_16_MODBUS).checksum(text) % 10000, 6991);
+    }
+
+    #[test]
+    fn test_pti_table() {
+        let lut = create_crc_lut(40961);
+        for (i, &entry) in PTI.table().iter().enumerate() {
+            assert_eq!(entry, u32::from(lut[i]));
+        }
+        assert_eq!(PTI.checksum(b"12345678901244LOT123030102") % 10000, 6991);
+    }
+
+    #[test]
+    fn test_incremental() {
+        let crc = Crc::new(CrcModel::CRC_16_IBM_3740);
+        let mut digest = crc.digest();
+        digest.update(b"1234");
+        let prefix = digest.clone();
+        digest.update(b"56789");
+        assert_eq!(digest.finalize(), 0x29b1);
+        assert_eq!(prefix.finalize(), crc.checksum(b"1234"));
+    }
+}
@@ -1,11 +1,11 @@
 ///
 /// Generate CRC look up table for hashing given a polynomial
 ///
-/// 40961 is the polynomial used by the PTI example implementation
+/// 40961 is the polynomial used by the PTI example implementation, `crc::Crc` builds tables
+/// for other widths and models
 ///
 /// # Example
 /// ```
-/// use voicecode::{ HashVoiceCode };
 /// const EXAMPLEP_HASH_T: [u16; 256] = [
 ///     0x0000, 0xc0c1, 0xc181, 0x0140, 0xc301, 0x03c0, 0x0280, 0xc241, 0xc601, 0x06c0, 0x0780, 0xc741, 0x0500, 0xc5c1, 0xc481, 0x0440,
 ///     0xcc01, 0x0cc0, 0x0d80, 0xcd41, 0x0f00, 0xcfc1, 0xce81, 0x0e40, 0x0a00, 0xcac1, 0xcb81, 0x0b40, 0xc901, 0x09c0, 0x0880, 0xc841,
@@ -28,23 +28,117 @@
 /// for i in 0..256 {
 ///    assert_eq!(crc_lut[i], EXAMPLEP_HASH_T[i]);
 /// }
-pub fn create_crc_lut(polynomial: u16) -> [u16; 256] {
+///
+/// // usable in const context
+/// const CRC_LUT: [u16; 256] = voicecode::create_crc_lut::create_crc_lut(40961);
+/// assert_eq!(CRC_LUT, EXAMPLEP_HASH_T);
+/// ```
+pub const fn create_crc_lut(polynomial: u16) -> [u16; 256] {
     let mut lut = [0u16; 256];
 
-    for i in 0..256 {
+    // const fn, so while loops instead of iterators
+    let mut i = 0;
+    while i < 256 {
         let mut value: u16 = 0;
         let mut temp: u16 = i as u16;
 
-        for _ in 0..8 {
+        let mut bit = 0;
+        while bit < 8 {
             if (value ^ temp) & 1 != 0 {
                 value = (value >> 1) ^ polynomial;
             } else {
                 value >>= 1;
             }
             temp >>= 1;
+            bit += 1;
         }
         lut[i] = value;
+        i += 1;
     }
 
     lut
 }
+
+/// `static` CRC look up tables for reflected 16 bit polynomials, computed at compile time
+///
+/// `crc_table!(poly)` is a `&'static [u16; 256]` expression, `crc_table!(static NAME = poly;)`
+/// declares the static, with any visibility. No build script or runtime init is involved and
+/// it works without `std`.
+///
+/// # Example
+/// ```
+/// use voicecode::crc_table;
+///
+/// crc_table!(pub static MODBUS = 0xA001;);
+/// crc_table!(static KERMIT = 0x8408;);
+///
+/// assert_eq!(MODBUS, voicecode::create_crc_lut(40961));
+/// assert_eq!(KERMIT[1], 0x1189);
+///
+/// let table: &'static [u16; 256] = crc_table!(0xA001);
+/// assert_eq!(table, &MODBUS);
+/// ```
+#[macro_export]
+macro_rules! crc_table {
+    ($vis:vis static $name:ident = $poly:expr;) => {
+        $vis static $name: [u16; 256] = $crate::create_crc_lut::create_crc_lut($poly);
+    };
+    ($poly:expr) => {{
+        static TABLE: [u16; 256] = $crate::create_crc_lut::create_crc_lut($poly);
+        &TABLE
+    }};
+}
+
+/// Target language for `lut_source`
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LutLanguage {
+    C,
+    JavaScript,
+    Python,
+    Rust,
+}
+
+/// The table for `polynomial` as source code, so firmware and scripts embed the same table
+/// this crate hashes with instead of a copied array
+///
+/// # Example
+/// ```
+/// use voicecode::create_crc_lut::{ lut_source, LutLanguage };
+/// let source = lut_source(40961, LutLanguage::C);
+/// assert!(source.starts_with("/* CRC look up table for polynomial 0xa001, generated by voicecode"));
+/// assert!(source.contains("static const uint16_t VOICE_CODE_CRC_LUT[256] = {\n    0x0000, 0xc0c1, 0xc181,"));
+/// // the header's polynomial and the 256 entries
+/// assert_eq!(source.matches("0x").count(), 257);
+/// assert!(lut_source(40961, LutLanguage::Python).contains("VOICE_CODE_CRC_LUT = ["));
+/// ```
+#[cfg(feature = "std")]
+pub fn lut_source(polynomial: u16, language: LutLanguage) -> String {
+    use std::fmt::Write;
+
+    let lut = create_crc_lut(polynomial);
+    let version = env!("CARGO_PKG_VERSION");
+    let header = format!("CRC look up table for polynomial {:#06x}, generated by voicecode {}", polynomial, version);
+    let (open, close) = match language {
+        LutLanguage::C => (format!("/* {} */\n#include <stdint.h>\n\nstatic const uint16_t VOICE_CODE_CRC_LUT[256] = {{", header), "};"),
+        LutLanguage::JavaScript => (format!("// {}\nexport const VOICE_CODE_CRC_LUT = Uint16Array.of(", header), ");"),
+        LutLanguage::Python => (format!("# {}\nVOICE_CODE_CRC_LUT = [", header), "]"),
+        LutLanguage::Rust => (format!("// {}\npub static VOICE_CODE_CRC_LUT: [u16; 256] = [", header), "];"),
+    };
+
+    let mut out = open;
+    for row in lut.chunks(16) {
+        out.push_str("\n   ");
+        for value in row {
+            let _ = write!(out, " {:#06x},", value);
+        }
+    }
+    if language == LutLanguage::JavaScript {
+        // no trailing comma in an argument list for older engines
+        out.pop();
+    }
+    out.push('\n');
+    out.push_str(close);
+    out.push('\n');
+    out
+}
@@ -0,0 +1,151 @@
+//! Diesel impls, see the module docs for the column types
+//!
+//! ```no_run
+//! # #[derive(diesel::Queryable)]
+//! # struct Case { gtin: voicecode::Gtin, lot: voicecode::Lot, pack_date: voicecode::PackDate }
+//! diesel::table! {
+//!     cases (id) {
+//!         id -> Integer,
+//!         gtin -> Text,
+//!         lot -> Text,
+//!         pack_date -> Date,
+//!     }
+//! }
+//! # fn run(conn: &mut impl diesel::connection::LoadConnection<Backend = diesel::pg::Pg>) -> diesel::QueryResult<()> {
+//! use diesel::prelude::*;
+//! let gtin = voicecode::Gtin::parse("00614141007349").unwrap();
+//! let case: Case = cases::table.filter(cases::gtin.eq(&gtin)).select((cases::gtin, cases::lot, cases::pack_date)).first(conn)?;
+//! let voice_code = voicecode::HashVoiceCode::from_parts(case.gtin, case.lot, case.pack_date);
+//! # Ok(())
+//! # }
+//! ```
+
+use ::diesel::backend::Backend;
+use ::diesel::deserialize::{self, FromSql};
+use ::diesel::pg::{Pg, PgValue};
+use ::diesel::serialize::{self, IsNull, Output, ToSql};
+use ::diesel::sql_types::{Date, Text};
+use chrono::NaiveDate;
+
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::lot::Lot;
+use crate::pack_date::PackDate;
+use crate::voicecode::VoiceCode;
+
+impl<DB> ToSql<Text, DB> for Gtin
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        self.as_str().to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Gtin
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(Gtin::parse_unchecked(&String::from_sql(bytes)?)?)
+    }
+}
+
+impl<DB> ToSql<Text, DB> for Lot
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        self.as_str().to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Lot
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(Lot::parse(&String::from_sql(bytes)?)?)
+    }
+}
+
+/// Fails for a pack date that is not a calendar date
+impl ToSql<Date, Pg> for PackDate {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let date = self.date().ok_or_else(|| VoiceCodeError::InvalidCalendarDate { yymmdd: self.yymmdd() })?;
+        <NaiveDate as ToSql<Date, Pg>>::to_sql(&date, &mut out.reborrow())
+    }
+}
+
+impl FromSql<Date, Pg> for PackDate {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        Ok(PackDate::from_naive(NaiveDate::from_sql(bytes)?))
+    }
+}
+
+/// Written on Postgres only, the digits are formatted rather than borrowed
+impl ToSql<Text, Pg> for VoiceCode {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        use std::io::Write;
+        write!(out, "{}", self)?;
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for VoiceCode
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(VoiceCode::parse(&String::from_sql(bytes)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::diesel::pg::{PgMetadataLookup, PgTypeMetadata};
+    use ::diesel::query_builder::bind_collector::RawBytesBindCollector;
+    use ::diesel::query_builder::BindCollector;
+    use ::diesel::sql_types::HasSqlType;
+
+    /// Only built in types are bound, which never look up an OID
+    struct NoLookup;
+
+    impl PgMetadataLookup for NoLookup {
+        fn lookup_type(&mut self, type_name: &str, _schema: Option<&str>) -> PgTypeMetadata {
+            unreachable!("{} is not built in", type_name)
+        }
+    }
+
+    fn bind<T, U>(value: &U) -> Result<String, ::diesel::result::Error>
+    where
+        Pg: HasSqlType<T>,
+        U: ToSql<T, Pg> + ?Sized,
+    {
+        let mut collector = RawBytesBindCollector::<Pg>::new();
+        collector.push_bound_value::<T, U>(value, &mut NoLookup)?;
+        Ok(format!("{:?}", collector))
+    }
+
+    #[test]
+    fn test_to_sql() {
+        let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+        assert_eq!(bind::<Text, _>(&gtin).unwrap(), bind::<Text, str>("12345678901244").unwrap());
+        let lot = Lot::parse("LOT123").unwrap();
+        assert_eq!(bind::<Text, _>(&lot).unwrap(), bind::<Text, str>("LOT123").unwrap());
+        let code = VoiceCode::new(65).unwrap();
+        assert_eq!(bind::<Text, _>(&code).unwrap(), bind::<Text, str>("0065").unwrap());
+
+        let date = NaiveDate::from_ymd_opt(2003, 1, 2).unwrap();
+        let pack_date = PackDate::from_parts("03", "01", "02").unwrap();
+        assert_eq!(bind::<Date, _>(&pack_date).unwrap(), bind::<Date, _>(&date).unwrap());
+        let err = bind::<Date, _>(&PackDate::from_parts("99", "99", "99").unwrap()).unwrap_err();
+        assert!(err.to_string().contains("Pack date 999999 is not a calendar date"), "{}", err);
+    }
+}
@@ -0,0 +1,19 @@
+//! Database column types for `Gtin`, `Lot`, `PackDate` and `VoiceCode`
+//!
+//! `Gtin` and `Lot` are stored as TEXT exactly as printed and read back with
+//! `Gtin::parse_unchecked` and `Lot::parse`, so a GTIN stored with a bad check digit still
+//! decodes and any other text `HashVoiceCode::new` would reject fails to decode instead of
+//! reaching the hash. `PackDate` is stored as DATE, a pack
+//! date that is not a calendar date, such as legacy `999999`, can't be written. `VoiceCode` is
+//! TEXT with its four digits, leading zeros kept, and fails to decode anything else.
+//!
+//! - `sqlx` - `Type`, `Encode` and `Decode` for Postgres, plus `PgHasArrayType` for
+//!   `= ANY($1)` with a slice
+//! - `diesel` - `ToSql` and `FromSql` for `Text` on any backend and for `Date` on Postgres,
+//!   `VoiceCode` writes `Text` on Postgres only, the types derive `AsExpression` and
+//!   `FromSqlRow` so they work in queries and rows
+
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "sqlx")]
+mod sqlx;
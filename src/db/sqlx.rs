@@ -0,0 +1,165 @@
+//! sqlx Postgres impls, see the module docs for the column types
+//!
+//! ```no_run
+//! # async fn run(pool: sqlx::PgPool) -> Result<(), sqlx::Error> {
+//! use voicecode::{ HashVoiceCode, Gtin, Lot, PackDate };
+//! let (gtin, lot, pack_date): (Gtin, Lot, PackDate) =
+//!     sqlx::query_as("SELECT gtin, lot, pack_date FROM cases WHERE id = $1").bind(1).fetch_one(&pool).await?;
+//! let voice_code = HashVoiceCode::from_parts(gtin, lot, pack_date);
+//! # Ok(())
+//! # }
+//! ```
+
+use ::sqlx::encode::IsNull;
+use ::sqlx::error::BoxDynError;
+use ::sqlx::postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef, Postgres};
+use ::sqlx::{Decode, Encode, Type};
+use chrono::NaiveDate;
+
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::lot::Lot;
+use crate::pack_date::PackDate;
+use crate::voicecode::VoiceCode;
+
+impl Type<Postgres> for Gtin {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for Gtin {
+    fn array_type_info() -> PgTypeInfo {
+        <&str as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Gtin {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Gtin {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Gtin::parse_unchecked(<&str as Decode<Postgres>>::decode(value)?)?)
+    }
+}
+
+impl Type<Postgres> for Lot {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for Lot {
+    fn array_type_info() -> PgTypeInfo {
+        <&str as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Lot {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Lot {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Lot::parse(<&str as Decode<Postgres>>::decode(value)?)?)
+    }
+}
+
+impl Type<Postgres> for PackDate {
+    fn type_info() -> PgTypeInfo {
+        <NaiveDate as Type<Postgres>>::type_info()
+    }
+}
+
+impl PgHasArrayType for PackDate {
+    fn array_type_info() -> PgTypeInfo {
+        <NaiveDate as PgHasArrayType>::array_type_info()
+    }
+}
+
+/// Fails for a pack date that is not a calendar date
+impl Encode<'_, Postgres> for PackDate {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        let date = self.date().ok_or_else(|| VoiceCodeError::InvalidCalendarDate { yymmdd: self.yymmdd() })?;
+        <NaiveDate as Encode<Postgres>>::encode(date, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PackDate {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(PackDate::from_naive(<NaiveDate as Decode<Postgres>>::decode(value)?))
+    }
+}
+
+impl Type<Postgres> for VoiceCode {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for VoiceCode {
+    fn array_type_info() -> PgTypeInfo {
+        <&str as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for VoiceCode {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <String as Encode<Postgres>>::encode(self.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for VoiceCode {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(VoiceCode::parse(<&str as Decode<Postgres>>::decode(value)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<T: for<'q> Encode<'q, Postgres>>(value: &T) -> Result<Vec<u8>, BoxDynError> {
+        let mut buf = PgArgumentBuffer::default();
+        assert!(matches!(value.encode_by_ref(&mut buf)?, IsNull::No));
+        Ok(buf.to_vec())
+    }
+
+    #[test]
+    fn test_types() {
+        assert_eq!(Gtin::type_info(), PgTypeInfo::with_name("TEXT"));
+        assert!(<Gtin as Type<Postgres>>::compatible(&PgTypeInfo::with_name("VARCHAR")));
+        assert_eq!(Lot::type_info(), PgTypeInfo::with_name("TEXT"));
+        assert_eq!(PackDate::type_info(), PgTypeInfo::with_name("DATE"));
+        assert_eq!(Gtin::array_type_info(), PgTypeInfo::with_name("_TEXT"));
+        assert_eq!(VoiceCode::type_info(), PgTypeInfo::with_name("TEXT"));
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&Gtin::parse_unchecked("12345678901244").unwrap()).unwrap(), b"12345678901244");
+        assert_eq!(encode(&Lot::parse("LOT123").unwrap()).unwrap(), b"LOT123");
+        assert_eq!(encode(&VoiceCode::new(65).unwrap()).unwrap(), b"0065");
+        // days since 2000-01-01
+        assert_eq!(encode(&PackDate::from_parts("03", "01", "02").unwrap()).unwrap(), 1097i32.to_be_bytes());
+        let err = encode(&PackDate::from_parts("99", "99", "99").unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "Pack date 999999 is not a calendar date");
+    }
+}
@@ -0,0 +1,3 @@
+//! EDI documents that carry the same GTIN, LOT and pack date as the case label
+
+pub mod x12_856;
@@ -0,0 +1,267 @@
+//! X12 856 Advance Ship Notice item segments
+//!
+//! Each case line is a `LIN` with the GTIN-14 (`UK`) and lot (`LT`), an `SN1` with the
+//! quantity shipped, and a `DTM` with the pack date as CCYYMMDD:
+//!
+//! `LIN*1*UK*10614141007346*LT*LOT123~SN1*1*40*CA~DTM*094*20030102~`
+//!
+//! `parse_items` reads those fields back from an inbound 856 so receiving can compare the
+//! voice code on each case with the one the ASN implies.
+
+use crate::batch::BatchRecord;
+use crate::error::VoiceCodeError;
+use crate::pack_date::{PackDate, YearWindow};
+use crate::verify::VerifyResult;
+use crate::voicecode::HashVoiceCode;
+
+/// DTM01 qualifier for the pack date, 094 Manufacture
+pub const PACK_DATE_QUALIFIER: &str = "094";
+
+/// LIN product id qualifiers that carry a GTIN, GTIN-14 first
+const GTIN_QUALIFIERS: &[&str] = &["UK", "EN", "UP"];
+
+/// Element and segment separators
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Separators {
+    pub element: char,
+    pub segment: char,
+}
+
+impl Default for Separators {
+    fn default() -> Self {
+        Separators { element: '*', segment: '~' }
+    }
+}
+
+impl Separators {
+    /// Separators declared by an interchange's `ISA` header, defaults without one
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::edi::x12_856::Separators;
+    /// let isa = "ISA|00|          |00|          |ZZ|SENDER         |ZZ|RECEIVER       |030102|1200|U|00401|000000001|0|P|>\n";
+    /// assert_eq!(Separators::detect(isa), Separators { element: '|', segment: '\n' });
+    /// assert_eq!(Separators::detect("ST*856*0001~"), Separators::default());
+    /// ```
+    pub fn detect(document: &str) -> Self {
+        let document = document.trim_start();
+        if !document.starts_with("ISA") {
+            return Separators::default();
+        }
+        // ISA is fixed width, the element separator follows "ISA" and the segment
+        // terminator follows the component separator at position 104
+        let mut chars = document.chars();
+        match (chars.nth(3), chars.nth(101)) {
+            (Some(element), Some(segment)) => Separators { element, segment },
+            _ => Separators::default(),
+        }
+    }
+}
+
+/// One case line of a shipment
+#[derive(Clone, Debug)]
+pub struct Item {
+    /// LIN01 and SN101, the line number within the ASN
+    pub line: u32,
+    pub quantity: u32,
+    /// SN103 unit of measure, `CA` for cases
+    pub unit: String,
+    pub voice_code: HashVoiceCode,
+}
+
+impl Item {
+    pub fn new(line: u32, quantity: u32, voice_code: HashVoiceCode) -> Self {
+        Item { line, quantity, unit: "CA".to_string(), voice_code }
+    }
+
+    /// The `LIN`, `SN1` and `DTM` segments for this item, each with its terminator
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::HashVoiceCode;
+    /// use voicecode::edi::x12_856::{ Item, Separators };
+    /// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(
+    ///     Item::new(1, 40, voice_code).segments(Separators::default()),
+    ///     "LIN*1*UK*10614141007346*LT*LOT123~SN1*1*40*CA~DTM*094*20030102~"
+    /// );
+    /// ```
+    pub fn segments(&self, separators: Separators) -> String {
+        let voice_code = &self.voice_code;
+        let (line, quantity) = (self.line.to_string(), self.quantity.to_string());
+        let (gtin, date) = (voice_code.gtin.to_gtin14(), ccyymmdd(&voice_code.pack_date()));
+        let segments: [&[&str]; 3] = [
+            &["LIN", &line, "UK", &gtin, "LT", voice_code.lot.as_str()],
+            &["SN1", &line, &quantity, &self.unit],
+            &["DTM", PACK_DATE_QUALIFIER, &date],
+        ];
+        let mut out = String::new();
+        for segment in segments {
+            out.push_str(&segment.join(&separators.element.to_string()));
+            out.push(separators.segment);
+        }
+        out
+    }
+}
+
+/// Pack date with its century, from the default `YearWindow` when it is not a calendar date
+fn ccyymmdd(pack_date: &PackDate) -> String {
+    match pack_date.calendar_date() {
+        Some((year, month, day)) => format!("{:04}{:02}{:02}", year, month, day),
+        None => {
+            let year = YearWindow::default().resolve(pack_date.yy().parse().unwrap_or_default());
+            format!("{}{:0>2}{:0>2}", year, pack_date.mm(), pack_date.dd())
+        }
+    }
+}
+
+/// GTIN, lot, quantity and pack date of one `LIN` loop in an inbound 856
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShippedItem {
+    /// LIN01
+    pub line: String,
+    pub gtin: Option<String>,
+    pub lot: Option<String>,
+    pub quantity: Option<String>,
+    pub unit: Option<String>,
+    /// DTM02 of the pack date `DTM`, CCYYMMDD
+    pub pack_date: Option<String>,
+}
+
+impl ShippedItem {
+    /// The item as a batch record, pack date as YYYY-MM-DD
+    pub fn record(&self) -> Result<BatchRecord, VoiceCodeError> {
+        let gtin = self.gtin.as_deref().ok_or(VoiceCodeError::MissingField { field: "gtin" })?;
+        let lot = self.lot.as_deref().ok_or(VoiceCodeError::MissingField { field: "lot" })?;
+        let date = self.pack_date.as_deref().ok_or(VoiceCodeError::MissingField { field: "pack_date" })?;
+        let pack_date = match (date.get(..4), date.get(4..6), date.get(6..)) {
+            (Some(year), Some(month), Some(day)) if date.len() == 8 => format!("{}-{}-{}", year, month, day),
+            _ => date.to_string(),
+        };
+        Ok(BatchRecord::new(gtin, lot, &pack_date))
+    }
+
+    /// The voice code this item's cases should carry
+    pub fn voice_code(&self) -> Result<HashVoiceCode, VoiceCodeError> {
+        self.record()?.compute()
+    }
+
+    /// Compare the voice code read off a received case with the one the ASN implies
+    pub fn verify(&self, printed: &str) -> VerifyResult {
+        self.voice_code().map(|voice_code| VerifyResult::compare(&voice_code.voice_code, printed)).into()
+    }
+}
+
+/// Every `LIN` loop of an 856, in document order
+///
+/// `SN1` and pack date `DTM` segments belong to the `LIN` before them, up to the next `LIN`
+/// or `HL`. Line breaks around segment terminators are ignored.
+///
+/// # Example
+/// ```
+/// use voicecode::edi::x12_856::parse_items;
+/// let asn = "ST*856*0001~HL*3*2*I~LIN*1*UK*61414100734933*LT*32abcd~SN1*1*40*CA~DTM*094*20030102~SE*6*0001~";
+/// let items = parse_items(asn);
+/// assert_eq!(items[0].lot.as_deref(), Some("32abcd"));
+/// assert_eq!(items[0].voice_code().unwrap().voice_code, "8079");
+/// assert!(items[0].verify("80 79").is_match());
+/// ```
+pub fn parse_items(document: &str) -> Vec<ShippedItem> {
+    let separators = Separators::detect(document);
+    let mut items = Vec::new();
+    let mut current: Option<ShippedItem> = None;
+    for segment in document.split(separators.segment) {
+        let elements: Vec<&str> = segment.trim_matches(['\r', '\n']).split(separators.element).collect();
+        let element = |i: usize| elements.get(i).map(|e| e.trim()).filter(|e| !e.is_empty()).map(str::to_string);
+        match elements[0].trim() {
+            "LIN" => {
+                items.extend(current.take());
+                let mut item = ShippedItem { line: element(1).unwrap_or_default(), ..Default::default() };
+                // LIN02 onward are qualifier and id pairs
+                for pair in elements[2..].chunks(2) {
+                    let (qualifier, id) = (pair[0].trim(), pair.get(1).map(|id| id.trim().to_string()));
+                    if qualifier == "LT" {
+                        item.lot = item.lot.or(id);
+                    } else if GTIN_QUALIFIERS.contains(&qualifier) && item.gtin.is_none() {
+                        item.gtin = id;
+                    }
+                }
+                current = Some(item);
+            }
+            "SN1" => {
+                if let Some(item) = current.as_mut() {
+                    item.quantity = element(2);
+                    item.unit = element(3);
+                }
+            }
+            "DTM" if element(1).as_deref() == Some(PACK_DATE_QUALIFIER) => {
+                if let Some(item) = current.as_mut() {
+                    item.pack_date = element(2);
+                }
+            }
+            "HL" => items.extend(current.take()),
+            _ => {}
+        }
+    }
+    items.extend(current);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+
+    #[test]
+    fn test_round_trip() {
+        let items = [
+            Item::new(1, 40, HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap()),
+            Item::new(2, 12, HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap()),
+        ];
+        let separators = Separators { element: '|', segment: '\n' };
+        let isa = "ISA|00|          |00|          |ZZ|SENDER         |ZZ|RECEIVER       |030102|1200|U|00401|000000001|0|P|>\n";
+        let mut asn = format!("{}ST|856|0001\nHL|1||S\n", isa);
+        for item in &items {
+            asn.push_str("HL|2|1|I\n");
+            asn.push_str(&item.segments(separators));
+        }
+        asn.push_str("SE|9|0001\n");
+
+        let parsed = parse_items(&asn);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            parsed[1],
+            ShippedItem {
+                line: "2".to_string(),
+                gtin: Some("61414100734933".to_string()),
+                lot: Some("LOT123".to_string()),
+                quantity: Some("12".to_string()),
+                unit: Some("CA".to_string()),
+                pack_date: Some("20030110".to_string()),
+            }
+        );
+        let codes: Vec<String> = parsed.iter().map(|item| item.voice_code().unwrap().voice_code).collect();
+        assert_eq!(codes, ["6991", "0065"]);
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        // UPC-A before a GTIN-14 still takes the first GTIN, other dates and segments ignored
+        let asn = "LIN**UP*614141007349*LT*32abcd\r\n~DTM*011*20030105~DTM*094*030102~LIN*2*VN*ABC~";
+        let items = parse_items(asn);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].line, "");
+        assert_eq!(items[0].gtin.as_deref(), Some("614141007349"));
+        assert_eq!(items[0].pack_date.as_deref(), Some("030102"));
+        assert_eq!(items[0].record().unwrap().pack_date, "030102");
+        assert_eq!(items[1].voice_code().unwrap_err(), VoiceCodeError::MissingField { field: "gtin" });
+        assert!(matches!(items[1].verify("6991"), VerifyResult::Invalid(_)));
+        assert!(parse_items("").is_empty());
+    }
+
+    #[test]
+    fn test_ccyymmdd() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "99", "02", "30").unwrap();
+        assert_eq!(ccyymmdd(&voice_code.pack_date()), "19990230");
+    }
+}
@@ -0,0 +1,129 @@
+//! Voice codes in fixed size buffers, for microcontrollers without an allocator
+//!
+//! Works without the `std` feature. The GTIN is checked for length and digits like
+//! `Gtin::parse_unchecked`, its check digit is not verified, the LOT is checked against the
+//! PTI set like `HashVoiceCode::new` and the pack date must be six digits as printed in AI(13).
+
+use core::fmt;
+
+use heapless::String;
+
+use crate::gtin::is_valid_gtin;
+use crate::hash::hash_into;
+use crate::lot::{is_valid_lot, MAX_LOT_LEN};
+
+/// Longest hashed text, a GTIN-14, a `MAX_LOT_LEN` LOT and YYMMDD
+pub const MAX_HASH_TEXT_LEN: usize = 14 + MAX_LOT_LEN + 6;
+
+/// Which input was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// GTIN is not 8, 12, 13 or 14 numeric digits
+    InvalidGtin,
+    /// LOT is empty, too long or contains a character outside the PTI set
+    InvalidLot,
+    /// Pack date is not six numeric digits
+    InvalidPackDate,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidGtin => f.write_str("GTIN must be numeric 8, 12, 13 or 14 digits"),
+            Error::InvalidLot => f.write_str("LOT must be 1 to 20 characters from the PTI set"),
+            Error::InvalidPackDate => f.write_str("Pack date must be numeric YYMMDD"),
+        }
+    }
+}
+
+/// Voice code and the text it was hashed from, without heap allocation
+///
+/// # Example
+/// ```
+/// use voicecode::embedded::VoiceCode;
+/// let voice_code = VoiceCode::new("12345678901244", "LOT123", "030102").unwrap();
+/// assert_eq!(voice_code.hash_text, "12345678901244LOT123030102");
+/// assert_eq!(&voice_code.voice_code, b"6991");
+/// assert_eq!((voice_code.minor(), voice_code.major()), ("69", "91"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VoiceCode {
+    pub hash_text: String<MAX_HASH_TEXT_LEN>,
+    /// The four ASCII digits
+    pub voice_code: [u8; 4],
+}
+
+impl VoiceCode {
+    /// Validate the inputs and compute the voice code
+    pub fn new(gtin: &str, lot: &str, yymmdd: &str) -> Result<Self, Error> {
+        if !is_valid_gtin(gtin.as_bytes()) {
+            return Err(Error::InvalidGtin);
+        }
+        if !is_valid_lot(lot.as_bytes()) {
+            return Err(Error::InvalidLot);
+        }
+        if yymmdd.len() != 6 || !yymmdd.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidPackDate);
+        }
+
+        let mut hash_text = String::new();
+        for part in [gtin, lot, yymmdd] {
+            hash_text.push_str(part).expect("validated parts fit MAX_HASH_TEXT_LEN");
+        }
+        let mut voice_code = [0; 4];
+        hash_into(hash_text.as_bytes(), &mut voice_code);
+        Ok(VoiceCode { hash_text, voice_code })
+    }
+
+    /// The four digits as text
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.voice_code).expect("voice code is ASCII digits")
+    }
+
+    /// Last two digits, printed large on the label
+    pub fn major(&self) -> &str {
+        &self.as_str()[2..]
+    }
+
+    /// First two digits, printed small on the label
+    pub fn minor(&self) -> &str {
+        &self.as_str()[..2]
+    }
+}
+
+impl fmt::Display for VoiceCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+    use crate::voicecode::HashVoiceCode;
+
+    #[test]
+    fn test_matches_hash_voice_code() {
+        let lot = "ABCDEFGHIJKLMNOPQRST";
+        for (gtin, lot, yymmdd) in [("12345678901244", "LOT123", "030102"), ("61414100734933", lot, "991231"), ("12345670", "a", "000000")] {
+            let voice_code = VoiceCode::new(gtin, lot, yymmdd).unwrap();
+            let expected = HashVoiceCode::new(Gtin::parse_unchecked(gtin).unwrap(), lot, &yymmdd[0..2], &yymmdd[2..4], &yymmdd[4..6]).unwrap();
+            assert_eq!(voice_code.hash_text.as_str(), expected.hash_text);
+            assert_eq!(voice_code.to_string(), expected.voice_code);
+            assert_eq!(voice_code.major(), expected.voice_code_major);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(VoiceCode::new("1234567890124", "LOT123", "030102").map(|_| ()), Ok(()));
+        assert_eq!(VoiceCode::new("123456789012445", "LOT123", "030102").unwrap_err(), Error::InvalidGtin);
+        assert_eq!(VoiceCode::new("1234567890124A", "LOT123", "030102").unwrap_err(), Error::InvalidGtin);
+        assert_eq!(VoiceCode::new("12345678901244", "", "030102").unwrap_err(), Error::InvalidLot);
+        assert_eq!(VoiceCode::new("12345678901244", "ABCDEFGHIJKLMNOPQRSTU", "030102").unwrap_err(), Error::InvalidLot);
+        assert_eq!(VoiceCode::new("12345678901244", "LOT 123", "030102").unwrap_err(), Error::InvalidLot);
+        assert_eq!(VoiceCode::new("12345678901244", "LOT123", "03012").unwrap_err(), Error::InvalidPackDate);
+        assert_eq!(VoiceCode::new("12345678901244", "LOT123", "03-1-2").unwrap_err(), Error::InvalidPackDate);
+    }
+}
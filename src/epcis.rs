@@ -0,0 +1,250 @@
+//! EPCIS 2.0 JSON-LD events for packed cases and pallets
+//!
+//! Reference: [EPCIS 2.0 and CBV 2.0](https://ref.gs1.org/standards/epcis/)
+//!
+//! - `commissioning_event` is an `ObjectEvent` that creates a quantity of cases of one
+//!   GTIN and lot, `bizStep` commissioning and `disposition` active, with the lot in `ilmd`
+//! - `aggregation_event` is an `AggregationEvent` that packs cases onto a pallet SSCC,
+//!   `bizStep` packing and `disposition` in_progress
+//! - `document` wraps events in an `EPCISDocument` for capture
+//!
+//! Cases are identified by class, `urn:epc:class:lgtin:` company prefix, indicator and item
+//! reference, lot, since PTI labels carry no serial. Splitting the GTIN needs the company
+//! prefix length, taken from `EpcisOptions::company_prefix_len` or `Gtin::company_prefix_len`.
+
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use serde_json::{json, Map, Value};
+
+use crate::error::VoiceCodeError;
+use crate::gtin::{Gtin, DEFAULT_COMPANY_PREFIX_LEN};
+use crate::lot::Lot;
+use crate::sscc::Sscc;
+use crate::voicecode::HashVoiceCode;
+
+/// JSON-LD context of every EPCIS 2.0 document
+pub const CONTEXT: &str = "https://ref.gs1.org/standards/epcis/epcis-context.jsonld";
+
+/// Where and how events are recorded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EpcisOptions {
+    /// GS1 company prefix length, 6 to 12, when the GTIN's own lookup would be wrong
+    pub company_prefix_len: Option<usize>,
+    /// `readPoint`, usually an SGLN URI such as `urn:epc:id:sgln:0614141.00777.0`
+    pub read_point: Option<String>,
+    /// `bizLocation`, usually an SGLN URI
+    pub biz_location: Option<String>,
+}
+
+impl EpcisOptions {
+    fn prefix_len(&self, gtin: &Gtin) -> usize {
+        self.company_prefix_len.unwrap_or_else(|| gtin.company_prefix_len())
+    }
+}
+
+/// EPC class URI for a GTIN and lot
+///
+/// # Example
+/// ```
+/// use voicecode::{ Gtin, Lot };
+/// use voicecode::epcis::lgtin;
+/// let gtin = Gtin::parse("10614141007346").unwrap();
+/// let lot = Lot::parse("32A/B").unwrap();
+/// assert_eq!(lgtin(&gtin, &lot, 7), "urn:epc:class:lgtin:0614141.100734.32A%2FB");
+/// ```
+pub fn lgtin(gtin: &Gtin, lot: &Lot, company_prefix_len: usize) -> String {
+    let gtin14 = gtin.to_gtin14();
+    let prefix_len = company_prefix_len.clamp(6, 12);
+    let (prefix, rest) = gtin14[1..13].split_at(prefix_len);
+    format!("urn:epc:class:lgtin:{}.{}{}.{}", prefix, &gtin14[..1], rest, escape(lot.as_str()))
+}
+
+/// EPC URI for an SSCC
+///
+/// # Example
+/// ```
+/// use voicecode::epcis::sscc;
+/// use voicecode::sscc::Sscc;
+/// let pallet = Sscc::parse("106141411234567897").unwrap();
+/// assert_eq!(sscc(&pallet, 7).unwrap(), "urn:epc:id:sscc:0614141.1123456789");
+/// ```
+pub fn sscc(sscc: &Sscc, company_prefix_len: usize) -> Result<String, VoiceCodeError> {
+    if !(6..=12).contains(&company_prefix_len) {
+        return Err(VoiceCodeError::InvalidSscc);
+    }
+    let digits = sscc.as_str();
+    let (prefix, serial) = digits[1..17].split_at(company_prefix_len);
+    Ok(format!("urn:epc:id:sscc:{}.{}{}", prefix, &digits[..1], serial))
+}
+
+/// Escape the characters EPC URIs reserve, the rest of the GS1 AI 82 set stays literal
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' | '%' | '&' | '/' | '<' | '>' | '?' => out.push_str(&format!("%{:02X}", ch as u32)),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Fields every event carries, in the order EPCIS examples list them
+fn event(kind: &str, event_time: &DateTime<FixedOffset>) -> Map<String, Value> {
+    let mut event = Map::new();
+    event.insert("type".to_string(), kind.into());
+    event.insert("eventTime".to_string(), event_time.to_rfc3339_opts(SecondsFormat::Millis, true).into());
+    event.insert("eventTimeZoneOffset".to_string(), event_time.format("%:z").to_string().into());
+    event
+}
+
+fn locations(event: &mut Map<String, Value>, options: &EpcisOptions) {
+    if let Some(read_point) = &options.read_point {
+        event.insert("readPoint".to_string(), json!({ "id": read_point }));
+    }
+    if let Some(biz_location) = &options.biz_location {
+        event.insert("bizLocation".to_string(), json!({ "id": biz_location }));
+    }
+}
+
+/// `ObjectEvent` commissioning `quantity` cases of a labeled GTIN and lot
+///
+/// # Example
+/// ```
+/// use chrono::DateTime;
+/// use voicecode::HashVoiceCode;
+/// use voicecode::epcis::{ commissioning_event, EpcisOptions };
+/// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+/// let time = DateTime::parse_from_rfc3339("2003-01-02T08:30:00-08:00").unwrap();
+/// let event = commissioning_event(&voice_code, 40, &time, &EpcisOptions::default());
+/// assert_eq!(event["type"], "ObjectEvent");
+/// assert_eq!(event["eventTimeZoneOffset"], "-08:00");
+/// assert_eq!(event["quantityList"][0]["epcClass"], "urn:epc:class:lgtin:0614141.100734.LOT123");
+/// assert_eq!(event["quantityList"][0]["quantity"], 40);
+/// ```
+pub fn commissioning_event(
+    voice_code: &HashVoiceCode,
+    quantity: u32,
+    event_time: &DateTime<FixedOffset>,
+    options: &EpcisOptions,
+) -> Value {
+    let class = lgtin(&voice_code.gtin, &voice_code.lot, options.prefix_len(&voice_code.gtin));
+    let mut event = event("ObjectEvent", event_time);
+    event.insert("epcList".to_string(), json!([]));
+    event.insert("quantityList".to_string(), json!([{ "epcClass": class, "quantity": quantity }]));
+    event.insert("action".to_string(), "ADD".into());
+    event.insert("bizStep".to_string(), "commissioning".into());
+    event.insert("disposition".to_string(), "active".into());
+    locations(&mut event, options);
+    event.insert("ilmd".to_string(), json!({ "cbvmda:lotNumber": voice_code.lot.as_str() }));
+    Value::Object(event)
+}
+
+/// `AggregationEvent` packing cases onto a pallet, `cases` pairs a labeled case with its count
+///
+/// # Example
+/// ```
+/// use chrono::DateTime;
+/// use voicecode::HashVoiceCode;
+/// use voicecode::epcis::{ aggregation_event, EpcisOptions };
+/// use voicecode::sscc::Sscc;
+/// let pallet = Sscc::parse("106141411234567897").unwrap();
+/// let case = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+/// let time = DateTime::parse_from_rfc3339("2003-01-02T16:30:00Z").unwrap();
+/// let event = aggregation_event(&pallet, &[(&case, 40)], &time, &EpcisOptions::default()).unwrap();
+/// assert_eq!(event["parentID"], "urn:epc:id:sscc:0614141.1123456789");
+/// assert_eq!(event["childQuantityList"][0]["quantity"], 40);
+/// assert_eq!(event["eventTime"], "2003-01-02T16:30:00.000Z");
+/// ```
+pub fn aggregation_event(
+    pallet: &Sscc,
+    cases: &[(&HashVoiceCode, u32)],
+    event_time: &DateTime<FixedOffset>,
+    options: &EpcisOptions,
+) -> Result<Value, VoiceCodeError> {
+    // an SSCC does not say how long its prefix is, so the case GTINs stand in for it
+    let prefix_len = match cases.first() {
+        Some((case, _)) => options.prefix_len(&case.gtin),
+        None => options.company_prefix_len.unwrap_or(DEFAULT_COMPANY_PREFIX_LEN),
+    };
+    let children: Vec<Value> = cases
+        .iter()
+        .map(|(case, quantity)| {
+            let class = lgtin(&case.gtin, &case.lot, options.prefix_len(&case.gtin));
+            json!({ "epcClass": class, "quantity": quantity })
+        })
+        .collect();
+
+    let mut event = event("AggregationEvent", event_time);
+    event.insert("parentID".to_string(), sscc(pallet, prefix_len)?.into());
+    event.insert("childEPCs".to_string(), json!([]));
+    event.insert("childQuantityList".to_string(), children.into());
+    event.insert("action".to_string(), "ADD".into());
+    event.insert("bizStep".to_string(), "packing".into());
+    event.insert("disposition".to_string(), "in_progress".into());
+    locations(&mut event, options);
+    Ok(Value::Object(event))
+}
+
+/// `EPCISDocument` holding `events`, ready to POST to a capture interface
+///
+/// # Example
+/// ```
+/// use chrono::DateTime;
+/// use voicecode::epcis::document;
+/// let created = DateTime::parse_from_rfc3339("2003-01-02T17:00:00Z").unwrap();
+/// let doc = document(Vec::new(), &created);
+/// assert_eq!(doc["type"], "EPCISDocument");
+/// assert_eq!(doc["schemaVersion"], "2.0");
+/// assert!(doc["epcisBody"]["eventList"].as_array().unwrap().is_empty());
+/// ```
+pub fn document(events: Vec<Value>, creation_date: &DateTime<FixedOffset>) -> Value {
+    json!({
+        "@context": [CONTEXT],
+        "type": "EPCISDocument",
+        "schemaVersion": "2.0",
+        "creationDate": creation_date.to_rfc3339_opts(SecondsFormat::Millis, true),
+        "epcisBody": { "eventList": events },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers() {
+        let gtin = Gtin::parse("614141007349").unwrap();
+        let lot = Lot::parse(r#"A"%&/<>?!(B)"#).unwrap();
+        assert_eq!(lgtin(&gtin, &lot, 7), "urn:epc:class:lgtin:0614141.000734.A%22%25%26%2F%3C%3E%3F!(B)");
+        assert_eq!(lgtin(&gtin, &lot, 12), "urn:epc:class:lgtin:061414100734.0.A%22%25%26%2F%3C%3E%3F!(B)");
+
+        let pallet = Sscc::parse("106141411234567897").unwrap();
+        assert_eq!(sscc(&pallet, 12).unwrap(), "urn:epc:id:sscc:061414112345.16789");
+        assert_eq!(sscc(&pallet, 5).unwrap_err(), VoiceCodeError::InvalidSscc);
+    }
+
+    #[test]
+    fn test_document() {
+        let case = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "32abcd", "03", "01", "02").unwrap();
+        let time = DateTime::parse_from_rfc3339("2003-01-02T08:30:00+02:00").unwrap();
+        let options = EpcisOptions {
+            company_prefix_len: Some(9),
+            read_point: Some("urn:epc:id:sgln:061414100.000.0".to_string()),
+            biz_location: None,
+        };
+        let pallet = Sscc::generate(0, "061414100", 7).unwrap();
+        let events = vec![
+            commissioning_event(&case, 12, &time, &options),
+            aggregation_event(&pallet, &[(&case, 12)], &time, &options).unwrap(),
+        ];
+        let doc = document(events, &time);
+        let events = doc["epcisBody"]["eventList"].as_array().unwrap();
+        assert_eq!(events[0]["eventTime"], "2003-01-02T08:30:00.000+02:00");
+        assert_eq!(events[0]["readPoint"]["id"], "urn:epc:id:sgln:061414100.000.0");
+        assert!(events[0].get("bizLocation").is_none());
+        assert_eq!(events[0]["ilmd"]["cbvmda:lotNumber"], "32abcd");
+        assert_eq!(events[1]["parentID"], "urn:epc:id:sscc:061414100.00000007");
+        assert_eq!(events[1]["childQuantityList"][0]["epcClass"], "urn:epc:class:lgtin:141410073.6493.32abcd");
+        assert_eq!(doc["@context"][0], CONTEXT);
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::gs1::Gs1Error;
+use crate::gtin::GtinError;
+use crate::lint::Lint;
+use crate::lot::LotError;
+
+/// Identifies which pack date component failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateComponent {
+    Year,
+    Month,
+    Day,
+}
+
+impl DateComponent {
+    /// Short label used in messages, YY, MM or DD
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateComponent::Year => "YY",
+            DateComponent::Month => "MM",
+            DateComponent::Day => "DD",
+        }
+    }
+}
+
+impl fmt::Display for DateComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Errors returned when building a voice code
+///
+/// # Example
+/// ```
+/// use voicecode::{ DateComponent, HashVoiceCode, VoiceCodeError };
+/// let result = HashVoiceCode::new("12345678901244", "LOT123", "03", "mm", "02");
+/// assert_eq!(result.unwrap_err(), VoiceCodeError::InvalidDateComponent { which: DateComponent::Month });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VoiceCodeError {
+    /// GTIN is not 8, 12, 13 or 14 numeric digits, or cannot be converted as asked
+    InvalidGtin { reason: GtinError },
+    /// GTIN check digit does not match the one computed from the other digits
+    InvalidGtinCheckDigit { expected: u8, found: u8 },
+    /// LOT is empty, too long or contains a character outside the PTI set
+    InvalidLot { reason: LotError },
+    /// A pack date component is not 1 or 2 numeric digits
+    InvalidDateComponent { which: DateComponent },
+    /// Pack date parts do not form a calendar date, only reported in strict mode
+    InvalidCalendarDate { yymmdd: String },
+    /// Date text is in none of the formats `PackDate::parse_flexible` accepts
+    UnrecognizedDate { input: String },
+    /// Date text reads as two different dates and the policy is `DateAmbiguity::Reject`
+    AmbiguousDate { input: String },
+    /// GS1 element string could not be parsed or lacks a required AI
+    InvalidGs1 { reason: Gs1Error },
+    /// A required builder field was not set
+    MissingField { field: &'static str },
+    /// Text to hash has a character outside ASCII and the policy is `NonAsciiPolicy::Reject`
+    NonAscii { position: usize },
+    /// SSCC is not 18 numeric digits, or its parts do not fit
+    InvalidSscc,
+    /// SSCC check digit does not match the one computed from the other digits
+    InvalidSsccCheckDigit { expected: u8, found: u8 },
+    /// Voice code is not four numeric digits
+    InvalidVoiceCode,
+    /// A lint set to `LintLevel::Deny` matched otherwise valid inputs
+    LintDenied { lint: Lint, message: String },
+}
+
+impl VoiceCodeError {
+    /// Stable identifier for APIs and UIs, the `Display` text may be reworded between releases
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// let err = HashVoiceCode::new("12345678901244", "LOT#123", "03", "01", "02").unwrap_err();
+    /// assert_eq!(err.code(), "lot_invalid_character");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            VoiceCodeError::InvalidGtin { reason } => reason.code(),
+            VoiceCodeError::InvalidGtinCheckDigit { .. } => "gtin_check_digit",
+            VoiceCodeError::InvalidLot { reason } => reason.code(),
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Year } => "date_yy_format",
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Month } => "date_mm_format",
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Day } => "date_dd_format",
+            VoiceCodeError::InvalidCalendarDate { .. } => "date_not_calendar",
+            VoiceCodeError::UnrecognizedDate { .. } => "date_unrecognized",
+            VoiceCodeError::AmbiguousDate { .. } => "date_ambiguous",
+            VoiceCodeError::InvalidGs1 { .. } => "gs1_invalid",
+            VoiceCodeError::MissingField { .. } => "missing_field",
+            VoiceCodeError::NonAscii { .. } => "non_ascii",
+            VoiceCodeError::InvalidSscc => "sscc_format",
+            VoiceCodeError::InvalidSsccCheckDigit { .. } => "sscc_check_digit",
+            VoiceCodeError::InvalidVoiceCode => "voice_code_format",
+            VoiceCodeError::LintDenied { lint, .. } => lint.code(),
+        }
+    }
+}
+
+impl fmt::Display for VoiceCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoiceCodeError::InvalidGtin { reason } => write!(f, "{}", reason),
+            VoiceCodeError::InvalidGtinCheckDigit { expected, found } => {
+                write!(f, "GTIN check digit should be {} but is {}", expected, found)
+            }
+            VoiceCodeError::InvalidLot { reason } => write!(f, "{}", reason),
+            VoiceCodeError::InvalidDateComponent { which } => {
+                write!(f, "Date component {} must be numeric and 1 or 2 digits", which)
+            }
+            VoiceCodeError::InvalidCalendarDate { yymmdd } => write!(f, "Pack date {} is not a calendar date", yymmdd),
+            VoiceCodeError::UnrecognizedDate { input } => write!(f, "Pack date {:?} is not in a recognized format", input),
+            VoiceCodeError::AmbiguousDate { input } => write!(f, "Pack date {:?} reads as more than one date", input),
+            VoiceCodeError::InvalidGs1 { reason } => write!(f, "{}", reason),
+            VoiceCodeError::MissingField { field } => write!(f, "{} is required", field),
+            VoiceCodeError::NonAscii { position } => write!(f, "Non ASCII character at byte {}", position),
+            VoiceCodeError::InvalidSscc => write!(f, "SSCC must be numeric 18 digits"),
+            VoiceCodeError::InvalidSsccCheckDigit { expected, found } => {
+                write!(f, "SSCC check digit should be {} but is {}", expected, found)
+            }
+            VoiceCodeError::InvalidVoiceCode => write!(f, "Voice code must be numeric 4 digits"),
+            VoiceCodeError::LintDenied { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for VoiceCodeError {}
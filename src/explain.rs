@@ -0,0 +1,128 @@
+//! Step by step trace of the voice code hash, for training material and for tracking down
+//! mismatches against other implementations
+
+use std::fmt;
+
+use crate::hash::{HASH_VOICE_CHECKSUM_HASH_T, VOICE_CODE_MODULUS};
+
+/// One input byte going through the CRC
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CrcStep {
+    /// Byte offset into the hashed text
+    pub offset: usize,
+    pub byte: u8,
+    /// CRC register before this byte
+    pub crc_before: u16,
+    /// Low byte of the register XOR the input byte
+    pub table_index: u8,
+    /// Table entry at `table_index`
+    pub table_value: u16,
+    /// `(crc_before >> 8) ^ table_value`
+    pub crc_after: u16,
+}
+
+/// How a voice code was derived from its hashed text
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let explanation = voice_code.explain();
+/// assert_eq!(explanation.steps.len(), 26);
+/// assert_eq!(explanation.crc % 10000, explanation.code);
+/// assert_eq!((explanation.minor, explanation.major), (69, 91));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Explanation {
+    pub hash_text: String,
+    pub steps: Vec<CrcStep>,
+    /// CRC-16/ARC after the last byte
+    pub crc: u16,
+    /// `crc % 10000`
+    pub code: u16,
+    /// First two digits of `code`, printed small
+    pub minor: u8,
+    /// Last two digits of `code`, printed large
+    pub major: u8,
+}
+
+impl Explanation {
+    /// Trace the hash of `hash_text` byte by byte, non ASCII text is traced as its UTF-8 bytes
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::explain::Explanation;
+    /// let explanation = Explanation::new("12345678901244LOT123030102");
+    /// assert_eq!(explanation.code, 6991);
+    /// assert_eq!(explanation.steps[0].crc_before, 0);
+    /// ```
+    pub fn new(hash_text: &str) -> Self {
+        let mut crc = 0u16;
+        let steps: Vec<CrcStep> = hash_text
+            .bytes()
+            .enumerate()
+            .map(|(offset, byte)| {
+                let table_index = ((crc ^ u16::from(byte)) & 0xff) as u8;
+                let table_value = HASH_VOICE_CHECKSUM_HASH_T[table_index as usize];
+                let step = CrcStep { offset, byte, crc_before: crc, table_index, table_value, crc_after: (crc >> 8) ^ table_value };
+                crc = step.crc_after;
+                step
+            })
+            .collect();
+        let code = crc % VOICE_CODE_MODULUS;
+
+        Explanation {
+            hash_text: hash_text.to_string(),
+            steps,
+            crc,
+            code,
+            minor: (code / 100) as u8,
+            major: (code % 100) as u8,
+        }
+    }
+}
+
+/// A table with one row per byte followed by the final CRC, the modulo and the split
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "text: {}", self.hash_text)?;
+        writeln!(f, "offset  char  byte  crc in  index  table   crc out")?;
+        for step in &self.steps {
+            let ch = if step.byte.is_ascii_graphic() { step.byte as char } else { '.' };
+            writeln!(
+                f,
+                "{:>6}  {:>4}  0x{:02X}  0x{:04X}   0x{:02X}  0x{:04X}  0x{:04X}",
+                step.offset, ch, step.byte, step.crc_before, step.table_index, step.table_value, step.crc_after
+            )?;
+        }
+        writeln!(f, "crc: 0x{:04X} ({})", self.crc, self.crc)?;
+        writeln!(f, "code: {} % {} = {:04}", self.crc, VOICE_CODE_MODULUS, self.code)?;
+        write!(f, "minor: {:02}, major: {:02}", self.minor, self.major)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::crc_u16;
+
+    #[test]
+    fn test_matches_hash() {
+        for text in ["", "123456789", "61414100734933LOT123030110", "00614141007349ABCDEFGHIJKLMNOPQRST991231"] {
+            let explanation = Explanation::new(text);
+            assert_eq!(explanation.crc, crc_u16(text.as_bytes()));
+            assert_eq!(explanation.steps.last().map_or(0, |step| step.crc_after), explanation.crc);
+            assert!(explanation.steps.windows(2).all(|pair| pair[0].crc_after == pair[1].crc_before));
+        }
+        assert_eq!(Explanation::new("123456789").crc, 0xBB3D);
+    }
+
+    #[test]
+    fn test_display() {
+        let text = Explanation::new("61414100734933LOT123030110").to_string();
+        assert!(text.ends_with("% 10000 = 0065\nminor: 00, major: 65"));
+        assert_eq!(text.lines().count(), 2 + 26 + 3);
+    }
+}
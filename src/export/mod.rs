@@ -0,0 +1,3 @@
+//! Voice codes in the import formats of voice picking hardware
+
+pub mod vocollect;
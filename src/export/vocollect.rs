@@ -0,0 +1,101 @@
+//! Pick tasks for a Honeywell Vocollect A700 host import
+//!
+//! One pipe delimited `PICK` record per task, CRLF terminated, with the voice code in the
+//! check digits field the picker speaks to confirm the case:
+//!
+//! `PICK|assignment|location|GTIN-14|LOT|YYMMDD|quantity|check digits`
+
+use std::io;
+
+use crate::voicecode::HashVoiceCode;
+
+/// One case line the picker is sent to
+#[derive(Clone)]
+pub struct PickTask {
+    /// Assignment or work id the host groups tasks under
+    pub assignment: String,
+    /// Slot the picker is directed to
+    pub location: String,
+    pub quantity: u32,
+    pub voice_code: HashVoiceCode,
+}
+
+impl PickTask {
+    pub fn new(assignment: &str, location: &str, quantity: u32, voice_code: HashVoiceCode) -> Self {
+        PickTask { assignment: assignment.to_string(), location: location.to_string(), quantity, voice_code }
+    }
+
+    /// The record for this task without its line ending
+    ///
+    /// # Errors
+    /// `InvalidInput` when the assignment or location contains `|` or a line break
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// use voicecode::export::vocollect::PickTask;
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// let task = PickTask::new("A1001", "01-014-B", 12, voice_code);
+    /// assert_eq!(task.record().unwrap(), "PICK|A1001|01-014-B|12345678901244|LOT123|030102|12|6991");
+    /// ```
+    pub fn record(&self) -> io::Result<String> {
+        let voice_code = &self.voice_code;
+        Ok(format!(
+            "PICK|{}|{}|{}|{}|{}|{}|{}",
+            field("assignment", &self.assignment)?,
+            field("location", &self.location)?,
+            voice_code.gtin.to_gtin14(),
+            voice_code.lot,
+            voice_code.pack_date().yymmdd(),
+            self.quantity,
+            voice_code.voice_code,
+        ))
+    }
+}
+
+/// Free text field checked for the delimiter, GTIN, LOT and date can never contain one
+fn field<'a>(name: &str, value: &'a str) -> io::Result<&'a str> {
+    if value.contains(['|', '\r', '\n']) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} must not contain '|' or a line break", name)));
+    }
+    Ok(value)
+}
+
+/// Write an import file, nothing is written past the first invalid task
+pub fn write<'a, I, W>(tasks: I, mut output: W) -> io::Result<()>
+where
+    I: IntoIterator<Item = &'a PickTask>,
+    W: io::Write,
+{
+    for task in tasks {
+        let record = task.record()?;
+        output.write_all(record.as_bytes())?;
+        output.write_all(b"\r\n")?;
+    }
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+
+    #[test]
+    fn test_write() {
+        let tasks = [
+            PickTask::new("A1", "01-001", 1, HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "32abcd", "03", "01", "02").unwrap()),
+            PickTask::new("A1", "01-002", 3, HashVoiceCode::new_no_lot(Gtin::parse_unchecked("61414100734933").unwrap(), ("03", "01", "02")).unwrap()),
+        ];
+        let mut out = Vec::new();
+        write(&tasks, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "PICK|A1|01-001|61414100734933|32abcd|030102|1|8079\r\nPICK|A1|01-002|61414100734933||030102|3|7010\r\n"
+        );
+
+        let bad = PickTask::new("A|1", "01-001", 1, tasks[0].voice_code.clone());
+        let mut out = Vec::new();
+        assert_eq!(write([&tasks[0], &bad], &mut out).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(out.len(), "PICK|A1|01-001|61414100734933|32abcd|030102|1|8079\r\n".len());
+    }
+}
@@ -0,0 +1,157 @@
+//! C API for calling the voice code calculator from C and C++
+//!
+//! Build a shared or static library with
+//! `cargo rustc --lib --release --features capi --crate-type cdylib` (or `staticlib`)
+//! and include `include/voicecode.h`, regenerate the header with `cbindgen --output include/voicecode.h`.
+//!
+//! ```c
+//! char code[5];
+//! VoiceCodeStatus status = voicecode_compute("12345678901244", "LOT123", "030102", code);
+//! if (status == VOICE_CODE_STATUS_OK) {
+//!     printf("%s\n", code); // 6991
+//! } else {
+//!     printf("%s\n", voicecode_status_message(status));
+//! }
+//! ```
+
+use std::ffi::{c_char, CStr};
+
+use crate::error::VoiceCodeError;
+use crate::pack_date::PackDate;
+use crate::validation::Field;
+use crate::voicecode::HashVoiceCode;
+
+/// Result of a C API call, 0 is success
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoiceCodeStatus {
+    Ok = 0,
+    /// An argument was NULL
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// GTIN is not 8, 12, 13 or 14 numeric digits
+    InvalidGtin = 3,
+    /// LOT is empty, too long or contains a character outside the PTI set
+    InvalidLot = 4,
+    /// Pack date is not six numeric digits
+    InvalidDate = 5,
+    /// Any other validation failure
+    Other = 6,
+}
+
+impl From<&VoiceCodeError> for VoiceCodeStatus {
+    fn from(value: &VoiceCodeError) -> Self {
+        match value {
+            VoiceCodeError::InvalidGtin { .. } | VoiceCodeError::InvalidGtinCheckDigit { .. } => VoiceCodeStatus::InvalidGtin,
+            VoiceCodeError::InvalidLot { .. } => VoiceCodeStatus::InvalidLot,
+            VoiceCodeError::InvalidDateComponent { .. }
+            | VoiceCodeError::InvalidCalendarDate { .. }
+            | VoiceCodeError::UnrecognizedDate { .. }
+            | VoiceCodeError::AmbiguousDate { .. } => VoiceCodeStatus::InvalidDate,
+            VoiceCodeError::InvalidGs1 { .. }
+            | VoiceCodeError::MissingField { .. }
+            | VoiceCodeError::NonAscii { .. }
+            | VoiceCodeError::InvalidSscc
+            | VoiceCodeError::InvalidSsccCheckDigit { .. }
+            | VoiceCodeError::InvalidVoiceCode => VoiceCodeStatus::Other,
+            VoiceCodeError::LintDenied { lint, .. } => match lint.field() {
+                Field::Gtin => VoiceCodeStatus::InvalidGtin,
+                Field::Lot => VoiceCodeStatus::InvalidLot,
+                _ => VoiceCodeStatus::InvalidDate,
+            },
+        }
+    }
+}
+
+/// Compute the voice code for a GTIN, LOT and YYMMDD pack date
+///
+/// On success `out` holds the four digits and a terminating NUL, on failure `out` holds
+/// an empty string when it is not NULL.
+///
+/// # Safety
+/// `gtin`, `lot` and `yymmdd` must be NULL or point to NUL terminated strings, `out` must be
+/// NULL or point to at least 5 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn voicecode_compute(
+    gtin: *const c_char,
+    lot: *const c_char,
+    yymmdd: *const c_char,
+    out: *mut c_char,
+) -> VoiceCodeStatus {
+    if out.is_null() {
+        return VoiceCodeStatus::NullArgument;
+    }
+    *out = 0;
+
+    let (gtin, lot, yymmdd) = match (to_str(gtin), to_str(lot), to_str(yymmdd)) {
+        (Ok(gtin), Ok(lot), Ok(yymmdd)) => (gtin, lot, yymmdd),
+        (Err(status), _, _) | (_, Err(status), _) | (_, _, Err(status)) => return status,
+    };
+
+    let voice_code = match PackDate::parse_yymmdd(yymmdd).and_then(|pack_date| {
+        HashVoiceCode::builder().gtin(gtin).lot(lot).pack_date(pack_date).build()
+    }) {
+        Ok(voice_code) => voice_code,
+        Err(e) => return VoiceCodeStatus::from(&e),
+    };
+
+    let out = std::slice::from_raw_parts_mut(out, 5);
+    for (dst, src) in out.iter_mut().zip(voice_code.voice_code.bytes()) {
+        *dst = src as c_char;
+    }
+    out[4] = 0;
+    VoiceCodeStatus::Ok
+}
+
+/// Static description of a status, never NULL and never freed
+#[no_mangle]
+pub extern "C" fn voicecode_status_message(status: VoiceCodeStatus) -> *const c_char {
+    let message = match status {
+        VoiceCodeStatus::Ok => c"ok",
+        VoiceCodeStatus::NullArgument => c"argument is NULL",
+        VoiceCodeStatus::InvalidUtf8 => c"argument is not valid UTF-8",
+        VoiceCodeStatus::InvalidGtin => c"GTIN must be numeric 8, 12, 13 or 14 digits",
+        VoiceCodeStatus::InvalidLot => c"LOT must be 1 to 20 characters from the PTI set",
+        VoiceCodeStatus::InvalidDate => c"pack date must be numeric YYMMDD",
+        VoiceCodeStatus::Other => c"invalid input",
+    };
+    message.as_ptr()
+}
+
+unsafe fn to_str<'a>(ptr: *const c_char) -> Result<&'a str, VoiceCodeStatus> {
+    if ptr.is_null() {
+        return Err(VoiceCodeStatus::NullArgument);
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|_| VoiceCodeStatus::InvalidUtf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compute(gtin: &CStr, lot: &CStr, yymmdd: &CStr) -> (VoiceCodeStatus, String) {
+        let mut out = [1 as c_char; 5];
+        let status = unsafe { voicecode_compute(gtin.as_ptr(), lot.as_ptr(), yymmdd.as_ptr(), out.as_mut_ptr()) };
+        let code = unsafe { CStr::from_ptr(out.as_ptr()) }.to_str().unwrap().to_string();
+        (status, code)
+    }
+
+    #[test]
+    fn test_compute() {
+        assert_eq!(compute(c"12345678901244", c"LOT123", c"030102"), (VoiceCodeStatus::Ok, "6991".to_string()));
+        assert_eq!(compute(c"1234", c"LOT123", c"030102"), (VoiceCodeStatus::InvalidGtin, String::new()));
+        assert_eq!(compute(c"12345678901244", c"LOT 123", c"030102"), (VoiceCodeStatus::InvalidLot, String::new()));
+        assert_eq!(compute(c"12345678901244", c"LOT123", c"0301"), (VoiceCodeStatus::InvalidDate, String::new()));
+        assert_eq!(compute(c"12345678901244", c"LOT\xff", c"030102"), (VoiceCodeStatus::InvalidUtf8, String::new()));
+    }
+
+    #[test]
+    fn test_null() {
+        let mut out = [0 as c_char; 5];
+        let status = unsafe { voicecode_compute(std::ptr::null(), c"LOT123".as_ptr(), c"030102".as_ptr(), out.as_mut_ptr()) };
+        assert_eq!(status, VoiceCodeStatus::NullArgument);
+        let status = unsafe { voicecode_compute(c"12345678901244".as_ptr(), c"LOT123".as_ptr(), c"030102".as_ptr(), std::ptr::null_mut()) };
+        assert_eq!(status, VoiceCodeStatus::NullArgument);
+    }
+}
@@ -0,0 +1,134 @@
+//! Entry points for cargo-fuzz harnesses
+//!
+//! Each target takes arbitrary bytes, runs them through the same parsing and hashing paths
+//! the library uses, and panics only when an internal consistency check fails, so any crash
+//! is a bug. A harness in a downstream repo is one line:
+//!
+//! ```ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| voicecode::fuzz_targets::parse_gs1(data));
+//! ```
+
+use crate::barcode::Gs1_128;
+use crate::gs1::Gs1ElementString;
+use crate::hash::{crc_u16, hash_into, hash_u16, NonAsciiPolicy, VoiceCodeHasher};
+use crate::scan::parse_scan;
+use crate::voicecode::HashVoiceCode;
+
+/// Hash `data` every way the crate can and check the results agree
+pub fn hash_any_bytes(data: &[u8]) {
+    let code = hash_u16(data);
+    assert!(code < 10000);
+    assert_eq!(crc_u16(data) % 10000, code);
+
+    let mut digits = [0u8; 4];
+    hash_into(data, &mut digits);
+    assert_eq!(digits, *format!("{:04}", code).as_bytes());
+
+    // split somewhere the input itself picks
+    let split = data.first().map_or(0, |b| usize::from(*b) % (data.len() + 1));
+    let mut hasher = VoiceCodeHasher::new();
+    hasher.update(&data[..split]);
+    hasher.update(&data[split..]);
+    assert_eq!(hasher.finalize(), code);
+
+    if let Ok(text) = std::str::from_utf8(data) {
+        for policy in [NonAsciiPolicy::Reject, NonAsciiPolicy::JsCompatUtf16, NonAsciiPolicy::RawScalar] {
+            if let Ok(voice_code) = HashVoiceCode::generate_voice_code_hash_with(text, policy) {
+                assert_eq!(voice_code.len(), 4);
+            }
+        }
+        if text.is_ascii() {
+            assert_eq!(HashVoiceCode::generate_voice_code_hash(text), format!("{:04}", code));
+        }
+    }
+}
+
+/// Parse `data` as scanner output, a GS1 element string and a Digital Link URI, and compute
+/// the voice code of anything that parses
+pub fn parse_gs1(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    for parsed in [parse_scan(text), Gs1ElementString::parse(text), Gs1ElementString::from_digital_link(text)] {
+        let Ok(elements) = parsed else { continue };
+        let _ = elements.to_string();
+        let _ = elements.to_unbracketed();
+        let _ = elements.to_digital_link("https://id.gs1.org");
+        if let Ok(voice_code) = elements.voice_code() {
+            assert_eq!(voice_code.voice_code, HashVoiceCode::generate_voice_code_hash(&voice_code.hash_text));
+        }
+    }
+}
+
+/// Parse `data` as an element string and encode every one that parses as GS1-128, checking
+/// the symbol is well formed
+pub fn encode_gs1_128(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    for parsed in [parse_scan(text), Gs1ElementString::parse(text)] {
+        let Ok(elements) = parsed else { continue };
+        let symbol = Gs1_128::encode(&elements);
+        let codewords = symbol.codewords();
+        assert!(codewords.len() >= 4 && codewords[1] == 102 && codewords.last() == Some(&106));
+        assert!(codewords.iter().all(|&cw| cw <= 106));
+        assert_eq!(symbol.width(), symbol.modules().len());
+    }
+}
+
+/// Split `data` on `|` into GTIN, LOT, YY, MM and DD, compute the voice code and check the
+/// canonical string form parses back to it
+pub fn compute_inputs(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    let mut parts = text.splitn(5, '|');
+    let mut next = || parts.next().unwrap_or_default();
+    let (gtin, lot, yy, mm, dd) = (next(), next(), next(), next(), next());
+    let _ = HashVoiceCode::validate_all(gtin, lot, yy, mm, dd);
+    if let Ok(voice_code) = HashVoiceCode::new(gtin, lot, yy, mm, dd) {
+        assert_eq!(voice_code.voice_code, HashVoiceCode::generate_voice_code_hash(&voice_code.hash_text));
+        let _ = voice_code.to_string().parse::<HashVoiceCode>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Valid inputs with random bytes overwritten, a cheap stand-in for a fuzzer run
+    #[test]
+    fn test_no_panics() {
+        let seeds: [&[u8]; 7] = [
+            b"]C1\x1d0100614141007349\x1d1032ABCD\x1d13200102\r\n",
+            b"(01)00614141007349(10)32ABCD(13)200102",
+            b"https://id.gs1.org/01/00614141007349/10/32ABCD?13=200102",
+            b"12345678901244|LOT123|03|01|02",
+            b"(00)106141411234567897(7007)200102",
+            "0100614141007349\u{e9}10\u{1d}".as_bytes(),
+            b"(01)614141007349(90)A B~(13)200102(10)1",
+        ];
+        let mut state: u32 = 0x9e37_79b9;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        for seed in seeds {
+            for _ in 0..500 {
+                let mut data = seed.to_vec();
+                for _ in 0..1 + next() % 3 {
+                    let i = next() as usize % data.len();
+                    data[i] = next() as u8;
+                }
+                data.truncate(1 + next() as usize % data.len());
+                hash_any_bytes(&data);
+                parse_gs1(&data);
+                encode_gs1_128(&data);
+                compute_inputs(&data);
+            }
+        }
+        for data in [&b""[..], b"|", b"((", b"(01)", b"01", b"\x1d\x1d", b"\xff\xfe"] {
+            hash_any_bytes(data);
+            parse_gs1(data);
+            encode_gs1_128(data);
+            compute_inputs(data);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! GS1 Company Prefix lengths, so `Gtin::company_prefix` splits where GS1 assigned the
+//! prefix instead of assuming `DEFAULT_COMPANY_PREFIX_LEN` digits
+//!
+//! The table embedded at build time is `data/gcpprefixformatlist.xml`, in the format GS1
+//! publishes its prefix length list. Drop the current download over that file to embed it,
+//! or load it at runtime and `install` it.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+use crate::gtin::Gtin;
+
+const EMBEDDED: &str = include_str!("../data/gcpprefixformatlist.xml");
+
+/// Why a prefix length list could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcpTableError {
+    /// 1 based line of the offending entry
+    pub line: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for GcpTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for GcpTableError {}
+
+/// Company prefix length by leading GTIN-13 digits, the longest matching prefix wins
+///
+/// A length of 0 marks ranges that never carry a company prefix, like restricted circulation
+/// numbers and coupons.
+///
+/// # Example
+/// ```
+/// use voicecode::Gtin;
+/// use voicecode::gcp::GcpTable;
+/// let table = GcpTable::from_gs1_xml(r#"
+///     <GCPPrefixFormatList>
+///       <entry prefix="061" gcpLength="8"/>
+///       <entry prefix="0614141" gcpLength="7"/>
+///       <entry prefix="2" gcpLength="0"/>
+///     </GCPPrefixFormatList>"#).unwrap();
+/// assert_eq!(table.prefix_len(&Gtin::parse_unchecked("00614141007349").unwrap()), Some(7));
+/// assert_eq!(table.prefix_len(&Gtin::parse_unchecked("00061999000001").unwrap()), None);
+/// assert_eq!(table.prefix_len(&Gtin::parse_unchecked("0610000000008").unwrap()), Some(8));
+/// assert_eq!(table.prefix_len(&Gtin::parse_unchecked("2000000000008").unwrap()), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GcpTable {
+    lengths: BTreeMap<String, u8>,
+}
+
+impl GcpTable {
+    /// Empty table, every lookup falls back to the default length
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read GS1's `gcpprefixformatlist.xml`, one `<entry prefix="…" gcpLength="…"/>` per entry
+    ///
+    /// Only the entry elements are read, everything else in the file is ignored.
+    pub fn from_gs1_xml(xml: &str) -> Result<Self, GcpTableError> {
+        let mut table = GcpTable::new();
+        for (index, line) in xml.lines().enumerate() {
+            let error = |reason| GcpTableError { line: index + 1, reason };
+            let Some(start) = line.find("<entry") else { continue };
+            let entry = &line[start..];
+            let prefix = attribute(entry, "prefix").ok_or(error("entry has no prefix"))?;
+            let length = attribute(entry, "gcpLength").ok_or(error("entry has no gcpLength"))?;
+            let length: u8 = length.parse().map_err(|_| error("gcpLength is not a number"))?;
+            table.insert(prefix, length).map_err(error)?;
+        }
+        Ok(table)
+    }
+
+    /// Add or replace one entry, `prefix` is the leading digits of the GTIN-13 form
+    pub fn insert(&mut self, prefix: &str, length: u8) -> Result<(), &'static str> {
+        if prefix.is_empty() || prefix.len() > 12 || !prefix.bytes().all(|b| b.is_ascii_digit()) {
+            return Err("prefix must be 1 to 12 digits");
+        }
+        if length != 0 && !(prefix.len()..=12).contains(&usize::from(length)) {
+            return Err("gcpLength must be 0 or from the prefix length to 12");
+        }
+        self.lengths.insert(prefix.to_string(), length);
+        Ok(())
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// True when there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// Company prefix length for `gtin`, None when no entry matches or the range has no prefix
+    pub fn prefix_len(&self, gtin: &Gtin) -> Option<usize> {
+        let gtin14 = gtin.to_gtin14();
+        let key = &gtin14[1..13];
+        (1..=key.len())
+            .rev()
+            .find_map(|len| self.lengths.get(&key[..len]))
+            .filter(|length| **length > 0)
+            .map(|length| usize::from(*length))
+    }
+}
+
+/// Value of `name="…"` in an element
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(" {}=\"", name);
+    let start = element.find(&pattern)? + pattern.len();
+    let end = element[start..].find('"')?;
+    Some(&element[start..start + end])
+}
+
+fn table() -> &'static RwLock<GcpTable> {
+    static TABLE: OnceLock<RwLock<GcpTable>> = OnceLock::new();
+    TABLE.get_or_init(|| RwLock::new(GcpTable::from_gs1_xml(EMBEDDED).expect("embedded GCP table parses")))
+}
+
+/// Replace the table used by `Gtin::company_prefix` for the rest of the process
+///
+/// # Example
+/// ```no_run
+/// use voicecode::gcp::{ self, GcpTable };
+/// let xml = std::fs::read_to_string("gcpprefixformatlist.xml").unwrap();
+/// gcp::install(GcpTable::from_gs1_xml(&xml).unwrap());
+/// ```
+pub fn install(new: GcpTable) {
+    *table().write().unwrap_or_else(|e| e.into_inner()) = new;
+}
+
+/// Company prefix length for `gtin` from the installed table
+pub fn prefix_len(gtin: &Gtin) -> Option<usize> {
+    table().read().unwrap_or_else(|e| e.into_inner()).prefix_len(gtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded() {
+        let table = GcpTable::from_gs1_xml(EMBEDDED).unwrap();
+        assert!(!table.is_empty());
+        assert_eq!(table.prefix_len(&Gtin::parse_unchecked("10614141007346").unwrap()), Some(7));
+        assert_eq!(table.prefix_len(&Gtin::parse_unchecked("0200000000004").unwrap()), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        let xml = "<list>\n<entry prefix=\"06\" gcpLength=\"x\"/>\n</list>";
+        assert_eq!(GcpTable::from_gs1_xml(xml).unwrap_err(), GcpTableError { line: 2, reason: "gcpLength is not a number" });
+        assert!(GcpTable::from_gs1_xml("<entry gcpLength=\"7\"/>").is_err());
+        assert!(GcpTable::new().insert("0614141", 6).is_err());
+        assert!(GcpTable::new().insert("06a", 7).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! gRPC service from `proto/voicecode/v1/voicecode.proto`, run it with `voicecode grpc`
+//!
+//! - `ComputeVoiceCode` fails with `INVALID_ARGUMENT` and the validation message
+//! - `ValidateGtin` always succeeds, the outcome is in the response
+//! - `BatchCompute` answers each request on the stream in order, failed records carry
+//!   `error` instead of failing the stream
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), tonic::transport::Error> {
+//! tonic::transport::Server::builder()
+//!     .add_service(voicecode::grpc::server())
+//!     .serve("0.0.0.0:50051".parse().unwrap())
+//!     .await
+//! # }
+//! ```
+
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::batch::BatchRecord;
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::voicecode::HashVoiceCode;
+
+/// Messages and service traits generated from the proto definition
+///
+/// `voicecode.v1.rs` is tonic-prost-build output checked in so building doesn't need
+/// `protoc`, regenerate it with `build_client(false)` after changing the proto.
+pub mod proto {
+    include!("voicecode.v1.rs");
+}
+
+use proto::batch_compute_result::Result as BatchResult;
+use proto::voice_code_service_server::{VoiceCodeService, VoiceCodeServiceServer};
+use proto::{BatchComputeResult, ComputeVoiceCodeRequest, ValidateGtinRequest, ValidateGtinResponse, VoiceCode};
+
+/// `VoiceCodeService` backed by the library, holds no state
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GrpcService;
+
+/// Service ready for `tonic::transport::Server::add_service`
+pub fn server() -> VoiceCodeServiceServer<GrpcService> {
+    VoiceCodeServiceServer::new(GrpcService)
+}
+
+impl From<HashVoiceCode> for VoiceCode {
+    fn from(value: HashVoiceCode) -> Self {
+        VoiceCode {
+            gtin: value.gtin.to_string(),
+            lot: value.lot.to_string(),
+            pack_date: value.pack_date().to_string(),
+            voice_code: value.voice_code,
+            major: value.voice_code_major,
+            minor: value.voice_code_minor,
+        }
+    }
+}
+
+impl From<VoiceCodeError> for Status {
+    fn from(value: VoiceCodeError) -> Self {
+        Status::invalid_argument(value.to_string())
+    }
+}
+
+fn compute(request: ComputeVoiceCodeRequest) -> Result<HashVoiceCode, VoiceCodeError> {
+    BatchRecord { gtin: request.gtin, lot: request.lot, pack_date: request.pack_date }.compute()
+}
+
+#[tonic::async_trait]
+impl VoiceCodeService for GrpcService {
+    async fn compute_voice_code(&self, request: Request<ComputeVoiceCodeRequest>) -> Result<Response<VoiceCode>, Status> {
+        let voice_code = compute(request.into_inner()).inspect_err(|e| {
+            event!(info, "ComputeVoiceCode", status = "INVALID_ARGUMENT", error = e);
+        })?;
+        event!(info, "ComputeVoiceCode", status = "OK");
+        Ok(Response::new(voice_code.into()))
+    }
+
+    async fn validate_gtin(&self, request: Request<ValidateGtinRequest>) -> Result<Response<ValidateGtinResponse>, Status> {
+        let request = request.into_inner();
+        let parsed = if request.check_digit { Gtin::parse(&request.gtin) } else { Gtin::parse_unchecked(&request.gtin) };
+        let response = match parsed {
+            Ok(_) => ValidateGtinResponse { valid: true, error: String::new() },
+            Err(e) => ValidateGtinResponse { valid: false, error: e.to_string() },
+        };
+        Ok(Response::new(response))
+    }
+
+    type BatchComputeStream = tonic::codegen::BoxStream<BatchComputeResult>;
+
+    async fn batch_compute(
+        &self,
+        request: Request<Streaming<ComputeVoiceCodeRequest>>,
+    ) -> Result<Response<Self::BatchComputeStream>, Status> {
+        event!(info, "BatchCompute stream opened");
+        let results = request.into_inner().map(|request| {
+            let result = match compute(request?) {
+                Ok(voice_code) => BatchResult::VoiceCode(voice_code.into()),
+                Err(e) => BatchResult::Error(e.to_string()),
+            };
+            Ok(BatchComputeResult { result: Some(result) })
+        });
+        Ok(Response::new(Box::pin(results)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(gtin: &str, lot: &str, pack_date: &str) -> Request<ComputeVoiceCodeRequest> {
+        Request::new(ComputeVoiceCodeRequest { gtin: gtin.to_string(), lot: lot.to_string(), pack_date: pack_date.to_string() })
+    }
+
+    #[tokio::test]
+    async fn test_compute_voice_code() {
+        let response = GrpcService.compute_voice_code(request("12345678901244", "LOT123", "2003-01-02")).await.unwrap();
+        let voice_code = response.into_inner();
+        assert_eq!(voice_code.voice_code, "6991");
+        assert_eq!(voice_code.pack_date, "030102");
+
+        let status = GrpcService.compute_voice_code(request("12345678901244", "LOT 123", "030102")).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_validate_gtin() {
+        let validate = |gtin: &str, check_digit: bool| {
+            GrpcService.validate_gtin(Request::new(ValidateGtinRequest { gtin: gtin.to_string(), check_digit }))
+        };
+        assert!(validate("12345678901244", false).await.unwrap().into_inner().valid);
+        let response = validate("12345678901244", true).await.unwrap().into_inner();
+        assert!(!response.valid);
+        assert!(!response.error.is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ComputeVoiceCodeRequest {
+    #[prost(string, tag = "1")]
+    pub gtin: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub lot: ::prost::alloc::string::String,
+    /// YYMMDD or YYYY-MM-DD
+    #[prost(string, tag = "3")]
+    pub pack_date: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct VoiceCode {
+    #[prost(string, tag = "1")]
+    pub gtin: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub lot: ::prost::alloc::string::String,
+    /// YYMMDD
+    #[prost(string, tag = "3")]
+    pub pack_date: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub voice_code: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub major: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub minor: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ValidateGtinRequest {
+    #[prost(string, tag = "1")]
+    pub gtin: ::prost::alloc::string::String,
+    /// Also verify the check digit
+    #[prost(bool, tag = "2")]
+    pub check_digit: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ValidateGtinResponse {
+    #[prost(bool, tag = "1")]
+    pub valid: bool,
+    /// Empty when valid
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BatchComputeResult {
+    #[prost(oneof = "batch_compute_result::Result", tags = "1, 2")]
+    pub result: ::core::option::Option<batch_compute_result::Result>,
+}
+/// Nested message and enum types in `BatchComputeResult`.
+pub mod batch_compute_result {
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag = "1")]
+        VoiceCode(super::VoiceCode),
+        #[prost(string, tag = "2")]
+        Error(::prost::alloc::string::String),
+    }
+}
+/// Generated server implementations.
+pub mod voice_code_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with VoiceCodeServiceServer.
+    #[async_trait]
+    pub trait VoiceCodeService: std::marker::Send + std::marker::Sync + 'static {
+        /// Compute one voice code, invalid input fails with INVALID_ARGUMENT
+        async fn compute_voice_code(
+            &self,
+            request: tonic::Request<super::ComputeVoiceCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::VoiceCode>, tonic::Status>;
+        /// Check a GTIN without computing a code
+        async fn validate_gtin(
+            &self,
+            request: tonic::Request<super::ValidateGtinRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ValidateGtinResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the BatchCompute method.
+        type BatchComputeStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::BatchComputeResult, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Compute a stream of records, one result per request in the same order
+        async fn batch_compute(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::ComputeVoiceCodeRequest>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::BatchComputeStream>,
+            tonic::Status,
+        >;
+    }
+    /// Voice code calculator for Produce Traceability Initiative labels
+    #[derive(Debug)]
+    pub struct VoiceCodeServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> VoiceCodeServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for VoiceCodeServiceServer<T>
+    where
+        T: VoiceCodeService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/voicecode.v1.VoiceCodeService/ComputeVoiceCode" => {
+                    #[allow(non_camel_case_types)]
+                    struct ComputeVoiceCodeSvc<T: VoiceCodeService>(pub Arc<T>);
+                    impl<
+                        T: VoiceCodeService,
+                    > tonic::server::UnaryService<super::ComputeVoiceCodeRequest>
+                    for ComputeVoiceCodeSvc<T> {
+                        type Response = super::VoiceCode;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ComputeVoiceCodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as VoiceCodeService>::compute_voice_code(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ComputeVoiceCodeSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/voicecode.v1.VoiceCodeService/ValidateGtin" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidateGtinSvc<T: VoiceCodeService>(pub Arc<T>);
+                    impl<
+                        T: VoiceCodeService,
+                    > tonic::server::UnaryService<super::ValidateGtinRequest>
+                    for ValidateGtinSvc<T> {
+                        type Response = super::ValidateGtinResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ValidateGtinRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as VoiceCodeService>::validate_gtin(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ValidateGtinSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/voicecode.v1.VoiceCodeService/BatchCompute" => {
+                    #[allow(non_camel_case_types)]
+                    struct BatchComputeSvc<T: VoiceCodeService>(pub Arc<T>);
+                    impl<
+                        T: VoiceCodeService,
+                    > tonic::server::StreamingService<super::ComputeVoiceCodeRequest>
+                    for BatchComputeSvc<T> {
+                        type Response = super::BatchComputeResult;
+                        type ResponseStream = T::BatchComputeStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::ComputeVoiceCodeRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as VoiceCodeService>::batch_compute(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BatchComputeSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(
+                            tonic::body::Body::default(),
+                        );
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for VoiceCodeServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "voicecode.v1.VoiceCodeService";
+    impl<T> tonic::server::NamedService for VoiceCodeServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
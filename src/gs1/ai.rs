@@ -0,0 +1,90 @@
+use crate::gtin::is_valid_gtin;
+
+/// Definition of a GS1 application identifier this crate understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AiDef {
+    /// Leading digits identifying the AI, for 4 digit AIs like 310n the last digit is a parameter
+    pub prefix: &'static str,
+    /// Total number of AI digits
+    pub ai_len: usize,
+    /// Fixed value length, None for variable length values terminated by FNC1
+    pub fixed_len: Option<usize>,
+    /// Maximum value length
+    pub max_len: usize,
+    /// Value must be all digits
+    pub numeric: bool,
+    /// GS1 data title
+    pub title: &'static str,
+}
+
+impl AiDef {
+    const fn fixed(prefix: &'static str, ai_len: usize, len: usize, title: &'static str) -> Self {
+        AiDef { prefix, ai_len, fixed_len: Some(len), max_len: len, numeric: true, title }
+    }
+
+    const fn variable(prefix: &'static str, ai_len: usize, max_len: usize, numeric: bool, title: &'static str) -> Self {
+        AiDef { prefix, ai_len, fixed_len: None, max_len, numeric, title }
+    }
+
+    /// True when `value` has an acceptable length and character set for this AI
+    ///
+    /// AI(01) also takes a GTIN-8, GTIN-12 or GTIN-13, the digits a voice code was hashed over.
+    pub fn accepts(&self, value: &str) -> bool {
+        let len_ok = match self.fixed_len {
+            Some(_) if self.prefix == "01" => is_valid_gtin(value.as_bytes()),
+            Some(len) => value.len() == len,
+            None => !value.is_empty() && value.len() <= self.max_len,
+        };
+        let chars_ok = if self.numeric {
+            value.bytes().all(|b| b.is_ascii_digit())
+        } else {
+            value.bytes().all(|b| (0x21..=0x7e).contains(&b))
+        };
+        len_ok && chars_ok
+    }
+}
+
+/// AIs found on PTI case and pallet labels
+pub const KNOWN_AIS: &[AiDef] = &[
+    AiDef::fixed("00", 2, 18, "SSCC"),
+    AiDef::fixed("01", 2, 14, "GTIN"),
+    AiDef::fixed("02", 2, 14, "CONTENT"),
+    AiDef::variable("10", 2, 20, false, "BATCH/LOT"),
+    AiDef::fixed("11", 2, 6, "PROD DATE"),
+    AiDef::fixed("12", 2, 6, "DUE DATE"),
+    AiDef::fixed("13", 2, 6, "PACK DATE"),
+    AiDef::fixed("15", 2, 6, "BEST BEFORE or BEST BY"),
+    AiDef::fixed("16", 2, 6, "SELL BY"),
+    AiDef::fixed("17", 2, 6, "USE BY or EXPIRY"),
+    AiDef::variable("21", 2, 20, false, "SERIAL"),
+    AiDef::variable("30", 2, 8, true, "VAR. COUNT"),
+    AiDef::fixed("310", 4, 6, "NET WEIGHT (kg)"),
+    AiDef::fixed("320", 4, 6, "NET WEIGHT (lb)"),
+    AiDef::fixed("330", 4, 6, "GROSS WEIGHT (kg)"),
+    AiDef::fixed("340", 4, 6, "GROSS WEIGHT (lb)"),
+    AiDef::variable("37", 2, 8, true, "COUNT"),
+    AiDef::variable("422", 3, 3, true, "ORIGIN"),
+    AiDef::variable("7007", 4, 12, true, "HARVEST DATE"),
+];
+
+/// Look up the definition for a complete AI such as "01" or "3102"
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::ai::lookup;
+/// assert_eq!(lookup("3102").unwrap().title, "NET WEIGHT (kg)");
+/// assert_eq!(lookup("10").unwrap().fixed_len, None);
+/// assert!(lookup("99").is_none());
+/// ```
+pub fn lookup(ai: &str) -> Option<&'static AiDef> {
+    KNOWN_AIS
+        .iter()
+        .find(|def| ai.len() == def.ai_len && ai.starts_with(def.prefix) && ai.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Find the AI at the start of `data`, used for element strings without parentheses
+pub fn match_prefix(data: &str) -> Option<&'static AiDef> {
+    KNOWN_AIS
+        .iter()
+        .find(|def| data.get(..def.ai_len).and_then(lookup) == Some(def))
+}
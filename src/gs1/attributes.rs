@@ -0,0 +1,188 @@
+//! Optional case data some buyers require on the same GS1-128 as the PTI fields
+//!
+//! AI(422) country of origin for country of origin labeling (COOL), AI(310n)/AI(320n) net and
+//! AI(330n)/AI(340n) gross weight and AI(21) serial. None of them change the voice code.
+
+use std::fmt;
+
+use crate::gs1::ai;
+use crate::gs1::weight::Weight;
+use crate::gs1::Gs1Error;
+
+/// Alpha-2 codes for common produce origins and their ISO 3166-1 numeric codes
+const ALPHA2: &[(&str, u16)] = &[
+    ("AR", 32),
+    ("AU", 36),
+    ("BR", 76),
+    ("CA", 124),
+    ("CL", 152),
+    ("CN", 156),
+    ("CO", 170),
+    ("CR", 188),
+    ("DO", 214),
+    ("EC", 218),
+    ("ES", 724),
+    ("GT", 320),
+    ("HN", 340),
+    ("IT", 380),
+    ("MA", 504),
+    ("MX", 484),
+    ("NL", 528),
+    ("NZ", 554),
+    ("PE", 604),
+    ("US", 840),
+    ("ZA", 710),
+];
+
+/// ISO 3166-1 numeric country code, the value of AI(422)
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::attributes::Country;
+/// let mexico = Country::parse("MX").unwrap();
+/// assert_eq!(mexico, Country::parse("484").unwrap());
+/// assert_eq!(mexico.to_string(), "484");
+/// assert_eq!(mexico.alpha2(), Some("MX"));
+/// assert_eq!(Country::new(32).unwrap().to_string(), "032");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Country(u16);
+
+impl Country {
+    /// Numeric code from 1 to 999
+    pub fn new(numeric: u16) -> Result<Self, Gs1Error> {
+        if numeric == 0 || numeric > 999 {
+            return Err(Gs1Error::InvalidValue { ai: "422".to_string() });
+        }
+        Ok(Country(numeric))
+    }
+
+    /// Three digit numeric code, or an alpha-2 code from the common produce origins
+    pub fn parse(input: &str) -> Result<Self, Gs1Error> {
+        let input = input.trim();
+        if input.len() == 3 && input.bytes().all(|b| b.is_ascii_digit()) {
+            return Country::new(input.parse().expect("three digits"));
+        }
+        ALPHA2
+            .iter()
+            .find(|(alpha2, _)| alpha2.eq_ignore_ascii_case(input))
+            .map(|(_, numeric)| Country(*numeric))
+            .ok_or(Gs1Error::InvalidValue { ai: "422".to_string() })
+    }
+
+    pub fn numeric(&self) -> u16 {
+        self.0
+    }
+
+    /// Alpha-2 code when the country is one of the common produce origins
+    pub fn alpha2(&self) -> Option<&'static str> {
+        ALPHA2.iter().find(|(_, numeric)| *numeric == self.0).map(|(alpha2, _)| *alpha2)
+    }
+}
+
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:03}", self.0)
+    }
+}
+
+/// Optional AIs carried next to AI(01), the date and AI(10)
+///
+/// The serial is checked against AI(21) when it is set, so every value here can be encoded.
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::attributes::{ CaseAttributes, Country };
+/// let attributes = CaseAttributes::new().with_country_of_origin(Country::parse("US").unwrap()).with_serial("SN0001").unwrap();
+/// assert_eq!(attributes.country_of_origin().unwrap().numeric(), 840);
+/// assert_eq!(attributes.serial(), Some("SN0001"));
+/// assert!(CaseAttributes::new().with_serial("SN 0001").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CaseAttributes {
+    net_weight: Option<Weight>,
+    gross_weight: Option<Weight>,
+    country_of_origin: Option<Country>,
+    serial: Option<String>,
+}
+
+impl CaseAttributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the AI(310n) or AI(320n) net weight, required on variable measure cases
+    pub fn with_net_weight(mut self, weight: Weight) -> Self {
+        self.net_weight = Some(weight);
+        self
+    }
+
+    /// Set the AI(330n) or AI(340n) gross weight
+    pub fn with_gross_weight(mut self, weight: Weight) -> Self {
+        self.gross_weight = Some(weight);
+        self
+    }
+
+    /// Set the AI(422) country of origin
+    pub fn with_country_of_origin(mut self, country: Country) -> Self {
+        self.country_of_origin = Some(country);
+        self
+    }
+
+    /// Set the AI(21) serial, up to 20 printable ASCII characters
+    pub fn with_serial(mut self, serial: &str) -> Result<Self, Gs1Error> {
+        let def = ai::lookup("21").expect("AI(21) is known");
+        if !def.accepts(serial) {
+            return Err(Gs1Error::InvalidValue { ai: "21".to_string() });
+        }
+        self.serial = Some(serial.to_string());
+        Ok(self)
+    }
+
+    pub fn net_weight(&self) -> Option<Weight> {
+        self.net_weight
+    }
+
+    pub fn gross_weight(&self) -> Option<Weight> {
+        self.gross_weight
+    }
+
+    pub fn country_of_origin(&self) -> Option<Country> {
+        self.country_of_origin
+    }
+
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    /// True when no optional AI is set
+    pub fn is_empty(&self) -> bool {
+        *self == CaseAttributes::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_country() {
+        assert_eq!(Country::parse("us").unwrap().numeric(), 840);
+        assert_eq!(Country::parse(" 036 ").unwrap().alpha2(), Some("AU"));
+        assert_eq!(Country::parse("250").unwrap().alpha2(), None);
+        for input in ["000", "USA", "84", "XX", ""] {
+            assert_eq!(Country::parse(input), Err(Gs1Error::InvalidValue { ai: "422".to_string() }), "{}", input);
+        }
+        assert!(Country::new(1000).is_err());
+    }
+
+    #[test]
+    fn test_serial() {
+        let attributes = CaseAttributes::new().with_serial("ABCDEFGHIJKLMNOPQRST").unwrap();
+        assert_eq!(attributes.serial(), Some("ABCDEFGHIJKLMNOPQRST"));
+        assert!(!attributes.is_empty());
+        assert!(CaseAttributes::default().is_empty());
+        assert!(CaseAttributes::default().with_serial("ABCDEFGHIJKLMNOPQRSTU").is_err());
+        assert!(CaseAttributes::default().with_serial("").is_err());
+    }
+}
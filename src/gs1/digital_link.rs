@@ -0,0 +1,191 @@
+//! GS1 Digital Link URIs
+//!
+//! Reference: [GS1 Digital Link Standard](https://www.gs1.org/standards/gs1-digital-link)
+//!
+//! `https://id.gs1.org/01/00614141007349/10/32ABCD?13=200102` carries the primary key and
+//! its qualifiers in the path and every other AI as a query parameter.
+
+use crate::error::VoiceCodeError;
+use crate::gs1::{Gs1ElementString, Gs1Error};
+
+/// Resolver used when no base is given
+pub const DEFAULT_RESOLVER: &str = "https://id.gs1.org";
+
+/// Path qualifiers allowed after AI(01), in the order they must appear
+const GTIN_QUALIFIERS: &[&str] = &["22", "10", "21"];
+
+/// Convenience names some Digital Link URIs use instead of numeric AIs
+const ALIASES: &[(&str, &str)] = &[("gtin", "01"), ("cpv", "22"), ("lot", "10"), ("ser", "21"), ("sscc", "00")];
+
+fn resolve_ai(key: &str) -> Option<&str> {
+    if (2..=4).contains(&key.len()) && key.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(key);
+    }
+    ALIASES.iter().find(|(alias, _)| *alias == key).map(|(_, ai)| *ai)
+}
+
+/// Parse a Digital Link URI into an element string
+///
+/// Query parameters that are not AIs, such as `linkType`, are ignored.
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::digital_link::parse;
+/// let elements = parse("https://id.gs1.org/01/00614141007349/10/32ABCD?13=200102").unwrap();
+/// assert_eq!(elements.to_string(), "(01)00614141007349(10)32ABCD(13)200102");
+/// ```
+pub fn parse(uri: &str) -> Result<Gs1ElementString, VoiceCodeError> {
+    let without_fragment = uri.split('#').next().unwrap_or_default();
+    let (location, query) = match without_fragment.split_once('?') {
+        Some((location, query)) => (location, query),
+        None => (without_fragment, ""),
+    };
+    let path = match location.find("://") {
+        Some(scheme_end) => {
+            let after_scheme = &location[scheme_end + 3..];
+            after_scheme.find('/').map_or("", |i| &after_scheme[i..])
+        }
+        None => location,
+    };
+
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    // anything before the primary key is the resolver's own path
+    let start = segments
+        .iter()
+        .position(|segment| matches!(resolve_ai(segment), Some("01") | Some("00")))
+        .ok_or(Gs1Error::MissingAi { ai: "01" })?;
+    let pairs = &segments[start..];
+    if !pairs.len().is_multiple_of(2) {
+        return Err(Gs1Error::InvalidDigitalLink.into());
+    }
+
+    let mut elements = Gs1ElementString::new();
+    for pair in pairs.chunks(2) {
+        let ai = resolve_ai(pair[0]).ok_or(Gs1Error::InvalidDigitalLink)?;
+        elements.push(ai, &percent_decode(pair[1])?)?;
+    }
+    for param in query.split('&').filter(|param| !param.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        if let Some(ai) = resolve_ai(key) {
+            elements.push(ai, &percent_decode(value)?)?;
+        }
+    }
+    Ok(elements)
+}
+
+/// Build a Digital Link URI, `base` is the resolver such as `https://id.gs1.org`
+///
+/// The element string must contain AI(01), qualifiers go in the path and other AIs in the query.
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::{ digital_link, Gs1ElementString };
+/// let elements = Gs1ElementString::parse("(01)00614141007349(13)200102(10)32/AB").unwrap();
+/// let uri = digital_link::generate(&elements, "https://example.com/").unwrap();
+/// assert_eq!(uri, "https://example.com/01/00614141007349/10/32%2FAB?13=200102");
+/// ```
+pub fn generate(elements: &Gs1ElementString, base: &str) -> Result<String, VoiceCodeError> {
+    let gtin = elements.gtin().ok_or(Gs1Error::MissingAi { ai: "01" })?;
+    let mut uri = format!("{}/01/{}", base.trim_end_matches('/'), gtin);
+    for qualifier in GTIN_QUALIFIERS {
+        if let Some(value) = elements.get(qualifier) {
+            uri.push_str(&format!("/{}/{}", qualifier, percent_encode(value)));
+        }
+    }
+
+    let mut separator = '?';
+    for element in elements.elements() {
+        if element.ai == "01" || GTIN_QUALIFIERS.contains(&element.ai.as_str()) {
+            continue;
+        }
+        uri.push_str(&format!("{}{}={}", separator, element.ai, percent_encode(&element.value)));
+        separator = '&';
+    }
+    Ok(uri)
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> Result<String, VoiceCodeError> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3).ok_or(Gs1Error::InvalidDigitalLink)?;
+            out.push(u8::from_str_radix(hex, 16).map_err(|_| Gs1Error::InvalidDigitalLink)?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| Gs1Error::InvalidDigitalLink.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashVoiceCode;
+
+    #[test]
+    fn test_parse_variants() {
+        let expected = "(01)61414100734933(10)32abcd(13)030102";
+        for uri in [
+            "https://id.gs1.org/01/61414100734933/10/32abcd?13=030102",
+            "https://example.com/products/01/61414100734933/10/32abcd?linkType=all&13=030102#top",
+            "https://example.com/gtin/61414100734933/lot/32abcd?13=030102",
+            "/01/61414100734933/10/32abcd?13=030102",
+        ] {
+            assert_eq!(parse(uri).unwrap().to_string(), expected, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("https://id.gs1.org/10/32abcd").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::MissingAi { ai: "01" } }
+        );
+        assert_eq!(
+            parse("https://id.gs1.org/01/61414100734933/10").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::InvalidDigitalLink }
+        );
+        assert_eq!(
+            parse("https://id.gs1.org/01/61414100734933/10/A%2").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::InvalidDigitalLink }
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let elements = Gs1ElementString::parse("(01)61414100734933(13)030102(10)A&B%C(21)12").unwrap();
+        let uri = generate(&elements, DEFAULT_RESOLVER).unwrap();
+        assert_eq!(uri, "https://id.gs1.org/01/61414100734933/10/A%26B%25C/21/12?13=030102");
+        let parsed = parse(&uri).unwrap();
+        assert_eq!(parsed.lot(), Some("A&B%C"));
+        assert_eq!(parsed.get("21"), Some("12"));
+        assert_eq!(parsed.pack_date(), Some("030102"));
+    }
+
+    #[test]
+    fn test_voice_code_round_trip() {
+        for (gtin, expected) in [("614141007349", "2979"), ("0614141007349", "2556")] {
+            let voice_code = HashVoiceCode::new(gtin, "LOT123", "03", "01", "02").unwrap();
+            assert_eq!(voice_code.voice_code, expected);
+            let uri = voice_code.to_digital_link(DEFAULT_RESOLVER);
+            assert_eq!(uri, format!("https://id.gs1.org/01/{}/10/LOT123?13=030102", gtin));
+            assert_eq!(HashVoiceCode::from_digital_link(&uri).unwrap().voice_code, voice_code.voice_code);
+        }
+    }
+}
@@ -0,0 +1,588 @@
+//! GS1 element strings as carried by GS1-128 case label barcodes
+//!
+//! Reference: [GS1 General Specifications](https://www.gs1.org/standards/barcodes-epcrfid-id-keys/gs1-general-specifications)
+
+pub mod ai;
+pub mod attributes;
+pub mod digital_link;
+pub mod weight;
+
+use std::fmt;
+
+use crate::error::VoiceCodeError;
+use crate::pack_date::PackDate;
+use crate::sscc::Sscc;
+use crate::voicecode::HashVoiceCode;
+use attributes::{CaseAttributes, Country};
+use weight::Weight;
+
+/// ASCII group separator, how scanners transmit FNC1 between fields
+pub const GS: char = '\x1d';
+
+/// Why an element string could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gs1Error {
+    /// Nothing to parse
+    Empty,
+    /// No known AI starts at this byte offset, AIs must be known to split unbracketed data
+    UnknownAi { offset: usize },
+    /// Bracketed AI syntax is broken at this byte offset
+    Malformed { offset: usize },
+    /// Value has the wrong length or characters for its AI
+    InvalidValue { ai: String },
+    /// A required AI is not present
+    MissingAi { ai: &'static str },
+    /// Digital Link URI path or percent encoding is malformed
+    InvalidDigitalLink,
+}
+
+impl fmt::Display for Gs1Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gs1Error::Empty => write!(f, "GS1 element string is empty"),
+            Gs1Error::UnknownAi { offset } => write!(f, "unknown GS1 AI at byte {}", offset),
+            Gs1Error::Malformed { offset } => write!(f, "malformed GS1 AI at byte {}", offset),
+            Gs1Error::InvalidValue { ai } => write!(f, "invalid value for GS1 AI ({})", ai),
+            Gs1Error::MissingAi { ai } => write!(f, "GS1 AI ({}) is required", ai),
+            Gs1Error::InvalidDigitalLink => write!(f, "malformed GS1 Digital Link URI"),
+        }
+    }
+}
+
+impl From<Gs1Error> for VoiceCodeError {
+    fn from(value: Gs1Error) -> Self {
+        VoiceCodeError::InvalidGs1 { reason: value }
+    }
+}
+
+/// AI a case label carries its date under
+///
+/// PTI labels use AI(13) pack date, some trading partners require a best before, expiry or
+/// harvest date instead. The voice code is always computed from the date printed on the label
+/// whichever AI carries it.
+///
+/// # Example
+/// ```
+/// use voicecode::HashVoiceCode;
+/// use voicecode::gs1::{ DateAi, Gs1ElementString };
+/// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+/// let elements = Gs1ElementString::for_voice_code(&voice_code, DateAi::BestBefore);
+/// assert_eq!(elements.to_string(), "(01)10614141007346(15)030102(10)LOT123");
+/// assert_eq!(elements.voice_code().unwrap(), voice_code);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DateAi {
+    /// AI(13)
+    #[default]
+    PackDate,
+    /// AI(15)
+    BestBefore,
+    /// AI(17)
+    Expiry,
+    /// AI(7007), a single YYMMDD rather than a range
+    Harvest,
+}
+
+impl DateAi {
+    /// Every date AI, in the order `Gs1ElementString::date` looks for them
+    pub const ALL: [DateAi; 4] = [DateAi::PackDate, DateAi::BestBefore, DateAi::Expiry, DateAi::Harvest];
+
+    /// The AI digits
+    pub fn ai(&self) -> &'static str {
+        match self {
+            DateAi::PackDate => "13",
+            DateAi::BestBefore => "15",
+            DateAi::Expiry => "17",
+            DateAi::Harvest => "7007",
+        }
+    }
+
+    /// Caption for the human readable date field
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateAi::PackDate => "PACK DATE",
+            DateAi::BestBefore => "BEST BEFORE",
+            DateAi::Expiry => "USE BY",
+            DateAi::Harvest => "HARVEST DATE",
+        }
+    }
+}
+
+/// One AI and its value
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gs1Element {
+    pub ai: String,
+    pub value: String,
+}
+
+/// Parsed GS1 element string
+///
+/// Accepts the human readable bracketed form and the raw form where FNC1 is sent as
+/// ASCII 29 (GS) after variable length fields.
+///
+/// Bracketed values run until the next `(NN)` so a LOT containing something that looks
+/// like an AI in parentheses cannot be recovered from the bracketed form.
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::Gs1ElementString;
+/// let scan = Gs1ElementString::parse("(01)00614141007349(13)200102(10)32ABCD").unwrap();
+/// assert_eq!(scan.gtin(), Some("00614141007349"));
+/// assert_eq!(scan.pack_date(), Some("200102"));
+/// assert_eq!(scan.lot(), Some("32ABCD"));
+///
+/// let raw = Gs1ElementString::parse("01006141410073491032ABCD\x1d13200102").unwrap();
+/// assert_eq!(raw.lot(), Some("32ABCD"));
+/// assert_eq!(raw.to_string(), "(01)00614141007349(10)32ABCD(13)200102");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gs1ElementString {
+    elements: Vec<Gs1Element>,
+}
+
+impl Gs1ElementString {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse bracketed or raw element strings
+    pub fn parse(input: &str) -> Result<Self, VoiceCodeError> {
+        let input = input.trim_start_matches(GS).trim_end_matches(GS);
+        if input.is_empty() {
+            return Err(Gs1Error::Empty.into());
+        }
+        if input.starts_with('(') {
+            Self::parse_bracketed(input)
+        } else {
+            Self::parse_unbracketed(input)
+        }
+    }
+
+    fn parse_bracketed(input: &str) -> Result<Self, VoiceCodeError> {
+        let mut result = Self::new();
+        let mut offset = 0;
+        while offset < input.len() {
+            let rest = &input[offset..];
+            let ai_len = bracketed_ai_len(rest).ok_or(Gs1Error::Malformed { offset })?;
+            let ai = &rest[1..1 + ai_len];
+            let value_start = ai_len + 2;
+            let value_end = next_bracketed_ai(&rest[value_start..]).map_or(rest.len(), |i| i + value_start);
+            let value: String = rest[value_start..value_end].chars().filter(|ch| *ch != GS).collect();
+            result.push(ai, &value)?;
+            offset += value_end;
+        }
+        Ok(result)
+    }
+
+    fn parse_unbracketed(input: &str) -> Result<Self, VoiceCodeError> {
+        let mut result = Self::new();
+        let mut offset = 0;
+        while offset < input.len() {
+            let rest = &input[offset..];
+            let def = ai::match_prefix(rest).ok_or(Gs1Error::UnknownAi { offset })?;
+            let ai = &rest[..def.ai_len];
+            let body = &rest[def.ai_len..];
+            // a value shorter than the fixed length, a GTIN-12 in AI(01), is ended by GS
+            let short = body.find(GS).filter(|end| def.fixed_len.is_some_and(|len| *end < len) && def.accepts(&body[..*end]));
+            let value_len = match def.fixed_len {
+                Some(_) if short.is_some() => short.unwrap_or_default(),
+                Some(len) if body.len() >= len && body.is_char_boundary(len) => len,
+                Some(_) => return Err(Gs1Error::InvalidValue { ai: ai.to_string() }.into()),
+                None => body.find(GS).unwrap_or(body.len()),
+            };
+            result.push(ai, &body[..value_len])?;
+            offset += def.ai_len + value_len;
+            if input[offset..].starts_with(GS) {
+                offset += GS.len_utf8();
+            }
+        }
+        Ok(result)
+    }
+
+    /// Append an element, values for known AIs are checked for length and characters, values
+    /// for other AIs must be printable ASCII so every element string can be encoded as GS1-128
+    pub fn push(&mut self, ai: &str, value: &str) -> Result<(), VoiceCodeError> {
+        let valid_ai = (2..=4).contains(&ai.len()) && ai.bytes().all(|b| b.is_ascii_digit());
+        let printable = |value: &str| !value.is_empty() && value.bytes().all(|b| (b' '..=b'~').contains(&b));
+        let valid_value = ai::lookup(ai).map_or(printable(value), |def| def.accepts(value));
+        if !valid_ai || !valid_value {
+            return Err(Gs1Error::InvalidValue { ai: ai.to_string() }.into());
+        }
+        self.elements.push(Gs1Element { ai: ai.to_string(), value: value.to_string() });
+        Ok(())
+    }
+
+    /// All elements in scan order
+    pub fn elements(&self) -> &[Gs1Element] {
+        &self.elements
+    }
+
+    /// Value of the first occurrence of `ai`
+    pub fn get(&self, ai: &str) -> Option<&str> {
+        self.elements.iter().find(|element| element.ai == ai).map(|element| element.value.as_str())
+    }
+
+    /// AI(00) SSCC
+    pub fn sscc(&self) -> Option<&str> {
+        self.get("00")
+    }
+
+    /// AI(01) GTIN
+    pub fn gtin(&self) -> Option<&str> {
+        self.get("01")
+    }
+
+    /// AI(10) batch or lot
+    pub fn lot(&self) -> Option<&str> {
+        self.get("10")
+    }
+
+    /// AI(13) pack date as YYMMDD
+    pub fn pack_date(&self) -> Option<&str> {
+        self.get("13")
+    }
+
+    /// AI(310n) or AI(320n) net weight
+    pub fn net_weight(&self) -> Option<Weight> {
+        self.elements.iter().find_map(|element| Weight::from_net_element(&element.ai, &element.value))
+    }
+
+    /// AI(330n) or AI(340n) gross weight
+    pub fn gross_weight(&self) -> Option<Weight> {
+        self.elements.iter().find_map(|element| Weight::from_gross_element(&element.ai, &element.value))
+    }
+
+    /// AI(422) country of origin
+    pub fn country_of_origin(&self) -> Option<Country> {
+        self.get("422").and_then(|value| Country::parse(value).ok())
+    }
+
+    /// AI(21) serial
+    pub fn serial(&self) -> Option<&str> {
+        self.get("21")
+    }
+
+    /// Optional case AIs present in the element string, see `with_attributes`
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::gs1::Gs1ElementString;
+    /// let scan = Gs1ElementString::parse("(01)61414100734933(13)030102(3302)001200(422)484(21)SN1(10)32abcd").unwrap();
+    /// let attributes = scan.attributes();
+    /// assert_eq!(attributes.gross_weight().unwrap().to_string(), "12.00 kg");
+    /// assert_eq!(attributes.country_of_origin().unwrap().alpha2(), Some("MX"));
+    /// assert_eq!(attributes.serial(), Some("SN1"));
+    /// ```
+    pub fn attributes(&self) -> CaseAttributes {
+        let mut attributes = CaseAttributes::new();
+        if let Some(weight) = self.net_weight() {
+            attributes = attributes.with_net_weight(weight);
+        }
+        if let Some(weight) = self.gross_weight() {
+            attributes = attributes.with_gross_weight(weight);
+        }
+        if let Some(country) = self.country_of_origin() {
+            attributes = attributes.with_country_of_origin(country);
+        }
+        if let Some(serial) = self.serial() {
+            attributes = attributes.with_serial(serial).expect("parsed AI(21) is valid");
+        }
+        attributes
+    }
+
+    /// Date the voice code is computed from and the AI carrying it, AI(13) first then AI(15),
+    /// AI(17) and the start of an AI(7007) harvest date range
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::gs1::{ DateAi, Gs1ElementString };
+    /// let scan = Gs1ElementString::parse("(01)61414100734933(7007)030102030105(10)32abcd").unwrap();
+    /// assert_eq!(scan.date(), Some((DateAi::Harvest, "030102")));
+    /// ```
+    pub fn date(&self) -> Option<(DateAi, &str)> {
+        DateAi::ALL.into_iter().find_map(|date_ai| {
+            let value = self.get(date_ai.ai())?;
+            Some((date_ai, value.get(..6).unwrap_or(value)))
+        })
+    }
+
+    /// Raw form with GS after every variable length element except the last
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::gs1::Gs1ElementString;
+    /// let scan = Gs1ElementString::parse("(01)00614141007349(10)32ABCD(13)200102").unwrap();
+    /// assert_eq!(scan.to_unbracketed(), "01006141410073491032ABCD\x1d13200102");
+    /// ```
+    pub fn to_unbracketed(&self) -> String {
+        let mut out = String::new();
+        for (i, element) in self.elements.iter().enumerate() {
+            out.push_str(&element.ai);
+            out.push_str(&element.value);
+            let fixed = ai::lookup(&element.ai).is_some_and(|def| def.fixed_len == Some(element.value.len()));
+            if !fixed && i + 1 < self.elements.len() {
+                out.push(GS);
+            }
+        }
+        out
+    }
+
+    /// Parse a GS1 Digital Link URI, see `digital_link::parse`
+    pub fn from_digital_link(uri: &str) -> Result<Self, VoiceCodeError> {
+        digital_link::parse(uri)
+    }
+
+    /// GS1 Digital Link URI on the resolver `base`, see `digital_link::generate`
+    pub fn to_digital_link(&self, base: &str) -> Result<String, VoiceCodeError> {
+        digital_link::generate(self, base)
+    }
+
+    /// Compute the voice code from AI(01), AI(10) and the date from `date`
+    pub fn voice_code(&self) -> Result<HashVoiceCode, VoiceCodeError> {
+        let gtin = self.gtin().ok_or(Gs1Error::MissingAi { ai: "01" })?;
+        let lot = self.lot().ok_or(Gs1Error::MissingAi { ai: "10" })?;
+        let (_, pack_date) = self.date().ok_or(Gs1Error::MissingAi { ai: "13" })?;
+        let pack_date = PackDate::parse_yymmdd(pack_date)?;
+        Ok(HashVoiceCode::from_parts(gtin.try_into()?, lot.try_into()?, pack_date))
+    }
+
+    /// Element string a case label carries for this voice code, AI(01) with the GTIN the code
+    /// was hashed over, the date under `date_ai` and AI(10), which is left out for
+    /// `HashVoiceCode::new_no_lot` codes
+    ///
+    /// A GTIN-8, GTIN-12 or GTIN-13 is not padded to 14 digits, padding would change the code
+    /// a scan recomputes, so `to_unbracketed` ends it with GS. Build the voice code from
+    /// `Gtin::to_gtin14` for a label other GS1 readers take the same way.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::HashVoiceCode;
+    /// use voicecode::gs1::{ DateAi, Gs1ElementString };
+    /// let voice_code = HashVoiceCode::new("614141007349", "32ABCD", "03", "01", "02").unwrap();
+    /// let elements = Gs1ElementString::for_voice_code(&voice_code, DateAi::PackDate);
+    /// assert_eq!(elements.to_string(), "(01)614141007349(13)030102(10)32ABCD");
+    /// assert_eq!(elements.to_unbracketed(), "01614141007349\x1d130301021032ABCD");
+    /// ```
+    pub fn for_voice_code(voice_code: &HashVoiceCode, date_ai: DateAi) -> Self {
+        let element = |ai: &str, value: String| Gs1Element { ai: ai.to_string(), value };
+        let mut elements = vec![
+            element("01", voice_code.gtin.to_string()),
+            element(date_ai.ai(), voice_code.pack_date().yymmdd()),
+        ];
+        if !voice_code.lot.is_empty() {
+            elements.push(element("10", voice_code.lot.to_string()));
+        }
+        Gs1ElementString { elements }
+    }
+
+    /// Add the AI(310n) or AI(320n) net weight a variable measure case carries, ahead of any
+    /// AI(10) so the LOT stays last and needs no separator
+    ///
+    /// The voice code does not depend on the weight.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::HashVoiceCode;
+    /// use voicecode::gs1::{ DateAi, Gs1ElementString };
+    /// use voicecode::gs1::weight::{ Weight, WeightUnit };
+    /// let voice_code = HashVoiceCode::new("90614141000015", "LOT123", "03", "01", "02").unwrap();
+    /// let weight = Weight::new(WeightUnit::Pounds, 2150, 2).unwrap();
+    /// let elements = Gs1ElementString::for_voice_code(&voice_code, DateAi::PackDate).with_net_weight(weight);
+    /// assert_eq!(elements.to_string(), "(01)90614141000015(13)030102(3202)002150(10)LOT123");
+    /// assert_eq!(elements.net_weight(), Some(weight));
+    /// ```
+    pub fn with_net_weight(mut self, weight: Weight) -> Self {
+        self.insert_before_lot(weight.net_ai(), weight.digits());
+        self
+    }
+
+    /// Add the weights, AI(422) country of origin and AI(21) serial that are set, ahead of any
+    /// AI(10) in that order
+    ///
+    /// The voice code does not depend on any of them. `to_unbracketed` separates the variable
+    /// length AI(422) and AI(21) with GS.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::HashVoiceCode;
+    /// use voicecode::gs1::{ DateAi, Gs1ElementString };
+    /// use voicecode::gs1::attributes::{ CaseAttributes, Country };
+    /// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+    /// let attributes = CaseAttributes::new().with_country_of_origin(Country::parse("US").unwrap()).with_serial("SN1").unwrap();
+    /// let elements = Gs1ElementString::for_voice_code(&voice_code, DateAi::PackDate).with_attributes(&attributes);
+    /// assert_eq!(elements.to_string(), "(01)10614141007346(13)030102(422)840(21)SN1(10)LOT123");
+    /// assert_eq!(elements.to_digital_link("https://id.example.com").unwrap(), "https://id.example.com/01/10614141007346/10/LOT123/21/SN1?13=030102&422=840");
+    /// ```
+    pub fn with_attributes(mut self, attributes: &CaseAttributes) -> Self {
+        if let Some(weight) = attributes.net_weight() {
+            self.insert_before_lot(weight.net_ai(), weight.digits());
+        }
+        if let Some(weight) = attributes.gross_weight() {
+            self.insert_before_lot(weight.gross_ai(), weight.digits());
+        }
+        if let Some(country) = attributes.country_of_origin() {
+            self.insert_before_lot("422".to_string(), country.to_string());
+        }
+        if let Some(serial) = attributes.serial() {
+            self.insert_before_lot("21".to_string(), serial.to_string());
+        }
+        self
+    }
+
+    /// Insert ahead of AI(10) so the LOT stays last and needs no separator
+    fn insert_before_lot(&mut self, ai: String, value: String) {
+        let at = self.elements.iter().position(|element| element.ai == "10").unwrap_or(self.elements.len());
+        self.elements.insert(at, Gs1Element { ai, value });
+    }
+}
+
+/// Element string a case label carries for this voice code, AI(01) with the hashed GTIN, AI(13) and AI(10)
+impl From<&HashVoiceCode> for Gs1ElementString {
+    fn from(value: &HashVoiceCode) -> Self {
+        Gs1ElementString::for_voice_code(value, DateAi::PackDate)
+    }
+}
+
+/// Element string a pallet label carries for its SSCC, AI(00) alone
+///
+/// # Example
+/// ```
+/// use voicecode::Sscc;
+/// use voicecode::gs1::Gs1ElementString;
+/// let sscc = Sscc::parse("106141411234567897").unwrap();
+/// assert_eq!(Gs1ElementString::from(&sscc).to_string(), "(00)106141411234567897");
+/// ```
+impl From<&Sscc> for Gs1ElementString {
+    fn from(value: &Sscc) -> Self {
+        Gs1ElementString { elements: vec![Gs1Element { ai: "00".to_string(), value: value.to_string() }] }
+    }
+}
+
+impl fmt::Display for Gs1ElementString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for element in &self.elements {
+            write!(f, "({}){}", element.ai, element.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Length of the AI when `input` starts with `(NN)`, `(NNN)` or `(NNNN)`
+fn bracketed_ai_len(input: &str) -> Option<usize> {
+    let rest = input.strip_prefix('(')?;
+    let close = rest.find(')')?;
+    let ai = &rest[..close];
+    ((2..=4).contains(&ai.len()) && ai.bytes().all(|b| b.is_ascii_digit())).then_some(close)
+}
+
+fn next_bracketed_ai(input: &str) -> Option<usize> {
+    input.match_indices('(').map(|(i, _)| i).find(|i| bracketed_ai_len(&input[*i..]).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bracketed() {
+        let scan = Gs1ElementString::parse("(01)10614141007346(10)32abcd(13)030102").unwrap();
+        assert_eq!(scan.elements().len(), 3);
+        assert_eq!(scan.voice_code().unwrap().voice_code, "1089");
+    }
+
+    #[test]
+    fn test_unbracketed() {
+        let scan = Gs1ElementString::parse("\x1d0110614141007346130301021032abcd").unwrap();
+        assert_eq!(scan.lot(), Some("32abcd"));
+        assert_eq!(scan.voice_code().unwrap().voice_code, "1089");
+
+        // fixed length fields may still be followed by a separator
+        let scan = Gs1ElementString::parse("0110614141007346\x1d1032abcd\x1d13030102").unwrap();
+        assert_eq!(scan.pack_date(), Some("030102"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Gs1ElementString::parse("").unwrap_err(), VoiceCodeError::InvalidGs1 { reason: Gs1Error::Empty });
+        assert_eq!(
+            Gs1ElementString::parse("(01)61414100734(10)A").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::InvalidValue { ai: "01".to_string() } }
+        );
+        assert_eq!(
+            Gs1ElementString::parse("(01)10614141007346(1)A").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::InvalidValue { ai: "01".to_string() } }
+        );
+        assert_eq!(
+            Gs1ElementString::parse("01106141410073469912").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::UnknownAi { offset: 16 } }
+        );
+        assert_eq!(
+            Gs1ElementString::parse("x(01)10614141007346").unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::UnknownAi { offset: 0 } }
+        );
+        for input in ["(90)\u{e9}", "(90)A\u{7}B", "(90)A\u{7f}"] {
+            assert_eq!(
+                Gs1ElementString::parse(input).unwrap_err(),
+                VoiceCodeError::InvalidGs1 { reason: Gs1Error::InvalidValue { ai: "90".to_string() } }
+            );
+        }
+        assert_eq!(
+            Gs1ElementString::parse("(01)10614141007346(10)A").unwrap().voice_code().unwrap_err(),
+            VoiceCodeError::InvalidGs1 { reason: Gs1Error::MissingAi { ai: "13" } }
+        );
+    }
+
+    #[test]
+    fn test_date_ai() {
+        let voice_code = HashVoiceCode::new("10614141007346", "32abcd", "03", "01", "02").unwrap();
+        for date_ai in DateAi::ALL {
+            let elements = Gs1ElementString::for_voice_code(&voice_code, date_ai);
+            let scan = Gs1ElementString::parse(&elements.to_unbracketed()).unwrap();
+            assert_eq!(scan.date(), Some((date_ai, "030102")));
+            assert_eq!(scan.voice_code().unwrap().voice_code, "1089");
+        }
+        let scan = Gs1ElementString::parse("(01)10614141007346(17)991231(13)030102(10)32abcd").unwrap();
+        assert_eq!(scan.date(), Some((DateAi::PackDate, "030102")));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = "(01)10614141007346(10)32abcd(21)SN1(13)030102";
+        let scan = Gs1ElementString::parse(input).unwrap();
+        assert_eq!(scan.to_string(), input);
+        assert_eq!(Gs1ElementString::parse(&scan.to_unbracketed()).unwrap(), scan);
+    }
+
+    #[test]
+    fn test_voice_code_round_trip() {
+        for (gtin, expected) in [("614141007349", "3878"), ("10614141007346", "6634")] {
+            let voice_code = HashVoiceCode::new(gtin, "32ABCD", "03", "01", "02").unwrap();
+            assert_eq!(voice_code.voice_code, expected);
+            let elements = Gs1ElementString::from(&voice_code);
+            for scanned in [elements.to_string(), elements.to_unbracketed()] {
+                let scan = Gs1ElementString::parse(&scanned).unwrap();
+                assert_eq!(scan.gtin(), Some(gtin));
+                assert_eq!(scan.voice_code().unwrap().voice_code, voice_code.voice_code);
+            }
+        }
+    }
+
+    #[test]
+    fn test_attributes() {
+        let voice_code = HashVoiceCode::new("10614141007346", "32abcd", "03", "01", "02").unwrap();
+        let attributes = CaseAttributes::new()
+            .with_net_weight(Weight::from_net_element("3202", "002150").unwrap())
+            .with_gross_weight(Weight::from_gross_element("3402", "002300").unwrap())
+            .with_country_of_origin(Country::parse("PE").unwrap())
+            .with_serial("0001")
+            .unwrap();
+        let elements = Gs1ElementString::for_voice_code(&voice_code, DateAi::PackDate).with_attributes(&attributes);
+        assert_eq!(elements.to_string(), "(01)10614141007346(13)030102(3202)002150(3402)002300(422)604(21)0001(10)32abcd");
+        assert_eq!(elements.to_unbracketed(), "01106141410073461303010232020021503402002300422604\x1d210001\x1d1032abcd");
+
+        let scan = Gs1ElementString::parse(&elements.to_unbracketed()).unwrap();
+        assert_eq!(scan.attributes(), attributes);
+        assert_eq!(scan.voice_code().unwrap().voice_code, "1089");
+        assert_eq!(Gs1ElementString::for_voice_code(&voice_code, DateAi::PackDate).attributes(), CaseAttributes::new());
+    }
+}
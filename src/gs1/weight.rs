@@ -0,0 +1,168 @@
+//! Trade measures carried in AI(310n) and AI(320n) net weight, for variable measure cases,
+//! and AI(330n) and AI(340n) gross weight
+//!
+//! The last AI digit is the number of decimal places in the six digit value, so
+//! `(3102)001250` is 12.50 kg and `(3201)000275` is 27.5 lb.
+
+use std::fmt;
+
+use crate::gs1::Gs1Error;
+
+/// Largest value a six digit measure can carry
+pub const MAX_WEIGHT_VALUE: u32 = 999_999;
+
+/// AI prefixes for kilograms then pounds
+const NET_AIS: [&str; 2] = ["310", "320"];
+const GROSS_AIS: [&str; 2] = ["330", "340"];
+
+/// Unit a weight is declared in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeightUnit {
+    Kilograms,
+    Pounds,
+}
+
+impl WeightUnit {
+    /// Unit symbol, `kg` or `lb`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            WeightUnit::Kilograms => "kg",
+            WeightUnit::Pounds => "lb",
+        }
+    }
+}
+
+/// Weight as the six digit value and decimal places of a GS1 measure AI
+///
+/// # Example
+/// ```
+/// use voicecode::gs1::weight::{ Weight, WeightUnit };
+/// let weight = Weight::new(WeightUnit::Kilograms, 1250, 2).unwrap();
+/// assert_eq!(weight.net_ai(), "3102");
+/// assert_eq!(weight.digits(), "001250");
+/// assert_eq!(weight.to_string(), "12.50 kg");
+/// assert_eq!(Weight::from_net_element("3201", "000275"), Some(Weight::new(WeightUnit::Pounds, 275, 1).unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Weight {
+    unit: WeightUnit,
+    value: u32,
+    decimals: u8,
+}
+
+impl Weight {
+    /// `value` with `decimals` implied decimal places, up to `MAX_WEIGHT_VALUE` and 5 places
+    pub fn new(unit: WeightUnit, value: u32, decimals: u8) -> Result<Self, Gs1Error> {
+        let weight = Weight { unit, value, decimals };
+        if value > MAX_WEIGHT_VALUE || decimals > 5 {
+            return Err(Gs1Error::InvalidValue { ai: weight.net_ai() });
+        }
+        Ok(weight)
+    }
+
+    /// Read an AI(310n) or AI(320n) element
+    pub fn from_net_element(ai: &str, value: &str) -> Option<Self> {
+        Self::from_element(ai, value, NET_AIS)
+    }
+
+    /// Read an AI(330n) or AI(340n) element
+    pub fn from_gross_element(ai: &str, value: &str) -> Option<Self> {
+        Self::from_element(ai, value, GROSS_AIS)
+    }
+
+    fn from_element(ai: &str, value: &str, prefixes: [&str; 2]) -> Option<Self> {
+        let unit = match ai.get(..3)? {
+            prefix if prefix == prefixes[0] => WeightUnit::Kilograms,
+            prefix if prefix == prefixes[1] => WeightUnit::Pounds,
+            _ => return None,
+        };
+        let decimals = ai.get(3..)?.parse().ok()?;
+        if value.len() != 6 || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Weight::new(unit, value.parse().ok()?, decimals).ok()
+    }
+
+    pub fn unit(&self) -> WeightUnit {
+        self.unit
+    }
+
+    /// Value without its decimal point
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Implied decimal places
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Net weight AI, `310n` for kilograms and `320n` for pounds
+    pub fn net_ai(&self) -> String {
+        self.ai(NET_AIS)
+    }
+
+    /// Gross weight AI, `330n` for kilograms and `340n` for pounds
+    pub fn gross_ai(&self) -> String {
+        self.ai(GROSS_AIS)
+    }
+
+    fn ai(&self, prefixes: [&str; 2]) -> String {
+        let prefix = match self.unit {
+            WeightUnit::Kilograms => prefixes[0],
+            WeightUnit::Pounds => prefixes[1],
+        };
+        format!("{}{}", prefix, self.decimals)
+    }
+
+    /// Six digit element value
+    pub fn digits(&self) -> String {
+        format!("{:06}", self.value)
+    }
+}
+
+impl fmt::Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = 10u32.pow(self.decimals as u32);
+        let whole = self.value / scale;
+        if self.decimals == 0 {
+            write!(f, "{} {}", whole, self.unit.symbol())
+        } else {
+            let fraction = self.value % scale;
+            write!(f, "{}.{:0width$} {}", whole, fraction, self.unit.symbol(), width = self.decimals as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weight() {
+        let weight = Weight::new(WeightUnit::Pounds, 40, 0).unwrap();
+        assert_eq!((weight.net_ai().as_str(), weight.digits().as_str()), ("3200", "000040"));
+        assert_eq!(weight.to_string(), "40 lb");
+        assert_eq!(Weight::new(WeightUnit::Kilograms, 5, 3).unwrap().to_string(), "0.005 kg");
+        assert_eq!(Weight::new(WeightUnit::Kilograms, 1_000_000, 0).unwrap_err(), Gs1Error::InvalidValue { ai: "3100".to_string() });
+        assert!(Weight::new(WeightUnit::Kilograms, 1, 6).is_err());
+    }
+
+    #[test]
+    fn test_from_net_element() {
+        assert_eq!(Weight::from_net_element("3105", "012345").unwrap().to_string(), "0.12345 kg");
+        assert_eq!(Weight::from_net_element("3106", "012345"), None);
+        assert_eq!(Weight::from_net_element("3302", "012345"), None);
+        assert_eq!(Weight::from_net_element("3102", "01234"), None);
+        assert_eq!(Weight::from_net_element("310", "012345"), None);
+    }
+
+    #[test]
+    fn test_gross() {
+        let weight = Weight::from_gross_element("3402", "004000").unwrap();
+        assert_eq!(weight.to_string(), "40.00 lb");
+        assert_eq!((weight.gross_ai().as_str(), weight.net_ai().as_str()), ("3402", "3202"));
+        assert_eq!(Weight::from_gross_element("3102", "004000"), None);
+        assert_eq!(Weight::from_net_element("3302", "004000"), None);
+    }
+}
@@ -0,0 +1,579 @@
+#[cfg(feature = "std")]
+use core::convert::Infallible;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
+use crate::error::VoiceCodeError;
+
+/// Company prefix length assumed when splitting a GTIN, matches the GS1 example prefix 0614141
+pub const DEFAULT_COMPANY_PREFIX_LEN: usize = 7;
+
+/// Why a GTIN failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GtinError {
+    /// GTIN is not 8, 12, 13 or 14 characters long
+    Length { len: usize },
+    /// GTIN contains a character that is not an ASCII digit, offset is in bytes
+    NonDigit { ch: char, offset: usize },
+    /// Indicator digit is above 9
+    Indicator { indicator: u8 },
+    /// UPC-E is not 8 digits starting with number system 0 or 1
+    UpcE,
+    /// GTIN length is not one a `ValidationPolicy` allows
+    LengthDisallowedByPolicy { len: usize },
+}
+
+impl GtinError {
+    /// Stable identifier for APIs and UIs, such as `gtin_format`
+    pub fn code(&self) -> &'static str {
+        match self {
+            GtinError::Length { .. } | GtinError::NonDigit { .. } => "gtin_format",
+            GtinError::Indicator { .. } => "gtin_indicator",
+            GtinError::UpcE => "upc_e_format",
+            GtinError::LengthDisallowedByPolicy { .. } => "gtin_length_disallowed_by_policy",
+        }
+    }
+}
+
+impl fmt::Display for GtinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GtinError::Length { len } => write!(f, "GTIN must be 8, 12, 13 or 14 digits, got {}", len),
+            GtinError::NonDigit { ch, offset } => {
+                write!(f, "GTIN contains {:?} at byte {}, GTIN must be numeric 8, 12, 13 or 14 digits", ch, offset)
+            }
+            GtinError::Indicator { indicator } => write!(f, "Indicator digit must be 0 to 9, got {}", indicator),
+            GtinError::UpcE => f.write_str("UPC-E must be 8 digits starting with number system 0 or 1"),
+            GtinError::LengthDisallowedByPolicy { len } => {
+                write!(f, "GTIN has {} digits, which the validation policy does not allow", len)
+            }
+        }
+    }
+}
+
+/// True when `gtin` is 8, 12, 13 or 14 ASCII digits, the check `Gtin::parse_unchecked` and
+/// `embedded::VoiceCode::new` do, without allocating
+///
+/// # Example
+/// ```
+/// use voicecode::gtin::is_valid_gtin;
+/// const VALID: bool = is_valid_gtin(b"12345678901244");
+/// assert!(VALID);
+/// assert!(!is_valid_gtin(b"1234567890124A"));
+/// assert!(!is_valid_gtin(b"123456789"));
+/// ```
+pub const fn is_valid_gtin(gtin: &[u8]) -> bool {
+    if !matches!(gtin.len(), 8 | 12 | 13 | 14) {
+        return false;
+    }
+    let mut i = 0;
+    while i < gtin.len() {
+        if !gtin[i].is_ascii_digit() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A GTIN-8, GTIN-12, GTIN-13 or GTIN-14
+///
+/// The digits are kept exactly as provided because the voice code is computed
+/// over the digits printed on the label, use `to_gtin14` for the normalized form.
+///
+/// `Gtin::parse` verifies the GS1 mod-10 check digit, as do `FromStr`, `TryFrom` and
+/// `HashVoiceCode::new` when handed a plain string. `Gtin::parse_unchecked` only checks the
+/// length and that every character is a digit, for labels already printed with a bad check
+/// digit.
+///
+/// # Example
+/// ```
+/// use voicecode::{ Gtin };
+/// let gtin = Gtin::parse("00614141007349").unwrap();
+/// assert_eq!(gtin.indicator_digit(), 0);
+/// assert_eq!(gtin.company_prefix(), "0614141");
+/// assert_eq!(gtin.item_reference(), "00734");
+/// assert_eq!(gtin.check_digit(), 9);
+///
+/// let gtin = Gtin::parse("614141007349").unwrap();
+/// assert_eq!(gtin.as_str(), "614141007349");
+/// assert_eq!(gtin.to_gtin14(), "00614141007349");
+///
+/// assert!(Gtin::parse("00614141007348").is_err());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+#[cfg_attr(feature = "diesel", derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
+pub struct Gtin {
+    digits: String,
+}
+
+#[cfg(feature = "std")]
+impl Gtin {
+    /// Parse a GTIN and verify its check digit
+    pub fn parse(gtin: &str) -> Result<Self, VoiceCodeError> {
+        let parsed = Self::parse_unchecked(gtin)?;
+        let expected = parsed.expected_check_digit();
+        let found = parsed.check_digit();
+        if expected != found {
+            return Err(VoiceCodeError::InvalidGtinCheckDigit { expected, found });
+        }
+        Ok(parsed)
+    }
+
+    /// Parse a GTIN checking only that it is 8, 12, 13 or 14 ASCII digits
+    ///
+    /// Many labels in circulation carry GTINs with bad check digits, this keeps them hashable.
+    pub fn parse_unchecked(gtin: &str) -> Result<Self, VoiceCodeError> {
+        if !is_valid_gtin(gtin.as_bytes()) {
+            let reason = match gtin.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+                Some((offset, ch)) => GtinError::NonDigit { ch, offset },
+                None => GtinError::Length { len: gtin.len() },
+            };
+            return Err(VoiceCodeError::InvalidGtin { reason });
+        }
+        Ok(Gtin { digits: gtin.to_string() })
+    }
+
+    /// Digits exactly as provided
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+
+    /// Number of digits as provided, 8, 12, 13 or 14
+    pub fn len(&self) -> usize {
+        self.digits.len()
+    }
+
+    /// Always false, a parsed GTIN has at least 8 digits
+    pub fn is_empty(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Left pad with zeros to 14 digits
+    pub fn to_gtin14(&self) -> String {
+        format!("{:0>14}", self.digits)
+    }
+
+    /// Check digit carried by the GTIN, the last digit
+    pub fn check_digit(&self) -> u8 {
+        self.digits.as_bytes()[self.digits.len() - 1] - b'0'
+    }
+
+    /// Check digit computed from the other digits
+    pub fn expected_check_digit(&self) -> u8 {
+        compute_check_digit(&self.digits.as_bytes()[..self.digits.len() - 1])
+    }
+
+    /// True when the carried check digit matches the computed one
+    pub fn has_valid_check_digit(&self) -> bool {
+        self.check_digit() == self.expected_check_digit()
+    }
+
+    /// Indicator digit, the first digit of the GTIN-14 form
+    pub fn indicator_digit(&self) -> u8 {
+        self.gtin14_digit(0)
+    }
+
+    /// True for a variable measure trade item, sold by weight rather than as fixed units
+    ///
+    /// That is indicator digit 9, or a restricted circulation number, a GTIN-13 starting
+    /// with 02 or 20 to 29, which includes GTIN-12s starting with 2. Restricted numbers
+    /// are assigned by each retailer or region, so what the digits after the prefix mean
+    /// is up to them. Variable measure cases carry their net weight, see
+    /// `Gs1ElementString::with_net_weight`.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin };
+    /// assert!(Gtin::parse("90614141000015").unwrap().is_variable_measure());
+    /// assert!(Gtin::parse("0212345000007").unwrap().is_variable_measure());
+    /// assert!(Gtin::parse("212345000007").unwrap().is_variable_measure());
+    /// assert!(!Gtin::parse("10614141007346").unwrap().is_variable_measure());
+    /// ```
+    pub fn is_variable_measure(&self) -> bool {
+        self.indicator_digit() == 9
+            || self.gtin14_digit(1) == 2
+            || (self.gtin14_digit(1) == 0 && self.gtin14_digit(2) == 2)
+    }
+
+    /// Company prefix length used by `company_prefix` and `item_reference`
+    ///
+    /// With the `gcp-table` feature this is looked up in the installed GS1 prefix length
+    /// table, otherwise, or when the table has no entry, it is `DEFAULT_COMPANY_PREFIX_LEN`.
+    pub fn company_prefix_len(&self) -> usize {
+        #[cfg(feature = "gcp-table")]
+        if let Some(len) = crate::gcp::prefix_len(self) {
+            return len;
+        }
+        DEFAULT_COMPANY_PREFIX_LEN
+    }
+
+    /// GS1 company prefix, `company_prefix_len` digits
+    pub fn company_prefix(&self) -> &str {
+        self.company_prefix_with_len(self.company_prefix_len())
+    }
+
+    /// Item reference after `company_prefix_len` digits of company prefix
+    pub fn item_reference(&self) -> &str {
+        self.item_reference_with_len(self.company_prefix_len())
+    }
+
+    /// GS1 company prefix for a known prefix length (6 to 12 digits)
+    ///
+    /// Digits that only exist as padding in the GTIN-14 form are returned as they
+    /// appear in the provided GTIN, so the slice may be shorter than `prefix_len`.
+    pub fn company_prefix_with_len(&self, prefix_len: usize) -> &str {
+        let (start, end) = self.gtin14_range(1, 1 + prefix_len.min(12));
+        &self.digits[start..end]
+    }
+
+    /// Item reference for a known company prefix length (6 to 12 digits)
+    pub fn item_reference_with_len(&self, prefix_len: usize) -> &str {
+        let (start, end) = self.gtin14_range(1 + prefix_len.min(12), 13);
+        &self.digits[start..end]
+    }
+
+    /// GTIN-14 with `indicator` as its first digit and the check digit recomputed
+    ///
+    /// The voice code hashes the GTIN digits exactly, so use the result for the label that
+    /// will carry the GTIN-14, a case and its consumer unit have different codes.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin, GtinError, VoiceCodeError };
+    /// let unit = Gtin::parse("614141007349").unwrap();
+    /// assert_eq!(unit.with_indicator(0).unwrap().as_str(), "00614141007349");
+    /// assert_eq!(unit.with_indicator(1).unwrap().as_str(), "10614141007346");
+    /// assert_eq!(unit.with_indicator(10).unwrap_err(), VoiceCodeError::InvalidGtin { reason: GtinError::Indicator { indicator: 10 } });
+    /// ```
+    pub fn with_indicator(&self, indicator: u8) -> Result<Gtin, VoiceCodeError> {
+        if indicator > 9 {
+            return Err(VoiceCodeError::InvalidGtin { reason: GtinError::Indicator { indicator } });
+        }
+        let body = format!("{}{}", indicator, &self.to_gtin14()[1..13]);
+        let check = compute_check_digit(body.as_bytes());
+        Ok(Gtin { digits: format!("{}{}", body, check) })
+    }
+
+    /// GTIN-13 form, None when the digits dropped from the front are not all zero
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin };
+    /// assert_eq!(Gtin::parse("00614141007349").unwrap().to_gtin13().unwrap().as_str(), "0614141007349");
+    /// assert!(Gtin::parse("10614141007346").unwrap().to_gtin13().is_none());
+    /// ```
+    pub fn to_gtin13(&self) -> Option<Gtin> {
+        self.shorten(13)
+    }
+
+    /// GTIN-12 (UPC-A) form, None when the digits dropped from the front are not all zero
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin };
+    /// assert_eq!(Gtin::parse("00614141007349").unwrap().to_gtin12().unwrap().as_str(), "614141007349");
+    /// ```
+    pub fn to_gtin12(&self) -> Option<Gtin> {
+        self.shorten(12)
+    }
+
+    fn shorten(&self, len: usize) -> Option<Gtin> {
+        let gtin14 = self.to_gtin14();
+        let (dropped, kept) = gtin14.split_at(14 - len);
+        dropped.bytes().all(|b| b == b'0').then(|| Gtin { digits: kept.to_string() })
+    }
+
+    fn padding(&self) -> usize {
+        14 - self.digits.len()
+    }
+
+    fn gtin14_digit(&self, index: usize) -> u8 {
+        match index.checked_sub(self.padding()) {
+            Some(i) => self.digits.as_bytes()[i] - b'0',
+            None => 0,
+        }
+    }
+
+    fn gtin14_range(&self, start: usize, end: usize) -> (usize, usize) {
+        let pad = self.padding();
+        (start.saturating_sub(pad), end.saturating_sub(pad))
+    }
+}
+
+/// GS1 mod-10 check digit over ASCII digits, weights 3,1,3,... from the right
+#[cfg(feature = "std")]
+pub(crate) fn compute_check_digit(body: &[u8]) -> u8 {
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| u32::from(b - b'0') * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// Expand an 8 digit UPC-E, number system 0 or 1 followed by six digits and the check
+/// digit, to the GTIN-12 (UPC-A) it stands for
+///
+/// The check digit is verified against the expanded UPC-A. A label that prints the UPC-E
+/// still carries the GTIN-12 in its GS1-128, so that is what the voice code is hashed over.
+///
+/// # Example
+/// ```
+/// use voicecode::{ gtin, VoiceCodeError };
+/// assert_eq!(gtin::expand_upc_e("04252614").unwrap().as_str(), "042100005264");
+/// assert_eq!(gtin::expand_upc_e("01234565").unwrap().as_str(), "012345000065");
+/// assert!(matches!(gtin::expand_upc_e("04252615"), Err(VoiceCodeError::InvalidGtinCheckDigit { .. })));
+/// ```
+#[cfg(feature = "std")]
+pub fn expand_upc_e(upc_e: &str) -> Result<Gtin, VoiceCodeError> {
+    let digits = upc_e.as_bytes();
+    if digits.len() != 8 || !digits.iter().all(u8::is_ascii_digit) || !matches!(digits[0], b'0' | b'1') {
+        return Err(VoiceCodeError::InvalidGtin { reason: GtinError::UpcE });
+    }
+    let d = |i: usize| digits[i + 1] as char;
+    let (manufacturer, product) = match d(5) {
+        '0'..='2' => (format!("{}{}{}00", d(0), d(1), d(5)), format!("00{}{}{}", d(2), d(3), d(4))),
+        '3' => (format!("{}{}{}00", d(0), d(1), d(2)), format!("000{}{}", d(3), d(4))),
+        '4' => (format!("{}{}{}{}0", d(0), d(1), d(2), d(3)), format!("0000{}", d(4))),
+        _ => (format!("{}{}{}{}{}", d(0), d(1), d(2), d(3), d(4)), format!("0000{}", d(5))),
+    };
+    let upc_a = format!("{}{}{}{}", digits[0] as char, manufacturer, product, digits[7] as char);
+    Gtin::parse(&upc_a)
+}
+
+/// GS1 mod-10 check digit for the digits of a GTIN without its check digit
+///
+/// Works for any GS1 key body, the 7, 11, 12 or 13 digits before a GTIN check digit.
+///
+/// # Panics
+/// When `body` has a character that is not an ASCII digit
+///
+/// # Example
+/// ```
+/// use voicecode::gtin;
+/// assert_eq!(gtin::check_digit("0061414100734"), 9);
+/// assert_eq!(gtin::check_digit("1234567890124"), 8);
+/// ```
+#[cfg(feature = "std")]
+pub fn check_digit(body: &str) -> u8 {
+    assert!(body.bytes().all(|b| b.is_ascii_digit()), "GTIN body must be ASCII digits");
+    compute_check_digit(body.as_bytes())
+}
+
+/// True when `gtin` is 8, 12, 13 or 14 digits and its last digit is the correct check digit
+///
+/// # Example
+/// ```
+/// use voicecode::gtin;
+/// assert!(gtin::verify("00614141007349"));
+/// assert!(!gtin::verify("12345678901244"));
+/// assert!(!gtin::verify("0061414100734X"));
+/// ```
+#[cfg(feature = "std")]
+pub fn verify(gtin: &str) -> bool {
+    Gtin::parse(gtin).is_ok()
+}
+
+/// The GTIN with its last digit replaced by the correct check digit
+///
+/// Only the check digit is repaired, a mistyped digit elsewhere still gives a valid but
+/// different GTIN, so confirm fixes against master data.
+///
+/// # Example
+/// ```
+/// use voicecode::{ gtin, Gtin };
+/// let bad = Gtin::parse_unchecked("12345678901244").unwrap();
+/// assert_eq!(gtin::fix(&bad), "12345678901248");
+/// assert!(gtin::verify(&gtin::fix(&bad)));
+/// ```
+#[cfg(feature = "std")]
+pub fn fix(gtin: &Gtin) -> String {
+    let body = &gtin.digits[..gtin.digits.len() - 1];
+    format!("{}{}", body, gtin.expected_check_digit())
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Gtin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.digits)
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsRef<str> for Gtin {
+    fn as_ref(&self) -> &str {
+        &self.digits
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<str> for Gtin {
+    fn eq(&self, other: &str) -> bool {
+        self.digits == other
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<&str> for Gtin {
+    fn eq(&self, other: &&str) -> bool {
+        self.digits == *other
+    }
+}
+
+/// Same as `Gtin::parse`, the check digit is verified
+#[cfg(feature = "std")]
+impl FromStr for Gtin {
+    type Err = VoiceCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Gtin::parse(s)
+    }
+}
+
+/// Same as `Gtin::parse`, the check digit is verified
+#[cfg(feature = "std")]
+impl TryFrom<&str> for Gtin {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Gtin::parse(value)
+    }
+}
+
+/// Same as `Gtin::parse`, the check digit is verified
+#[cfg(feature = "std")]
+impl TryFrom<&String> for Gtin {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Gtin::parse(value)
+    }
+}
+
+/// Same as `Gtin::parse`, the check digit is verified
+#[cfg(feature = "std")]
+impl TryFrom<String> for Gtin {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Gtin::parse(&value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Gtin> for String {
+    fn from(value: Gtin) -> Self {
+        value.digits
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&Gtin> for Gtin {
+    fn from(value: &Gtin) -> Self {
+        value.clone()
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Infallible> for VoiceCodeError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_digit() {
+        assert!(Gtin::parse("00614141007349").is_ok());
+        assert!(Gtin::parse("614141007349").is_ok());
+        assert!(Gtin::parse("96385074").is_ok());
+        assert_eq!(
+            Gtin::parse("12345678901244").unwrap_err(),
+            VoiceCodeError::InvalidGtinCheckDigit { expected: 8, found: 4 }
+        );
+        assert!(Gtin::parse_unchecked("12345678901244").is_ok());
+    }
+
+    #[test]
+    fn test_fix() {
+        for digits in ["96385070", "614141007340", "0614141007340", "00614141007340"] {
+            let fixed = fix(&Gtin::parse_unchecked(digits).unwrap());
+            assert_eq!(fixed.len(), digits.len());
+            assert!(verify(&fixed), "{}", fixed);
+        }
+        assert!(!verify("1234567"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_check_digit_rejects_letters() {
+        check_digit("12a");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let upc_a = expand_upc_e("01234565").unwrap();
+        let case = upc_a.with_indicator(3).unwrap();
+        assert!(case.has_valid_check_digit());
+        assert_eq!(case.with_indicator(0).unwrap().to_gtin12(), Some(upc_a.clone()));
+        assert_eq!(Gtin::parse("96385074").unwrap().with_indicator(0).unwrap().as_str(), "00000096385074");
+        assert!(Gtin::parse("4006381333931").unwrap().to_gtin12().is_none());
+        assert_eq!(expand_upc_e("21234565").unwrap_err(), VoiceCodeError::InvalidGtin { reason: GtinError::UpcE });
+    }
+
+    #[test]
+    fn test_variable_measure() {
+        for digits in ["90614141000019", "02345678901234", "00234567890123", "0212345000007", "2912345000003", "212345000006"] {
+            assert!(Gtin::parse_unchecked(digits).unwrap().is_variable_measure(), "{}", digits);
+        }
+        // GTIN-8s are padded with six zeros, so a leading 2 is not a restricted GTIN-13
+        for digits in ["10614141007346", "614141007349", "0614141007349", "3012345000001", "21234565"] {
+            assert!(!Gtin::parse_unchecked(digits).unwrap().is_variable_measure(), "{}", digits);
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        let invalid = |reason| VoiceCodeError::InvalidGtin { reason };
+        assert_eq!(Gtin::parse_unchecked("1234567890").unwrap_err(), invalid(GtinError::Length { len: 10 }));
+        assert_eq!(Gtin::parse_unchecked("1234567890123a").unwrap_err(), invalid(GtinError::NonDigit { ch: 'a', offset: 13 }));
+        assert_eq!(Gtin::parse_unchecked("").unwrap_err(), invalid(GtinError::Length { len: 0 }));
+        assert_eq!(Gtin::parse_unchecked("12 45").unwrap_err(), invalid(GtinError::NonDigit { ch: ' ', offset: 2 }));
+        assert_eq!(invalid(GtinError::Length { len: 10 }).to_string(), "GTIN must be 8, 12, 13 or 14 digits, got 10");
+    }
+
+    #[test]
+    fn test_conversions_verify_check_digit() {
+        let bad = VoiceCodeError::InvalidGtinCheckDigit { expected: 8, found: 4 };
+        assert_eq!(Gtin::try_from("12345678901244").unwrap_err(), bad);
+        assert_eq!(Gtin::try_from("12345678901244".to_string()).unwrap_err(), bad);
+        assert_eq!("12345678901244".parse::<Gtin>().unwrap_err(), bad);
+        assert_eq!("00614141007349".parse::<Gtin>().unwrap().as_str(), "00614141007349");
+    }
+
+    #[test]
+    fn test_accessors() {
+        let gtin = Gtin::parse("10614141007346").unwrap();
+        assert_eq!(gtin.indicator_digit(), 1);
+        assert_eq!(gtin.company_prefix(), "0614141");
+        assert_eq!(gtin.item_reference(), "00734");
+        assert_eq!(gtin.company_prefix_with_len(9), "061414100");
+        assert_eq!(gtin.item_reference_with_len(9), "734");
+
+        let gtin = Gtin::parse("614141007349").unwrap();
+        assert_eq!(gtin.indicator_digit(), 0);
+        assert_eq!(gtin.company_prefix(), "614141");
+        assert_eq!(gtin.item_reference(), "00734");
+    }
+}
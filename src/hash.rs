@@ -0,0 +1,330 @@
+//! Allocation free voice code hashing for high throughput callers
+//!
+//! These work on bytes, for ASCII input, which is all a valid GTIN, LOT and pack date
+//! can contain, they agree with `HashVoiceCode::generate_voice_code_hash`.
+
+use crate::create_crc_lut::create_crc_lut;
+
+/// CRC look up table similar to reference impl on producetraceability.org using 40961 as the polynomial, built at compile time
+pub(crate) static HASH_VOICE_CHECKSUM_HASH_T: [u16; 256] = create_crc_lut(40961);
+
+/// Slice-by-8 tables, `[0]` is the plain table and `[k]` advances an entry by `k` more zero bytes
+static HASH_SLICE_T: [[u16; 256]; 8] = create_slice_tables(&HASH_VOICE_CHECKSUM_HASH_T);
+
+const fn create_slice_tables(lut: &[u16; 256]) -> [[u16; 256]; 8] {
+    let mut tables = [[0u16; 256]; 8];
+    tables[0] = *lut;
+
+    // const fn, so while loops instead of iterators
+    let mut k = 1;
+    while k < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = tables[k - 1][i];
+            tables[k][i] = (prev >> 8) ^ lut[(prev & 0xff) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+    tables
+}
+
+/// The voice code is the CRC reduced to four decimal digits
+pub(crate) const VOICE_CODE_MODULUS: u16 = 10000;
+
+/// Voice code for `input` as a number from 0 to 9999
+///
+/// # Example
+/// ```
+/// use voicecode::hash_u16;
+/// assert_eq!(hash_u16(b"12345678901244LOT123030102"), 6991);
+/// ```
+pub fn hash_u16(input: &[u8]) -> u16 {
+    crc_u16(input) % VOICE_CODE_MODULUS
+}
+
+/// CRC of `input` before it is reduced to a voice code
+///
+/// The CRC is CRC-16/ARC (reflected polynomial 0xA001, zero init, no final XOR), so it
+/// can be cross-checked against any other CRC-16/ARC implementation.
+///
+/// # Example
+/// ```
+/// use voicecode::{ crc_u16, hash_u16 };
+/// assert_eq!(crc_u16(b"123456789"), 0xBB3D);
+/// assert_eq!(crc_u16(b"12345678901244LOT123030102") % 10000, hash_u16(b"12345678901244LOT123030102"));
+/// ```
+pub fn crc_u16(input: &[u8]) -> u16 {
+    let mut hasher = VoiceCodeHasher::new();
+    hasher.update(input);
+    hasher.crc()
+}
+
+/// Write the four ASCII digits of the voice code for `input` into `out`
+///
+/// # Example
+/// ```
+/// use voicecode::hash_into;
+/// let mut out = [0u8; 4];
+/// hash_into(b"61414100734933LOT123030110", &mut out);
+/// assert_eq!(&out, b"0065");
+/// ```
+pub fn hash_into(input: &[u8], out: &mut [u8; 4]) {
+    let mut code = hash_u16(input);
+    for digit in out.iter_mut().rev() {
+        *digit = b'0' + (code % 10) as u8;
+        code /= 10;
+    }
+}
+
+/// What to do with characters outside ASCII when hashing text
+///
+/// Valid GTINs, LOTs and pack dates are all ASCII, so `HashVoiceCode::new` and the builder
+/// never hash anything else. This only matters for raw text passed to
+/// `HashVoiceCode::generate_voice_code_hash_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NonAsciiPolicy {
+    /// Fail with `VoiceCodeError::NonAscii`
+    #[default]
+    Reject,
+    /// Hash UTF-16 code units like the JavaScript widget's `charCodeAt`, characters
+    /// outside the BMP count as two surrogate units
+    JsCompatUtf16,
+    /// Hash one step per Unicode scalar value, what `generate_voice_code_hash` has always done
+    RawScalar,
+}
+
+/// Voice code hashing over input that arrives in pieces
+///
+/// Feeding the GTIN, LOT and pack date separately gives the same code as hashing the
+/// concatenated text, with `std` it is also an `io::Write` so it can be the target of `io::copy`.
+///
+/// # Example
+/// ```
+/// use voicecode::VoiceCodeHasher;
+/// let mut hasher = VoiceCodeHasher::new();
+/// hasher.update(b"12345678901244");
+/// hasher.update(b"LOT123");
+/// hasher.update(b"030102");
+/// assert_eq!(hasher.finalize(), 6991);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct VoiceCodeHasher {
+    crc: u16,
+}
+
+impl VoiceCodeHasher {
+    pub const fn new() -> Self {
+        VoiceCodeHasher { crc: 0 }
+    }
+
+    /// Hasher that has already consumed `prefix`, usually the GTIN
+    ///
+    /// The hasher is `Copy`, so one prefix state serves every LOT and pack date for that
+    /// GTIN and each code only hashes the suffix.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::VoiceCodeHasher;
+    /// let gtin = VoiceCodeHasher::with_prefix("12345678901244");
+    /// assert_eq!(gtin.hash_suffix(b"LOT123030102"), 6991);
+    /// assert_eq!(gtin.hash_suffix(b"LOT124030102"), voicecode::hash_u16(b"12345678901244LOT124030102"));
+    /// ```
+    pub fn with_prefix<P: AsRef<str>>(prefix: P) -> Self {
+        let mut hasher = VoiceCodeHasher::new();
+        hasher.update(prefix.as_ref().as_bytes());
+        hasher
+    }
+
+    /// Voice code for the input so far followed by `suffix`, leaving this hasher unchanged
+    pub fn hash_suffix(&self, suffix: &[u8]) -> u16 {
+        let mut hasher = *self;
+        hasher.update(suffix);
+        hasher.finalize()
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        let t = &HASH_SLICE_T;
+        let mut crc = self.crc;
+
+        // eight bytes per step, the register only overlaps the first two
+        let mut chunks = bytes.chunks_exact(8);
+        for c in &mut chunks {
+            let low = (crc ^ u16::from(c[0])) & 0xff;
+            let high = ((crc >> 8) ^ u16::from(c[1])) & 0xff;
+            crc = t[7][low as usize]
+                ^ t[6][high as usize]
+                ^ t[5][c[2] as usize]
+                ^ t[4][c[3] as usize]
+                ^ t[3][c[4] as usize]
+                ^ t[2][c[5] as usize]
+                ^ t[1][c[6] as usize]
+                ^ t[0][c[7] as usize];
+        }
+        for &b in chunks.remainder() {
+            crc = (crc >> 8) ^ HASH_VOICE_CHECKSUM_HASH_T[((crc ^ u16::from(b)) & 0xff) as usize];
+        }
+        self.crc = crc;
+    }
+
+    /// Voice code for everything passed to `update` so far, 0 to 9999
+    pub fn finalize(&self) -> u16 {
+        self.crc % VOICE_CODE_MODULUS
+    }
+
+    /// CRC-16/ARC of everything passed to `update` so far, before the modulo
+    pub fn crc(&self) -> u16 {
+        self.crc
+    }
+
+    /// Clear back to the state of `new`
+    pub fn reset(&mut self) {
+        self.crc = 0;
+    }
+}
+
+/// CRC-16/ARC as a `core::hash::Hasher`, for integrity checks that want the voice code
+/// table without another CRC dependency
+///
+/// `finish` is the full CRC widened to `u64`, not reduced to a voice code. `Hash` impls add
+/// their own framing, `str` for one appends a 0xFF byte, so call `write` directly when the
+/// result must match a CRC-16/ARC computed elsewhere.
+///
+/// # Example
+/// ```
+/// use core::hash::{ BuildHasherDefault, Hasher };
+/// use voicecode::Crc16Hasher;
+/// let mut hasher = Crc16Hasher::new();
+/// hasher.write(b"123456789");
+/// assert_eq!(hasher.finish(), 0xBB3D);
+/// assert_eq!(hasher.crc16(), 0xBB3D);
+///
+/// let map: std::collections::HashMap<&str, u8, BuildHasherDefault<Crc16Hasher>> = [("LOT123", 1)].into_iter().collect();
+/// assert_eq!(map["LOT123"], 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Crc16Hasher {
+    inner: VoiceCodeHasher,
+}
+
+impl Crc16Hasher {
+    pub const fn new() -> Self {
+        Crc16Hasher { inner: VoiceCodeHasher::new() }
+    }
+
+    /// CRC-16/ARC of everything written so far
+    pub fn crc16(&self) -> u16 {
+        self.inner.crc()
+    }
+}
+
+impl core::hash::Hasher for Crc16Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        u64::from(self.inner.crc())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for VoiceCodeHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::voicecode::HashVoiceCode;
+
+    #[test]
+    fn test_matches_string_hash() {
+        for input in ["", "a", "12345678901244LOT123030102", "6141410073493332abcd030102", "~!\"%&'()*+,-./:;<=>?_"] {
+            let mut out = [0u8; 4];
+            hash_into(input.as_bytes(), &mut out);
+            let expected = HashVoiceCode::generate_voice_code_hash(input);
+            assert_eq!(std::str::from_utf8(&out).unwrap(), expected, "{}", input);
+            assert_eq!(format!("{:04}", hash_u16(input.as_bytes())), expected);
+        }
+    }
+
+    #[test]
+    fn test_hasher_write() {
+        let input = b"61414100734933LOT123030110";
+        let mut hasher = VoiceCodeHasher::new();
+        std::io::copy(&mut &input[..], &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), 65);
+
+        // any split of the input gives the same code
+        for split in 0..input.len() {
+            let mut hasher = VoiceCodeHasher::new();
+            hasher.update(&input[..split]);
+            hasher.update(&input[split..]);
+            assert_eq!(hasher.finalize(), hash_u16(input));
+        }
+    }
+
+    #[test]
+    fn test_crc16_hasher() {
+        use core::hash::{Hash, Hasher};
+
+        let mut hasher = Crc16Hasher::new();
+        hasher.write(b"12345678901244");
+        hasher.write(b"LOT123030102");
+        assert_eq!(hasher.finish() % 10000, 6991);
+
+        // str hashing appends 0xff, so it is the CRC of the framed bytes
+        let mut framed = Crc16Hasher::new();
+        "123456789".hash(&mut framed);
+        assert_eq!(framed.crc16(), crc_u16(b"123456789\xff"));
+    }
+
+    /// Byte at a time loop the slice-by-8 update must agree with
+    fn reference(input: &[u8]) -> u16 {
+        let mut crc: u16 = 0;
+        for &b in input {
+            crc = (crc >> 8) ^ HASH_VOICE_CHECKSUM_HASH_T[((crc ^ u16::from(b)) & 0xff) as usize];
+        }
+        crc % 10000
+    }
+
+    #[test]
+    fn test_slice_by_8() {
+        // xorshift so every byte value and length up to a few chunks gets covered
+        let mut state: u32 = 0x9e37_79b9;
+        let mut input = Vec::new();
+        for _ in 0..2048 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            input.push(state as u8);
+        }
+        for len in 0..=40 {
+            for start in [0, 1, 7, 500] {
+                let slice = &input[start..start + len];
+                assert_eq!(hash_u16(slice), reference(slice), "start {} len {}", start, len);
+            }
+        }
+        assert_eq!(hash_u16(&input), reference(&input));
+    }
+
+    #[test]
+    fn test_prefix() {
+        let gtin = crate::Gtin::parse_unchecked("61414100734933").unwrap();
+        let prefix = VoiceCodeHasher::with_prefix(&gtin);
+        for lot in ["LOT123", "A", "32abcd", "12345678901234567890"] {
+            let voice_code = HashVoiceCode::new(&gtin, lot, "03", "01", "10").unwrap();
+            let code = prefix.hash_suffix(format!("{}030110", lot).as_bytes());
+            assert_eq!(format!("{:04}", code), voice_code.voice_code);
+        }
+        assert_eq!(prefix, VoiceCodeHasher::with_prefix("61414100734933"));
+    }
+}
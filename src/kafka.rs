@@ -0,0 +1,353 @@
+//! Pack-out event enrichment for Kafka style message pipelines
+//!
+//! A pack-out event is a JSON object with string `gtin`, `lot` and `pack_date` fields and
+//! any other fields. [`Pipeline`] reads an event, adds `voice_code`, `major` and `minor`,
+//! and writes it to the output topic. Events that are not JSON objects or fail validation
+//! go to the dead-letter topic as `{"error":…,"event":…}` with the original payload as a
+//! string, so one bad event never blocks its partition.
+//!
+//! Delivery is at-least-once: an offset is committed only after the enriched or dead-letter
+//! message was acknowledged, a crash in between redelivers the event. Consumers of the output
+//! topic should treat events as idempotent, which enrichment is.
+//!
+//! rdkafka's `BaseConsumer` is an [`EventSource`] and its `FutureProducer` an [`EventSink`].
+//! Create the consumer with `enable.auto.commit=false` so offsets only move through
+//! [`EventSource::commit`]. Other clients work by implementing the two traits.
+//!
+//! ```no_run
+//! use rdkafka::config::ClientConfig;
+//! use rdkafka::consumer::{BaseConsumer, Consumer};
+//! use rdkafka::producer::FutureProducer;
+//! use voicecode::kafka::Pipeline;
+//!
+//! let mut consumer: BaseConsumer = ClientConfig::new()
+//!     .set("bootstrap.servers", "localhost:9092")
+//!     .set("group.id", "voicecode")
+//!     .set("enable.auto.commit", "false")
+//!     .create()
+//!     .unwrap();
+//! consumer.subscribe(&["packout"]).unwrap();
+//! let mut producer: FutureProducer = ClientConfig::new().set("bootstrap.servers", "localhost:9092").create().unwrap();
+//!
+//! let pipeline = Pipeline::new("packout.enriched", "packout.dead-letter");
+//! loop {
+//!     pipeline.process_next(&mut consumer, &mut producer).unwrap();
+//! }
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use rdkafka::consumer::{BaseConsumer, CommitMode, Consumer, ConsumerContext};
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::{AsyncRuntime, Timeout};
+use rdkafka::{ClientContext, Offset, TopicPartitionList};
+use serde_json::{json, Value};
+
+use crate::batch::jsonl::enrich;
+
+/// Message read from a topic
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub key: Option<Vec<u8>>,
+    pub payload: Vec<u8>,
+}
+
+/// Consumer side of the pipeline
+pub trait EventSource {
+    type Error;
+
+    /// Next message, `None` when none is ready yet
+    fn poll(&mut self) -> Result<Option<Message>, Self::Error>;
+
+    /// Mark `message` and everything before it on its partition as processed
+    fn commit(&mut self, message: &Message) -> Result<(), Self::Error>;
+}
+
+/// Producer side of the pipeline
+pub trait EventSink {
+    type Error;
+
+    /// Write a message, only returning `Ok` once the broker acknowledged it
+    fn send(&mut self, topic: &str, key: Option<&[u8]>, payload: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// How long `BaseConsumer` waits for a message before `poll` returns `None`
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+impl<C: ConsumerContext> EventSource for BaseConsumer<C> {
+    type Error = KafkaError;
+
+    fn poll(&mut self) -> Result<Option<Message>, KafkaError> {
+        let Some(message) = BaseConsumer::poll(self, POLL_TIMEOUT).transpose()? else { return Ok(None) };
+        use rdkafka::Message as _;
+        Ok(Some(Message {
+            topic: message.topic().to_string(),
+            partition: message.partition(),
+            offset: message.offset(),
+            key: message.key().map(<[u8]>::to_vec),
+            payload: message.payload().unwrap_or_default().to_vec(),
+        }))
+    }
+
+    fn commit(&mut self, message: &Message) -> Result<(), KafkaError> {
+        // the committed offset is the next one to read
+        let mut offsets = TopicPartitionList::new();
+        offsets.add_partition_offset(&message.topic, message.partition, Offset::Offset(message.offset + 1))?;
+        Consumer::commit(self, &offsets, CommitMode::Sync)
+    }
+}
+
+impl<C: ClientContext + 'static, R: AsyncRuntime> EventSink for FutureProducer<C, R> {
+    type Error = KafkaError;
+
+    /// Blocks until the delivery report arrives, waiting for room when the local queue is full
+    fn send(&mut self, topic: &str, key: Option<&[u8]>, payload: &[u8]) -> Result<(), KafkaError> {
+        let mut record = FutureRecord::to(topic).payload(payload);
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+        futures_executor::block_on(FutureProducer::send(self, record, Timeout::Never)).map(drop).map_err(|(error, _)| error)
+    }
+}
+
+/// Result of enriching one event payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Event with voice code fields added
+    Enriched(Vec<u8>),
+    /// Dead-letter record for an event that could not be enriched
+    DeadLetter(Vec<u8>),
+}
+
+/// Enrich one pack-out event
+///
+/// # Example
+/// ```
+/// use voicecode::kafka::{ enrich_event, Outcome };
+/// let event = br#"{"pallet":"P1","gtin":"12345678901244","lot":"LOT123","pack_date":"030102"}"#;
+/// let Outcome::Enriched(out) = enrich_event(event) else { panic!() };
+/// assert_eq!(
+///     out,
+///     br#"{"pallet":"P1","gtin":"12345678901244","lot":"LOT123","pack_date":"030102","voice_code":"6991","major":"91","minor":"69"}"#
+/// );
+/// assert!(matches!(enrich_event(b"not json"), Outcome::DeadLetter(_)));
+/// ```
+pub fn enrich_event(payload: &[u8]) -> Outcome {
+    let error = match serde_json::from_slice::<Value>(payload) {
+        Ok(Value::Object(mut object)) => match enrich(&mut object) {
+            Ok(()) => return Outcome::Enriched(Value::Object(object).to_string().into_bytes()),
+            Err(e) => e.to_string(),
+        },
+        Ok(_) => "event is not a JSON object".to_string(),
+        Err(e) => e.to_string(),
+    };
+    let event = String::from_utf8_lossy(payload);
+    Outcome::DeadLetter(json!({ "error": error, "event": event }).to_string().into_bytes())
+}
+
+/// Where a processed event was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivered {
+    Output,
+    DeadLetter,
+}
+
+/// Failure that stops the pipeline, the failed event is not committed and is redelivered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineError<S, K> {
+    Source(S),
+    Sink(K),
+}
+
+impl<S: fmt::Display, K: fmt::Display> fmt::Display for PipelineError<S, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::Source(e) => write!(f, "consumer: {}", e),
+            PipelineError::Sink(e) => write!(f, "producer: {}", e),
+        }
+    }
+}
+
+impl<S, K> std::error::Error for PipelineError<S, K>
+where
+    S: fmt::Debug + fmt::Display,
+    K: fmt::Debug + fmt::Display,
+{
+}
+
+/// Consume, enrich and produce with at-least-once delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub output_topic: String,
+    pub dead_letter_topic: String,
+}
+
+impl Pipeline {
+    pub fn new(output_topic: &str, dead_letter_topic: &str) -> Self {
+        Pipeline { output_topic: output_topic.to_string(), dead_letter_topic: dead_letter_topic.to_string() }
+    }
+
+    /// Process the next message, `Ok(None)` when the source had none ready
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::kafka::{ Delivered, EventSink, EventSource, Message, Pipeline };
+    ///
+    /// struct Source(Vec<Message>, Vec<i64>);
+    /// impl EventSource for Source {
+    ///     type Error = String;
+    ///     fn poll(&mut self) -> Result<Option<Message>, String> {
+    ///         Ok(self.0.pop())
+    ///     }
+    ///     fn commit(&mut self, message: &Message) -> Result<(), String> {
+    ///         Ok(self.1.push(message.offset))
+    ///     }
+    /// }
+    ///
+    /// struct Sink(Vec<String>);
+    /// impl EventSink for Sink {
+    ///     type Error = String;
+    ///     fn send(&mut self, topic: &str, _key: Option<&[u8]>, _payload: &[u8]) -> Result<(), String> {
+    ///         Ok(self.0.push(topic.to_string()))
+    ///     }
+    /// }
+    ///
+    /// let payload = br#"{"gtin":"12345678901244","lot":"LOT 123","pack_date":"030102"}"#.to_vec();
+    /// let mut source = Source(vec![Message { offset: 7, payload, ..Default::default() }], Vec::new());
+    /// let mut sink = Sink(Vec::new());
+    /// let pipeline = Pipeline::new("packout.enriched", "packout.dead-letter");
+    /// assert_eq!(pipeline.process_next(&mut source, &mut sink), Ok(Some(Delivered::DeadLetter)));
+    /// assert_eq!(pipeline.process_next(&mut source, &mut sink), Ok(None));
+    /// assert_eq!(sink.0, ["packout.dead-letter"]);
+    /// assert_eq!(source.1, [7]);
+    /// ```
+    pub fn process_next<S, K>(
+        &self,
+        source: &mut S,
+        sink: &mut K,
+    ) -> Result<Option<Delivered>, PipelineError<S::Error, K::Error>>
+    where
+        S: EventSource,
+        K: EventSink,
+    {
+        let Some(message) = source.poll().map_err(PipelineError::Source)? else { return Ok(None) };
+        let (topic, payload, delivered) = match enrich_event(&message.payload) {
+            Outcome::Enriched(payload) => (&self.output_topic, payload, Delivered::Output),
+            Outcome::DeadLetter(payload) => (&self.dead_letter_topic, payload, Delivered::DeadLetter),
+        };
+        sink.send(topic, message.key.as_deref(), &payload).map_err(PipelineError::Sink)?;
+        source.commit(&message).map_err(PipelineError::Source)?;
+        Ok(Some(delivered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    /// Redelivers from the last committed offset, like a consumer restarting after a crash
+    #[derive(Default)]
+    struct Partition {
+        log: Vec<Vec<u8>>,
+        committed: usize,
+        pending: VecDeque<usize>,
+    }
+
+    impl Partition {
+        fn restart(&mut self) {
+            self.pending = (self.committed..self.log.len()).collect();
+        }
+    }
+
+    impl EventSource for Partition {
+        type Error = String;
+
+        fn poll(&mut self) -> Result<Option<Message>, String> {
+            Ok(self.pending.pop_front().map(|offset| Message {
+                topic: "packout".to_string(),
+                offset: offset as i64,
+                key: Some(b"pallet".to_vec()),
+                payload: self.log[offset].clone(),
+                ..Default::default()
+            }))
+        }
+
+        fn commit(&mut self, message: &Message) -> Result<(), String> {
+            self.committed = message.offset as usize + 1;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Topics {
+        sent: Vec<(String, Value)>,
+        fail_next: bool,
+    }
+
+    impl EventSink for Topics {
+        type Error = String;
+
+        fn send(&mut self, topic: &str, key: Option<&[u8]>, payload: &[u8]) -> Result<(), String> {
+            assert_eq!(key, Some(&b"pallet"[..]));
+            if std::mem::take(&mut self.fail_next) {
+                return Err("broker unavailable".to_string());
+            }
+            self.sent.push((topic.to_string(), serde_json::from_slice(payload).unwrap()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_at_least_once() {
+        let mut source = Partition {
+            log: vec![
+                br#"{"gtin":"61414100734933","lot":"32abcd","pack_date":"030102"}"#.to_vec(),
+                br#"{"gtin":"61414100734933","lot":"32abcd"}"#.to_vec(),
+                br#"{"gtin":"61414100734933","lot":"LOT123","pack_date":"030110"}"#.to_vec(),
+            ],
+            ..Default::default()
+        };
+        source.restart();
+        let mut sink = Topics::default();
+        let pipeline = Pipeline::new("out", "dlq");
+
+        assert_eq!(pipeline.process_next(&mut source, &mut sink), Ok(Some(Delivered::Output)));
+        assert_eq!(pipeline.process_next(&mut source, &mut sink), Ok(Some(Delivered::DeadLetter)));
+        sink.fail_next = true;
+        let error = pipeline.process_next(&mut source, &mut sink).unwrap_err();
+        assert_eq!(error.to_string(), "producer: broker unavailable");
+        assert_eq!(source.committed, 2);
+
+        source.restart();
+        assert_eq!(pipeline.process_next(&mut source, &mut sink), Ok(Some(Delivered::Output)));
+        assert_eq!(pipeline.process_next(&mut source, &mut sink), Ok(None));
+        assert_eq!(source.committed, 3);
+
+        let topics: Vec<&str> = sink.sent.iter().map(|(topic, _)| topic.as_str()).collect();
+        assert_eq!(topics, ["out", "dlq", "out"]);
+        assert_eq!(sink.sent[0].1["voice_code"], "8079");
+        assert_eq!(sink.sent[1].1["error"], "pack_date is required");
+        assert_eq!(sink.sent[1].1["event"], r#"{"gtin":"61414100734933","lot":"32abcd"}"#);
+        assert_eq!(sink.sent[2].1["voice_code"], "0065");
+    }
+
+    #[test]
+    fn test_rdkafka_without_broker() {
+        use rdkafka::config::ClientConfig;
+
+        let mut consumer: BaseConsumer = ClientConfig::new().set("group.id", "voicecode").create().unwrap();
+        assert_eq!(EventSource::poll(&mut consumer), Ok(None));
+
+        let mut producer: FutureProducer =
+            ClientConfig::new().set("bootstrap.servers", "127.0.0.1:1").set("message.timeout.ms", "200").create().unwrap();
+        let error = EventSink::send(&mut producer, "packout", Some(b"pallet"), b"{}").unwrap_err();
+        assert!(matches!(error, KafkaError::MessageProduction(_)), "{:?}", error);
+    }
+}
@@ -0,0 +1,199 @@
+//! EPL2 output for Eltron and older Zebra desktop printers such as the LP2844
+
+use std::fmt::Write;
+
+use crate::gs1::attributes::CaseAttributes;
+use crate::gs1::DateAi;
+use crate::label::{elements, fields};
+use crate::voicecode::HashVoiceCode;
+
+/// Placement and sizes for the EPL2 label, positions in dots
+///
+/// EPL2 only has fixed bitmap fonts, text sizes are a font number scaled by an integer
+/// multiplier. The defaults fit a 4 inch wide label at 203 dpi.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EplOptions {
+    /// Printable width, `q`
+    pub label_width: u32,
+    /// Left edge of the label content
+    pub x: u32,
+    /// Top edge of the label content
+    pub y: u32,
+    /// Narrow bar width for the barcode
+    pub module_width: u32,
+    /// Barcode bar height
+    pub barcode_height: u32,
+    /// Font for the GTIN, LOT and pack date text, 1 to 4
+    pub text_font: u8,
+    /// Font for the interpretation line under the barcode, 1 to 4
+    pub human_readable_font: u8,
+    /// Left edge of the voice code box
+    pub voice_code_x: u32,
+    /// Multiplier applied to `text_font` for the two minor digits
+    pub minor_multiplier: u32,
+    /// Multiplier applied to `text_font` for the two major digits
+    pub major_multiplier: u32,
+    /// AI the date is carried under, AI(13) pack date unless a trading partner requires another
+    pub date_ai: DateAi,
+    /// Weights, country of origin and serial carried ahead of the LOT
+    pub attributes: CaseAttributes,
+}
+
+impl Default for EplOptions {
+    fn default() -> Self {
+        EplOptions {
+            label_width: 812,
+            x: 30,
+            y: 30,
+            module_width: 2,
+            barcode_height: 100,
+            text_font: 4,
+            human_readable_font: 2,
+            voice_code_x: 560,
+            minor_multiplier: 2,
+            major_multiplier: 3,
+            date_ai: DateAi::PackDate,
+            attributes: CaseAttributes::default(),
+        }
+    }
+}
+
+/// Complete `N … P1` label ready to send to the printer
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode };
+/// use voicecode::label::epl::{ render, EplOptions };
+/// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+/// let epl = render(&voice_code, &EplOptions::default());
+/// assert!(epl.starts_with("\nN\n"));
+/// assert!(epl.contains("B30,132,0,1E,2,2,100,N,\"01106141410073461303010210LOT123\""));
+/// assert!(epl.ends_with("P1\n"));
+/// ```
+pub fn render(voice_code: &HashVoiceCode, options: &EplOptions) -> String {
+    // the leading newline ends any half sent command left in the printer's buffer
+    format!("\nN\nq{}\n{}P1\n", options.label_width, render_fragment(voice_code, options))
+}
+
+/// Label commands without `N`/`P1`, for placing inside a larger label format
+pub fn render_fragment(voice_code: &HashVoiceCode, options: &EplOptions) -> String {
+    let o = options;
+    let mut out = String::new();
+    let (char_width, char_height) = font_cell(o.text_font);
+    let line = char_height + char_height / 4;
+
+    // human readable fields
+    let fields = fields(voice_code, o.date_ai);
+    for (i, text) in fields.iter().enumerate() {
+        let y = o.y + line * i as u32;
+        let _ = writeln!(out, "A{},{},0,{},1,1,N,\"{}\"", o.x, y, o.text_font, escape(text));
+    }
+
+    // GS1-128, type 1E starts with FNC1, the lot is the last element so no separator is needed
+    let barcode_y = o.y + line * fields.len() as u32 + char_height / 2;
+    let elements = elements(voice_code, o.date_ai, &o.attributes);
+    let data = elements.to_unbracketed();
+    let _ = writeln!(
+        out,
+        "B{},{},0,1E,{},{},{},N,\"{}\"",
+        o.x,
+        barcode_y,
+        o.module_width,
+        o.module_width,
+        o.barcode_height,
+        escape(&data)
+    );
+    // EPL2 does not bracket the AIs in its own interpretation line, so print it as text
+    let _ = writeln!(
+        out,
+        "A{},{},0,{},1,1,N,\"{}\"",
+        o.x,
+        barcode_y + o.barcode_height + 4,
+        o.human_readable_font,
+        escape(&elements.to_string())
+    );
+
+    // voice code box, minor digits small then major digits large, bottoms aligned
+    let minor_height = char_height * o.minor_multiplier;
+    let major_height = char_height * o.major_multiplier;
+    let minor_width = char_width * o.minor_multiplier * 2;
+    let major_width = char_width * o.major_multiplier * 2;
+    let pad = minor_height / 4;
+    let box_width = pad * 3 + minor_width + major_width;
+    let box_height = major_height + pad * 2;
+    let baseline = o.y + pad + major_height;
+    let _ = writeln!(out, "X{},{},3,{},{}", o.voice_code_x, o.y, o.voice_code_x + box_width, o.y + box_height);
+    let _ = writeln!(
+        out,
+        "A{},{},0,{},{m},{m},N,\"{}\"",
+        o.voice_code_x + pad,
+        baseline - minor_height,
+        o.text_font,
+        voice_code.voice_code_minor,
+        m = o.minor_multiplier
+    );
+    let _ = writeln!(
+        out,
+        "A{},{},0,{},{m},{m},N,\"{}\"",
+        o.voice_code_x + pad * 2 + minor_width,
+        o.y + pad,
+        o.text_font,
+        voice_code.voice_code_major,
+        m = o.major_multiplier
+    );
+    out
+}
+
+/// Character cell of the resident fonts at 203 dpi, width and height in dots
+fn font_cell(font: u8) -> (u32, u32) {
+    match font {
+        1 => (10, 12),
+        2 => (12, 16),
+        3 => (14, 20),
+        5 => (34, 48),
+        _ => (16, 24),
+    }
+}
+
+/// Quotes and backslashes inside EPL2 string data are backslash escaped
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch == '"' || ch == '\\' {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+
+    #[test]
+    fn test_render() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        let epl = render_fragment(&voice_code, &EplOptions::default());
+        let lines: Vec<&str> = epl.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "A30,30,0,4,1,1,N,\"GTIN 12345678901244\"",
+                "A30,60,0,4,1,1,N,\"LOT LOT123\"",
+                "A30,90,0,4,1,1,N,\"PACK DATE 030102\"",
+                "B30,132,0,1E,2,2,100,N,\"01123456789012441303010210LOT123\"",
+                "A30,236,0,2,1,1,N,\"(01)12345678901244(13)030102(10)LOT123\"",
+                "X560,30,3,756,126",
+                "A572,66,0,4,2,2,N,\"69\"",
+                "A648,42,0,4,3,3,N,\"91\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"LOT"1\"#), r#"LOT\"1\\"#);
+    }
+}
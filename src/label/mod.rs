@@ -0,0 +1,41 @@
+//! Printable PTI case label output for a `HashVoiceCode`
+//!
+//! Every printer language lays out the same three parts, a GS1-128 barcode carrying
+//! (01) GTIN, (13) pack date and (10) lot, the human readable text, and the voice code
+//! box with the two minor digits small and the two major digits large. `zpl::render_pallet`
+//! adds the SSCC and its (00) barcode above the same content for hybrid pallet labels.
+//!
+//! The `date_ai` option of each renderer moves the date to AI(15), AI(17) or AI(7007) for
+//! trading partners that require it, the voice code is unchanged. `attributes` adds the
+//! AI(310n) or AI(320n) weight a variable measure case must carry, see
+//! `Gtin::is_variable_measure`, and the gross weight, AI(422) country of origin and AI(21)
+//! serial some buyers require on the same barcode.
+//!
+//! With the `template` feature `template::Template` lays out a label from TOML or JSON instead.
+
+pub mod epl;
+#[cfg(feature = "image")]
+pub mod png;
+pub mod svg;
+#[cfg(feature = "template")]
+pub mod template;
+pub mod zpl;
+
+use crate::gs1::attributes::CaseAttributes;
+use crate::gs1::{DateAi, Gs1ElementString};
+use crate::voicecode::HashVoiceCode;
+
+/// Element string the GS1-128 carries, `(01)…(13)…(10)…` with any optional case AIs ahead of
+/// the LOT
+pub(crate) fn elements(voice_code: &HashVoiceCode, date_ai: DateAi, attributes: &CaseAttributes) -> Gs1ElementString {
+    Gs1ElementString::for_voice_code(voice_code, date_ai).with_attributes(attributes)
+}
+
+/// Human readable GTIN, LOT and date fields printed above the barcode
+pub(crate) fn fields(voice_code: &HashVoiceCode, date_ai: DateAi) -> [String; 3] {
+    [
+        format!("GTIN {}", voice_code.gtin),
+        format!("LOT {}", voice_code.lot),
+        format!("{} {}", date_ai.label(), voice_code.pack_date().yymmdd()),
+    ]
+}
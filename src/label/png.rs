@@ -0,0 +1,113 @@
+//! Monochrome PNG output for printers that only accept bitmaps
+//!
+//! Rasterizes the `label::svg` layout one pixel per printer dot, text uses the system fonts.
+
+use std::sync::{Arc, OnceLock};
+
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{fontdb, Options, Tree};
+
+use crate::label::svg::{self, SvgOptions};
+use crate::voicecode::HashVoiceCode;
+
+/// Luminance below which an anti-aliased pixel prints dark
+const THRESHOLD: u8 = 128;
+
+fn system_fonts() -> Arc<fontdb::Database> {
+    static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = fontdb::Database::new();
+            fonts.load_system_fonts();
+            // fontdb maps sans-serif to Arial, fall back to a sans face that is installed
+            let query = fontdb::Query { families: &[fontdb::Family::SansSerif], ..fontdb::Query::default() };
+            if fonts.query(&query).is_none() {
+                let families: Vec<String> = fonts.faces().flat_map(|face| face.families.iter().map(|(name, _)| name.clone())).collect();
+                let fallback = families.iter().find(|name| name.contains("Sans") && !name.contains("Mono")).or(families.first());
+                if let Some(family) = fallback.cloned() {
+                    fonts.set_sans_serif_family(family);
+                }
+            }
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// 1 bit grayscale PNG of the label, `options.width` by `options.height` pixels tagged with `options.dpi`
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode };
+/// use voicecode::label::png::render;
+/// use voicecode::label::svg::SvgOptions;
+/// let voice_code = HashVoiceCode::new("12345678901244", "LOT123", "03", "01", "02").unwrap();
+/// let png = render(&voice_code, &SvgOptions::for_dpi(300));
+/// assert!(png.starts_with(b"\x89PNG"));
+/// ```
+pub fn render(voice_code: &HashVoiceCode, options: &SvgOptions) -> Vec<u8> {
+    let dark = rasterize(voice_code, options);
+    encode(&dark, options.width, options.height, options.dpi)
+}
+
+/// One entry per dot row by row, true for dark
+fn rasterize(voice_code: &HashVoiceCode, options: &SvgOptions) -> Vec<bool> {
+    let document = svg::render(voice_code, options);
+    let usvg_options = Options { fontdb: system_fonts(), ..Options::default() };
+    let tree = Tree::from_str(&document, &usvg_options).expect("label::svg produces valid SVG");
+
+    // the SVG is sized in inches, scale so one viewBox unit is one pixel
+    let mut pixmap = Pixmap::new(options.width, options.height).expect("label has a non zero size");
+    let size = tree.size();
+    let transform = Transform::from_scale(options.width as f32 / size.width(), options.height as f32 / size.height());
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    pixmap.pixels().iter().map(|p| p.alpha() > 0 && p.demultiply().red() < THRESHOLD).collect()
+}
+
+fn encode(dark: &[bool], width: u32, height: u32, dpi: u32) -> Vec<u8> {
+    let row_bytes = (width as usize).div_ceil(8);
+    let mut packed = vec![0u8; row_bytes * height as usize];
+    for (i, &is_dark) in dark.iter().enumerate() {
+        let (y, x) = (i / width as usize, i % width as usize);
+        // grayscale 1 bit, 1 is white
+        if !is_dark {
+            packed[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::One);
+    let per_meter = (f64::from(dpi) / 0.0254).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions { xppu: per_meter, yppu: per_meter, unit: png::Unit::Meter }));
+    let mut writer = encoder.write_header().expect("writing to a Vec cannot fail");
+    writer.write_image_data(&packed).expect("image data matches the header");
+    writer.finish().expect("writing to a Vec cannot fail");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        let options = SvgOptions::default();
+        let dark = rasterize(&voice_code, &options);
+        assert_eq!(dark.len(), (options.width * options.height) as usize);
+
+        // the first bar of the start character begins after the margin and quiet zone
+        let barcode_y = 20 + 35 * 3 + 14;
+        let row = &dark[(barcode_y + 10) as usize * options.width as usize..][..options.width as usize];
+        let first_bar = row.iter().position(|&d| d).unwrap();
+        assert_eq!(first_bar, 40);
+
+        let decoded = png::Decoder::new(std::io::Cursor::new(encode(&dark, options.width, options.height, options.dpi)))
+            .read_info()
+            .unwrap();
+        assert_eq!(decoded.info().width, options.width);
+        assert_eq!(decoded.info().bit_depth, png::BitDepth::One);
+    }
+}
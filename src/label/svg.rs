@@ -0,0 +1,198 @@
+//! SVG output for previewing labels in the browser
+
+use std::fmt::Write;
+
+use crate::barcode::Gs1_128;
+use crate::gs1::attributes::CaseAttributes;
+use crate::gs1::DateAi;
+use crate::label::{elements, fields};
+use crate::voicecode::HashVoiceCode;
+
+/// Label size, fonts and element sizes, all lengths in printer dots
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SvgOptions {
+    /// Printer resolution, sets the physical size of the SVG
+    pub dpi: u32,
+    /// Label width
+    pub width: u32,
+    /// Label height
+    pub height: u32,
+    /// Blank border around the content
+    pub margin: u32,
+    /// CSS font family for all text
+    pub font_family: String,
+    /// Height of the GTIN, LOT, pack date and barcode text
+    pub text_height: u32,
+    /// Narrow bar width of the barcode
+    pub module_width: u32,
+    /// Barcode bar height
+    pub barcode_height: u32,
+    /// Height of the two minor digits
+    pub minor_height: u32,
+    /// Height of the two major digits
+    pub major_height: u32,
+    /// AI the date is carried under, AI(13) pack date unless a trading partner requires another
+    pub date_ai: DateAi,
+    /// Weights, country of origin and serial carried ahead of the LOT
+    pub attributes: CaseAttributes,
+}
+
+impl SvgOptions {
+    /// 4 x 2 inch label at `dpi`, typically 203 or 300
+    pub fn for_dpi(dpi: u32) -> Self {
+        let dots = |inches: f64| (inches * f64::from(dpi)).round() as u32;
+        SvgOptions {
+            dpi,
+            width: dots(4.0),
+            height: dots(2.0),
+            margin: dots(0.1),
+            font_family: "Arial, Helvetica, sans-serif".to_string(),
+            text_height: dots(0.14),
+            module_width: (dpi / 100).max(1),
+            barcode_height: dots(0.5),
+            minor_height: dots(0.22),
+            major_height: dots(0.4),
+            date_ai: DateAi::PackDate,
+            attributes: CaseAttributes::default(),
+        }
+    }
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions::for_dpi(203)
+    }
+}
+
+/// Complete SVG document for the label
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::label::svg::{ render, SvgOptions };
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let svg = render(&voice_code, &SvgOptions::for_dpi(300));
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains(r#"width="4in" height="2in""#));
+/// assert!(svg.contains(">(01)12345678901244(13)030102(10)LOT123</text>"));
+/// ```
+pub fn render(voice_code: &HashVoiceCode, options: &SvgOptions) -> String {
+    let o = options;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}in" height="{}in" viewBox="0 0 {} {}" font-family="{}">"#,
+        f64::from(o.width) / f64::from(o.dpi),
+        f64::from(o.height) / f64::from(o.dpi),
+        o.width,
+        o.height,
+        escape(&o.font_family)
+    );
+    let _ = writeln!(out, r#"<rect width="{}" height="{}" fill="white"/>"#, o.width, o.height);
+
+    // human readable fields
+    let line = o.text_height + o.text_height / 4;
+    let fields = fields(voice_code, o.date_ai);
+    for (i, text) in fields.iter().enumerate() {
+        let baseline = o.margin + line * i as u32 + o.text_height;
+        text_element(&mut out, o.margin, baseline, o.text_height, "start", text);
+    }
+
+    // GS1-128 with a ten module quiet zone on the left, interpretation line centered below
+    let elements = elements(voice_code, o.date_ai, &o.attributes);
+    let symbol = Gs1_128::encode(&elements);
+    let barcode_x = o.margin + 10 * o.module_width;
+    let barcode_y = o.margin + line * fields.len() as u32 + o.text_height / 2;
+    let mut path = String::new();
+    let mut x = barcode_x;
+    for (i, &w) in symbol.widths().iter().enumerate() {
+        let w = u32::from(w) * o.module_width;
+        if i % 2 == 0 {
+            let _ = write!(path, "M{},{}h{}v{}h-{}z", x, barcode_y, w, o.barcode_height, w);
+        }
+        x += w;
+    }
+    let _ = writeln!(out, r#"<path d="{}" fill="black"/>"#, path);
+    let barcode_width = symbol.width() as u32 * o.module_width;
+    let hri = elements.to_string();
+    // shrink the interpretation line to the barcode width, digits are about 0.65 em wide
+    let hri_height = o.text_height.min(barcode_width * 20 / (13 * hri.len() as u32));
+    let baseline = barcode_y + o.barcode_height + hri_height + hri_height / 4;
+    text_element(&mut out, barcode_x + barcode_width / 2, baseline, hri_height, "middle", &hri);
+
+    // voice code box, minor digits small then major digits large, bottoms aligned
+    let pad = o.minor_height / 4;
+    let box_width = pad * 3 + digits_width(o.minor_height) + digits_width(o.major_height);
+    let box_height = o.major_height + pad * 2;
+    let box_x = o.width.saturating_sub(o.margin + box_width);
+    let stroke = (o.dpi / 70).max(1);
+    let _ = writeln!(
+        out,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black" stroke-width="{}"/>"#,
+        box_x, o.margin, box_width, box_height, stroke
+    );
+    let baseline = o.margin + pad + o.major_height;
+    text_element(&mut out, box_x + pad, baseline, o.minor_height, "start", &voice_code.voice_code_minor);
+    let major_x = box_x + pad * 2 + digits_width(o.minor_height);
+    text_element(&mut out, major_x, baseline, o.major_height, "start", &voice_code.voice_code_major);
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn text_element(out: &mut String, x: u32, baseline: u32, size: u32, anchor: &str, text: &str) {
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="{}" text-anchor="{}">{}</text>"#,
+        x,
+        baseline,
+        size,
+        anchor,
+        escape(text)
+    );
+}
+
+/// Approximate width of two digits at `height`
+fn digits_width(height: u32) -> u32 {
+    height * 6 / 5
+}
+
+/// Escape text for element content and attribute values, LOTs may carry &, <, >, " and '
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+
+    #[test]
+    fn test_render() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "A&B<1>", "03", "01", "02").unwrap();
+        let svg = render(&voice_code, &SvgOptions::default());
+        assert!(svg.contains(r#"viewBox="0 0 812 406""#));
+        assert!(svg.contains(">LOT A&amp;B&lt;1&gt;</text>"));
+        let minor = format!(r#"font-size="45" text-anchor="start">{}</text>"#, voice_code.voice_code_minor);
+        let major = format!(r#"font-size="81" text-anchor="start">{}</text>"#, voice_code.voice_code_major);
+        assert!(svg.contains(&minor));
+        assert!(svg.contains(&major));
+
+        // one rectangle per bar, a symbol has three bars per codeword plus the extra stop bar
+        let symbol = Gs1_128::from(&voice_code);
+        let bars = svg.matches('M').count();
+        assert_eq!(bars, symbol.widths().len().div_ceil(2));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
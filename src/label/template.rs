@@ -0,0 +1,602 @@
+//! Label layouts defined in TOML or JSON instead of code
+//!
+//! `data/label-template.toml` is the standard case label as a template to start from. A
+//! template gives the label size and a list of elements positioned in printer dots from
+//! the top left corner:
+//!
+//! ```toml
+//! width = 812
+//! height = 406
+//! date_ai = "13"
+//!
+//! [[elements]]
+//! type = "text"
+//! x = 30
+//! y = 30
+//! height = 28
+//! text = "PACKED {pack_date:%m/%d/%y}  LOT {lot}"
+//!
+//! [[elements]]
+//! type = "barcode"
+//! x = 30
+//! y = 80
+//! height = 100
+//!
+//! [[elements]]
+//! type = "box"
+//! x = 560
+//! y = 30
+//! width = 190
+//! height = 110
+//!
+//! [[elements]]
+//! type = "text"
+//! x = 575
+//! y = 45
+//! height = 80
+//! text = "{voice_code_minor} {voice_code_major}"
+//! ```
+//!
+//! Text may use `{gtin}` (the GTIN as hashed), `{lot}`, `{pack_date}` (YYMMDD), `{pack_date:FORMAT}`
+//! with a chrono strftime format, `{voice_code}`, `{voice_code_major}`, `{voice_code_minor}`
+//! and `{gs1}`, the bracketed element string. `{{` and `}}` are literal braces.
+//!
+//! `barcode` is the case's GS1-128 with the date under `date_ai`, `"13"` unless set, and
+//! takes `module_width` (2) and `human_readable` (true). `box` takes `thickness` (3). `dpi`
+//! (203) only sets the physical size of SVG output. Unknown placeholders, fields and element
+//! types are rejected when the template is loaded.
+
+use std::fmt::{self, Write};
+
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Deserializer};
+
+use crate::barcode::Gs1_128;
+use crate::gs1::{DateAi, Gs1ElementString};
+use crate::voicecode::HashVoiceCode;
+
+const SVG_FONT_FAMILY: &str = "Arial, Helvetica, sans-serif";
+
+/// Errors loading or rendering a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The template is not valid TOML or JSON, or does not describe a label
+    Parse(String),
+    /// `{pack_date:…}` used with a pack date that is not a calendar date
+    NotACalendarDate { yymmdd: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Parse(reason) => write!(f, "invalid template: {}", reason),
+            TemplateError::NotACalendarDate { yymmdd } => {
+                write!(f, "pack date {} is not a calendar date and cannot be formatted", yymmdd)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Value a placeholder is replaced with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Gtin,
+    Lot,
+    PackDate,
+    VoiceCode,
+    VoiceCodeMajor,
+    VoiceCodeMinor,
+    Gs1,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "gtin" => Field::Gtin,
+            "lot" => Field::Lot,
+            "pack_date" => Field::PackDate,
+            "voice_code" => Field::VoiceCode,
+            "voice_code_major" => Field::VoiceCodeMajor,
+            "voice_code_minor" => Field::VoiceCodeMinor,
+            "gs1" => Field::Gs1,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+    /// `{pack_date:FORMAT}`
+    Date(String),
+}
+
+/// Text with placeholders, checked when parsed
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::gs1::DateAi;
+/// use voicecode::label::template::Text;
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let text: Text = "{lot} {pack_date:%d.%m.%Y} {{{voice_code}}}".parse().unwrap();
+/// assert_eq!(text.render(&voice_code, DateAi::PackDate).unwrap(), "LOT123 02.01.2003 {6991}");
+/// assert!("{best_before}".parse::<Text>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text(Vec<Segment>);
+
+impl std::str::FromStr for Text {
+    type Err = TemplateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| TemplateError::Parse(reason);
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').ok_or_else(|| error(format!("unclosed placeholder in {:?}", text)))?;
+                    let placeholder = &rest[..end];
+                    chars = rest[end + 1..].chars();
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(match placeholder.split_once(':') {
+                        Some(("pack_date", format)) => {
+                            if StrftimeItems::new(format).any(|item| item == Item::Error) {
+                                return Err(error(format!("invalid date format {:?}", format)));
+                            }
+                            Segment::Date(format.to_string())
+                        }
+                        Some(_) => {
+                            return Err(error(format!("only pack_date takes a format, found {{{}}}", placeholder)))
+                        }
+                        None => Segment::Field(
+                            Field::from_name(placeholder)
+                                .ok_or_else(|| error(format!("unknown placeholder {{{}}}", placeholder)))?,
+                        ),
+                    });
+                }
+                '}' => return Err(error(format!("unmatched }} in {:?}", text))),
+                ch => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Text(segments))
+    }
+}
+
+impl<'de> Deserialize<'de> for Text {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(|e: TemplateError| match e {
+            TemplateError::Parse(reason) => serde::de::Error::custom(reason),
+            e => serde::de::Error::custom(e),
+        })
+    }
+}
+
+impl Text {
+    /// The text with every placeholder replaced, `{gs1}` carries the date under `date_ai`
+    pub fn render(&self, voice_code: &HashVoiceCode, date_ai: DateAi) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        for segment in &self.0 {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field(Field::Gtin) => out.push_str(voice_code.gtin.as_str()),
+                Segment::Field(Field::Lot) => out.push_str(voice_code.lot.as_str()),
+                Segment::Field(Field::PackDate) => out.push_str(&voice_code.pack_date().yymmdd()),
+                Segment::Field(Field::VoiceCode) => out.push_str(&voice_code.voice_code),
+                Segment::Field(Field::VoiceCodeMajor) => out.push_str(&voice_code.voice_code_major),
+                Segment::Field(Field::VoiceCodeMinor) => out.push_str(&voice_code.voice_code_minor),
+                Segment::Field(Field::Gs1) => {
+                    out.push_str(&Gs1ElementString::for_voice_code(voice_code, date_ai).to_string())
+                }
+                Segment::Date(format) => {
+                    let pack_date = voice_code.pack_date();
+                    let date = pack_date
+                        .date()
+                        .ok_or_else(|| TemplateError::NotACalendarDate { yymmdd: pack_date.yymmdd() })?;
+                    let _ = write!(out, "{}", date.format(format));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// One positioned part of the label, positions and sizes in dots
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Element {
+    Text { x: u32, y: u32, height: u32, text: Text },
+    /// The case's GS1-128
+    Barcode {
+        x: u32,
+        y: u32,
+        height: u32,
+        #[serde(default = "default_module_width")]
+        module_width: u32,
+        /// Print the bracketed element string under the bars
+        #[serde(default = "default_true")]
+        human_readable: bool,
+    },
+    /// Rectangle outline
+    Box {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        #[serde(default = "default_thickness")]
+        thickness: u32,
+    },
+}
+
+fn default_module_width() -> u32 {
+    2
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_thickness() -> u32 {
+    3
+}
+
+fn default_dpi() -> u32 {
+    203
+}
+
+fn date_ai<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateAi, D::Error> {
+    let ai = String::deserialize(deserializer)?;
+    DateAi::ALL
+        .into_iter()
+        .find(|date_ai| date_ai.ai() == ai)
+        .ok_or_else(|| serde::de::Error::custom(format!("date_ai {:?} is not 13, 15, 17 or 7007", ai)))
+}
+
+/// Label layout, load it with `from_toml` or `from_json`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    /// Label width in dots
+    pub width: u32,
+    /// Label height in dots
+    pub height: u32,
+    /// Printer resolution, sets the physical size of SVG output
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// AI the barcode and `{gs1}` carry the date under
+    #[serde(default, deserialize_with = "date_ai")]
+    pub date_ai: DateAi,
+    pub elements: Vec<Element>,
+}
+
+impl Template {
+    pub fn from_toml(template: &str) -> Result<Self, TemplateError> {
+        toml::from_str(template).map_err(|e| TemplateError::Parse(e.message().to_string()))
+    }
+
+    pub fn from_json(template: &str) -> Result<Self, TemplateError> {
+        serde_json::from_str(template).map_err(|e| TemplateError::Parse(e.to_string()))
+    }
+
+    /// Complete `^XA … ^XZ` ZPL label
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// use voicecode::label::template::Template;
+    /// let template = Template::from_json(r#"{
+    ///     "width": 812, "height": 406,
+    ///     "elements": [{ "type": "text", "x": 30, "y": 30, "height": 80, "text": "{voice_code_major}" }]
+    /// }"#).unwrap();
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(template.render_zpl(&voice_code).unwrap(), "^XA\n^PW812\n^LL406\n^FO30,30^A0N,80,80^FD91^FS\n^XZ\n");
+    /// ```
+    pub fn render_zpl(&self, voice_code: &HashVoiceCode) -> Result<String, TemplateError> {
+        let mut out = format!("^XA\n^PW{}\n^LL{}\n", self.width, self.height);
+        for element in &self.elements {
+            let _ = match element {
+                Element::Text { x, y, height, text } => {
+                    let text = text.render(voice_code, self.date_ai)?;
+                    writeln!(out, "^FO{},{}^A0N,{},{}^FD{}^FS", x, y, height, height, text)
+                }
+                Element::Barcode { x, y, height, module_width, human_readable } => writeln!(
+                    out,
+                    "^FO{},{}^BY{}^BCN,{},{},N,N,D^FD{}^FS",
+                    x,
+                    y,
+                    module_width,
+                    height,
+                    if *human_readable { "Y" } else { "N" },
+                    self.elements(voice_code)
+                ),
+                Element::Box { x, y, width, height, thickness } => {
+                    writeln!(out, "^FO{},{}^GB{},{},{}^FS", x, y, width, height, thickness)
+                }
+            };
+        }
+        out.push_str("^XZ\n");
+        Ok(out)
+    }
+
+    /// Complete `N … P1` EPL2 label, text uses the font and multiplier closest to its height
+    pub fn render_epl(&self, voice_code: &HashVoiceCode) -> Result<String, TemplateError> {
+        let mut out = format!("\nN\nq{}\n", self.width);
+        for element in &self.elements {
+            let _ = match element {
+                Element::Text { x, y, height, text } => {
+                    let (font, multiplier) = epl_font(*height);
+                    let text = epl_escape(&text.render(voice_code, self.date_ai)?);
+                    writeln!(out, "A{},{},0,{},{},{},N,\"{}\"", x, y, font, multiplier, multiplier, text)
+                }
+                Element::Barcode { x, y, height, module_width, human_readable } => writeln!(
+                    out,
+                    "B{},{},0,1E,{},{},{},{},\"{}\"",
+                    x,
+                    y,
+                    module_width,
+                    module_width,
+                    height,
+                    if *human_readable { "B" } else { "N" },
+                    epl_escape(&self.elements(voice_code).to_unbracketed())
+                ),
+                Element::Box { x, y, width, height, thickness } => {
+                    writeln!(out, "X{},{},{},{},{}", x, y, thickness, x + width, y + height)
+                }
+            };
+        }
+        out.push_str("P1\n");
+        Ok(out)
+    }
+
+    /// Complete SVG document for previewing the label
+    pub fn render_svg(&self, voice_code: &HashVoiceCode) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}in" height="{}in" viewBox="0 0 {} {}" font-family="{}">"#,
+            f64::from(self.width) / f64::from(self.dpi),
+            f64::from(self.height) / f64::from(self.dpi),
+            self.width,
+            self.height,
+            SVG_FONT_FAMILY
+        );
+        let _ = writeln!(out, r#"<rect width="{}" height="{}" fill="white"/>"#, self.width, self.height);
+        for element in &self.elements {
+            match element {
+                Element::Text { x, y, height, text } => {
+                    svg_text(&mut out, *x, y + height, *height, "start", &text.render(voice_code, self.date_ai)?)
+                }
+                Element::Barcode { x, y, height, module_width, human_readable } => {
+                    let elements = self.elements(voice_code);
+                    let symbol = Gs1_128::encode(&elements);
+                    let mut path = String::new();
+                    let mut bar_x = *x;
+                    for (i, &w) in symbol.widths().iter().enumerate() {
+                        let w = u32::from(w) * module_width;
+                        if i % 2 == 0 {
+                            let _ = write!(path, "M{},{}h{}v{}h-{}z", bar_x, y, w, height, w);
+                        }
+                        bar_x += w;
+                    }
+                    let _ = writeln!(out, r#"<path d="{}" fill="black"/>"#, path);
+                    if *human_readable {
+                        let width = symbol.width() as u32 * module_width;
+                        let hri = elements.to_string();
+                        // digits are about 0.65 em wide, fit the line to the bars
+                        let size = (width * 20 / (13 * hri.len() as u32)).max(1);
+                        svg_text(&mut out, x + width / 2, y + height + size + size / 4, size, "middle", &hri);
+                    }
+                }
+                Element::Box { x, y, width, height, thickness } => {
+                    let _ = writeln!(
+                        out,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black" stroke-width="{}"/>"#,
+                        x, y, width, height, thickness
+                    );
+                }
+            }
+        }
+        out.push_str("</svg>\n");
+        Ok(out)
+    }
+
+    fn elements(&self, voice_code: &HashVoiceCode) -> Gs1ElementString {
+        Gs1ElementString::for_voice_code(voice_code, self.date_ai)
+    }
+}
+
+/// EPL2 font and multiplier whose character height is closest to `height` dots
+fn epl_font(height: u32) -> (u8, u32) {
+    let mut best = (4, 1);
+    let mut best_error = u32::MAX;
+    // lowest multiplier first, native font sizes print cleanest
+    for multiplier in 1..=9 {
+        for (font, char_height) in [(1, 12), (2, 16), (3, 20), (4, 24), (5, 48)] {
+            let error = height.abs_diff(char_height * multiplier);
+            if error < best_error {
+                (best, best_error) = ((font, multiplier), error);
+            }
+        }
+    }
+    best
+}
+
+/// Quotes and backslashes inside EPL2 string data are backslash escaped
+fn epl_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn svg_text(out: &mut String, x: u32, baseline: u32, size: u32, anchor: &str, text: &str) {
+    let text = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="{}" text-anchor="{}">{}</text>"#,
+        x,
+        baseline,
+        size,
+        anchor,
+        text
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+
+    const TEMPLATE: &str = r#"
+        width = 812
+        height = 406
+        date_ai = "15"
+
+        [[elements]]
+        type = "text"
+        x = 30
+        y = 30
+        height = 24
+        text = "BEST BY {pack_date:%m/%d/%y} \"{lot}\""
+
+        [[elements]]
+        type = "barcode"
+        x = 30
+        y = 80
+        height = 100
+        human_readable = false
+
+        [[elements]]
+        type = "box"
+        x = 560
+        y = 30
+        width = 190
+        height = 110
+    "#;
+
+    fn voice_code() -> HashVoiceCode {
+        HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let template = Template::from_toml(TEMPLATE).unwrap();
+        assert_eq!(template.dpi, 203);
+        assert_eq!(template.date_ai, DateAi::BestBefore);
+
+        let zpl = template.render_zpl(&voice_code()).unwrap();
+        let lines: Vec<&str> = zpl.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "^XA",
+                "^PW812",
+                "^LL406",
+                "^FO30,30^A0N,24,24^FDBEST BY 01/02/03 \"LOT123\"^FS",
+                "^FO30,80^BY2^BCN,100,N,N,N,D^FD(01)12345678901244(15)030102(10)LOT123^FS",
+                "^FO560,30^GB190,110,3^FS",
+                "^XZ",
+            ]
+        );
+
+        let epl = template.render_epl(&voice_code()).unwrap();
+        let lines: Vec<&str> = epl.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "",
+                "N",
+                "q812",
+                r#"A30,30,0,4,1,1,N,"BEST BY 01/02/03 \"LOT123\"""#,
+                r#"B30,80,0,1E,2,2,100,N,"01123456789012441503010210LOT123""#,
+                "X560,30,3,750,140",
+                "P1",
+            ]
+        );
+
+        let svg = template.render_svg(&voice_code()).unwrap();
+        assert!(svg.contains(r#"<text x="30" y="54" font-size="24" text-anchor="start">BEST BY 01/02/03 "LOT123"</text>"#));
+        assert!(svg.contains(r#"<rect x="560" y="30" width="190" height="110" fill="none""#));
+        assert_eq!(svg.matches("<text").count(), 1);
+    }
+
+    #[test]
+    fn test_json_matches_toml() {
+        let json = r#"{"width":812,"height":406,"date_ai":"15","elements":[
+            {"type":"text","x":30,"y":30,"height":24,"text":"BEST BY {pack_date:%m/%d/%y} \"{lot}\""},
+            {"type":"barcode","x":30,"y":80,"height":100,"human_readable":false},
+            {"type":"box","x":560,"y":30,"width":190,"height":110}
+        ]}"#;
+        assert_eq!(Template::from_json(json).unwrap(), Template::from_toml(TEMPLATE).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for (template, reason) in [
+            ("{gtin} {weight}", "unknown placeholder {weight}"),
+            ("{lot:%Y}", "only pack_date takes a format"),
+            ("{pack_date:%Q}", "invalid date format"),
+            ("{lot", "unclosed placeholder"),
+            ("lot}", "unmatched }"),
+        ] {
+            let TemplateError::Parse(error) = template.parse::<Text>().unwrap_err() else { panic!() };
+            assert!(error.starts_with(reason), "{}", error);
+        }
+        let error = Template::from_json(r#"{"width":1,"height":1,"elements":[{"type":"text","x":0,"y":0,"height":1,"text":"{x}"}]}"#);
+        assert!(error.unwrap_err().to_string().contains("unknown placeholder {x}"));
+        assert!(Template::from_json(r#"{"width":1,"height":1,"elements":[{"type":"qr","x":0,"y":0}]}"#).is_err());
+        assert!(Template::from_json(r#"{"width":1,"height":1,"date_ai":"11","elements":[]}"#).is_err());
+        assert!(Template::from_toml("width = 1\nheight = 1\ncolor = 2\nelements = []").is_err());
+    }
+
+    #[test]
+    fn test_not_a_calendar_date() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "02", "30").unwrap();
+        let text: Text = "{pack_date}".parse().unwrap();
+        assert_eq!(text.render(&voice_code, DateAi::PackDate).unwrap(), "030230");
+        let text: Text = "{pack_date:%x}".parse().unwrap();
+        assert_eq!(
+            text.render(&voice_code, DateAi::PackDate).unwrap_err(),
+            TemplateError::NotACalendarDate { yymmdd: "030230".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_gtin_as_hashed() {
+        let voice_code = HashVoiceCode::new("614141007349", "LOT123", "03", "01", "02").unwrap();
+        let text: Text = "{gtin} {gs1}".parse().unwrap();
+        assert_eq!(text.render(&voice_code, DateAi::PackDate).unwrap(), "614141007349 (01)614141007349(13)030102(10)LOT123");
+    }
+
+    #[test]
+    fn test_bundled_template() {
+        let template = Template::from_toml(include_str!("../../data/label-template.toml")).unwrap();
+        let zpl = template.render_zpl(&voice_code()).unwrap();
+        assert!(zpl.contains("^FDPACKED Jan 02 2003^FS"));
+        assert!(zpl.contains("^FD(01)12345678901244(13)030102(10)LOT123^FS"));
+    }
+
+    #[test]
+    fn test_epl_font() {
+        assert_eq!(epl_font(24), (4, 1));
+        assert_eq!(epl_font(80), (3, 4));
+        assert_eq!(epl_font(1), (1, 1));
+    }
+}
@@ -0,0 +1,226 @@
+//! ZPL II output for Zebra printers
+
+use std::fmt::Write;
+
+use crate::gs1::attributes::CaseAttributes;
+use crate::gs1::{DateAi, Gs1ElementString};
+use crate::label::{elements, fields};
+use crate::sscc::Sscc;
+use crate::voicecode::HashVoiceCode;
+
+/// Placement and sizes for the ZPL label, all in dots
+///
+/// The defaults fit a 4 inch wide label at 203 dpi.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ZplOptions {
+    /// Left edge of the label content
+    pub x: u32,
+    /// Top edge of the label content
+    pub y: u32,
+    /// Narrow bar width for the barcode, `^BY`
+    pub module_width: u32,
+    /// Barcode bar height
+    pub barcode_height: u32,
+    /// Height of the GTIN, LOT and pack date text
+    pub text_height: u32,
+    /// Left edge of the voice code box
+    pub voice_code_x: u32,
+    /// Height of the two minor digits
+    pub minor_height: u32,
+    /// Height of the two major digits
+    pub major_height: u32,
+    /// AI the date is carried under, AI(13) pack date unless a trading partner requires another
+    pub date_ai: DateAi,
+    /// Weights, country of origin and serial carried ahead of the LOT
+    pub attributes: CaseAttributes,
+}
+
+impl Default for ZplOptions {
+    fn default() -> Self {
+        ZplOptions {
+            x: 30,
+            y: 30,
+            module_width: 2,
+            barcode_height: 100,
+            text_height: 28,
+            voice_code_x: 560,
+            minor_height: 45,
+            major_height: 80,
+            date_ai: DateAi::PackDate,
+            attributes: CaseAttributes::default(),
+        }
+    }
+}
+
+/// Complete `^XA … ^XZ` label ready to send to the printer
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode };
+/// use voicecode::label::zpl::{ render, ZplOptions };
+/// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+/// let zpl = render(&voice_code, &ZplOptions::default());
+/// assert!(zpl.starts_with("^XA"));
+/// assert!(zpl.contains("^FD(01)10614141007346(13)030102(10)LOT123^FS"));
+/// assert!(zpl.ends_with("^XZ\n"));
+/// ```
+pub fn render(voice_code: &HashVoiceCode, options: &ZplOptions) -> String {
+    format!("^XA\n{}^XZ\n", render_fragment(voice_code, options))
+}
+
+/// Hybrid pallet label, the SSCC and its GS1-128 above the case label content
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Sscc };
+/// use voicecode::label::zpl::{ render_pallet, ZplOptions };
+/// let voice_code = HashVoiceCode::new("10614141007346", "LOT123", "03", "01", "02").unwrap();
+/// let sscc = Sscc::generate(0, "0614141", 1).unwrap();
+/// let zpl = render_pallet(&sscc, &voice_code, &ZplOptions::default());
+/// assert!(zpl.contains("^FD(00)006141410000000012^FS"));
+/// assert!(zpl.contains("^FD(01)10614141007346(13)030102(10)LOT123^FS"));
+/// ```
+pub fn render_pallet(sscc: &Sscc, voice_code: &HashVoiceCode, options: &ZplOptions) -> String {
+    let o = options;
+    let mut out = String::from("^XA\n");
+    let _ = writeln!(out, "^FO{},{}^A0N,{},{}^FDSSCC {}^FS", o.x, o.y, o.text_height, o.text_height, sscc);
+    let barcode_y = o.y + o.text_height + o.text_height / 2;
+    let _ = writeln!(
+        out,
+        "^FO{},{}^BY{}^BCN,{},Y,N,N,D^FD{}^FS",
+        o.x,
+        barcode_y,
+        o.module_width,
+        o.barcode_height,
+        Gs1ElementString::from(sscc)
+    );
+
+    // case content starts below the interpretation line
+    let case_y = barcode_y + o.barcode_height + o.text_height * 2;
+    out.push_str(&render_fragment(voice_code, &ZplOptions { y: case_y, ..o.clone() }));
+    out.push_str("^XZ\n");
+    out
+}
+
+/// Label commands without `^XA`/`^XZ`, for placing inside a larger label format
+pub fn render_fragment(voice_code: &HashVoiceCode, options: &ZplOptions) -> String {
+    let o = options;
+    let mut out = String::new();
+    let line = o.text_height + o.text_height / 4;
+
+    // human readable fields
+    let fields = fields(voice_code, o.date_ai);
+    for (i, text) in fields.iter().enumerate() {
+        let y = o.y + line * i as u32;
+        let _ = writeln!(out, "^FO{},{}^A0N,{},{}^FD{}^FS", o.x, y, o.text_height, o.text_height, text);
+    }
+
+    // GS1-128, mode D inserts FNC1 and prints the bracketed interpretation line
+    let barcode_y = o.y + line * fields.len() as u32 + o.text_height / 2;
+    let _ = writeln!(
+        out,
+        "^FO{},{}^BY{}^BCN,{},Y,N,N,D^FD{}^FS",
+        o.x,
+        barcode_y,
+        o.module_width,
+        o.barcode_height,
+        elements(voice_code, o.date_ai, &o.attributes)
+    );
+
+    // voice code box, minor digits small then major digits large, bottoms aligned
+    let pad = o.minor_height / 4;
+    let box_width = pad * 3 + digits_width(o.minor_height) + digits_width(o.major_height);
+    let box_height = o.major_height + pad * 2;
+    let baseline = o.y + pad + o.major_height;
+    let _ = writeln!(out, "^FO{},{}^GB{},{},3^FS", o.voice_code_x, o.y, box_width, box_height);
+    let _ = writeln!(
+        out,
+        "^FO{},{}^A0N,{},{}^FD{}^FS",
+        o.voice_code_x + pad,
+        baseline - o.minor_height,
+        o.minor_height,
+        o.minor_height,
+        voice_code.voice_code_minor
+    );
+    let _ = writeln!(
+        out,
+        "^FO{},{}^A0N,{},{}^FD{}^FS",
+        o.voice_code_x + pad * 2 + digits_width(o.minor_height),
+        o.y + pad,
+        o.major_height,
+        o.major_height,
+        voice_code.voice_code_major
+    );
+    out
+}
+
+/// Approximate width of two digits in font 0 at `height` dots
+fn digits_width(height: u32) -> u32 {
+    height * 6 / 5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+    use crate::gs1::weight::{Weight, WeightUnit};
+
+    #[test]
+    fn test_render() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        let zpl = render_fragment(&voice_code, &ZplOptions::default());
+        let lines: Vec<&str> = zpl.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "^FO30,30^A0N,28,28^FDGTIN 12345678901244^FS",
+                "^FO30,65^A0N,28,28^FDLOT LOT123^FS",
+                "^FO30,100^A0N,28,28^FDPACK DATE 030102^FS",
+                "^FO30,149^BY2^BCN,100,Y,N,N,D^FD(01)12345678901244(13)030102(10)LOT123^FS",
+                "^FO560,30^GB183,102,3^FS",
+                "^FO571,76^A0N,45,45^FD69^FS",
+                "^FO636,41^A0N,80,80^FD91^FS",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_date_ai() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        let zpl = render_fragment(&voice_code, &ZplOptions { date_ai: DateAi::Expiry, ..ZplOptions::default() });
+        let lines: Vec<&str> = zpl.lines().collect();
+        assert_eq!(lines[2], "^FO30,100^A0N,28,28^FDUSE BY 030102^FS");
+        assert_eq!(lines[3], "^FO30,149^BY2^BCN,100,Y,N,N,D^FD(01)12345678901244(17)030102(10)LOT123^FS");
+    }
+
+    #[test]
+    fn test_gtin_as_hashed() {
+        let voice_code = HashVoiceCode::new("614141007349", "LOT123", "03", "01", "02").unwrap();
+        let zpl = render_fragment(&voice_code, &ZplOptions::default());
+        let lines: Vec<&str> = zpl.lines().collect();
+        assert_eq!(lines[0], "^FO30,30^A0N,28,28^FDGTIN 614141007349^FS");
+        assert_eq!(lines[3], "^FO30,149^BY2^BCN,100,Y,N,N,D^FD(01)614141007349(13)030102(10)LOT123^FS");
+    }
+
+    #[test]
+    fn test_net_weight() {
+        let voice_code = HashVoiceCode::new("90614141000015", "LOT123", "03", "01", "02").unwrap();
+        let attributes = CaseAttributes::new().with_net_weight(Weight::new(WeightUnit::Kilograms, 1250, 2).unwrap());
+        let zpl = render_fragment(&voice_code, &ZplOptions { attributes, ..ZplOptions::default() });
+        let lines: Vec<&str> = zpl.lines().collect();
+        assert_eq!(lines[3], "^FO30,149^BY2^BCN,100,Y,N,N,D^FD(01)90614141000015(13)030102(3102)001250(10)LOT123^FS");
+        assert_eq!(lines[6], "^FO636,41^A0N,80,80^FD".to_string() + &voice_code.voice_code_major + "^FS");
+    }
+
+    #[test]
+    fn test_render_pallet() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        let sscc = Sscc::parse("106141411234567897").unwrap();
+        let zpl = render_pallet(&sscc, &voice_code, &ZplOptions::default());
+        let lines: Vec<&str> = zpl.lines().collect();
+        assert_eq!(lines[1], "^FO30,30^A0N,28,28^FDSSCC 106141411234567897^FS");
+        assert_eq!(lines[2], "^FO30,72^BY2^BCN,100,Y,N,N,D^FD(00)106141411234567897^FS");
+        assert_eq!(lines[3], "^FO30,228^A0N,28,28^FDGTIN 12345678901244^FS");
+        assert_eq!(lines.last(), Some(&"^XZ"));
+    }
+}
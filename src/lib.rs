@@ -1,5 +1,130 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+#[macro_use]
+mod trace;
+
+#[cfg(feature = "std")]
+pub mod algorithm;
+#[cfg(feature = "std")]
+pub mod analysis;
+
+#[cfg(feature = "std")]
+pub mod barcode;
+#[cfg(feature = "csv")]
+pub mod conformance;
+#[cfg(any(feature = "sqlx", feature = "diesel"))]
+pub mod db;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub use error::{DateComponent, VoiceCodeError};
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub mod edi;
+#[cfg(feature = "heapless")]
+pub mod embedded;
+#[cfg(feature = "epcis")]
+pub mod epcis;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz_targets;
+#[cfg(feature = "gcp-table")]
+pub mod gcp;
+#[cfg(feature = "std")]
+pub mod gs1;
+pub mod gtin;
+pub use gtin::GtinError;
+#[cfg(feature = "std")]
+pub use gtin::Gtin;
+pub mod hash;
+pub use hash::{crc_u16, hash_into, hash_u16, Crc16Hasher, NonAsciiPolicy, VoiceCodeHasher};
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "std")]
+pub mod label;
+#[cfg(feature = "std")]
+pub mod lint;
+pub mod lot;
+pub use lot::LotError;
+#[cfg(feature = "std")]
+pub use lot::{Lot, LotCase, SanitizeStrategy};
+#[cfg(feature = "std")]
+pub mod manifest;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod pack_date;
+#[cfg(feature = "std")]
+pub mod policy;
+#[cfg(feature = "std")]
+pub use policy::ValidationPolicy;
+#[cfg(feature = "std")]
+pub mod print;
+#[cfg(feature = "std")]
+pub use pack_date::PackDate;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod calculator;
+#[cfg(feature = "std")]
+pub use calculator::VoiceCodeCalculator;
+#[cfg(feature = "std")]
+pub use builder::HashVoiceCodeBuilder;
+#[cfg(feature = "std")]
+pub mod scan;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "chrono")]
+pub mod series;
+#[cfg(feature = "std")]
+pub mod speech;
+#[cfg(feature = "std")]
+pub mod sscc;
+#[cfg(feature = "std")]
+pub use sscc::Sscc;
+#[cfg(feature = "tui")]
+pub mod station;
+#[cfg(feature = "testdata")]
+pub mod testdata;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod validation;
+#[cfg(feature = "std")]
+pub use validation::ValidationReport;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(feature = "std")]
 pub mod voicecode;
-pub use voicecode::HashVoiceCode;
+#[cfg(feature = "std")]
+pub use voicecode::{HashVoiceCode, VoiceCode};
+pub mod crc;
 pub mod create_crc_lut;
 pub use create_crc_lut::create_crc_lut;
+#[cfg(feature = "chrono")]
 pub use chrono::NaiveDate;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "capi")]
+pub mod ffi;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
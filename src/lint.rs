@@ -0,0 +1,313 @@
+//! Warnings for inputs that hash fine but are probably data entry mistakes
+//!
+//! A GTIN with the wrong check digit, a LOT that is really a date or has lowercase letters, and
+//! a pack date in the future all produce a voice code, just not the one printed on the case
+//! the picker holds. Each lint is allowed, returned as a `Warning` next to the voice code, or
+//! denied with `VoiceCodeError::LintDenied`, as set in a `LintConfig`.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::VoiceCodeError;
+use crate::lot::LotCase;
+use crate::pack_date::{DateAmbiguity, PackDate};
+use crate::validation::Field;
+use crate::voicecode::HashVoiceCode;
+
+/// Something suspicious about otherwise valid inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Lint {
+    /// GTIN check digit does not match the other digits, usually a mistyped digit
+    GtinCheckDigit,
+    /// LOT reads as a calendar date, often the pack date keyed into the LOT field
+    LotLooksLikeDate,
+    /// LOT has lowercase letters, which hash differently from the uppercase a label shows
+    LotLowercase,
+    /// Pack date is later than today in every time zone
+    FuturePackDate,
+}
+
+impl Lint {
+    /// Every lint in the order they are checked
+    pub const ALL: [Lint; 4] = [Lint::GtinCheckDigit, Lint::LotLooksLikeDate, Lint::LotLowercase, Lint::FuturePackDate];
+
+    /// Stable identifier for APIs and UIs
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lint::GtinCheckDigit => "gtin_check_digit",
+            Lint::LotLooksLikeDate => "lot_looks_like_date",
+            Lint::LotLowercase => "lot_lowercase",
+            Lint::FuturePackDate => "pack_date_future",
+        }
+    }
+
+    /// Input field the lint is about
+    pub fn field(&self) -> Field {
+        match self {
+            Lint::GtinCheckDigit => Field::Gtin,
+            Lint::LotLooksLikeDate | Lint::LotLowercase => Field::Lot,
+            Lint::FuturePackDate => Field::PackDate,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// What to do when a lint matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LintLevel {
+    /// Ignore it
+    Allow,
+    /// Return a `Warning` with the voice code
+    #[default]
+    Warn,
+    /// Fail with `VoiceCodeError::LintDenied`
+    Deny,
+}
+
+/// Level of each lint and the date pack dates are compared with
+///
+/// # Example
+/// ```
+/// use voicecode::lint::{ Lint, LintConfig, LintLevel };
+/// let config = LintConfig { lot_lowercase: LintLevel::Deny, ..LintConfig::default() };
+/// assert_eq!(config.level(Lint::LotLowercase), LintLevel::Deny);
+/// assert_eq!(config.level(Lint::GtinCheckDigit), LintLevel::Warn);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LintConfig {
+    pub gtin_check_digit: LintLevel,
+    pub lot_looks_like_date: LintLevel,
+    pub lot_lowercase: LintLevel,
+    pub future_pack_date: LintLevel,
+    /// Year, month and day a pack date may not be after, the current date at UTC+14 when
+    /// None so a case packed today anywhere is never flagged
+    pub today: Option<(i32, u32, u32)>,
+}
+
+impl LintConfig {
+    /// Every lint at `level`
+    pub fn all(level: LintLevel) -> Self {
+        LintConfig {
+            gtin_check_digit: level,
+            lot_looks_like_date: level,
+            lot_lowercase: level,
+            future_pack_date: level,
+            today: None,
+        }
+    }
+
+    /// Level set for `lint`
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        match lint {
+            Lint::GtinCheckDigit => self.gtin_check_digit,
+            Lint::LotLooksLikeDate => self.lot_looks_like_date,
+            Lint::LotLowercase => self.lot_lowercase,
+            Lint::FuturePackDate => self.future_pack_date,
+        }
+    }
+
+    /// Warnings for `voice_code`, or the first denied lint as an error
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, VoiceCodeError, Gtin };
+    /// use voicecode::lint::{ Lint, LintConfig, LintLevel };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "lot123", "03", "01", "02").unwrap();
+    /// let warnings = LintConfig::default().check(&voice_code).unwrap();
+    /// let lints: Vec<Lint> = warnings.iter().map(|warning| warning.lint).collect();
+    /// assert_eq!(lints, [Lint::GtinCheckDigit, Lint::LotLowercase]);
+    ///
+    /// let config = LintConfig { gtin_check_digit: LintLevel::Allow, lot_lowercase: LintLevel::Deny, ..LintConfig::default() };
+    /// let err = config.check(&voice_code).unwrap_err();
+    /// assert_eq!(err.code(), "lot_lowercase");
+    /// assert!(matches!(err, VoiceCodeError::LintDenied { lint: Lint::LotLowercase, .. }));
+    /// ```
+    pub fn check(&self, voice_code: &HashVoiceCode) -> Result<Vec<Warning>, VoiceCodeError> {
+        let mut warnings = Vec::new();
+        for lint in Lint::ALL {
+            let level = self.level(lint);
+            if level == LintLevel::Allow {
+                continue;
+            }
+            let Some(message) = self.message(lint, voice_code) else {
+                continue;
+            };
+            if level == LintLevel::Deny {
+                return Err(VoiceCodeError::LintDenied { lint, message });
+            }
+            warnings.push(Warning { lint, message });
+        }
+        Ok(warnings)
+    }
+
+    /// Description of the problem when `lint` matches `voice_code`
+    fn message(&self, lint: Lint, voice_code: &HashVoiceCode) -> Option<String> {
+        let lot = voice_code.lot.as_str();
+        match lint {
+            Lint::GtinCheckDigit => {
+                let gtin = &voice_code.gtin;
+                (!gtin.has_valid_check_digit()).then(|| {
+                    format!("GTIN check digit should be {} but is {}", gtin.expected_check_digit(), gtin.check_digit())
+                })
+            }
+            Lint::LotLooksLikeDate => {
+                let (year, month, day) = PackDate::parse_flexible(lot, DateAmbiguity::YearFirst).ok()?.calendar_date()?;
+                Some(format!("LOT {} reads as the date {:04}-{:02}-{:02}", lot, year, month, day))
+            }
+            Lint::LotLowercase => {
+                let lowercase = voice_code.lot_case() == LotCase::Preserve && lot.bytes().any(|b| b.is_ascii_lowercase());
+                lowercase.then(|| format!("LOT {} has lowercase letters and hashes differently from {}", lot, lot.to_ascii_uppercase()))
+            }
+            Lint::FuturePackDate => {
+                let date = voice_code.pack_date().calendar_date()?;
+                let today = self.today.unwrap_or_else(latest_today);
+                (date > today).then(|| {
+                    format!("Pack date {:04}-{:02}-{:02} is after {:04}-{:02}-{:02}", date.0, date.1, date.2, today.0, today.1, today.2)
+                })
+            }
+        }
+    }
+}
+
+/// Lint that matched at `LintLevel::Warn`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub lint: Lint,
+    pub message: String,
+}
+
+impl Warning {
+    /// Stable identifier, see `Lint::code`
+    pub fn code(&self) -> &'static str {
+        self.lint.code()
+    }
+
+    /// Input field the warning is about
+    pub fn field(&self) -> Field {
+        self.lint.field()
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Serialized as `{"field":…,"code":…,"message":…}` like `Violation`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Warning {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Warning", 3)?;
+        state.serialize_field("field", &self.field())?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message)?;
+        state.end()
+    }
+}
+
+/// Current date at UTC+14, the time zone furthest ahead
+fn latest_today() -> (i32, u32, u32) {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    civil_from_days(((seconds + 14 * 3600) / 86_400) as i64)
+}
+
+/// Gregorian year, month and day of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+    use crate::lot::Lot;
+
+    fn lints(config: &LintConfig, gtin: &str, lot: &str, yymmdd: &str) -> Vec<&'static str> {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked(gtin).unwrap(), lot, &yymmdd[0..2], &yymmdd[2..4], &yymmdd[4..6]).unwrap();
+        config.check(&voice_code).unwrap().iter().map(Warning::code).collect()
+    }
+
+    #[test]
+    fn test_lints() {
+        let config = LintConfig { today: Some((2024, 6, 15)), ..LintConfig::default() };
+        assert!(lints(&config, "10614141007346", "LOT123", "240615").is_empty());
+        assert_eq!(lints(&config, "10614141007347", "LOT123", "240615"), ["gtin_check_digit"]);
+        assert_eq!(lints(&config, "10614141007346", "240614", "240615"), ["lot_looks_like_date"]);
+        assert_eq!(lints(&config, "10614141007346", "06/14/2024", "240615"), ["lot_looks_like_date"]);
+        assert!(lints(&config, "10614141007346", "241399", "240615").is_empty());
+        assert_eq!(lints(&config, "10614141007346", "Lot123", "240616"), ["lot_lowercase", "pack_date_future"]);
+        // not a calendar date, so neither past nor future
+        assert!(lints(&config, "10614141007346", "LOT123", "249999").is_empty());
+
+        let allowed = LintConfig { today: config.today, ..LintConfig::all(LintLevel::Allow) };
+        assert!(lints(&allowed, "10614141007347", "lot", "991231").is_empty());
+    }
+
+    #[test]
+    fn test_deny() {
+        let config = LintConfig { future_pack_date: LintLevel::Deny, today: Some((2024, 6, 15)), ..LintConfig::default() };
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("10614141007347").unwrap(), "LOT123", "24", "06", "16").unwrap();
+        assert_eq!(
+            config.check(&voice_code).unwrap_err(),
+            VoiceCodeError::LintDenied { lint: Lint::FuturePackDate, message: "Pack date 2024-06-16 is after 2024-06-15".to_string() }
+        );
+        let warnings = config.check(&voice_code.with_date(PackDate::from_parts("24", "06", "15").unwrap())).unwrap();
+        assert_eq!(warnings[0].to_string(), "GTIN check digit should be 6 but is 7");
+    }
+
+    #[test]
+    fn test_normalized_lot_case() {
+        let voice_code = HashVoiceCode::builder()
+            .gtin("10614141007346")
+            .lot("lot123")
+            .pack_date(("24", "06", "15"))
+            .lot_case(LotCase::Lower)
+            .build()
+            .unwrap();
+        assert_eq!(voice_code.lot, Lot::parse("lot123").unwrap());
+        let config = LintConfig { lot_lowercase: LintLevel::Deny, ..LintConfig::default() };
+        assert!(config.check(&voice_code).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19_889), (2024, 6, 15));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let warning = Warning { lint: Lint::LotLowercase, message: "LOT a has lowercase letters".to_string() };
+        assert_eq!(
+            serde_json::to_string(&warning).unwrap(),
+            r#"{"field":"lot","code":"lot_lowercase","message":"LOT a has lowercase letters"}"#
+        );
+        let config: LintConfig = serde_json::from_str(r#"{"lot_lowercase":"deny"}"#).unwrap();
+        assert_eq!(config, LintConfig { lot_lowercase: LintLevel::Deny, ..LintConfig::default() });
+    }
+}
@@ -0,0 +1,418 @@
+use core::fmt;
+
+#[cfg(feature = "std")]
+use crate::error::VoiceCodeError;
+
+/// Maximum LOT length in characters
+pub const MAX_LOT_LEN: usize = 20;
+
+/// Punctuation allowed in a LOT alongside ASCII letters and digits
+pub const LOT_PUNCTUATION: &str = r##"!"%&'()*+,-./:;<=>?_"##;
+
+/// Byte lookup table for the PTI LOT character set, true for allowed bytes
+static LOT_BYTE_TABLE: [bool; 256] = build_lot_byte_table();
+
+const fn build_lot_byte_table() -> [bool; 256] {
+    let mut table = [false; 256];
+
+    // const fn, so while loops instead of iterators
+    let mut b = 0;
+    while b < 256 {
+        table[b] = (b as u8).is_ascii_alphanumeric();
+        b += 1;
+    }
+    let punctuation = LOT_PUNCTUATION.as_bytes();
+    let mut i = 0;
+    while i < punctuation.len() {
+        table[punctuation[i] as usize] = true;
+        i += 1;
+    }
+
+    table
+}
+
+/// Why a LOT failed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LotError {
+    /// LOT has no characters
+    Empty,
+    /// LOT is longer than `MAX_LOT_LEN` characters
+    TooLong { len: usize },
+    /// LOT contains a character outside the PTI set, offset is in bytes
+    InvalidCharacter { ch: char, offset: usize },
+    /// LOT is longer than a `ValidationPolicy` allows
+    LongerThanPolicy { len: usize, max: usize },
+    /// LOT contains a PTI character a `ValidationPolicy` rules out, offset is in bytes
+    DisallowedByPolicy { ch: char, offset: usize },
+}
+
+impl LotError {
+    /// Stable identifier for APIs and UIs, such as `lot_too_long`
+    pub fn code(&self) -> &'static str {
+        match self {
+            LotError::Empty => "lot_empty",
+            LotError::TooLong { .. } => "lot_too_long",
+            LotError::InvalidCharacter { .. } => "lot_invalid_character",
+            LotError::LongerThanPolicy { .. } => "lot_longer_than_policy",
+            LotError::DisallowedByPolicy { .. } => "lot_disallowed_by_policy",
+        }
+    }
+}
+
+impl fmt::Display for LotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LotError::Empty => write!(f, "LOT must be 1 to {} characters", MAX_LOT_LEN),
+            LotError::TooLong { len } => write!(f, "LOT must be 1 to {} characters, got {}", MAX_LOT_LEN, len),
+            LotError::InvalidCharacter { ch, offset } => write!(
+                f,
+                r##"LOT contains {:?} at byte {}, LOT must be alphanumeric and/or !, ", %, &, ', (, ), *, +, -, ., /, :, ;, <, =, >, ?, _ and comma"##,
+                ch, offset
+            ),
+            LotError::LongerThanPolicy { len, max } => write!(f, "LOT must be at most {} characters, got {}", max, len),
+            LotError::DisallowedByPolicy { ch, offset } => {
+                write!(f, "LOT contains {:?} at byte {}, which the validation policy does not allow", ch, offset)
+            }
+        }
+    }
+}
+
+/// How `Lot::sanitize` treats characters outside the PTI set
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SanitizeStrategy {
+    /// Drop invalid characters
+    Strip,
+    /// Replace each invalid character with the given character, which must itself be valid
+    ReplaceWith(char),
+    /// Leave the characters alone and fail on the first invalid one, only the trimming applies
+    Reject,
+}
+
+/// Case normalization applied to a LOT before it is validated and hashed
+///
+/// The voice code is case sensitive, so the same LOT gives different codes under `Upper` and
+/// `Preserve` unless it is already uppercase. `HashVoiceCode::lot_case` records which one
+/// produced a code.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, LotCase };
+/// let voice_code = HashVoiceCode::builder()
+///     .gtin("12345678901244")
+///     .lot("lot123")
+///     .lot_case(LotCase::Upper)
+///     .pack_date(("03", "01", "02"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(voice_code.lot, "LOT123");
+/// assert_eq!(voice_code.lot_case(), LotCase::Upper);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LotCase {
+    /// Hash the LOT as given
+    #[default]
+    Preserve,
+    /// Uppercase ASCII letters, what users of the JavaScript widget usually type
+    Upper,
+    /// Lowercase ASCII letters
+    Lower,
+}
+
+#[cfg(feature = "std")]
+impl LotCase {
+    /// `lot` with this normalization applied
+    pub fn apply(&self, lot: &str) -> String {
+        match self {
+            LotCase::Preserve => lot.to_string(),
+            LotCase::Upper => lot.to_ascii_uppercase(),
+            LotCase::Lower => lot.to_ascii_lowercase(),
+        }
+    }
+}
+
+/// True when `ch` may appear in a LOT
+///
+/// # Example
+/// ```
+/// use voicecode::lot::is_lot_char;
+/// assert!(is_lot_char('A'));
+/// assert!(is_lot_char('('));
+/// assert!(!is_lot_char('#'));
+/// assert!(!is_lot_char(' '));
+/// ```
+pub fn is_lot_char(ch: char) -> bool {
+    ch.is_ascii() && is_lot_byte(ch as u8)
+}
+
+/// True when the byte `b` may appear in a LOT, every allowed byte is ASCII
+pub fn is_lot_byte(b: u8) -> bool {
+    LOT_BYTE_TABLE[b as usize]
+}
+
+/// True when `lot` is 1 to `MAX_LOT_LEN` bytes from the PTI set
+///
+/// Table driven and allocation free, usable without the `std` feature. Agrees with
+/// `Lot::parse`, use that when the reason for a failure matters.
+///
+/// # Example
+/// ```
+/// use voicecode::lot::is_valid_lot;
+/// assert!(is_valid_lot(b"32ABCD"));
+/// assert!(!is_valid_lot(b"32 ABCD"));
+/// assert!(!is_valid_lot(b""));
+/// assert!(!is_valid_lot(b"ABCDEFGHIJKLMNOPQRSTU"));
+/// ```
+pub fn is_valid_lot(lot: &[u8]) -> bool {
+    (1..=MAX_LOT_LEN).contains(&lot.len()) && lot.iter().all(|&b| is_lot_byte(b))
+}
+
+/// A LOT code validated against the PTI character set
+///
+/// # Example
+/// ```
+/// use voicecode::{ Lot, LotError, VoiceCodeError };
+/// let lot = Lot::parse("32ABCD").unwrap();
+/// assert_eq!(lot.as_str(), "32ABCD");
+///
+/// let err = Lot::parse("32 ABCD").unwrap_err();
+/// assert_eq!(err, VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: ' ', offset: 2 } });
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+#[cfg_attr(feature = "diesel", derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
+pub struct Lot {
+    value: String,
+}
+
+#[cfg(feature = "std")]
+impl Lot {
+    /// Parse a LOT, reporting the first violation
+    pub fn parse(lot: &str) -> Result<Self, VoiceCodeError> {
+        if is_valid_lot(lot.as_bytes()) {
+            return Ok(Lot { value: lot.to_string() });
+        }
+        Err(VoiceCodeError::InvalidLot { reason: Self::diagnose(lot) })
+    }
+
+    fn diagnose(lot: &str) -> LotError {
+        if lot.is_empty() {
+            return LotError::Empty;
+        }
+        if let Some((offset, ch)) = lot.char_indices().find(|(_, ch)| !is_lot_char(*ch)) {
+            return LotError::InvalidCharacter { ch, offset };
+        }
+        LotError::TooLong { len: lot.chars().count() }
+    }
+
+    /// Build a valid LOT from arbitrary input, such as an ERP export
+    ///
+    /// Leading and trailing whitespace is trimmed, then invalid characters are handled by
+    /// `strategy` and the result truncated to `MAX_LOT_LEN` characters. `Reject` never
+    /// truncates, and its offsets point into `input` before trimming.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Lot, SanitizeStrategy };
+    /// assert_eq!(Lot::sanitize("32 AB#CD", SanitizeStrategy::Strip).unwrap(), "32ABCD");
+    /// assert_eq!(Lot::sanitize(" 32 AB#CD\t", SanitizeStrategy::ReplaceWith('-')).unwrap(), "32-AB-CD");
+    /// assert_eq!(Lot::sanitize(" 32ABCD\r\n", SanitizeStrategy::Reject).unwrap(), "32ABCD");
+    /// assert!(Lot::sanitize("32 ABCD", SanitizeStrategy::Reject).is_err());
+    /// assert!(Lot::sanitize("###", SanitizeStrategy::Strip).is_err());
+    /// ```
+    pub fn sanitize(input: &str, strategy: SanitizeStrategy) -> Result<Self, VoiceCodeError> {
+        let leading = input.len() - input.trim_start().len();
+        let input = input.trim();
+        let cleaned: String = match strategy {
+            SanitizeStrategy::Strip => input.chars().filter(|ch| is_lot_char(*ch)).collect(),
+            SanitizeStrategy::ReplaceWith(replacement) => {
+                if !is_lot_char(replacement) {
+                    return Err(VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: replacement, offset: 0 } });
+                }
+                input.chars().map(|ch| if is_lot_char(ch) { ch } else { replacement }).collect()
+            }
+            SanitizeStrategy::Reject => {
+                return Self::parse(input).map_err(|e| match e {
+                    VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch, offset } } => {
+                        VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch, offset: offset + leading } }
+                    }
+                    e => e,
+                })
+            }
+        };
+        let truncated: String = cleaned.chars().take(MAX_LOT_LEN).collect();
+        Self::parse(&truncated)
+    }
+
+    /// Same as `sanitize` after uppercasing ASCII letters
+    ///
+    /// The voice code is case sensitive, only use this when the printed LOT is uppercase too.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Lot, SanitizeStrategy };
+    /// assert_eq!(Lot::sanitize_uppercase(" 32ab#cd ", SanitizeStrategy::Strip).unwrap(), "32ABCD");
+    /// ```
+    pub fn sanitize_uppercase(input: &str, strategy: SanitizeStrategy) -> Result<Self, VoiceCodeError> {
+        Self::sanitize(&input.to_ascii_uppercase(), strategy)
+    }
+
+    /// LOT as provided
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// Empty LOT for commodity labels that print none, hashed as nothing
+    ///
+    /// `parse` never returns this, it exists for `HashVoiceCode::new_no_lot`.
+    pub fn none() -> Self {
+        Lot { value: String::new() }
+    }
+
+    /// True for `Lot::none()`
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Lot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsRef<str> for Lot {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<str> for Lot {
+    fn eq(&self, other: &str) -> bool {
+        self.value == other
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<&str> for Lot {
+    fn eq(&self, other: &&str) -> bool {
+        self.value == *other
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<&str> for Lot {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Lot::parse(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<&String> for Lot {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Lot::parse(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<String> for Lot {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Lot::parse(&value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Lot> for String {
+    fn from(value: Lot) -> Self {
+        value.value
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&Lot> for Lot {
+    fn from(value: &Lot) -> Self {
+        value.clone()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn reason(lot: &str) -> LotError {
+        match Lot::parse(lot) {
+            Err(VoiceCodeError::InvalidLot { reason }) => reason,
+            other => panic!("expected InvalidLot, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        assert_eq!(reason(""), LotError::Empty);
+        assert_eq!(reason("ABCDEFGHIJKLMNOPQRSTU"), LotError::TooLong { len: 21 });
+        assert_eq!(reason("AB#"), LotError::InvalidCharacter { ch: '#', offset: 2 });
+        assert_eq!(reason("éA~"), LotError::InvalidCharacter { ch: 'é', offset: 0 });
+        assert_eq!(reason("Aé~"), LotError::InvalidCharacter { ch: 'é', offset: 1 });
+        assert_eq!(reason("Aa~"), LotError::InvalidCharacter { ch: '~', offset: 2 });
+    }
+
+    #[test]
+    fn test_accepted_bytes() {
+        // the PTI set, the same class as the regex this table replaced,
+        // ^[\!"%&'()\*\+,\-\./0-9:;<=>\?A-Z_a-z]{1,20}$
+        let accepted: Vec<u8> = (0u8..=255).filter(|&b| is_lot_byte(b)).collect();
+        assert_eq!(
+            String::from_utf8(accepted).unwrap(),
+            r##"!"%&'()*+,-./0123456789:;<=>?ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz"##
+        );
+        for ch in ['#', '$', '@', '[', '\\', ']', '^', '`', '{', '|', '}', '~', ' ', '\t', '\0', '\u{7f}', 'é', '\u{ff}'] {
+            assert!(!is_lot_char(ch), "{:?}", ch);
+        }
+        for b in 0u8..=127 {
+            assert_eq!(is_lot_char(b as char), is_lot_byte(b), "{:#04x}", b);
+        }
+    }
+
+    #[test]
+    fn test_valid_lot() {
+        for lot in ["A", "32ABCD", "ABCDEFGHIJKLMNOPQRST", "a,b", r##"!"%&'()*+,-./:;<=>?_"##] {
+            assert!(is_valid_lot(lot.as_bytes()) && Lot::parse(lot).is_ok(), "{:?}", lot);
+        }
+        for lot in ["", "ABCDEFGHIJKLMNOPQRSTU", "AB#", "Aé", "A\n", "ÉÉÉÉÉÉÉÉÉÉ"] {
+            assert!(!is_valid_lot(lot.as_bytes()) && Lot::parse(lot).is_err(), "{:?}", lot);
+        }
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let long = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(Lot::sanitize(long, SanitizeStrategy::Strip).unwrap(), "ABCDEFGHIJKLMNOPQRST");
+        assert!(Lot::sanitize("A B", SanitizeStrategy::ReplaceWith('#')).is_err());
+        assert_eq!(Lot::sanitize("\u{a0}A B ", SanitizeStrategy::ReplaceWith('_')).unwrap(), "A_B");
+        assert_eq!(Lot::sanitize(long, SanitizeStrategy::Reject).unwrap_err(), VoiceCodeError::InvalidLot { reason: LotError::TooLong { len: 26 } });
+        assert_eq!(
+            Lot::sanitize(" A#B ", SanitizeStrategy::Reject).unwrap_err(),
+            VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: '#', offset: 2 } }
+        );
+        let input = "\u{a0}\tA\u{e9}B";
+        let Err(VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch, offset } }) = Lot::sanitize(input, SanitizeStrategy::Reject) else { panic!() };
+        assert_eq!((ch, offset), ('\u{e9}', 4));
+        assert_eq!(input[offset..].chars().next(), Some(ch));
+        assert_eq!(Lot::sanitize_uppercase("lot-é1", SanitizeStrategy::Strip).unwrap(), "LOT-1");
+    }
+}
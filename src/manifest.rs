@@ -0,0 +1,812 @@
+//! Voice code collisions across the cases of one shipment or pallet
+//!
+//! Pickers confirm a case by reading its voice code aloud, so two different products on
+//! the same pallet with the same code, or with codes one swap apart, get mis-picked.
+//!
+//! `VoiceCodeMap` and `VoiceCodeSet` collect the cases of a shipment as they are built,
+//! dropping repeats of the same case and grouping the rest by voice code. `pick_list` orders
+//! pick tasks into stops by location for voice picking, with the voice code order within a
+//! stop and the lines whose major digits alone are ambiguous marked.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::export::vocollect::PickTask;
+use crate::voicecode::HashVoiceCode;
+
+/// Why a group of items was flagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CollisionKind {
+    /// Different GTIN, LOT or pack date with the same voice code
+    Duplicate,
+    /// Voice codes one adjacent digit swap apart, or with the minor and major pairs swapped
+    Transposed,
+}
+
+/// One product in a collision, `index` is its position in the analyzed items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollisionItem {
+    pub index: usize,
+    pub gtin: String,
+    pub lot: String,
+    /// Zero padded YYMMDD
+    pub pack_date: String,
+    pub voice_code: String,
+}
+
+impl From<(usize, &HashVoiceCode)> for CollisionItem {
+    fn from((index, voice_code): (usize, &HashVoiceCode)) -> Self {
+        CollisionItem {
+            index,
+            gtin: voice_code.gtin.to_string(),
+            lot: voice_code.lot.to_string(),
+            pack_date: voice_code.pack_date().yymmdd(),
+            voice_code: voice_code.voice_code.clone(),
+        }
+    }
+}
+
+/// Products that a picker could confuse, each listed once at its first occurrence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub kind: CollisionKind,
+    pub items: Vec<CollisionItem>,
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            CollisionKind::Duplicate => "duplicate",
+            CollisionKind::Transposed => "transposed",
+        };
+        write!(f, "{}:", kind)?;
+        for (i, item) in self.items.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} (GTIN {} LOT {} pack date {})", sep, item.voice_code, item.gtin, item.lot, item.pack_date)?;
+        }
+        Ok(())
+    }
+}
+
+/// Duplicate and confusable voice codes among a set of cases
+///
+/// Repeats of the same hashed GTIN, LOT and pack date are the same product and never collide.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::manifest::{ CollisionReport };
+/// let items = [
+///     HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap(),
+///     HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap(),
+///     HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap(),
+/// ];
+/// let report = CollisionReport::analyze(&items);
+/// assert!(report.is_clean());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollisionReport {
+    pub collisions: Vec<Collision>,
+}
+
+impl CollisionReport {
+    pub fn analyze<'a, I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = &'a HashVoiceCode>,
+    {
+        // first index of each distinct product, grouped by voice code
+        let mut by_code: BTreeMap<String, Vec<CollisionItem>> = BTreeMap::new();
+        let mut seen = BTreeSet::new();
+        for (index, voice_code) in items.into_iter().enumerate() {
+            if seen.insert(voice_code.hash_text.as_str()) {
+                by_code.entry(voice_code.voice_code.clone()).or_default().push((index, voice_code).into());
+            }
+        }
+
+        let mut collisions = Vec::new();
+        for products in by_code.values() {
+            if products.len() > 1 {
+                collisions.push(Collision { kind: CollisionKind::Duplicate, items: products.clone() });
+            }
+        }
+        for (code, products) in &by_code {
+            for other in confusable(code) {
+                // each pair once, from the lower code
+                if other.as_str() <= code.as_str() {
+                    continue;
+                }
+                if let Some(others) = by_code.get(&other) {
+                    let mut items = products.clone();
+                    items.extend(others.iter().cloned());
+                    items.sort_by_key(|item| item.index);
+                    collisions.push(Collision { kind: CollisionKind::Transposed, items });
+                }
+            }
+        }
+
+        CollisionReport { collisions }
+    }
+
+    /// True when no two products can be confused
+    pub fn is_clean(&self) -> bool {
+        self.collisions.is_empty()
+    }
+
+    pub fn duplicates(&self) -> impl Iterator<Item = &Collision> {
+        self.collisions.iter().filter(|collision| collision.kind == CollisionKind::Duplicate)
+    }
+
+    pub fn transpositions(&self) -> impl Iterator<Item = &Collision> {
+        self.collisions.iter().filter(|collision| collision.kind == CollisionKind::Transposed)
+    }
+}
+
+impl fmt::Display for CollisionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for collision in &self.collisions {
+            writeln!(f, "{}", collision)?;
+        }
+        Ok(())
+    }
+}
+
+/// One distinct case in a `VoiceCodeMap`, with when it was first and last inserted
+///
+/// `first` and `last` count every `insert` call on the map from 0, repeats included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry<V> {
+    pub voice_code: HashVoiceCode,
+    pub value: V,
+    pub first: usize,
+    pub last: usize,
+    /// Times the case was inserted
+    pub count: usize,
+}
+
+/// Values keyed by case, deduplicated by GTIN, LOT and pack date like `HashVoiceCode`'s `Eq`
+///
+/// Iterates in first insertion order. Inserting a case again replaces its value and moves
+/// `last` but keeps its position.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::manifest::VoiceCodeMap;
+/// let case = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let other = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap();
+/// let mut cases = VoiceCodeMap::new();
+/// cases.insert(case.clone(), 10);
+/// cases.insert(other, 5);
+/// assert_eq!(cases.insert(case.clone(), 12), Some(10));
+///
+/// assert_eq!(cases.len(), 2);
+/// let entry = cases.entry(&case).unwrap();
+/// assert_eq!((entry.value, entry.first, entry.last, entry.count), (12, 0, 2, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VoiceCodeMap<V> {
+    entries: Vec<Entry<V>>,
+    index: HashMap<HashVoiceCode, usize>,
+    inserts: usize,
+}
+
+impl<V> VoiceCodeMap<V> {
+    pub fn new() -> Self {
+        VoiceCodeMap { entries: Vec::new(), index: HashMap::new(), inserts: 0 }
+    }
+
+    /// Insert or replace the value for `voice_code`, returning the value it replaced
+    pub fn insert(&mut self, voice_code: HashVoiceCode, value: V) -> Option<V> {
+        let sequence = self.inserts;
+        self.inserts += 1;
+        if let Some(&i) = self.index.get(&voice_code) {
+            let entry = &mut self.entries[i];
+            entry.last = sequence;
+            entry.count += 1;
+            return Some(std::mem::replace(&mut entry.value, value));
+        }
+        self.index.insert(voice_code.clone(), self.entries.len());
+        self.entries.push(Entry { voice_code, value, first: sequence, last: sequence, count: 1 });
+        None
+    }
+
+    /// Remove a case, later cases keep their order
+    pub fn remove(&mut self, voice_code: &HashVoiceCode) -> Option<Entry<V>> {
+        let i = self.index.remove(voice_code)?;
+        let entry = self.entries.remove(i);
+        for position in self.index.values_mut() {
+            if *position > i {
+                *position -= 1;
+            }
+        }
+        Some(entry)
+    }
+
+    pub fn get(&self, voice_code: &HashVoiceCode) -> Option<&V> {
+        self.entry(voice_code).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, voice_code: &HashVoiceCode) -> Option<&mut V> {
+        let i = *self.index.get(voice_code)?;
+        Some(&mut self.entries[i].value)
+    }
+
+    pub fn entry(&self, voice_code: &HashVoiceCode) -> Option<&Entry<V>> {
+        self.index.get(voice_code).map(|i| &self.entries[*i])
+    }
+
+    pub fn contains(&self, voice_code: &HashVoiceCode) -> bool {
+        self.index.contains_key(voice_code)
+    }
+
+    /// Distinct cases
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `insert` calls so far, repeats included
+    pub fn inserts(&self) -> usize {
+        self.inserts
+    }
+
+    /// Entries in first insertion order
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry<V>> {
+        self.entries.iter()
+    }
+
+    /// Entries by voice code, each group in first insertion order
+    pub fn group_by_voice_code(&self) -> BTreeMap<&str, Vec<&Entry<V>>> {
+        let mut groups: BTreeMap<&str, Vec<&Entry<V>>> = BTreeMap::new();
+        for entry in &self.entries {
+            groups.entry(entry.voice_code.voice_code.as_str()).or_default().push(entry);
+        }
+        groups
+    }
+
+    /// Groups of different cases sharing a voice code, see `CollisionReport` for transpositions
+    pub fn shared_voice_codes(&self) -> BTreeMap<&str, Vec<&Entry<V>>> {
+        let mut groups = self.group_by_voice_code();
+        groups.retain(|_, entries| entries.len() > 1);
+        groups
+    }
+}
+
+impl<V> Default for VoiceCodeMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Extend<(HashVoiceCode, V)> for VoiceCodeMap<V> {
+    fn extend<I: IntoIterator<Item = (HashVoiceCode, V)>>(&mut self, iter: I) {
+        for (voice_code, value) in iter {
+            self.insert(voice_code, value);
+        }
+    }
+}
+
+impl<V> FromIterator<(HashVoiceCode, V)> for VoiceCodeMap<V> {
+    fn from_iter<I: IntoIterator<Item = (HashVoiceCode, V)>>(iter: I) -> Self {
+        let mut map = VoiceCodeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, V> IntoIterator for &'a VoiceCodeMap<V> {
+    type Item = &'a Entry<V>;
+    type IntoIter = std::slice::Iter<'a, Entry<V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Distinct cases in first insertion order, a `VoiceCodeMap` without values
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::manifest::VoiceCodeSet;
+/// let case = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let mut scanned = VoiceCodeSet::new();
+/// assert!(scanned.insert(case.clone()));
+/// assert!(!scanned.insert(case.clone()));
+/// assert_eq!(scanned.len(), 1);
+/// assert_eq!(scanned.entry(&case).unwrap().count, 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VoiceCodeSet {
+    map: VoiceCodeMap<()>,
+}
+
+impl VoiceCodeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True when the case was not already in the set, repeats still count towards `last`
+    pub fn insert(&mut self, voice_code: HashVoiceCode) -> bool {
+        self.map.insert(voice_code, ()).is_none()
+    }
+
+    pub fn remove(&mut self, voice_code: &HashVoiceCode) -> bool {
+        self.map.remove(voice_code).is_some()
+    }
+
+    pub fn contains(&self, voice_code: &HashVoiceCode) -> bool {
+        self.map.contains(voice_code)
+    }
+
+    /// When the case was first and last inserted and how often
+    pub fn entry(&self, voice_code: &HashVoiceCode) -> Option<&Entry<()>> {
+        self.map.entry(voice_code)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Cases in first insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &HashVoiceCode> {
+        self.map.iter().map(|entry| &entry.voice_code)
+    }
+
+    /// Cases by voice code, each group in first insertion order
+    pub fn group_by_voice_code(&self) -> BTreeMap<&str, Vec<&HashVoiceCode>> {
+        self.map
+            .group_by_voice_code()
+            .into_iter()
+            .map(|(code, entries)| (code, entries.into_iter().map(|entry| &entry.voice_code).collect()))
+            .collect()
+    }
+
+    /// Groups of different cases sharing a voice code
+    pub fn shared_voice_codes(&self) -> BTreeMap<&str, Vec<&HashVoiceCode>> {
+        let mut groups = self.group_by_voice_code();
+        groups.retain(|_, cases| cases.len() > 1);
+        groups
+    }
+}
+
+impl Extend<HashVoiceCode> for VoiceCodeSet {
+    fn extend<I: IntoIterator<Item = HashVoiceCode>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|voice_code| (voice_code, ())));
+    }
+}
+
+impl FromIterator<HashVoiceCode> for VoiceCodeSet {
+    fn from_iter<I: IntoIterator<Item = HashVoiceCode>>(iter: I) -> Self {
+        let mut set = VoiceCodeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Codes by their two major digits, the pair pickers usually speak, each group in input order
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::manifest::group_by_major;
+/// let codes = [
+///     HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap(),
+///     HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "32abcd", "03", "01", "02").unwrap(),
+/// ];
+/// let groups = group_by_major(&codes);
+/// assert_eq!(groups.keys().copied().collect::<Vec<_>>(), ["79", "91"]);
+/// assert_eq!(groups["91"][0].voice_code, "6991");
+/// ```
+pub fn group_by_major<'a, I>(codes: I) -> BTreeMap<&'a str, Vec<&'a HashVoiceCode>>
+where
+    I: IntoIterator<Item = &'a HashVoiceCode>,
+{
+    let mut groups: BTreeMap<&str, Vec<&HashVoiceCode>> = BTreeMap::new();
+    for voice_code in codes {
+        groups.entry(voice_code.voice_code_major.as_str()).or_default().push(voice_code);
+    }
+    groups
+}
+
+/// Order slot names with runs of digits compared as numbers, so `A-2` comes before `A-10`
+///
+/// Digit runs that are numerically equal fall back to their text, `01` before `1`.
+///
+/// # Example
+/// ```
+/// use voicecode::manifest::compare_locations;
+/// let mut locations = ["A-10", "B-1", "A-2", "A-02-B"];
+/// locations.sort_by(|a, b| compare_locations(a, b));
+/// assert_eq!(locations, ["A-2", "A-02-B", "A-10", "B-1"]);
+/// ```
+pub fn compare_locations(a: &str, b: &str) -> Ordering {
+    let (mut a_rest, mut b_rest) = (a.as_bytes(), b.as_bytes());
+    loop {
+        match (a_rest.first(), b_rest.first()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_len = a_rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                let b_len = b_rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                let a_digits = trim_leading_zeros(&a_rest[..a_len]);
+                let b_digits = trim_leading_zeros(&b_rest[..b_len]);
+                let order = a_digits.len().cmp(&b_digits.len()).then_with(|| a_digits.cmp(b_digits));
+                if order != Ordering::Equal {
+                    return order;
+                }
+                a_rest = &a_rest[a_len..];
+                b_rest = &b_rest[b_len..];
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(y);
+                }
+                a_rest = &a_rest[1..];
+                b_rest = &b_rest[1..];
+            }
+        }
+    }
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|b| **b == b'0').count();
+    &digits[zeros..]
+}
+
+/// Sort pick tasks by location, then voice code, then case, the order a picker walks and
+/// hears them
+pub fn sort_pick_tasks(tasks: &mut [PickTask]) {
+    tasks.sort_by(|a, b| {
+        compare_locations(&a.location, &b.location)
+            .then_with(|| a.voice_code.voice_code.cmp(&b.voice_code.voice_code))
+            .then_with(|| a.voice_code.cmp(&b.voice_code))
+    });
+}
+
+/// One case to pick at a stop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickLine {
+    pub voice_code: HashVoiceCode,
+    /// Quantity of every task for this case at the stop
+    pub quantity: u32,
+    /// Another case at the stop has the same major digits, so the picker must confirm all four
+    pub speak_full_code: bool,
+}
+
+/// The cases at one location, in voice code order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickStop {
+    pub location: String,
+    pub lines: Vec<PickLine>,
+}
+
+/// Tasks of one assignment as stops in walking order, see `sort_pick_tasks`
+///
+/// Tasks for the same case at the same location become one line with their quantities added.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::export::vocollect::PickTask;
+/// use voicecode::manifest::pick_list;
+/// let case = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// let other = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "32abcd", "03", "01", "02").unwrap();
+/// let tasks = [
+///     PickTask::new("A1", "01-010", 2, case.clone()),
+///     PickTask::new("A1", "01-002", 1, case.clone()),
+///     PickTask::new("A1", "01-010", 1, other),
+///     PickTask::new("A1", "01-010", 3, case),
+/// ];
+/// let stops = pick_list(&tasks);
+/// assert_eq!(stops.iter().map(|stop| stop.location.as_str()).collect::<Vec<_>>(), ["01-002", "01-010"]);
+/// let lines: Vec<(&str, u32)> = stops[1].lines.iter().map(|line| (line.voice_code.voice_code.as_str(), line.quantity)).collect();
+/// assert_eq!(lines, [("6991", 5), ("8079", 1)]);
+/// ```
+pub fn pick_list<'a, I>(tasks: I) -> Vec<PickStop>
+where
+    I: IntoIterator<Item = &'a PickTask>,
+{
+    let mut tasks: Vec<PickTask> = tasks.into_iter().cloned().collect();
+    sort_pick_tasks(&mut tasks);
+
+    let mut stops: Vec<PickStop> = Vec::new();
+    for task in tasks {
+        let stop = match stops.last_mut() {
+            Some(stop) if stop.location == task.location => stop,
+            _ => {
+                stops.push(PickStop { location: task.location.clone(), lines: Vec::new() });
+                stops.last_mut().expect("just pushed")
+            }
+        };
+        // sorted, so a repeat of the case is the previous line
+        match stop.lines.last_mut() {
+            Some(line) if line.voice_code == task.voice_code => line.quantity += task.quantity,
+            _ => stop.lines.push(PickLine { voice_code: task.voice_code, quantity: task.quantity, speak_full_code: false }),
+        }
+    }
+
+    for stop in &mut stops {
+        let groups = group_by_major(stop.lines.iter().map(|line| &line.voice_code));
+        let shared: BTreeSet<String> =
+            groups.into_iter().filter(|(_, codes)| codes.len() > 1).map(|(major, _)| major.to_string()).collect();
+        for line in &mut stop.lines {
+            line.speak_full_code = shared.contains(&line.voice_code.voice_code_major);
+        }
+    }
+    stops
+}
+
+/// Distance `confusability` flags by default, single digit and transposition mistakes
+pub const DEFAULT_MAX_DISTANCE: usize = 1;
+
+/// How two codes in a pick zone differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WarningKind {
+    /// The same code for different products
+    Duplicate,
+    /// One adjacent digit swap apart, or the minor and major pairs swapped
+    Transposed,
+    /// Any other difference within the threshold, one wrong digit at distance 1
+    Substituted,
+}
+
+/// Two products a picker could confuse, `first` is the earlier of the two
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    /// Digit edits between the codes, a swap counting as one
+    pub distance: usize,
+    pub first: CollisionItem,
+    pub second: CollisionItem,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            WarningKind::Duplicate => "duplicate",
+            WarningKind::Transposed => "transposed",
+            WarningKind::Substituted => "substituted",
+        };
+        let (first, second) = (&self.first, &self.second);
+        write!(
+            f,
+            "{} {} and {} (GTIN {} LOT {}, GTIN {} LOT {})",
+            kind, first.voice_code, second.voice_code, first.gtin, first.lot, second.gtin, second.lot
+        )
+    }
+}
+
+/// Every pair of codes in one pick zone within `max_distance` digit edits of each other
+///
+/// Single wrong digits and transpositions are the mis-picks seen on the floor, so
+/// `DEFAULT_MAX_DISTANCE` flags only those. Repeats of the same product are skipped like
+/// `CollisionReport::analyze`.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::manifest::{ confusability, WarningKind, DEFAULT_MAX_DISTANCE };
+/// let zone = [
+///     HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap(),
+///     HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap(),
+/// ];
+/// assert!(confusability(&zone, DEFAULT_MAX_DISTANCE).is_empty());
+/// let warnings = confusability(&zone, 4);
+/// assert_eq!(warnings[0].kind, WarningKind::Substituted);
+/// ```
+pub fn confusability<'a, I>(codes: I, max_distance: usize) -> Vec<Warning>
+where
+    I: IntoIterator<Item = &'a HashVoiceCode>,
+{
+    let mut seen = BTreeSet::new();
+    let products: Vec<CollisionItem> = codes
+        .into_iter()
+        .enumerate()
+        .filter(|(_, voice_code)| seen.insert(voice_code.hash_text.as_str()))
+        .map(CollisionItem::from)
+        .collect();
+
+    let mut warnings = Vec::new();
+    for (i, first) in products.iter().enumerate() {
+        for second in &products[i + 1..] {
+            let (kind, distance) = if first.voice_code == second.voice_code {
+                (WarningKind::Duplicate, 0)
+            } else if confusable(&first.voice_code).contains(&second.voice_code) {
+                (WarningKind::Transposed, 1)
+            } else {
+                (WarningKind::Substituted, edit_distance(&first.voice_code, &second.voice_code))
+            };
+            if distance <= max_distance {
+                warnings.push(Warning { kind, distance, first: first.clone(), second: second.clone() });
+            }
+        }
+    }
+    warnings
+}
+
+/// Substitutions, insertions, deletions and adjacent swaps between two codes
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    d[0] = (0..=b.len()).collect();
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Codes a listener could mistake for `code`, adjacent swaps and the two pairs swapped
+fn confusable(code: &str) -> BTreeSet<String> {
+    let digits = code.as_bytes();
+    let mut out = BTreeSet::new();
+    if digits.len() != 4 {
+        return out;
+    }
+    for i in 0..3 {
+        let mut swapped = digits.to_vec();
+        swapped.swap(i, i + 1);
+        out.insert(String::from_utf8(swapped).expect("ASCII digits"));
+    }
+    out.insert(format!("{}{}", &code[2..], &code[..2]));
+    out.remove(code);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{find_lots, UPPER_ALPHANUMERIC};
+    use crate::pack_date::DatePadding;
+    use crate::{Gtin, PackDate};
+
+    /// A LOT giving `code` for the test GTIN and date
+    fn lot_for(code: u16) -> HashVoiceCode {
+        let gtin = Gtin::parse_unchecked("61414100734933").unwrap();
+        let pack_date = PackDate::from_parts("03", "01", "10").unwrap();
+        let lot = find_lots(&gtin, &pack_date, code, UPPER_ALPHANUMERIC, 4, 1).unwrap().next().unwrap();
+        HashVoiceCode::from_parts(gtin, lot, pack_date)
+    }
+
+    /// The PTI calculator example, its GTIN has a bad check digit
+    fn pti_example() -> HashVoiceCode {
+        HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap()
+    }
+
+    #[test]
+    fn test_confusable() {
+        let expected: BTreeSet<String> = ["9691", "6919", "9169"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(confusable("6991"), expected);
+        assert_eq!(confusable("1111"), BTreeSet::new());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("6991", "6991"), 0);
+        assert_eq!(edit_distance("6991", "6992"), 1);
+        assert_eq!(edit_distance("6991", "9691"), 1);
+        assert_eq!(edit_distance("6991", "9169"), 4);
+        assert_eq!(edit_distance("6991", "6812"), 3);
+    }
+
+    #[test]
+    fn test_confusability() {
+        let a = pti_example();
+        let items = vec![a.clone(), lot_for(6992), a.clone(), lot_for(9169), lot_for(6919), lot_for(1234), lot_for(6991)];
+        let warnings = confusability(&items, DEFAULT_MAX_DISTANCE);
+        let found: Vec<(WarningKind, usize, usize)> =
+            warnings.iter().map(|warning| (warning.kind, warning.first.index, warning.second.index)).collect();
+        assert_eq!(
+            found,
+            [
+                (WarningKind::Substituted, 0, 1),
+                (WarningKind::Transposed, 0, 3),
+                (WarningKind::Transposed, 0, 4),
+                (WarningKind::Duplicate, 0, 6),
+                (WarningKind::Substituted, 1, 6),
+                (WarningKind::Transposed, 3, 6),
+                (WarningKind::Transposed, 4, 6),
+            ]
+        );
+        assert_eq!(warnings[0].to_string(), format!("substituted 6991 and 6992 (GTIN 12345678901244 LOT LOT123, GTIN 61414100734933 LOT {})", items[1].lot));
+        assert_eq!(confusability(&items, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_voice_code_map() {
+        let a = pti_example();
+        let (b, c, d) = (lot_for(6991), lot_for(1234), lot_for(6919));
+        let mut map: VoiceCodeMap<u32> = [(a.clone(), 1), (b.clone(), 2), (c.clone(), 3), (a.clone(), 4), (d.clone(), 5)].into_iter().collect();
+        assert_eq!((map.len(), map.inserts()), (4, 5));
+        let order: Vec<(u32, usize, usize)> = map.iter().map(|entry| (entry.value, entry.first, entry.last)).collect();
+        assert_eq!(order, [(4, 0, 3), (2, 1, 1), (3, 2, 2), (5, 4, 4)]);
+
+        let shared = map.shared_voice_codes();
+        assert_eq!(shared.keys().copied().collect::<Vec<_>>(), ["6991"]);
+        assert_eq!(shared["6991"].iter().map(|entry| entry.value).collect::<Vec<_>>(), [4, 2]);
+        assert_eq!(map.group_by_voice_code().len(), 3);
+
+        *map.get_mut(&c).unwrap() += 10;
+        assert_eq!(map.remove(&b).map(|entry| entry.value), Some(2));
+        assert_eq!(map.get(&c), Some(&13));
+        assert_eq!(map.get(&d), Some(&5));
+        assert!(!map.contains(&b));
+        assert!(map.shared_voice_codes().is_empty());
+
+        // a different padding of the same inputs is a different hashed case
+        let raw = HashVoiceCode::builder().gtin("12345678901244").lot("LOT123").pack_date(("3", "1", "2")).date_padding(DatePadding::Raw).build().unwrap();
+        let padded = HashVoiceCode::builder().gtin("12345678901244").lot("LOT123").pack_date(("3", "1", "2")).build().unwrap();
+        let set: VoiceCodeSet = [a.clone(), raw, padded, a.clone()].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.entry(&a).map(|entry| (entry.first, entry.last, entry.count)), Some((0, 3, 3)));
+    }
+
+    #[test]
+    fn test_compare_locations() {
+        let mut locations = vec!["B", "a", "A10", "A9", "A009", "A9-1", "A9-01", "A", "10", "9"];
+        locations.sort_by(|a, b| compare_locations(a, b));
+        assert_eq!(locations, ["9", "10", "A", "A009", "A9", "A9-01", "A9-1", "A10", "B", "a"]);
+    }
+
+    #[test]
+    fn test_pick_list() {
+        let a = pti_example();
+        let (b, c) = (lot_for(1291), lot_for(4455));
+        let tasks = [
+            PickTask::new("A1", "2-10", 1, c.clone()),
+            PickTask::new("A1", "2-9", 4, c.clone()),
+            PickTask::new("A1", "2-10", 2, a.clone()),
+            PickTask::new("A1", "2-10", 1, b.clone()),
+            PickTask::new("A1", "2-10", 5, a.clone()),
+        ];
+        let stops = pick_list(&tasks);
+        let found: Vec<(&str, &str, u32, bool)> = stops
+            .iter()
+            .flat_map(|stop| stop.lines.iter().map(|line| (stop.location.as_str(), line.voice_code.voice_code.as_str(), line.quantity, line.speak_full_code)))
+            .collect();
+        assert_eq!(found, [("2-9", "4455", 4, false), ("2-10", "1291", 1, true), ("2-10", "4455", 1, false), ("2-10", "6991", 7, true)]);
+        assert_eq!(stops.len(), 2);
+        assert!(pick_list(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let a = pti_example();
+        let b = lot_for(6991);
+        let c = lot_for(6919);
+        let d = lot_for(1234);
+        let items = vec![a.clone(), d, a, b.clone(), c.clone()];
+        let report = CollisionReport::analyze(&items);
+
+        let duplicates: Vec<&Collision> = report.duplicates().collect();
+        assert_eq!(duplicates.len(), 1);
+        let indexes: Vec<usize> = duplicates[0].items.iter().map(|item| item.index).collect();
+        assert_eq!(indexes, [0, 3]);
+        assert_eq!(duplicates[0].items[1].lot, b.lot.as_str());
+
+        let transposed: Vec<&Collision> = report.transpositions().collect();
+        assert_eq!(transposed.len(), 1);
+        let indexes: Vec<usize> = transposed[0].items.iter().map(|item| item.index).collect();
+        assert_eq!(indexes, [0, 3, 4]);
+        assert_eq!(transposed[0].items[2].voice_code, c.voice_code);
+        assert!(report.to_string().starts_with("duplicate: 6991 (GTIN 12345678901244 LOT LOT123 pack date 030102), 6991"));
+    }
+}
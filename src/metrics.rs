@@ -0,0 +1,208 @@
+//! Prometheus metrics for the service, batch and print subsystems
+//!
+//! Metrics are kept per process and [`render`] writes them in the Prometheus text format.
+//! With the `serve` feature the HTTP service answers `GET /metrics` with it.
+//!
+//! - `voicecode_codes_computed_total` voice codes computed
+//! - `voicecode_validation_failures_total{kind}` failed computations by `VoiceCodeError::code`
+//! - `voicecode_batch_duration_seconds{format}` histogram of CSV, JSON Lines and XLSX runs
+//! - `voicecode_print_errors_total{printer}` print jobs that failed after every retry
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::error::VoiceCodeError;
+use crate::voicecode::HashVoiceCode;
+
+/// Upper bounds of the batch duration buckets, in seconds
+const DURATION_BUCKETS: [f64; 8] = [0.01, 0.1, 0.5, 1.0, 5.0, 30.0, 120.0, 600.0];
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Histogram {
+    /// Observations per bucket, not cumulative, the last one past every bound
+    buckets: [u64; DURATION_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+#[cfg_attr(not(any(feature = "csv", feature = "jsonl", feature = "xlsx")), allow(dead_code))]
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        let bucket = DURATION_BUCKETS.iter().position(|bound| value <= *bound).unwrap_or(DURATION_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Labeled {
+    failures: BTreeMap<&'static str, u64>,
+    batch_durations: BTreeMap<&'static str, Histogram>,
+    print_errors: BTreeMap<String, u64>,
+}
+
+struct Registry {
+    computed: AtomicU64,
+    labeled: Mutex<Labeled>,
+}
+
+impl Registry {
+    const fn new() -> Self {
+        Registry {
+            computed: AtomicU64::new(0),
+            labeled: Mutex::new(Labeled {
+                failures: BTreeMap::new(),
+                batch_durations: BTreeMap::new(),
+                print_errors: BTreeMap::new(),
+            }),
+        }
+    }
+
+    fn labeled(&self) -> std::sync::MutexGuard<'_, Labeled> {
+        // the maps stay consistent whatever panicked while holding the lock
+        self.labeled.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn computed(&self, result: &Result<HashVoiceCode, VoiceCodeError>) {
+        match result {
+            Ok(_) => {
+                self.computed.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(error) => *self.labeled().failures.entry(error.code()).or_default() += 1,
+        }
+    }
+
+    fn render(&self) -> String {
+        let labeled = self.labeled();
+        let mut out = String::new();
+        header(&mut out, "voicecode_codes_computed_total", "counter", "Voice codes computed");
+        let _ = writeln!(out, "voicecode_codes_computed_total {}", self.computed.load(Ordering::Relaxed));
+
+        let help = "Voice code computations that failed validation";
+        header(&mut out, "voicecode_validation_failures_total", "counter", help);
+        for (kind, count) in &labeled.failures {
+            let _ = writeln!(out, "voicecode_validation_failures_total{{kind=\"{}\"}} {}", kind, count);
+        }
+
+        header(&mut out, "voicecode_batch_duration_seconds", "histogram", "Duration of batch file runs");
+        for (format, histogram) in &labeled.batch_durations {
+            let name = "voicecode_batch_duration_seconds";
+            let mut cumulative = 0;
+            for (bound, count) in DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(out, "{}_bucket{{format=\"{}\",le=\"{}\"}} {}", name, format, bound, cumulative);
+            }
+            let _ = writeln!(out, "{}_bucket{{format=\"{}\",le=\"+Inf\"}} {}", name, format, histogram.count);
+            let _ = writeln!(out, "{}_sum{{format=\"{}\"}} {}", name, format, histogram.sum);
+            let _ = writeln!(out, "{}_count{{format=\"{}\"}} {}", name, format, histogram.count);
+        }
+
+        header(&mut out, "voicecode_print_errors_total", "counter", "Print jobs that failed after every retry");
+        for (printer, count) in &labeled.print_errors {
+            let _ = writeln!(out, "voicecode_print_errors_total{{printer=\"{}\"}} {}", escape(printer), count);
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+}
+
+/// Label value with `\`, `"` and line feeds escaped
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+static REGISTRY: Registry = Registry::new();
+
+/// Every metric in the Prometheus text exposition format, version 0.0.4
+///
+/// # Example
+/// ```
+/// use voicecode::batch::BatchRecord;
+/// BatchRecord::new("12345678901244", "LOT123", "030102").compute().unwrap();
+/// assert!(voicecode::metrics::render().contains("# TYPE voicecode_codes_computed_total counter"));
+/// ```
+pub fn render() -> String {
+    REGISTRY.render()
+}
+
+/// Count a computed voice code or its validation failure
+pub(crate) fn computed(result: &Result<HashVoiceCode, VoiceCodeError>) {
+    REGISTRY.computed(result);
+}
+
+/// Count a print job that failed after every retry
+pub(crate) fn print_error(printer: &str) {
+    *REGISTRY.labeled().print_errors.entry(printer.to_string()).or_default() += 1;
+}
+
+/// Observes the time from `start` until dropped as a batch run of `format`
+#[cfg(any(feature = "csv", feature = "jsonl", feature = "xlsx"))]
+pub(crate) struct BatchTimer {
+    format: &'static str,
+    start: std::time::Instant,
+}
+
+#[cfg(any(feature = "csv", feature = "jsonl", feature = "xlsx"))]
+impl BatchTimer {
+    pub(crate) fn start(format: &'static str) -> Self {
+        BatchTimer { format, start: std::time::Instant::now() }
+    }
+}
+
+#[cfg(any(feature = "csv", feature = "jsonl", feature = "xlsx"))]
+impl Drop for BatchTimer {
+    fn drop(&mut self) {
+        let seconds = self.start.elapsed().as_secs_f64();
+        REGISTRY.labeled().batch_durations.entry(self.format).or_default().observe(seconds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let registry = Registry::new();
+        registry.computed(&HashVoiceCode::new("00614141007349", "LOT123", "03", "01", "02"));
+        registry.computed(&HashVoiceCode::new("00614141007349", "LOT 123", "03", "01", "02"));
+        registry.computed(&HashVoiceCode::new("1234", "LOT123", "03", "01", "02"));
+        registry.computed(&HashVoiceCode::new("1234", "LOT123", "03", "01", "02"));
+        registry.labeled().print_errors.insert("printer \"a\"\\1".to_string(), 1);
+        {
+            let mut labeled = registry.labeled();
+            let histogram = labeled.batch_durations.entry("csv").or_default();
+            histogram.observe(0.2);
+            histogram.observe(1000.0);
+        }
+        let text = registry.render();
+        let lines: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(
+            lines,
+            [
+                "voicecode_codes_computed_total 1",
+                "voicecode_validation_failures_total{kind=\"gtin_format\"} 2",
+                "voicecode_validation_failures_total{kind=\"lot_invalid_character\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"0.01\"} 0",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"0.1\"} 0",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"0.5\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"1\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"5\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"30\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"120\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"600\"} 1",
+                "voicecode_batch_duration_seconds_bucket{format=\"csv\",le=\"+Inf\"} 2",
+                "voicecode_batch_duration_seconds_sum{format=\"csv\"} 1000.2",
+                "voicecode_batch_duration_seconds_count{format=\"csv\"} 2",
+                "voicecode_print_errors_total{printer=\"printer \\\"a\\\"\\\\1\"} 1",
+            ]
+        );
+        assert!(text.contains("# TYPE voicecode_batch_duration_seconds histogram\n"));
+    }
+}
@@ -0,0 +1,139 @@
+//! UniFFI bindings for the Kotlin and Swift scanning apps
+//!
+//! Build the library with `cargo rustc --lib --release --features uniffi --crate-type cdylib`
+//! then generate bindings from it
+//!
+//! ```sh
+//! cargo run --features uniffi-bindgen --bin uniffi-bindgen -- generate \
+//!     --library target/release/libvoicecode.so --language kotlin --out-dir out
+//! ```
+//!
+//! Kotlin sees `compute`, `validateGtin`, `validateLot`, the `VoiceCode` record and
+//! `VoiceCodeException`, Swift sees the same with `VoiceCodeError`.
+
+use std::fmt;
+
+use crate::gtin::Gtin;
+use crate::lot::Lot;
+use crate::pack_date::PackDate;
+use crate::validation::Field;
+use crate::voicecode::HashVoiceCode;
+
+/// Why an input was rejected, `message` is the crate's description of the failure
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Error)]
+pub enum VoiceCodeError {
+    InvalidGtin { message: String },
+    InvalidLot { message: String },
+    InvalidDate { message: String },
+    Invalid { message: String },
+}
+
+impl fmt::Display for VoiceCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoiceCodeError::InvalidGtin { message }
+            | VoiceCodeError::InvalidLot { message }
+            | VoiceCodeError::InvalidDate { message }
+            | VoiceCodeError::Invalid { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for VoiceCodeError {}
+
+impl From<crate::error::VoiceCodeError> for VoiceCodeError {
+    fn from(value: crate::error::VoiceCodeError) -> Self {
+        use crate::error::VoiceCodeError as E;
+        let message = value.to_string();
+        match value {
+            E::InvalidGtin { .. } | E::InvalidGtinCheckDigit { .. } => VoiceCodeError::InvalidGtin { message },
+            E::InvalidLot { .. } => VoiceCodeError::InvalidLot { message },
+            E::InvalidDateComponent { .. }
+            | E::InvalidCalendarDate { .. }
+            | E::UnrecognizedDate { .. }
+            | E::AmbiguousDate { .. } => VoiceCodeError::InvalidDate { message },
+            E::InvalidGs1 { .. }
+            | E::MissingField { .. }
+            | E::NonAscii { .. }
+            | E::InvalidSscc
+            | E::InvalidSsccCheckDigit { .. }
+            | E::InvalidVoiceCode => VoiceCodeError::Invalid { message },
+            E::LintDenied { lint, .. } => match lint.field() {
+                Field::Gtin => VoiceCodeError::InvalidGtin { message },
+                Field::Lot => VoiceCodeError::InvalidLot { message },
+                _ => VoiceCodeError::InvalidDate { message },
+            },
+        }
+    }
+}
+
+/// Voice code and the inputs it was computed from
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct VoiceCode {
+    pub gtin: String,
+    pub lot: String,
+    /// Zero padded YYMMDD
+    pub pack_date: String,
+    pub hash_text: String,
+    pub voice_code: String,
+    pub major: String,
+    pub minor: String,
+}
+
+impl From<HashVoiceCode> for VoiceCode {
+    fn from(value: HashVoiceCode) -> Self {
+        VoiceCode {
+            gtin: value.gtin.to_string(),
+            lot: value.lot.to_string(),
+            pack_date: value.pack_date().yymmdd(),
+            hash_text: value.hash_text,
+            voice_code: value.voice_code,
+            major: value.voice_code_major,
+            minor: value.voice_code_minor,
+        }
+    }
+}
+
+/// Compute the voice code for a GTIN, LOT and YYMMDD or YYYY-MM-DD pack date
+#[uniffi::export]
+pub fn compute(gtin: String, lot: String, date: String) -> Result<VoiceCode, VoiceCodeError> {
+    let pack_date: PackDate = date.parse()?;
+    Ok(HashVoiceCode::builder().gtin(gtin).lot(lot).pack_date(pack_date).build()?.into())
+}
+
+/// Check a GTIN is 8, 12, 13 or 14 digits, and its check digit when `check_digit` is set
+#[uniffi::export]
+pub fn validate_gtin(gtin: String, check_digit: bool) -> Result<(), VoiceCodeError> {
+    if check_digit {
+        Gtin::parse(&gtin)?;
+    } else {
+        Gtin::parse_unchecked(&gtin)?;
+    }
+    Ok(())
+}
+
+/// Check a LOT is 1 to 20 characters from the PTI set
+#[uniffi::export]
+pub fn validate_lot(lot: String) -> Result<(), VoiceCodeError> {
+    Lot::parse(&lot)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let code = compute("12345678901244".into(), "LOT123".into(), "2003-01-02".into()).unwrap();
+        assert_eq!(code.voice_code, "6991");
+        assert_eq!(code.major, "91");
+        assert_eq!(code.minor, "69");
+        assert_eq!(code.pack_date, "030102");
+
+        assert!(matches!(compute("1234".into(), "LOT123".into(), "030102".into()), Err(VoiceCodeError::InvalidGtin { .. })));
+        assert!(matches!(validate_gtin("12345678901244".into(), true), Err(VoiceCodeError::InvalidGtin { .. })));
+        assert!(validate_gtin("12345678901244".into(), false).is_ok());
+        assert!(matches!(validate_lot("LOT 123".into()), Err(VoiceCodeError::InvalidLot { .. })));
+    }
+}
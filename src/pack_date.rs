@@ -0,0 +1,528 @@
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, NaiveDate};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{DateComponent, VoiceCodeError};
+
+/// Century window used to turn a two digit YY into a full year
+///
+/// The window covers `start..=start + 99`, the default 1970..=2069 matches chrono's `%y`.
+///
+/// # Example
+/// ```
+/// use voicecode::pack_date::YearWindow;
+/// let window = YearWindow::default();
+/// assert_eq!(window.resolve(69), 2069);
+/// assert_eq!(window.resolve(70), 1970);
+///
+/// let window = YearWindow::new(2000);
+/// assert_eq!(window.resolve(99), 2099);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct YearWindow {
+    start: i32,
+}
+
+impl YearWindow {
+    /// Window covering `start..=start + 99`
+    pub const fn new(start: i32) -> Self {
+        YearWindow { start }
+    }
+
+    /// First year of the window
+    pub fn start(&self) -> i32 {
+        self.start
+    }
+
+    /// Full year for a two digit year, `yy` above 99 only uses its last two digits
+    pub fn resolve(&self, yy: u32) -> i32 {
+        let yy = (yy % 100) as i32;
+        let base = self.start.div_euclid(100) * 100;
+        let year = base + yy;
+        if year < self.start {
+            year + 100
+        } else {
+            year
+        }
+    }
+}
+
+impl Default for YearWindow {
+    fn default() -> Self {
+        YearWindow::new(1970)
+    }
+}
+
+/// How the pack date parts are written into the hashed text
+///
+/// Before 0.3 `HashVoiceCode::new("…", "L", "3", "1", "2")` hashed `312` while the label
+/// prints `030102`, so its code disagreed with any scanner that reads the barcode. `ZeroPad`,
+/// the default, hashes the printed form, `Raw` reproduces codes already on existing labels.
+///
+/// # Example
+/// ```
+/// use voicecode::{ PackDate };
+/// use voicecode::pack_date::DatePadding;
+/// let pack_date = PackDate::from_parts("3", "1", "2").unwrap();
+/// assert_eq!(pack_date.hash_text(DatePadding::Raw), "312");
+/// assert_eq!(pack_date.hash_text(DatePadding::ZeroPad), "030102");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DatePadding {
+    /// YY, MM and DD exactly as provided, how versions before 0.3 hashed them
+    Raw,
+    /// Each part padded to two digits, the same text as `yymmdd`
+    #[default]
+    ZeroPad,
+}
+
+/// How `PackDate::parse_flexible` reads digits that form a date in both year first and
+/// month first order
+///
+/// `"010203"` is 2001-02-03 as YYMMDD and 2003-01-02 as MMDDYY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DateAmbiguity {
+    /// YYYYMMDD and YYMMDD, the GS1 order, falling back to month first
+    #[default]
+    YearFirst,
+    /// MMDDYYYY and MMDDYY, falling back to year first
+    MonthFirst,
+    /// Reject digits that read as two different dates
+    Reject,
+}
+
+/// Pack date as printed on the label
+///
+/// Keeps the original YY, MM and DD strings, which `DatePadding::Raw` hashes, alongside the
+/// calendar date they describe. Legacy labels can carry impossible dates such as month 99,
+/// those still parse and `calendar_date()` returns None.
+///
+/// # Example
+/// ```
+/// use voicecode::{ PackDate };
+/// let pack_date = PackDate::from_parts("03", "01", "02").unwrap();
+/// assert_eq!(pack_date.yymmdd(), "030102");
+/// assert_eq!(pack_date.calendar_date(), Some((2003, 1, 2)));
+///
+/// let legacy = PackDate::from_parts("99", "99", "99").unwrap();
+/// assert_eq!(legacy.calendar_date(), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+#[cfg_attr(feature = "diesel", derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Date))]
+pub struct PackDate {
+    yy: String,
+    mm: String,
+    dd: String,
+    /// Year, month and day, which order chronologically as a tuple
+    date: Option<(i32, u32, u32)>,
+}
+
+impl PackDate {
+    /// Build from YY, MM and DD strings of 1 or 2 digits using the default `YearWindow`
+    pub fn from_parts(yy: &str, mm: &str, dd: &str) -> Result<Self, VoiceCodeError> {
+        Self::from_parts_with_window(yy, mm, dd, YearWindow::default())
+    }
+
+    /// Build from YY, MM and DD strings of 1 or 2 digits interpreting YY in `window`
+    pub fn from_parts_with_window(yy: &str, mm: &str, dd: &str, window: YearWindow) -> Result<Self, VoiceCodeError> {
+        let year = parse_component(yy, DateComponent::Year)?;
+        let month = parse_component(mm, DateComponent::Month)?;
+        let day = parse_component(dd, DateComponent::Day)?;
+
+        Ok(PackDate {
+            yy: yy.to_string(),
+            mm: mm.to_string(),
+            dd: dd.to_string(),
+            date: calendar_date(window.resolve(year), month, day),
+        })
+    }
+
+    /// Parse a six digit YYMMDD as carried in AI(13)
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ PackDate };
+    /// assert_eq!(PackDate::parse_yymmdd("030102").unwrap(), PackDate::from_parts("03", "01", "02").unwrap());
+    /// assert!(PackDate::parse_yymmdd("0301").is_err());
+    /// ```
+    pub fn parse_yymmdd(yymmdd: &str) -> Result<Self, VoiceCodeError> {
+        if yymmdd.len() != 6 || !yymmdd.is_ascii() {
+            return Err(VoiceCodeError::InvalidDateComponent { which: DateComponent::Year });
+        }
+        Self::from_parts(&yymmdd[0..2], &yymmdd[2..4], &yymmdd[4..6])
+    }
+
+    /// Parse a calendar date typed by a person or exported by another system
+    ///
+    /// Accepts `mm/dd/yyyy`, ISO 8601 `yyyy-mm-dd` with or without a time, `yyyymmdd` and
+    /// `yymmdd`, plus the month first `mmddyyyy` and `mmddyy` that `ambiguity` chooses
+    /// between when the digits fit both orders. Two digit years use the default
+    /// `YearWindow`. Unlike `parse_yymmdd` the result must be a calendar date.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ PackDate, VoiceCodeError };
+    /// use voicecode::pack_date::DateAmbiguity;
+    /// let pack_date = PackDate::parse_flexible("01/02/2003", DateAmbiguity::default()).unwrap();
+    /// assert_eq!(pack_date.yymmdd(), "030102");
+    /// assert_eq!(PackDate::parse_flexible("2003-01-02T08:30:00Z", DateAmbiguity::default()).unwrap(), pack_date);
+    /// assert_eq!(PackDate::parse_flexible("20030102", DateAmbiguity::default()).unwrap(), pack_date);
+    /// assert_eq!(PackDate::parse_flexible("010203", DateAmbiguity::MonthFirst).unwrap(), pack_date);
+    /// assert!(matches!(
+    ///     PackDate::parse_flexible("010203", DateAmbiguity::Reject),
+    ///     Err(VoiceCodeError::AmbiguousDate { .. })
+    /// ));
+    /// ```
+    pub fn parse_flexible(input: &str, ambiguity: DateAmbiguity) -> Result<Self, VoiceCodeError> {
+        let text = input.trim();
+        let date = if text.contains('/') {
+            parse_slashed(text)
+        } else if text.contains('-') {
+            parse_iso(text)
+        } else if text.bytes().all(|b| b.is_ascii_digit()) {
+            let (year_first, month_first) = match text.len() {
+                8 => (ymd(&text[0..4], &text[4..6], &text[6..8]), ymd(&text[4..8], &text[0..2], &text[2..4])),
+                6 => (yy_md(&text[0..2], &text[2..4], &text[4..6]), yy_md(&text[4..6], &text[0..2], &text[2..4])),
+                _ => (None, None),
+            };
+            match (ambiguity, year_first, month_first) {
+                (_, Some(a), Some(b)) if a == b => Some(a),
+                (DateAmbiguity::Reject, Some(_), Some(_)) => {
+                    return Err(VoiceCodeError::AmbiguousDate { input: input.to_string() });
+                }
+                (DateAmbiguity::MonthFirst, _, Some(date)) => Some(date),
+                (_, year_first, month_first) => year_first.or(month_first),
+            }
+        } else {
+            None
+        };
+        date.map(PackDate::from_calendar_date).ok_or_else(|| VoiceCodeError::UnrecognizedDate { input: input.to_string() })
+    }
+
+    /// Build from a calendar date
+    #[cfg(feature = "chrono")]
+    pub fn from_naive(date: NaiveDate) -> Self {
+        PackDate::from_calendar_date((date.year(), date.month(), date.day()))
+    }
+
+    /// Build from a `time` crate calendar date
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ PackDate };
+    /// let date = time::Date::from_calendar_date(2003, time::Month::January, 2).unwrap();
+    /// assert_eq!(PackDate::from_time_date(date).yymmdd(), "030102");
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn from_time_date(date: time::Date) -> Self {
+        PackDate::from_calendar_date((date.year(), u8::from(date.month()) as u32, date.day() as u32))
+    }
+
+    /// Zero padded parts of a known calendar date
+    fn from_calendar_date((year, month, day): (i32, u32, u32)) -> Self {
+        PackDate {
+            yy: format!("{:02}", year.rem_euclid(100)),
+            mm: format!("{:02}", month),
+            dd: format!("{:02}", day),
+            date: Some((year, month, day)),
+        }
+    }
+
+    /// Calendar date, None when the parts do not form a real date
+    #[cfg(feature = "chrono")]
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.date.and_then(|(year, month, day)| NaiveDate::from_ymd_opt(year, month, day))
+    }
+
+    /// Year, month and day, None when the parts do not form a real date
+    pub fn calendar_date(&self) -> Option<(i32, u32, u32)> {
+        self.date
+    }
+
+    /// YY as provided
+    pub fn yy(&self) -> &str {
+        &self.yy
+    }
+
+    /// MM as provided
+    pub fn mm(&self) -> &str {
+        &self.mm
+    }
+
+    /// DD as provided
+    pub fn dd(&self) -> &str {
+        &self.dd
+    }
+
+    /// Zero padded YYMMDD as printed in AI(13)
+    pub fn yymmdd(&self) -> String {
+        format!("{:0>2}{:0>2}{:0>2}", self.yy, self.mm, self.dd)
+    }
+
+    /// Date part of the hashed text
+    pub fn hash_text(&self, padding: DatePadding) -> String {
+        match padding {
+            DatePadding::Raw => format!("{}{}{}", self.yy, self.mm, self.dd),
+            DatePadding::ZeroPad => self.yymmdd(),
+        }
+    }
+
+    /// The same date with its parts written the way `padding` hashes them
+    pub(crate) fn padded(&self, padding: DatePadding) -> PackDate {
+        match padding {
+            DatePadding::Raw => self.clone(),
+            DatePadding::ZeroPad => PackDate {
+                yy: format!("{:0>2}", self.yy),
+                mm: format!("{:0>2}", self.mm),
+                dd: format!("{:0>2}", self.dd),
+                date: self.date,
+            },
+        }
+    }
+}
+
+/// Chronological, impossible legacy dates sort first, ties on the date fall back to the
+/// parts as provided
+impl Ord for PackDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.date
+            .cmp(&other.date)
+            .then_with(|| (&self.yy, &self.mm, &self.dd).cmp(&(&other.yy, &other.mm, &other.dd)))
+    }
+}
+
+impl PartialOrd for PackDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for PackDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:0>2}{:0>2}{:0>2}", self.yy, self.mm, self.dd)
+    }
+}
+
+/// Parses YYMMDD or an ISO 8601 YYYY-MM-DD date
+impl FromStr for PackDate {
+    type Err = VoiceCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match iso_date(s) {
+            Some(date) => Ok(PackDate::from_calendar_date(date)),
+            None => PackDate::parse_yymmdd(s),
+        }
+    }
+}
+
+impl TryFrom<String> for PackDate {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PackDate> for String {
+    fn from(value: PackDate) -> Self {
+        value.yymmdd()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveDate> for PackDate {
+    fn from(value: NaiveDate) -> Self {
+        PackDate::from_naive(value)
+    }
+}
+
+/// `m/d/yyyy` with one or two digit month and day
+fn parse_slashed(text: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = text.split('/');
+    let (mm, dd, yyyy) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || !(1..=2).contains(&mm.len()) || !(1..=2).contains(&dd.len()) {
+        return None;
+    }
+    ymd(yyyy, mm, dd)
+}
+
+/// `yyyy-mm-dd`, optionally followed by a time with or without an offset, the date is taken
+/// as written rather than converted to UTC
+fn parse_iso(text: &str) -> Option<(i32, u32, u32)> {
+    match text.find(['T', 't', ' ']) {
+        Some(split) => iso_date(&text[..split]).filter(|_| is_iso_time(&text[split + 1..])),
+        None => iso_date(text),
+    }
+}
+
+/// Exactly `yyyy-mm-dd`
+fn iso_date(text: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = text.split('-');
+    let (yyyy, mm, dd) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || mm.len() != 2 || dd.len() != 2 {
+        return None;
+    }
+    ymd(yyyy, mm, dd)
+}
+
+/// `hh:mm`, `hh:mm:ss` or `hh:mm:ss.fff` followed by an optional `Z` or `±hh:mm` offset
+fn is_iso_time(time: &str) -> bool {
+    let time = time.trim_end_matches(['Z', 'z']);
+    let time = time.find(['+', '-']).map_or(time, |offset| &time[..offset]);
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (time, None),
+    };
+    let parts: Vec<&str> = time.split(':').collect();
+    let fraction_ok = fraction.is_none_or(|fraction| {
+        parts.len() == 3 && !fraction.is_empty() && fraction.bytes().all(|b| b.is_ascii_digit())
+    });
+    // 60 seconds for a leap second
+    (2..=3).contains(&parts.len())
+        && fraction_ok
+        && parts.iter().zip([23, 59, 60]).all(|(part, max)| {
+            part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit()) && part.parse::<u32>().is_ok_and(|n| n <= max)
+        })
+}
+
+/// Calendar date from a four digit year and one or two digit month and day
+fn ymd(yyyy: &str, mm: &str, dd: &str) -> Option<(i32, u32, u32)> {
+    if yyyy.len() != 4 || !yyyy.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let month = parse_component(mm, DateComponent::Month).ok()?;
+    let day = parse_component(dd, DateComponent::Day).ok()?;
+    calendar_date(yyyy.parse().ok()?, month, day)
+}
+
+/// Calendar date from two digit year, month and day
+fn yy_md(yy: &str, mm: &str, dd: &str) -> Option<(i32, u32, u32)> {
+    PackDate::from_parts(yy, mm, dd).ok()?.calendar_date()
+}
+
+/// Year, month and day when they are a date of the Gregorian calendar
+fn calendar_date(year: i32, month: u32, day: u32) -> Option<(i32, u32, u32)> {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days).contains(&day).then_some((year, month, day))
+}
+
+#[cfg(feature = "time")]
+impl From<time::Date> for PackDate {
+    fn from(value: time::Date) -> Self {
+        PackDate::from_time_date(value)
+    }
+}
+
+pub(crate) fn parse_component(value: &str, which: DateComponent) -> Result<u32, VoiceCodeError> {
+    // ASCII digits only, anything else would put non ASCII text into the hash
+    if value.is_empty() || value.len() > 2 || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(VoiceCodeError::InvalidDateComponent { which });
+    }
+    Ok(value.parse().expect("one or two ASCII digits"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window() {
+        let window = YearWindow::new(1950);
+        assert_eq!(window.resolve(49), 2049);
+        assert_eq!(window.resolve(50), 1950);
+        assert_eq!(window.resolve(0), 2000);
+
+        let date = PackDate::from_parts_with_window("49", "1", "2", window).unwrap();
+        assert_eq!(date.calendar_date(), Some((2049, 1, 2)));
+        assert_eq!(date.yy(), "49");
+        assert_eq!(date.mm(), "1");
+        assert_eq!(date.to_string(), "490102");
+    }
+
+    #[test]
+    fn test_invalid_components() {
+        assert_eq!(
+            PackDate::from_parts("", "01", "01").unwrap_err(),
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Year }
+        );
+        assert_eq!(
+            PackDate::from_parts("01", "001", "01").unwrap_err(),
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Month }
+        );
+        assert_eq!(
+            PackDate::from_parts("01", "01", "d").unwrap_err(),
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Day }
+        );
+        assert_eq!(
+            PackDate::from_parts("01", "\u{0663}", "01").unwrap_err(),
+            VoiceCodeError::InvalidDateComponent { which: DateComponent::Month }
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("2003-01-02".parse::<PackDate>().unwrap().yymmdd(), "030102");
+        assert_eq!("030102".parse::<PackDate>().unwrap().calendar_date(), Some((2003, 1, 2)));
+        assert!("03-01-02".parse::<PackDate>().is_err());
+    }
+
+    #[test]
+    fn test_parse_flexible() {
+        let date = Some((2003, 12, 31));
+        let parse = |input| PackDate::parse_flexible(input, DateAmbiguity::default()).map(|pack_date| pack_date.calendar_date());
+        assert_eq!(parse("12/31/2003"), Ok(date));
+        assert_eq!(parse("2003-12-31"), Ok(date));
+        assert_eq!(parse(" 2003-12-31 23:59:59.5-08:00 "), Ok(date));
+        assert_eq!(parse("20031231"), Ok(date));
+        assert_eq!(parse("031231"), Ok(date));
+        // only valid month first
+        assert_eq!(parse("12312003"), Ok(date));
+        assert_eq!(parse("1/2/2003"), Ok(Some((2003, 1, 2))));
+
+        for input in ["", "2/30/2003", "12/31/03", "2003-1-2", "2003-12-31T25:00", "0301", "991399", "03.01.02"] {
+            assert_eq!(parse(input), Err(VoiceCodeError::UnrecognizedDate { input: input.to_string() }), "{}", input);
+        }
+
+        assert_eq!(parse("010203"), Ok(Some((2001, 2, 3))));
+        let month_first = PackDate::parse_flexible("010203", DateAmbiguity::MonthFirst).unwrap();
+        assert_eq!(month_first.calendar_date(), Some((2003, 1, 2)));
+        assert_eq!(
+            PackDate::parse_flexible("010203", DateAmbiguity::Reject).unwrap_err(),
+            VoiceCodeError::AmbiguousDate { input: "010203".to_string() }
+        );
+        // both orders agree
+        assert!(PackDate::parse_flexible("010101", DateAmbiguity::Reject).is_ok());
+    }
+
+    #[test]
+    fn test_calendar_date() {
+        assert_eq!(PackDate::from_parts("24", "02", "29").unwrap().calendar_date(), Some((2024, 2, 29)));
+        assert_eq!(PackDate::from_parts("23", "02", "29").unwrap().calendar_date(), None);
+        // 1900 is not a leap year
+        assert_eq!(PackDate::from_parts_with_window("00", "02", "29", YearWindow::new(1900)).unwrap().calendar_date(), None);
+        assert_eq!(PackDate::from_parts("00", "02", "29").unwrap().calendar_date(), Some((2000, 2, 29)));
+        assert_eq!(PackDate::from_parts("03", "04", "31").unwrap().calendar_date(), None);
+        assert_eq!(PackDate::from_parts("03", "00", "01").unwrap().calendar_date(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_from_naive() {
+        let date = NaiveDate::from_ymd_opt(1999, 12, 31).unwrap();
+        let pack_date = PackDate::from_naive(date);
+        assert_eq!(pack_date.yymmdd(), "991231");
+        assert_eq!(pack_date.date(), Some(date));
+    }
+}
@@ -0,0 +1,140 @@
+//! Validation rules that can be tightened per retailer
+//!
+//! The defaults are the rules `HashVoiceCode::new` applies. Turning `require_check_digit` off
+//! hashes GTINs printed with a bad check digit, every other field can only narrow the rules,
+//! a GTIN or LOT accepted by a policy is always accepted by `Gtin::parse_unchecked` and
+//! `Lot::parse`.
+
+use crate::error::VoiceCodeError;
+use crate::gtin::{Gtin, GtinError};
+use crate::lot::{Lot, LotError, MAX_LOT_LEN};
+use crate::pack_date::PackDate;
+
+/// Which GTINs, LOTs and pack dates are accepted
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, ValidationPolicy, VoiceCodeError };
+/// use voicecode::lot::LotError;
+/// let policy = ValidationPolicy {
+///     allow_parens_in_lot: false,
+///     allowed_gtin_lengths: vec![14],
+///     require_check_digit: false,
+///     ..ValidationPolicy::default()
+/// };
+/// let voice_code = HashVoiceCode::new_with_policy("12345678901244", "LOT123", "03", "01", "02", &policy).unwrap();
+/// assert_eq!(voice_code.voice_code, "6991");
+///
+/// let err = HashVoiceCode::new_with_policy("12345678901244", "LOT(1)", "03", "01", "02", &policy).unwrap_err();
+/// assert_eq!(err, VoiceCodeError::InvalidLot { reason: LotError::DisallowedByPolicy { ch: '(', offset: 3 } });
+/// assert!(HashVoiceCode::new_with_policy("123456789012", "LOT123", "03", "01", "02", &policy).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ValidationPolicy {
+    /// Accept `(` and `)` in a LOT, they can be misread as a GS1 AI in bracketed text
+    pub allow_parens_in_lot: bool,
+    /// GTIN lengths to accept, from 8, 12, 13 and 14
+    pub allowed_gtin_lengths: Vec<usize>,
+    /// Verify the GTIN check digit, off for labels already printed with a bad one
+    pub require_check_digit: bool,
+    /// Reject date parts that are not a calendar date, such as month 13 or Feb 30
+    pub strict_dates: bool,
+    /// Longest LOT to accept, capped at `MAX_LOT_LEN`
+    pub max_lot_len: usize,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        ValidationPolicy {
+            allow_parens_in_lot: true,
+            allowed_gtin_lengths: vec![8, 12, 13, 14],
+            require_check_digit: true,
+            strict_dates: false,
+            max_lot_len: MAX_LOT_LEN,
+        }
+    }
+}
+
+impl ValidationPolicy {
+    /// Validate a GTIN against this policy
+    pub fn gtin(&self, gtin: &str) -> Result<Gtin, VoiceCodeError> {
+        let parsed = if self.require_check_digit { Gtin::parse(gtin)? } else { Gtin::parse_unchecked(gtin)? };
+        if !self.allowed_gtin_lengths.contains(&parsed.len()) {
+            return Err(VoiceCodeError::InvalidGtin { reason: GtinError::LengthDisallowedByPolicy { len: parsed.len() } });
+        }
+        Ok(parsed)
+    }
+
+    /// Validate a LOT against this policy
+    pub fn lot(&self, lot: &str) -> Result<Lot, VoiceCodeError> {
+        let parsed = Lot::parse(lot)?;
+        let max = self.max_lot_len.min(MAX_LOT_LEN);
+        let len = lot.chars().count();
+        if len > max {
+            return Err(VoiceCodeError::InvalidLot { reason: LotError::LongerThanPolicy { len, max } });
+        }
+        if !self.allow_parens_in_lot {
+            if let Some((offset, ch)) = lot.char_indices().find(|(_, ch)| matches!(ch, '(' | ')')) {
+                return Err(VoiceCodeError::InvalidLot { reason: LotError::DisallowedByPolicy { ch, offset } });
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Validate pack date parts against this policy
+    pub fn pack_date(&self, yy: &str, mm: &str, dd: &str) -> Result<PackDate, VoiceCodeError> {
+        let pack_date = PackDate::from_parts(yy, mm, dd)?;
+        if self.strict_dates && pack_date.calendar_date().is_none() {
+            return Err(VoiceCodeError::InvalidCalendarDate { yymmdd: pack_date.yymmdd() });
+        }
+        Ok(pack_date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_new() {
+        let policy = ValidationPolicy::default();
+        for gtin in ["96385074", "614141007349", "12345678901244", "1234567890", "1234567890123a"] {
+            assert_eq!(policy.gtin(gtin), Gtin::parse(gtin), "{}", gtin);
+        }
+        for lot in ["", "LOT(1)", "32 ABCD", "ABCDEFGHIJKLMNOPQRST", "ABCDEFGHIJKLMNOPQRSTU"] {
+            assert_eq!(policy.lot(lot), Lot::parse(lot), "{}", lot);
+        }
+        assert!(policy.pack_date("24", "02", "30").is_ok());
+    }
+
+    #[test]
+    fn test_tightened() {
+        let policy = ValidationPolicy {
+            allow_parens_in_lot: false,
+            allowed_gtin_lengths: vec![12, 14],
+            require_check_digit: true,
+            strict_dates: true,
+            max_lot_len: 6,
+        };
+        assert_eq!(policy.gtin("12345678901244").unwrap_err(), VoiceCodeError::InvalidGtinCheckDigit { expected: 8, found: 4 });
+        assert_eq!(
+            policy.gtin("0614141007349").unwrap_err(),
+            VoiceCodeError::InvalidGtin { reason: GtinError::LengthDisallowedByPolicy { len: 13 } }
+        );
+        assert!(policy.gtin("614141007349").is_ok());
+        assert_eq!(
+            policy.lot("LOT1234").unwrap_err(),
+            VoiceCodeError::InvalidLot { reason: LotError::LongerThanPolicy { len: 7, max: 6 } }
+        );
+        assert_eq!(
+            policy.lot("L)").unwrap_err(),
+            VoiceCodeError::InvalidLot { reason: LotError::DisallowedByPolicy { ch: ')', offset: 1 } }
+        );
+        assert!(matches!(policy.pack_date("24", "02", "30"), Err(VoiceCodeError::InvalidCalendarDate { .. })));
+
+        let loose = ValidationPolicy { max_lot_len: 40, ..ValidationPolicy::default() };
+        assert!(loose.lot("ABCDEFGHIJKLMNOPQRSTU").is_err());
+    }
+}
@@ -0,0 +1,353 @@
+//! Many labels sent as pages across a pool of printers
+
+use std::thread;
+use std::time::Duration;
+
+use crate::print::{PrintError, Printer};
+
+/// Labels to print, ZPL or EPL, each with a copy count
+///
+/// Labels are sent in pages of `page_size` formats, so thousands of labels never sit in one
+/// write. Pages go to the pool's printers in turn. A printer whose send fails, even after its
+/// own reconnects, is dropped from the pool and the page is sent to the next printer. With
+/// `check_status` a ZPL printer is asked for its `~HS` status before each page, a printer
+/// that is not ready is asked again `not_ready_retries` times `retry_delay` apart before it is
+/// dropped, so a full buffer or a quick paper reload does not fail the job.
+///
+/// # Example
+/// ```no_run
+/// use voicecode::HashVoiceCode;
+/// use voicecode::label::zpl::{ render, ZplOptions };
+/// use voicecode::print::{ Job, JobEvent, RawSocketPrinter };
+///
+/// let mut job = Job::new().page_size(100).check_status(true);
+/// for lot in ["LOT1", "LOT2"] {
+///     let voice_code = HashVoiceCode::new("12345678901244", lot, "03", "01", "02").unwrap();
+///     job.push(render(&voice_code, &ZplOptions::default()), 2500);
+/// }
+/// let mut pool = [RawSocketPrinter::new("10.0.0.5"), RawSocketPrinter::new("10.0.0.6")];
+/// job.run(&mut pool, |event| {
+///     if let JobEvent::PageSent { labels_sent, labels, .. } = event {
+///         println!("{}/{}", labels_sent, labels);
+///     }
+/// })?;
+/// # Ok::<(), voicecode::print::JobError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Job {
+    /// Label with its copies applied and the number of labels it prints
+    formats: Vec<(String, usize)>,
+    page_size: usize,
+    check_status: bool,
+    not_ready_retries: u32,
+    retry_delay: Duration,
+}
+
+impl Default for Job {
+    fn default() -> Self {
+        Job {
+            formats: Vec::new(),
+            page_size: 50,
+            check_status: false,
+            not_ready_retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Progress reported while a job runs
+#[derive(Debug)]
+pub enum JobEvent<'a> {
+    /// `printer` accepted page `page`, counted from 0
+    PageSent { page: usize, printer: &'a str, labels_sent: usize, labels: usize },
+    /// `printer` reported it is not ready and is asked again after the retry delay
+    NotReady { printer: &'a str, error: &'a PrintError },
+    /// `printer` failed and gets no more pages
+    PrinterDropped { printer: &'a str, error: &'a PrintError },
+}
+
+/// Outcome of a completed job
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobSummary {
+    pub pages: usize,
+    /// Labels printed, copies included
+    pub labels: usize,
+    /// Pages each printer of the pool accepted, in pool order
+    pub pages_per_printer: Vec<usize>,
+}
+
+/// Every printer of the pool failed
+#[derive(Debug)]
+pub struct JobError {
+    /// Pages sent before the failure, resend from this page to finish the job
+    pub pages_sent: usize,
+    /// Error of the last printer dropped
+    pub error: PrintError,
+}
+
+impl std::fmt::Display for JobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "every printer failed after {} pages: {}", self.pages_sent, self.error)
+    }
+}
+
+impl std::error::Error for JobError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Job {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Formats per page, 50 by default
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Ask ZPL printers for their status before each page, off by default
+    pub fn check_status(mut self, check_status: bool) -> Self {
+        self.check_status = check_status;
+        self
+    }
+
+    /// Status checks before dropping a printer that is not ready, 3 by default
+    pub fn not_ready_retries(mut self, retries: u32) -> Self {
+        self.not_ready_retries = retries;
+        self
+    }
+
+    /// Pause between status checks of a printer that is not ready, 1 second by default
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Add a complete ZPL or EPL label printed `copies` times
+    ///
+    /// The copy count goes into the label's `^PQ` or `P` command when it has the usual
+    /// ending, otherwise the label is repeated.
+    pub fn push(&mut self, label: impl Into<String>, copies: usize) {
+        if copies > 0 {
+            self.formats.push((with_copies(label.into(), copies), copies));
+        }
+    }
+
+    /// Labels the job prints, copies included
+    pub fn labels(&self) -> usize {
+        self.formats.iter().map(|(_, copies)| copies).sum()
+    }
+
+    /// Pages the job is sent in
+    pub fn pages(&self) -> usize {
+        self.formats.len().div_ceil(self.page_size)
+    }
+
+    /// Send every page, handing progress to `on_event`
+    pub fn run<P: Printer>(
+        &self,
+        printers: &mut [P],
+        mut on_event: impl FnMut(&JobEvent),
+    ) -> Result<JobSummary, JobError> {
+        let _span = span!("print job", pages = self.pages(), labels = self.labels());
+        let labels = self.labels();
+        let mut alive = vec![true; printers.len()];
+        let mut pages_per_printer = vec![0; printers.len()];
+        let mut labels_sent = 0;
+        let mut next = 0;
+        let mut last_error = None;
+
+        for (page, formats) in self.formats.chunks(self.page_size).enumerate() {
+            let data: String = formats.iter().map(|(format, _)| format.as_str()).collect();
+            loop {
+                let Some(printer) = (0..printers.len()).map(|i| (next + i) % printers.len()).find(|&i| alive[i]) else {
+                    let error = last_error.unwrap_or_else(|| PrintError::Io(std::io::ErrorKind::NotFound.into()));
+                    return Err(JobError { pages_sent: page, error });
+                };
+                next = printer + 1;
+                match self.send_page(&mut printers[printer], data.as_bytes(), &mut on_event) {
+                    Ok(()) => {
+                        labels_sent += formats.iter().map(|(_, copies)| copies).sum::<usize>();
+                        pages_per_printer[printer] += 1;
+                        let address = printers[printer].address();
+                        on_event(&JobEvent::PageSent { page, printer: address, labels_sent, labels });
+                        break;
+                    }
+                    Err(error) => {
+                        alive[printer] = false;
+                        event!(warn, "printer dropped from job", printer = printers[printer].address(), error = error);
+                        on_event(&JobEvent::PrinterDropped { printer: printers[printer].address(), error: &error });
+                        last_error = Some(error);
+                    }
+                }
+            }
+        }
+        Ok(JobSummary { pages: self.pages(), labels, pages_per_printer })
+    }
+
+    fn send_page<P: Printer>(
+        &self,
+        printer: &mut P,
+        page: &[u8],
+        on_event: &mut impl FnMut(&JobEvent),
+    ) -> Result<(), PrintError> {
+        if self.check_status {
+            let mut attempt = 0;
+            loop {
+                let status = printer.status()?;
+                if status.is_ready() {
+                    break;
+                }
+                let error = PrintError::NotReady(status);
+                if attempt == self.not_ready_retries {
+                    return Err(error);
+                }
+                on_event(&JobEvent::NotReady { printer: printer.address(), error: &error });
+                attempt += 1;
+                thread::sleep(self.retry_delay);
+            }
+        }
+        printer.send(page)
+    }
+}
+
+/// The label with its print quantity set to `copies`
+fn with_copies(label: String, copies: usize) -> String {
+    if copies == 1 {
+        return label;
+    }
+    let body = label.trim_end();
+    // ZPL, ^PQ before the final ^XZ unless the label sets its own quantity
+    if let Some(start) = body.strip_suffix("^XZ") {
+        if !start.contains("^PQ") {
+            return format!("{}^PQ{}\n^XZ\n", start, copies);
+        }
+    }
+    // EPL, the final P1 prints one label
+    if let Some(start) = body.strip_suffix("\nP1") {
+        return format!("{}\nP{}\n", start, copies);
+    }
+    label.repeat(copies)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::print::PrinterStatus;
+
+    #[derive(Default)]
+    struct FakePrinter {
+        address: String,
+        pages: Vec<String>,
+        /// Sends accepted before every send fails
+        fail_after: Option<usize>,
+        statuses: VecDeque<PrinterStatus>,
+    }
+
+    impl FakePrinter {
+        fn new(address: &str) -> Self {
+            FakePrinter { address: address.to_string(), ..Default::default() }
+        }
+    }
+
+    impl Printer for FakePrinter {
+        fn address(&self) -> &str {
+            &self.address
+        }
+
+        fn send(&mut self, job: &[u8]) -> Result<(), PrintError> {
+            if self.fail_after.is_some_and(|fail_after| self.pages.len() >= fail_after) {
+                return Err(PrintError::Io(std::io::ErrorKind::ConnectionReset.into()));
+            }
+            self.pages.push(String::from_utf8(job.to_vec()).unwrap());
+            Ok(())
+        }
+
+        fn status(&mut self) -> Result<PrinterStatus, PrintError> {
+            Ok(self.statuses.pop_front().unwrap_or_default())
+        }
+    }
+
+    fn job(labels: usize) -> Job {
+        let mut job = Job::new().page_size(2).retry_delay(Duration::ZERO);
+        for i in 0..labels {
+            job.push(format!("^XA^FD{}^FS^XZ\n", i), 1);
+        }
+        job
+    }
+
+    #[test]
+    fn test_copies() {
+        assert_eq!(with_copies("^XA\n^FDA^FS\n^XZ\n".to_string(), 3), "^XA\n^FDA^FS\n^PQ3\n^XZ\n");
+        assert_eq!(with_copies("^XA^PQ2^XZ".to_string(), 2), "^XA^PQ2^XZ^XA^PQ2^XZ");
+        assert_eq!(with_copies("\nN\nA1,1,0,1,1,1,N,\"A\"\nP1\n".to_string(), 12), "\nN\nA1,1,0,1,1,1,N,\"A\"\nP12\n");
+        assert_eq!(with_copies("raw".to_string(), 2), "rawraw");
+
+        let mut job = Job::new();
+        job.push("^XA^XZ", 5000);
+        job.push("^XA^XZ", 0);
+        assert_eq!((job.labels(), job.pages()), (5000, 1));
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut pool = [FakePrinter::new("a"), FakePrinter::new("b")];
+        let mut progress = Vec::new();
+        let summary = job(5)
+            .run(&mut pool, |event| {
+                if let JobEvent::PageSent { printer, labels_sent, .. } = event {
+                    progress.push(format!("{}:{}", printer, labels_sent));
+                }
+            })
+            .unwrap();
+        assert_eq!(summary, JobSummary { pages: 3, labels: 5, pages_per_printer: vec![2, 1] });
+        assert_eq!(progress, ["a:2", "b:4", "a:5"]);
+        assert_eq!(pool[0].pages, ["^XA^FD0^FS^XZ\n^XA^FD1^FS^XZ\n", "^XA^FD4^FS^XZ\n"]);
+    }
+
+    #[test]
+    fn test_failover() {
+        let mut pool = [FakePrinter { fail_after: Some(1), ..FakePrinter::new("a") }, FakePrinter::new("b")];
+        let mut dropped = Vec::new();
+        let summary = job(6)
+            .run(&mut pool, |event| {
+                if let JobEvent::PrinterDropped { printer, .. } = event {
+                    dropped.push(printer.to_string());
+                }
+            })
+            .unwrap();
+        assert_eq!(summary.pages_per_printer, [1, 2]);
+        assert_eq!(dropped, ["a"]);
+        // the page a failed on went to b
+        assert_eq!(pool[1].pages[1], "^XA^FD4^FS^XZ\n^XA^FD5^FS^XZ\n");
+    }
+
+    #[test]
+    fn test_not_ready() {
+        let paper_out = PrinterStatus { paper_out: true, ..Default::default() };
+        let mut pool = [FakePrinter { statuses: VecDeque::from([paper_out, paper_out]), ..FakePrinter::new("a") }];
+        let mut retries = 0;
+        let job = job(2).check_status(true);
+        job.run(&mut pool, |event| retries += matches!(event, JobEvent::NotReady { .. }) as usize).unwrap();
+        assert_eq!(retries, 2);
+
+        pool[0].statuses = VecDeque::from([paper_out; 4]);
+        let error = job.run(&mut pool, |_| {}).unwrap_err();
+        assert_eq!(error.pages_sent, 0);
+        assert_eq!(error.to_string(), "every printer failed after 0 pages: printer is not ready: paper out");
+    }
+
+    #[test]
+    fn test_all_printers_fail() {
+        let mut pool = [FakePrinter { fail_after: Some(1), ..FakePrinter::new("a") }];
+        let error = job(5).run(&mut pool, |_| {}).unwrap_err();
+        assert_eq!(error.pages_sent, 1);
+        assert!(matches!(error.error, PrintError::Io(_)));
+        assert_eq!(job(1).run(&mut [] as &mut [FakePrinter], |_| {}).unwrap_err().pages_sent, 0);
+    }
+}
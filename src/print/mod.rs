@@ -0,0 +1,350 @@
+//! Raw socket printing to networked label printers, the JetDirect style port 9100
+//!
+//! [`Job`] sends many labels across a pool of printers.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+mod job;
+pub use job::{Job, JobError, JobEvent, JobSummary};
+
+/// Raw print port used by Zebra and most network label printers
+pub const DEFAULT_PORT: u16 = 9100;
+
+/// ZPL host status request
+const HOST_STATUS: &[u8] = b"~HS";
+
+/// Errors from talking to a printer
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PrintError {
+    Io(io::Error),
+    /// The `~HS` reply did not have the expected fields
+    MalformedStatus(String),
+    /// The printer reported a condition that stops it printing
+    NotReady(PrinterStatus),
+}
+
+impl fmt::Display for PrintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrintError::Io(e) => write!(f, "{}", e),
+            PrintError::MalformedStatus(reply) => write!(f, "printer status reply is malformed: {:?}", reply),
+            PrintError::NotReady(status) => write!(f, "printer is not ready: {}", status.faults().join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for PrintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PrintError::Io(e) => Some(e),
+            PrintError::MalformedStatus(_) | PrintError::NotReady(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for PrintError {
+    fn from(value: io::Error) -> Self {
+        PrintError::Io(value)
+    }
+}
+
+/// Printer state decoded from the ZPL `~HS` host status reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PrinterStatus {
+    pub paper_out: bool,
+    pub paused: bool,
+    pub head_open: bool,
+    pub ribbon_out: bool,
+    pub buffer_full: bool,
+    pub under_temperature: bool,
+    pub over_temperature: bool,
+    /// Formats received but not yet printed
+    pub formats_in_buffer: u32,
+    /// Labels left in the current batch
+    pub labels_remaining: u32,
+}
+
+impl PrinterStatus {
+    /// True when nothing stops the printer from printing
+    pub fn is_ready(&self) -> bool {
+        !(self.paper_out
+            || self.paused
+            || self.head_open
+            || self.ribbon_out
+            || self.buffer_full
+            || self.under_temperature
+            || self.over_temperature)
+    }
+
+    /// Every condition stopping the printer, such as `paper out`
+    pub fn faults(&self) -> Vec<&'static str> {
+        [
+            (self.paper_out, "paper out"),
+            (self.paused, "paused"),
+            (self.head_open, "head open"),
+            (self.ribbon_out, "ribbon out"),
+            (self.buffer_full, "buffer full"),
+            (self.under_temperature, "under temperature"),
+            (self.over_temperature, "over temperature"),
+        ]
+        .into_iter()
+        .filter_map(|(fault, name)| fault.then_some(name))
+        .collect()
+    }
+
+    /// Parse the three STX/ETX framed lines of a `~HS` reply
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::print::PrinterStatus;
+    /// let reply = "\x02030,1,0,1245,000,0,0,0,000,0,0,0\x03\r\n\
+    ///              \x02001,0,0,0,1,2,6,0,00000000,1,000\x03\r\n\
+    ///              \x021234,0\x03\r\n";
+    /// let status = PrinterStatus::parse(reply).unwrap();
+    /// assert!(status.paper_out);
+    /// assert!(!status.is_ready());
+    /// ```
+    pub fn parse(reply: &str) -> Result<Self, PrintError> {
+        let malformed = || PrintError::MalformedStatus(reply.to_string());
+        let mut lines = reply
+            .split('\x02')
+            .map(|line| line.trim_end_matches(['\x03', '\r', '\n']))
+            .filter(|line| !line.is_empty());
+        let first: Vec<&str> = lines.next().ok_or_else(malformed)?.split(',').collect();
+        let second: Vec<&str> = lines.next().ok_or_else(malformed)?.split(',').collect();
+        if first.len() < 12 || second.len() < 9 {
+            return Err(malformed());
+        }
+
+        let flag = |field: &str| field.trim() == "1";
+        let number = |field: &str| field.trim().parse::<u32>().map_err(|_| malformed());
+        Ok(PrinterStatus {
+            paper_out: flag(first[1]),
+            paused: flag(first[2]),
+            formats_in_buffer: number(first[4])?,
+            buffer_full: flag(first[5]),
+            under_temperature: flag(first[10]),
+            over_temperature: flag(first[11]),
+            head_open: flag(second[2]),
+            ribbon_out: flag(second[3]),
+            labels_remaining: number(second[8])?,
+        })
+    }
+}
+
+/// Anything a [`Job`] can print to
+pub trait Printer {
+    /// Name used in job events
+    fn address(&self) -> &str;
+
+    /// Send label data exactly as given
+    fn send(&mut self, job: &[u8]) -> Result<(), PrintError>;
+
+    /// Current printer state, only ZPL printers answer
+    fn status(&mut self) -> Result<PrinterStatus, PrintError>;
+}
+
+impl Printer for RawSocketPrinter {
+    fn address(&self) -> &str {
+        RawSocketPrinter::address(self)
+    }
+
+    fn send(&mut self, job: &[u8]) -> Result<(), PrintError> {
+        RawSocketPrinter::send(self, job)
+    }
+
+    fn status(&mut self) -> Result<PrinterStatus, PrintError> {
+        RawSocketPrinter::status(self)
+    }
+}
+
+/// A printer reached over a raw TCP socket
+///
+/// The connection is opened on first use and kept open, a failed send drops it, reconnects
+/// and resends up to `retries` times.
+///
+/// # Example
+/// ```no_run
+/// use voicecode::{ HashVoiceCode };
+/// use voicecode::label::zpl::{ render, ZplOptions };
+/// use voicecode::print::RawSocketPrinter;
+/// use std::time::Duration;
+///
+/// let voice_code = HashVoiceCode::new("12345678901244", "LOT123", "03", "01", "02").unwrap();
+/// let mut printer = RawSocketPrinter::new("10.0.0.5").timeout(Duration::from_secs(2));
+/// if printer.status()?.is_ready() {
+///     printer.send(render(&voice_code, &ZplOptions::default()).as_bytes())?;
+/// }
+/// # Ok::<(), voicecode::print::PrintError>(())
+/// ```
+#[derive(Debug)]
+pub struct RawSocketPrinter {
+    address: String,
+    timeout: Duration,
+    retries: u32,
+    retry_delay: Duration,
+    stream: Option<TcpStream>,
+}
+
+impl RawSocketPrinter {
+    /// Printer at `host` or `host:port`, the port defaults to `DEFAULT_PORT`
+    pub fn new(address: &str) -> Self {
+        // IPv6 addresses need brackets, [::1]:9100
+        let has_port = address
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()));
+        let address = if has_port { address.to_string() } else { format!("{}:{}", address, DEFAULT_PORT) };
+        RawSocketPrinter {
+            address,
+            timeout: Duration::from_secs(5),
+            retries: 2,
+            retry_delay: Duration::from_millis(250),
+            stream: None,
+        }
+    }
+
+    /// Connect, read and write timeout, 5 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Reconnect and resend this many times after a failed send, 2 by default
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Pause before each reconnect, 250 ms by default
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// `host:port` being printed to
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Send a complete job, ZPL or EPL, exactly as given
+    pub fn send(&mut self, job: &[u8]) -> Result<(), PrintError> {
+        let mut attempt = 0;
+        loop {
+            match self.try_send(job) {
+                Ok(()) => {
+                    event!(info, "print job sent", printer = self.address, bytes = job.len());
+                    return Ok(());
+                }
+                Err(e) if attempt < self.retries => {
+                    event!(warn, "print job failed, retrying", printer = self.address, attempt = attempt + 1, error = e);
+                    self.stream = None;
+                    attempt += 1;
+                    thread::sleep(self.retry_delay);
+                }
+                Err(e) => {
+                    event!(error, "print job failed", printer = self.address, error = e);
+                    #[cfg(feature = "metrics")]
+                    crate::metrics::print_error(&self.address);
+                    self.stream = None;
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+
+    /// Ask a ZPL printer for its host status, EPL printers do not answer
+    pub fn status(&mut self) -> Result<PrinterStatus, PrintError> {
+        self.send(HOST_STATUS)?;
+        let stream = self.stream.as_ref().expect("send leaves the connection open");
+        let mut reader = BufReader::new(stream);
+        let mut reply = String::new();
+        for _ in 0..3 {
+            if let Err(e) = reader.read_line(&mut reply) {
+                self.stream = None;
+                return Err(e.into());
+            }
+        }
+        PrinterStatus::parse(&reply)
+    }
+
+    /// Drop the connection, the next send reconnects
+    pub fn close(&mut self) {
+        self.stream = None;
+    }
+
+    fn try_send(&mut self, job: &[u8]) -> io::Result<()> {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => self.stream.insert(self.connect()?),
+        };
+        stream.write_all(job)?;
+        stream.flush()
+    }
+
+    fn connect(&self) -> io::Result<TcpStream> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, format!("{} did not resolve", self.address));
+        for addr in self.address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_address() {
+        assert_eq!(RawSocketPrinter::new("10.0.0.5").address(), "10.0.0.5:9100");
+        assert_eq!(RawSocketPrinter::new("printer.local:6101").address(), "printer.local:6101");
+    }
+
+    #[test]
+    fn test_send_and_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut received = vec![0u8; 9];
+            stream.read_exact(&mut received).unwrap();
+            let mut request = [0u8; 3];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(&request, HOST_STATUS);
+            stream
+                .write_all(b"\x02030,0,0,1245,002,0,0,0,000,0,0,0\x03\r\n\x02001,0,0,0,1,2,6,0,00000003,1,000\x03\r\n\x021234,0\x03\r\n")
+                .unwrap();
+            received
+        });
+
+        let mut printer = RawSocketPrinter::new(&format!("127.0.0.1:{}", port));
+        printer.send(b"^XA^FS^XZ").unwrap();
+        let status = printer.status().unwrap();
+        assert!(status.is_ready());
+        assert_eq!(status.formats_in_buffer, 2);
+        assert_eq!(status.labels_remaining, 3);
+        assert_eq!(server.join().unwrap(), b"^XA^FS^XZ");
+    }
+
+    #[test]
+    fn test_connect_failure() {
+        // bind then drop to find a port nothing listens on
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut printer = RawSocketPrinter::new(&format!("127.0.0.1:{}", port)).retries(1).retry_delay(Duration::ZERO);
+        assert!(matches!(printer.send(b"^XA^XZ"), Err(PrintError::Io(_))));
+    }
+}
@@ -0,0 +1,160 @@
+//! Python bindings, build a wheel with `maturin build --release`, `pyproject.toml` enables this feature
+//!
+//! ```python
+//! import datetime
+//! import voicecode
+//!
+//! code = voicecode.compute("12345678901244", "LOT123", "030102")
+//! code.voice_code  # '6991'
+//! voicecode.compute("12345678901244", "LOT123", datetime.date(2003, 1, 2)).voice_code  # '6991'
+//!
+//! voicecode.validate_lot("LOT 123")  # raises voicecode.VoiceCodeError
+//! ```
+
+use chrono::NaiveDate;
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::gtin::Gtin;
+use crate::lot::Lot;
+use crate::pack_date::PackDate;
+use crate::voicecode::HashVoiceCode;
+
+create_exception!(voicecode, VoiceCodeError, PyValueError, "Invalid GTIN, LOT or pack date");
+
+impl From<crate::error::VoiceCodeError> for PyErr {
+    fn from(value: crate::error::VoiceCodeError) -> Self {
+        VoiceCodeError::new_err(value.to_string())
+    }
+}
+
+/// Pack date as a `datetime.date` or a YYMMDD / YYYY-MM-DD string
+#[derive(FromPyObject)]
+enum DateArg {
+    Date(NaiveDate),
+    Text(String),
+}
+
+impl TryFrom<DateArg> for PackDate {
+    type Error = crate::error::VoiceCodeError;
+
+    fn try_from(value: DateArg) -> Result<Self, Self::Error> {
+        match value {
+            DateArg::Date(date) => Ok(PackDate::from_naive(date)),
+            DateArg::Text(text) => text.parse(),
+        }
+    }
+}
+
+/// Voice code and the inputs it was computed from
+#[pyclass(name = "HashVoiceCode", module = "voicecode", frozen)]
+pub struct PyHashVoiceCode {
+    voice_code: HashVoiceCode,
+}
+
+#[pymethods]
+impl PyHashVoiceCode {
+    /// Same as `HashVoiceCode::new`, date components as strings of 1 or 2 digits
+    #[new]
+    fn new(gtin: &str, lot: &str, yy: &str, mm: &str, dd: &str) -> PyResult<Self> {
+        Ok(HashVoiceCode::new(gtin, lot, yy, mm, dd)?.into())
+    }
+
+    #[getter]
+    fn gtin(&self) -> &str {
+        self.voice_code.gtin.as_str()
+    }
+
+    #[getter]
+    fn lot(&self) -> &str {
+        self.voice_code.lot.as_str()
+    }
+
+    /// Zero padded YYMMDD
+    #[getter]
+    fn pack_date(&self) -> String {
+        self.voice_code.pack_date().yymmdd()
+    }
+
+    #[getter]
+    fn hash_text(&self) -> &str {
+        &self.voice_code.hash_text
+    }
+
+    #[getter]
+    fn voice_code(&self) -> &str {
+        &self.voice_code.voice_code
+    }
+
+    #[getter]
+    fn voice_code_major(&self) -> &str {
+        &self.voice_code.voice_code_major
+    }
+
+    #[getter]
+    fn voice_code_minor(&self) -> &str {
+        &self.voice_code.voice_code_minor
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HashVoiceCode(gtin={:?}, lot={:?}, pack_date={:?}, voice_code={:?})",
+            self.voice_code.gtin.as_str(),
+            self.voice_code.lot.as_str(),
+            self.voice_code.pack_date().yymmdd(),
+            self.voice_code.voice_code
+        )
+    }
+}
+
+impl From<HashVoiceCode> for PyHashVoiceCode {
+    fn from(voice_code: HashVoiceCode) -> Self {
+        PyHashVoiceCode { voice_code }
+    }
+}
+
+/// Compute the voice code for a GTIN, LOT and pack date
+#[pyfunction]
+fn compute(gtin: &str, lot: &str, date: DateArg) -> PyResult<PyHashVoiceCode> {
+    let pack_date = PackDate::try_from(date)?;
+    Ok(HashVoiceCode::builder().gtin(gtin).lot(lot).pack_date(pack_date).build()?.into())
+}
+
+/// Raise `VoiceCodeError` unless `gtin` is 8, 12, 13 or 14 digits, optionally with a valid check digit
+#[pyfunction]
+#[pyo3(signature = (gtin, check_digit = false))]
+fn validate_gtin(gtin: &str, check_digit: bool) -> PyResult<()> {
+    if check_digit {
+        Gtin::parse(gtin)?;
+    } else {
+        Gtin::parse_unchecked(gtin)?;
+    }
+    Ok(())
+}
+
+/// Raise `VoiceCodeError` unless `lot` is 1 to 20 characters from the PTI set
+#[pyfunction]
+fn validate_lot(lot: &str) -> PyResult<()> {
+    Lot::parse(lot)?;
+    Ok(())
+}
+
+/// Raise `VoiceCodeError` unless `date` is a date or a YYMMDD / YYYY-MM-DD string
+#[pyfunction]
+fn validate_date(date: DateArg) -> PyResult<()> {
+    PackDate::try_from(date)?;
+    Ok(())
+}
+
+/// Voice code calculator for Produce Traceability Initiative labels
+#[pymodule]
+fn voicecode(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("VoiceCodeError", m.py().get_type::<VoiceCodeError>())?;
+    m.add_class::<PyHashVoiceCode>()?;
+    m.add_function(wrap_pyfunction!(compute, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_gtin, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_lot, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_date, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! Cleanup of raw scanner output before GS1 parsing
+//!
+//! Scanners configured to transmit AIM symbology identifiers prefix the data with
+//! `]` plus a symbology character and a modifier, `]C1` for GS1-128 or `]d2` for GS1
+//! DataMatrix, and send FNC1 as ASCII 29 (GS).
+//!
+//! Keyboard wedge scanners type the barcode as keystrokes, often with a configured prefix
+//! and suffix around it, and some cannot type GS so they send a substitute such as `~`.
+//! [`WedgeConfig`] undoes that before parsing.
+
+use std::fmt;
+
+use crate::error::VoiceCodeError;
+use crate::gs1::{Gs1ElementString, GS};
+
+/// AIM symbology identifier found at the start of a scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symbology {
+    /// `]C1`
+    Gs1_128,
+    /// `]e0`
+    Gs1DataBar,
+    /// `]d2`
+    Gs1DataMatrix,
+    /// `]Q3`
+    Gs1QrCode,
+    /// Any other identifier, data is usually not a GS1 element string
+    Other { code: char, modifier: char },
+}
+
+impl Symbology {
+    fn from_chars(code: char, modifier: char) -> Self {
+        match (code, modifier) {
+            ('C', '1') => Symbology::Gs1_128,
+            ('e', '0') => Symbology::Gs1DataBar,
+            ('d', '2') => Symbology::Gs1DataMatrix,
+            ('Q', '3') => Symbology::Gs1QrCode,
+            (code, modifier) => Symbology::Other { code, modifier },
+        }
+    }
+
+    /// True for the identifiers that announce GS1 element string data
+    pub fn is_gs1(&self) -> bool {
+        !matches!(self, Symbology::Other { .. })
+    }
+}
+
+impl fmt::Display for Symbology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Symbology::Gs1_128 => write!(f, "]C1"),
+            Symbology::Gs1DataBar => write!(f, "]e0"),
+            Symbology::Gs1DataMatrix => write!(f, "]d2"),
+            Symbology::Gs1QrCode => write!(f, "]Q3"),
+            Symbology::Other { code, modifier } => write!(f, "]{}{}", code, modifier),
+        }
+    }
+}
+
+/// Scanner output with the symbology identifier split off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedScan {
+    /// Symbology identifier when the scanner sent one
+    pub symbology: Option<Symbology>,
+    /// Element string data, GS separators kept
+    pub data: String,
+}
+
+impl NormalizedScan {
+    /// Parse the data as a GS1 element string
+    pub fn parse(&self) -> Result<Gs1ElementString, VoiceCodeError> {
+        Gs1ElementString::parse(&self.data)
+    }
+}
+
+/// Strip the symbology identifier, line terminators and leading FNC1 from raw scanner output
+///
+/// # Example
+/// ```
+/// use voicecode::scan::{ normalize_scan, Symbology };
+/// let scan = normalize_scan("]C1\x1d0100614141007349\x1d1032ABCD\x1d13200102\r\n");
+/// assert_eq!(scan.symbology, Some(Symbology::Gs1_128));
+/// assert_eq!(scan.data, "0100614141007349\x1d1032ABCD\x1d13200102");
+/// ```
+pub fn normalize_scan(raw: &str) -> NormalizedScan {
+    let mut data = raw.trim_end_matches(['\r', '\n']);
+    let mut symbology = None;
+
+    let mut chars = data.chars();
+    if let (Some(']'), Some(code), Some(modifier)) = (chars.next(), chars.next(), chars.next()) {
+        if code.is_ascii_alphabetic() && modifier.is_ascii_alphanumeric() {
+            symbology = Some(Symbology::from_chars(code, modifier));
+            data = chars.as_str();
+        }
+    }
+
+    NormalizedScan { symbology, data: data.trim_start_matches(GS).to_string() }
+}
+
+/// Normalize raw scanner output and parse it as a GS1 element string
+///
+/// # Example
+/// ```
+/// use voicecode::scan::parse_scan;
+/// let scan = parse_scan("]d20100614141007349132001021032ABCD").unwrap();
+/// assert_eq!(scan.lot(), Some("32ABCD"));
+/// ```
+pub fn parse_scan(raw: &str) -> Result<Gs1ElementString, VoiceCodeError> {
+    normalize_scan(raw).parse()
+}
+
+/// What a keyboard wedge scanner types around the data and in place of GS
+///
+/// # Example
+/// ```
+/// use voicecode::scan::WedgeConfig;
+/// let wedge = WedgeConfig { prefix: "#".into(), gs_substitute: Some("~".into()), ..Default::default() };
+/// let scan = wedge.parse("#]C10110614141007346~1032abcd~13030102\r\n").unwrap();
+/// assert_eq!(scan.voice_code().unwrap().voice_code, "1089");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WedgeConfig {
+    /// Typed before every barcode
+    pub prefix: String,
+    /// Typed after every barcode, before the Enter
+    pub suffix: String,
+    /// Typed for GS, such as `~` or `<GS>`, when the scanner cannot type ASCII 29
+    pub gs_substitute: Option<String>,
+}
+
+impl WedgeConfig {
+    /// The scanner output with the prefix, suffix and line terminator removed and GS restored
+    pub fn decode(&self, typed: &str) -> String {
+        let typed = typed.trim_end_matches(['\r', '\n']);
+        let typed = typed.strip_prefix(self.prefix.as_str()).unwrap_or(typed);
+        let typed = typed.strip_suffix(self.suffix.as_str()).unwrap_or(typed);
+        match self.gs_substitute.as_deref() {
+            Some(substitute) if !substitute.is_empty() => typed.replace(substitute, &GS.to_string()),
+            _ => typed.to_string(),
+        }
+    }
+
+    /// Decode one line of scanner output and parse it as a GS1 element string
+    pub fn parse(&self, typed: &str) -> Result<Gs1ElementString, VoiceCodeError> {
+        parse_scan(&self.decode(typed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbologies() {
+        assert_eq!(normalize_scan("]e00100614141007349").symbology, Some(Symbology::Gs1DataBar));
+        assert_eq!(normalize_scan("]Q3010061414100734910A").symbology, Some(Symbology::Gs1QrCode));
+        let other = normalize_scan("]C0ABC");
+        assert_eq!(other.symbology, Some(Symbology::Other { code: 'C', modifier: '0' }));
+        assert!(!other.symbology.unwrap().is_gs1());
+        assert_eq!(other.data, "ABC");
+    }
+
+    #[test]
+    fn test_no_identifier() {
+        let scan = normalize_scan("(01)00614141007349(10)32ABCD\n");
+        assert_eq!(scan.symbology, None);
+        assert_eq!(scan.data, "(01)00614141007349(10)32ABCD");
+
+        let scan = normalize_scan("]");
+        assert_eq!(scan.symbology, None);
+        assert_eq!(scan.data, "]");
+    }
+
+    #[test]
+    fn test_parse_scan() {
+        let scan = parse_scan("]C10110614141007346\x1d1032abcd\x1d13030102\r").unwrap();
+        assert_eq!(scan.voice_code().unwrap().voice_code, "1089");
+    }
+
+    #[test]
+    fn test_wedge_decode() {
+        let wedge = WedgeConfig { prefix: "STX".into(), suffix: "ETX".into(), gs_substitute: Some("<GS>".into()) };
+        assert_eq!(wedge.decode("STX]C10100614141007349<GS>10A<GS>ETX\n"), "]C10100614141007349\x1d10A\x1d");
+        // missing prefix or suffix is left alone, an empty substitute replaces nothing
+        let wedge = WedgeConfig { prefix: "#".into(), gs_substitute: Some(String::new()), ..Default::default() };
+        assert_eq!(wedge.decode("0100614141007349\x1d10A"), "0100614141007349\x1d10A");
+    }
+}
@@ -0,0 +1,198 @@
+//! Brute force reverse lookup of LOTs that give a voice code
+//!
+//! Ten thousand codes means roughly one LOT in ten thousand matches any target, so a
+//! search yields a stream of candidates to narrow down with other evidence, not an answer.
+
+use std::collections::VecDeque;
+
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::hash::VoiceCodeHasher;
+use crate::lot::{is_lot_byte, Lot, LotError, MAX_LOT_LEN};
+use crate::pack_date::{DatePadding, PackDate};
+
+/// Digits only
+pub const DIGITS: &str = "0123456789";
+/// Digits and uppercase letters, the usual LOT alphabet
+pub const UPPER_ALPHANUMERIC: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Candidates hashed per step, each step runs across the rayon pool with the `rayon` feature
+const CHUNK: u64 = 1 << 16;
+
+/// Every LOT of 1 to `max_len` characters from `charset` whose voice code with `gtin` and
+/// `pack_date` is `target_code`, shortest first and then in `charset` order
+///
+/// Stops after `limit` matches. `max_len` is capped at `MAX_LOT_LEN`, the search space is
+/// `charset.len()` to the power `max_len` so keep both small.
+///
+/// # Example
+/// ```
+/// use voicecode::{ Gtin, HashVoiceCode, PackDate };
+/// use voicecode::search::{ find_lots, UPPER_ALPHANUMERIC };
+/// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+/// let pack_date = PackDate::from_parts("03", "01", "02").unwrap();
+/// let lots: Vec<_> = find_lots(&gtin, &pack_date, 6991, UPPER_ALPHANUMERIC, 3, 3).unwrap().collect();
+/// assert_eq!(lots.len(), 3);
+/// for lot in lots {
+///     let voice_code = HashVoiceCode::from_parts(gtin.clone(), lot, pack_date.clone());
+///     assert_eq!(voice_code.voice_code, "6991");
+/// }
+/// ```
+pub fn find_lots(
+    gtin: &Gtin,
+    pack_date: &PackDate,
+    target_code: u16,
+    charset: &str,
+    max_len: usize,
+    limit: usize,
+) -> Result<LotSearch, VoiceCodeError> {
+    if charset.is_empty() {
+        return Err(VoiceCodeError::InvalidLot { reason: LotError::Empty });
+    }
+    if let Some((offset, ch)) = charset.char_indices().find(|&(_, ch)| !ch.is_ascii() || !is_lot_byte(ch as u8)) {
+        return Err(VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch, offset } });
+    }
+
+    Ok(LotSearch {
+        prefix: VoiceCodeHasher::with_prefix(gtin),
+        suffix: pack_date.hash_text(DatePadding::default()).into_bytes(),
+        target_code,
+        charset: charset.as_bytes().to_vec(),
+        max_len: max_len.min(MAX_LOT_LEN),
+        len: 1,
+        next: 0,
+        remaining: limit,
+        found: VecDeque::new(),
+    })
+}
+
+/// Iterator returned by `find_lots`
+#[derive(Debug, Clone)]
+pub struct LotSearch {
+    prefix: VoiceCodeHasher,
+    suffix: Vec<u8>,
+    target_code: u16,
+    charset: Vec<u8>,
+    max_len: usize,
+    /// Length being searched
+    len: usize,
+    /// Next candidate index at `len`
+    next: u64,
+    remaining: usize,
+    found: VecDeque<Lot>,
+}
+
+impl LotSearch {
+    /// Number of LOTs of the current length, saturating for spaces too big to finish anyway
+    fn space(&self) -> u64 {
+        (self.charset.len() as u64).checked_pow(self.len as u32).unwrap_or(u64::MAX)
+    }
+
+    /// Hash candidates `start..end` of the current length and queue the matches in order
+    fn search_chunk(&mut self, start: u64, end: u64) {
+        let check = |index: u64| -> Option<Lot> {
+            let mut lot = [0u8; MAX_LOT_LEN];
+            let lot = candidate(&self.charset, self.len, index, &mut lot);
+            let mut hasher = self.prefix;
+            hasher.update(lot);
+            hasher.update(&self.suffix);
+            if hasher.finalize() == self.target_code {
+                Lot::parse(std::str::from_utf8(lot).expect("charset is ASCII")).ok()
+            } else {
+                None
+            }
+        };
+
+        #[cfg(feature = "rayon")]
+        let found: Vec<Lot> = {
+            use rayon::prelude::*;
+            (start..end).into_par_iter().filter_map(check).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let found: Vec<Lot> = (start..end).filter_map(check).collect();
+
+        self.found.extend(found);
+    }
+}
+
+impl Iterator for LotSearch {
+    type Item = Lot;
+
+    fn next(&mut self) -> Option<Lot> {
+        while self.remaining > 0 {
+            if let Some(lot) = self.found.pop_front() {
+                self.remaining -= 1;
+                return Some(lot);
+            }
+            if self.len > self.max_len {
+                return None;
+            }
+
+            let space = self.space();
+            let start = self.next;
+            let end = start.saturating_add(CHUNK).min(space);
+            self.search_chunk(start, end);
+            if end == space {
+                self.len += 1;
+                self.next = 0;
+            } else {
+                self.next = end;
+            }
+        }
+        None
+    }
+}
+
+/// Write the `index`th LOT of `len` characters into `buf`, the last character varies fastest
+fn candidate<'a>(charset: &[u8], len: usize, mut index: u64, buf: &'a mut [u8; MAX_LOT_LEN]) -> &'a [u8] {
+    let base = charset.len() as u64;
+    for slot in buf[..len].iter_mut().rev() {
+        *slot = charset[(index % base) as usize];
+        index /= base;
+    }
+    &buf[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voicecode::HashVoiceCode;
+
+    #[test]
+    fn test_matches_exhaustive() {
+        let gtin = Gtin::parse_unchecked("61414100734933").unwrap();
+        let pack_date = PackDate::from_parts("03", "01", "10").unwrap();
+        let target = HashVoiceCode::new(&gtin, "7Q", "03", "01", "10").unwrap().voice_code.parse().unwrap();
+
+        let mut expected = Vec::new();
+        for len in 1..=3u32 {
+            for index in 0..36u64.pow(len) {
+                let mut buf = [0u8; MAX_LOT_LEN];
+                let lot = std::str::from_utf8(candidate(UPPER_ALPHANUMERIC.as_bytes(), len as usize, index, &mut buf)).unwrap().to_string();
+                let voice_code = HashVoiceCode::new(&gtin, lot.as_str(), "03", "01", "10").unwrap();
+                if voice_code.voice_code.parse::<u16>().unwrap() == target {
+                    expected.push(lot);
+                }
+            }
+        }
+
+        let found: Vec<String> =
+            find_lots(&gtin, &pack_date, target, UPPER_ALPHANUMERIC, 3, usize::MAX).unwrap().map(|lot| lot.to_string()).collect();
+        assert!(found.contains(&"7Q".to_string()));
+        assert_eq!(found, expected);
+
+        let limited: Vec<Lot> = find_lots(&gtin, &pack_date, target, UPPER_ALPHANUMERIC, 3, 2).unwrap().collect();
+        assert_eq!(limited.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_charset() {
+        let gtin = Gtin::parse_unchecked("61414100734933").unwrap();
+        let pack_date = PackDate::from_parts("03", "01", "10").unwrap();
+        assert_eq!(
+            find_lots(&gtin, &pack_date, 1, "AB C", 3, 1).unwrap_err(),
+            VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: ' ', offset: 2 } }
+        );
+        assert!(find_lots(&gtin, &pack_date, 1, "", 3, 1).is_err());
+    }
+}
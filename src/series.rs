@@ -0,0 +1,82 @@
+//! Voice codes for a run of pack dates, for printing labels ahead and pre-loading pick systems
+
+use std::ops::RangeInclusive;
+
+use chrono::NaiveDate;
+
+use crate::gtin::Gtin;
+use crate::lot::Lot;
+use crate::pack_date::PackDate;
+use crate::voicecode::HashVoiceCode;
+
+/// One voice code per day for a GTIN and LOT, in date order
+///
+/// # Example
+/// ```
+/// use voicecode::{ Gtin, Lot, NaiveDate };
+/// use voicecode::series::VoiceCodeSeries;
+/// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+/// let lot = Lot::parse("LOT123").unwrap();
+/// let start = NaiveDate::from_ymd_opt(2003, 1, 1).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2003, 1, 7).unwrap();
+///
+/// let week: Vec<_> = VoiceCodeSeries::new(gtin, lot, start..=end).collect();
+/// assert_eq!(week.len(), 7);
+/// assert_eq!(week[1].voice_code, "6991");
+/// ```
+#[derive(Debug, Clone)]
+pub struct VoiceCodeSeries {
+    gtin: Gtin,
+    lot: Lot,
+    /// Next date to yield, None once the series is exhausted
+    next: Option<NaiveDate>,
+    end: NaiveDate,
+}
+
+impl VoiceCodeSeries {
+    /// Series over every day in `dates`, empty when the range is
+    pub fn new(gtin: Gtin, lot: Lot, dates: RangeInclusive<NaiveDate>) -> Self {
+        let (start, end) = dates.into_inner();
+        VoiceCodeSeries { gtin, lot, next: (start <= end).then_some(start), end }
+    }
+}
+
+impl Iterator for VoiceCodeSeries {
+    type Item = HashVoiceCode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date = self.next?;
+        self.next = date.succ_opt().filter(|next| *next <= self.end);
+        Some(HashVoiceCode::from_parts(self.gtin.clone(), self.lot.clone(), PackDate::from_naive(date)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.next.map_or(0, |next| (self.end - next).num_days() as usize + 1);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for VoiceCodeSeries {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_series() {
+        let gtin = Gtin::parse_unchecked("61414100734933").unwrap();
+        let lot = Lot::parse("32abcd").unwrap();
+
+        let series = VoiceCodeSeries::new(gtin.clone(), lot.clone(), date(2023, 12, 30)..=date(2024, 1, 2));
+        assert_eq!(series.len(), 4);
+        let dates: Vec<String> = series.map(|voice_code| voice_code.pack_date().yymmdd()).collect();
+        assert_eq!(dates, ["231230", "231231", "240101", "240102"]);
+
+        assert_eq!(VoiceCodeSeries::new(gtin.clone(), lot.clone(), date(2024, 1, 2)..=date(2024, 1, 1)).count(), 0);
+        assert_eq!(VoiceCodeSeries::new(gtin, lot, NaiveDate::MAX..=NaiveDate::MAX).count(), 1);
+    }
+}
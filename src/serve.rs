@@ -0,0 +1,220 @@
+//! HTTP service for teams that can't link Rust, run it as a sidecar with `voicecode serve`
+//!
+//! - `POST /voicecode` takes `{"gtin":…,"lot":…,"pack_date":…}` and answers with the voice
+//!   code as serialized by the `serde` feature, or `422` and `{"error":…}`
+//! - `POST /voicecode/batch` takes an array of the same records and answers with an array
+//!   in the same order, failed records become `{"error":…}` in place
+//! - `GET /healthz` answers `200 ok`
+//! - `GET /openapi.json` answers with the OpenAPI 3 document for the routes above, also
+//!   printed by `voicecode openapi` for generating client SDKs
+//! - `GET /metrics` answers with the Prometheus metrics, with the `metrics` feature
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+//! voicecode::serve::serve(listener).await
+//! # }
+//! ```
+
+use std::io;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use tokio::net::TcpListener;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::RefOr;
+use utoipa::{OpenApi, PartialSchema, ToSchema};
+
+use crate::batch::{compute_all, BatchRecord};
+use crate::error::VoiceCodeError;
+use crate::voicecode::HashVoiceCode;
+
+/// Body of every failed request and of failed records in a batch
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[schema(as = Error)]
+pub struct ErrorBody {
+    /// Validation message
+    pub error: String,
+}
+
+impl From<VoiceCodeError> for ErrorBody {
+    fn from(value: VoiceCodeError) -> Self {
+        ErrorBody { error: value.to_string() }
+    }
+}
+
+/// One entry of a batch response, in the same position as its record
+#[derive(Clone, Debug, serde::Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum BatchResult {
+    VoiceCode(Box<HashVoiceCode>),
+    Error(ErrorBody),
+}
+
+impl From<Result<HashVoiceCode, VoiceCodeError>> for BatchResult {
+    fn from(value: Result<HashVoiceCode, VoiceCodeError>) -> Self {
+        match value {
+            Ok(voice_code) => BatchResult::VoiceCode(Box::new(voice_code)),
+            Err(e) => BatchResult::Error(e.into()),
+        }
+    }
+}
+
+/// Matches the hand written `Serialize` impl
+impl PartialSchema for HashVoiceCode {
+    fn schema() -> RefOr<Schema> {
+        let field = |description: &str| ObjectBuilder::new().schema_type(Type::String).description(Some(description));
+        ObjectBuilder::new()
+            .property("gtin", field("GTIN as provided"))
+            .property("lot", field("LOT as provided"))
+            .property("pack_date", field("YYMMDD"))
+            .property("voice_code", field("Four digit voice code"))
+            .property("major", field("Last two digits, printed large"))
+            .property("minor", field("First two digits, printed small"))
+            .required("gtin")
+            .required("lot")
+            .required("pack_date")
+            .required("voice_code")
+            .required("major")
+            .required("minor")
+            .into()
+    }
+}
+
+impl ToSchema for HashVoiceCode {
+    fn name() -> std::borrow::Cow<'static, str> {
+        "VoiceCode".into()
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(info(title = "voicecode", description = "Voice code calculator for Produce Traceability Initiative labels"), paths(voice_code, batch, healthz))]
+struct ApiDoc;
+
+/// OpenAPI 3 document describing `router`
+///
+/// # Example
+/// ```
+/// let json = voicecode::serve::openapi().to_pretty_json().unwrap();
+/// assert!(json.contains("/voicecode/batch"));
+/// ```
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+/// Routes for the service, for embedding in a larger axum application
+pub fn router() -> Router {
+    let router = Router::new()
+        .route("/voicecode", post(voice_code))
+        .route("/voicecode/batch", post(batch))
+        .route("/healthz", get(healthz))
+        .route("/openapi.json", get(|| async { Json(openapi()) }));
+    #[cfg(feature = "metrics")]
+    let router = router.route("/metrics", get(metrics));
+    router
+}
+
+/// Serve `router` on `listener` until the listener fails
+pub async fn serve(listener: TcpListener) -> io::Result<()> {
+    axum::serve(listener, router()).await
+}
+
+/// Compute one voice code
+#[utoipa::path(
+    post,
+    path = "/voicecode",
+    request_body = BatchRecord,
+    responses(
+        (status = 200, description = "Computed voice code", body = HashVoiceCode),
+        (status = 422, description = "Record failed validation", body = ErrorBody),
+    ),
+)]
+async fn voice_code(Json(record): Json<BatchRecord>) -> Response {
+    match record.compute() {
+        Ok(voice_code) => {
+            event!(info, "POST /voicecode", status = 200);
+            Json(voice_code).into_response()
+        }
+        Err(e) => {
+            event!(info, "POST /voicecode", status = 422, error = e);
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(ErrorBody::from(e))).into_response()
+        }
+    }
+}
+
+/// Compute many voice codes, results keep the request order
+#[utoipa::path(
+    post,
+    path = "/voicecode/batch",
+    request_body = Vec<BatchRecord>,
+    responses((status = 200, description = "One result per record", body = Vec<BatchResult>)),
+)]
+async fn batch(Json(records): Json<Vec<BatchRecord>>) -> Json<Vec<BatchResult>> {
+    let results = compute_all(records);
+    event!(info, "POST /voicecode/batch", records = results.len(), failed = results.iter().filter(|r| r.is_err()).count());
+    Json(results.into_iter().map(BatchResult::from).collect())
+}
+
+/// Liveness check
+#[utoipa::path(get, path = "/healthz", responses((status = 200, description = "Service is up", body = String)))]
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Prometheus scrape endpoint, left out of the OpenAPI document
+#[cfg(feature = "metrics")]
+async fn metrics() -> impl IntoResponse {
+    ([(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")], crate::metrics::render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_voice_code() {
+        let response = voice_code(Json(BatchRecord::new("12345678901244", "LOT123", "030102"))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = voice_code(Json(BatchRecord::new("12345678901244", "LOT 123", "030102"))).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_batch_keeps_order() {
+        let Json(results) = batch(Json(vec![
+            BatchRecord::new("12345678901244", "LOT123", "030102"),
+            BatchRecord::new("1234", "LOT123", "030102"),
+            BatchRecord::new("12345678901244", "LOT123", "2003-01-02"),
+        ]))
+        .await;
+        let results = serde_json::to_value(results).unwrap();
+        assert_eq!(results.as_array().unwrap().len(), 3);
+        assert_eq!(results[0]["voice_code"], "6991");
+        assert!(results[1]["error"].is_string());
+        assert_eq!(results[2]["voice_code"], "6991");
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_metrics() {
+        voice_code(Json(BatchRecord::new("1234", "LOT123", "030102"))).await;
+        let response = metrics().await.into_response();
+        assert_eq!(response.headers()["content-type"], "text/plain; version=0.0.4");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("voicecode_validation_failures_total{kind=\"gtin_format\"}"));
+    }
+
+    #[test]
+    fn test_openapi_schemas() {
+        let doc = serde_json::to_value(openapi()).unwrap();
+        let schemas = &doc["components"]["schemas"];
+        for name in ["BatchRecord", "VoiceCode", "Error", "BatchResult"] {
+            assert!(schemas[name].is_object(), "{} missing", name);
+        }
+        assert_eq!(doc["paths"]["/voicecode"]["post"]["responses"]["422"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Error");
+    }
+}
@@ -0,0 +1,205 @@
+//! Voice codes as words and SSML, for the prompts of voice picking systems
+//!
+//! Pickers read the code back digit by digit, so every prompt is built from the same four
+//! digits the label prints.
+
+use std::fmt::Write;
+
+use crate::voicecode::HashVoiceCode;
+
+/// Language the digits are spelled in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+impl Language {
+    /// Word for a single digit
+    ///
+    /// # Panics
+    /// When `digit` is greater than 9
+    pub fn digit(&self, digit: u8) -> &'static str {
+        const EN: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+        const ES: [&str; 10] = ["cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve"];
+        const FR: [&str; 10] = ["zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf"];
+        match self {
+            Language::En => EN[digit as usize],
+            Language::Es => ES[digit as usize],
+            Language::Fr => FR[digit as usize],
+        }
+    }
+}
+
+/// Words used for each digit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Vocabulary {
+    /// "zero" to "nine", or their equivalent in the chosen language
+    #[default]
+    Words,
+    /// ICAO radiotelephony digits, "tree", "fife" and "niner", harder to mishear over a headset
+    ///
+    /// ICAO digits are English on every channel, so they are used whatever the language.
+    Phonetic,
+}
+
+impl Vocabulary {
+    /// Word for a single digit
+    ///
+    /// # Panics
+    /// When `digit` is greater than 9
+    pub fn digit(&self, digit: u8) -> &'static str {
+        self.digit_in(Language::En, digit)
+    }
+
+    /// Word for a single digit in `language`
+    ///
+    /// # Panics
+    /// When `digit` is greater than 9
+    pub fn digit_in(&self, language: Language, digit: u8) -> &'static str {
+        const PHONETIC: [&str; 10] = ["zero", "wun", "too", "tree", "fower", "fife", "six", "seven", "ait", "niner"];
+        match self {
+            Vocabulary::Words => language.digit(digit),
+            Vocabulary::Phonetic => PHONETIC[digit as usize],
+        }
+    }
+}
+
+/// How the four digits are grouped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Grouping {
+    /// Minor pair then major pair, matching the small and large printed digits
+    #[default]
+    Pairs,
+    /// Four digits with no break
+    Digits,
+}
+
+/// Vocabulary, grouping and language for `spoken`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SpokenOptions {
+    pub vocabulary: Vocabulary,
+    pub grouping: Grouping,
+    pub language: Language,
+}
+
+/// The four digits as words, pairs separated by a comma
+///
+/// # Example
+/// ```
+/// use voicecode::speech::{ spoken, Grouping, Language, SpokenOptions, Vocabulary };
+/// assert_eq!(spoken([6, 9, 9, 1], &SpokenOptions::default()), "six nine, nine one");
+/// let options = SpokenOptions { vocabulary: Vocabulary::Phonetic, grouping: Grouping::Digits, ..SpokenOptions::default() };
+/// assert_eq!(spoken([6, 9, 9, 1], &options), "six niner niner wun");
+/// let options = SpokenOptions { language: Language::Es, ..SpokenOptions::default() };
+/// assert_eq!(spoken([6, 9, 9, 1], &options), "seis nueve, nueve uno");
+/// ```
+pub fn spoken(digits: [u8; 4], options: &SpokenOptions) -> String {
+    let [a, b, c, d] = digits.map(|digit| options.vocabulary.digit_in(options.language, digit));
+    match options.grouping {
+        Grouping::Pairs => format!("{} {}, {} {}", a, b, c, d),
+        Grouping::Digits => format!("{} {} {} {}", a, b, c, d),
+    }
+}
+
+/// Speaking rate, pause and wording for `to_ssml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SsmlOptions {
+    /// Speaking rate as a percentage of the engine's default, Polly accepts 20 and up
+    pub rate_percent: u32,
+    /// Pause between the minor and major pairs in milliseconds
+    pub pair_break_ms: u32,
+    /// Spell the digits out with this vocabulary instead of `say-as interpret-as="digits"`,
+    /// needed for phonetic digits the engine would not say on its own
+    pub vocabulary: Option<Vocabulary>,
+    /// Language of the spelled out words, `say-as` digits follow the voice's own language
+    pub language: Language,
+}
+
+impl Default for SsmlOptions {
+    fn default() -> Self {
+        SsmlOptions { rate_percent: 100, pair_break_ms: 300, vocabulary: None, language: Language::En }
+    }
+}
+
+/// SSML prompt for the voice code, ready for Amazon Polly or Azure text to speech
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// use voicecode::speech::{ to_ssml, SsmlOptions, Vocabulary };
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+/// assert_eq!(
+///     to_ssml(&voice_code, &SsmlOptions::default()),
+///     r#"<speak><prosody rate="100%"><say-as interpret-as="digits">69</say-as><break time="300ms"/><say-as interpret-as="digits">91</say-as></prosody></speak>"#
+/// );
+///
+/// let options = SsmlOptions { rate_percent: 85, vocabulary: Some(Vocabulary::Phonetic), ..SsmlOptions::default() };
+/// assert_eq!(
+///     to_ssml(&voice_code, &options),
+///     r#"<speak><prosody rate="85%">six niner<break time="300ms"/>niner wun</prosody></speak>"#
+/// );
+/// ```
+pub fn to_ssml(voice_code: &HashVoiceCode, options: &SsmlOptions) -> String {
+    let [a, b, c, d] = voice_code.digits();
+    let mut out = String::new();
+    let _ = write!(out, r#"<speak><prosody rate="{}%">"#, options.rate_percent);
+    for (i, (first, second)) in [(a, b), (c, d)].into_iter().enumerate() {
+        if i > 0 {
+            let _ = write!(out, r#"<break time="{}ms"/>"#, options.pair_break_ms);
+        }
+        // only digits and fixed words go into the markup, nothing needs escaping
+        match options.vocabulary {
+            Some(vocabulary) => {
+                let words = [first, second].map(|digit| vocabulary.digit_in(options.language, digit));
+                let _ = write!(out, "{} {}", words[0], words[1]);
+            }
+            None => {
+                let _ = write!(out, r#"<say-as interpret-as="digits">{}{}</say-as>"#, first, second);
+            }
+        }
+    }
+    out.push_str("</prosody></speak>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtin::Gtin;
+
+    #[test]
+    fn test_every_digit() {
+        let words: Vec<&str> = (0..10).map(|digit| Vocabulary::Words.digit(digit)).collect();
+        assert_eq!(words.join(" "), "zero one two three four five six seven eight nine");
+        let phonetic: Vec<&str> = (0..10).map(|digit| Vocabulary::Phonetic.digit(digit)).collect();
+        assert_eq!(phonetic.join(" "), "zero wun too tree fower fife six seven ait niner");
+        let french: Vec<&str> = (0..10).map(|digit| Language::Fr.digit(digit)).collect();
+        assert_eq!(french.join(" "), "zéro un deux trois quatre cinq six sept huit neuf");
+    }
+
+    #[test]
+    fn test_language() {
+        let options = SpokenOptions { vocabulary: Vocabulary::Phonetic, language: Language::Fr, ..SpokenOptions::default() };
+        assert_eq!(spoken([3, 5, 9, 0], &options), "tree fife, niner zero");
+        let options = SsmlOptions { vocabulary: Some(Vocabulary::Words), language: Language::Fr, ..SsmlOptions::default() };
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+        assert!(to_ssml(&voice_code, &options).contains("six neuf<break"));
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        assert_eq!(spoken([0, 0, 6, 5], &SpokenOptions::default()), "zero zero, six five");
+    }
+
+    #[test]
+    fn test_ssml_keeps_leading_zeros() {
+        let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap();
+        let ssml = to_ssml(&voice_code, &SsmlOptions { pair_break_ms: 0, ..SsmlOptions::default() });
+        assert!(ssml.contains(r#"<say-as interpret-as="digits">00</say-as><break time="0ms"/><say-as interpret-as="digits">65</say-as>"#));
+    }
+}
@@ -0,0 +1,187 @@
+//! SQLite loadable extension registering `voicecode(gtin, lot, pack_date)`
+//!
+//! Build it with `cargo rustc --lib --release --features sqlite --crate-type cdylib` and load
+//! the library, SQLite finds the `sqlite3_voicecode_init` entry point from the file name.
+//!
+//! ```sql
+//! .load target/release/libvoicecode
+//! SELECT voicecode('12345678901244', 'LOT123', '2003-01-02'); -- 6991
+//! ```
+//!
+//! The pack date may be YYMMDD or YYYY-MM-DD. Integer GTIN and pack date values are zero
+//! padded back to 14 and 6 digits since SQLite drops leading zeros from integers. Any NULL
+//! argument gives NULL, invalid input is an SQL error with the validation message.
+//!
+//! The extension talks to SQLite only through the `sqlite3_api_routines` table SQLite passes
+//! in, so no SQLite library is linked.
+
+use std::ffi::{c_char, c_int, c_void};
+use std::sync::OnceLock;
+
+use crate::batch::BatchRecord;
+
+const SQLITE_INTEGER: c_int = 1;
+const SQLITE_NULL: c_int = 5;
+const SQLITE_UTF8: c_int = 1;
+const SQLITE_DETERMINISTIC: c_int = 0x800;
+/// SQLite copies the result before the call returns
+const SQLITE_TRANSIENT: isize = -1;
+
+/// Opaque `sqlite3`
+#[repr(C)]
+pub struct Sqlite3 {
+    _private: [u8; 0],
+}
+
+/// Opaque `sqlite3_context`
+#[repr(C)]
+pub struct Context {
+    _private: [u8; 0],
+}
+
+/// Opaque `sqlite3_value`
+#[repr(C)]
+pub struct Value {
+    _private: [u8; 0],
+}
+
+type ScalarFn = unsafe extern "C" fn(*mut Context, c_int, *mut *mut Value);
+
+/// Leading part of `sqlite3_api_routines` from sqlite3ext.h, every slot is a function
+/// pointer and SQLite only ever appends slots, the numbers are slot indices
+#[repr(C)]
+pub struct ApiRoutines {
+    _0: [usize; 45],
+    /// 45
+    create_function: unsafe extern "C" fn(
+        *mut Sqlite3,
+        *const c_char,
+        c_int,
+        c_int,
+        *mut c_void,
+        Option<ScalarFn>,
+        Option<ScalarFn>,
+        Option<unsafe extern "C" fn(*mut Context)>,
+    ) -> c_int,
+    _46: [usize; 34],
+    /// 80
+    result_error: unsafe extern "C" fn(*mut Context, *const c_char, c_int),
+    _81: [usize; 3],
+    /// 84
+    result_null: unsafe extern "C" fn(*mut Context),
+    /// 85, the destructor is a pointer sized `SQLITE_TRANSIENT`
+    result_text: unsafe extern "C" fn(*mut Context, *const c_char, c_int, isize),
+    _86: [usize; 17],
+    /// 103
+    value_bytes: unsafe extern "C" fn(*mut Value) -> c_int,
+    _104: [usize; 5],
+    /// 109
+    value_text: unsafe extern "C" fn(*mut Value) -> *const u8,
+    _110: [usize; 3],
+    /// 113
+    value_type: unsafe extern "C" fn(*mut Value) -> c_int,
+}
+
+/// Argument of the SQL function as SQLite stored it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arg<'a> {
+    Null,
+    Integer(&'a str),
+    Text(&'a str),
+}
+
+impl<'a> Arg<'a> {
+    fn padded(self, width: usize) -> Option<String> {
+        match self {
+            Arg::Null => None,
+            Arg::Integer(digits) => Some(format!("{:0>width$}", digits)),
+            Arg::Text(text) => Some(text.to_string()),
+        }
+    }
+}
+
+/// Voice code for the SQL arguments, `Ok(None)` for SQL NULL
+fn voicecode(gtin: Arg, lot: Arg, pack_date: Arg) -> Result<Option<String>, String> {
+    let (Some(gtin), Some(lot), Some(pack_date)) = (gtin.padded(14), lot.padded(0), pack_date.padded(6)) else {
+        return Ok(None);
+    };
+    match BatchRecord::new(&gtin, &lot, &pack_date).compute() {
+        Ok(voice_code) => Ok(Some(voice_code.voice_code)),
+        Err(e) => Err(format!("voicecode: {}", e)),
+    }
+}
+
+/// `sqlite3_api_routines` of the connection, saved when the extension is loaded
+static API: OnceLock<&'static ApiRoutines> = OnceLock::new();
+
+unsafe fn arg<'a>(api: &ApiRoutines, value: *mut Value) -> Result<Arg<'a>, String> {
+    let kind = (api.value_type)(value);
+    if kind == SQLITE_NULL {
+        return Ok(Arg::Null);
+    }
+    let text = (api.value_text)(value);
+    let len = (api.value_bytes)(value);
+    if text.is_null() {
+        return Ok(Arg::Null);
+    }
+    let text = std::str::from_utf8(std::slice::from_raw_parts(text, len as usize))
+        .map_err(|_| "voicecode: argument is not valid UTF-8".to_string())?;
+    Ok(if kind == SQLITE_INTEGER { Arg::Integer(text) } else { Arg::Text(text) })
+}
+
+unsafe extern "C" fn voicecode_sql(context: *mut Context, argc: c_int, argv: *mut *mut Value) {
+    let Some(api) = API.get() else { return };
+    let argv = std::slice::from_raw_parts(argv, argc as usize);
+    let result = match (arg(api, argv[0]), arg(api, argv[1]), arg(api, argv[2])) {
+        (Ok(gtin), Ok(lot), Ok(pack_date)) => voicecode(gtin, lot, pack_date),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => Err(e),
+    };
+    match result {
+        Ok(Some(code)) => (api.result_text)(context, code.as_ptr().cast(), code.len() as c_int, SQLITE_TRANSIENT),
+        Ok(None) => (api.result_null)(context),
+        Err(message) => (api.result_error)(context, message.as_ptr().cast(), message.len() as c_int),
+    }
+}
+
+/// Extension entry point called by SQLite's `load_extension`
+///
+/// # Safety
+/// Only SQLite should call this, with a valid connection and its `sqlite3_api_routines`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_voicecode_init(
+    db: *mut Sqlite3,
+    _error: *mut *mut c_char,
+    api: *const ApiRoutines,
+) -> c_int {
+    let Some(api) = api.as_ref() else { return 1 };
+    let api = *API.get_or_init(|| api);
+    let flags = SQLITE_UTF8 | SQLITE_DETERMINISTIC;
+    (api.create_function)(db, c"voicecode".as_ptr(), 3, flags, std::ptr::null_mut(), Some(voicecode_sql), None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voicecode() {
+        assert_eq!(voicecode(Arg::Text("12345678901244"), Arg::Text("LOT123"), Arg::Text("2003-01-02")), Ok(Some("6991".into())));
+        assert_eq!(voicecode(Arg::Integer("61414100734933"), Arg::Text("32abcd"), Arg::Integer("30102")), Ok(Some("8079".into())));
+        assert_eq!(voicecode(Arg::Text("12345678901244"), Arg::Null, Arg::Text("030102")), Ok(None));
+        assert_eq!(
+            voicecode(Arg::Text("12345678901244"), Arg::Text("LOT 123"), Arg::Text("030102")).unwrap_err(),
+            format!("voicecode: {}", BatchRecord::new("12345678901244", "LOT 123", "030102").compute().unwrap_err())
+        );
+    }
+
+    #[test]
+    fn test_slot_offsets() {
+        let slot = std::mem::size_of::<usize>();
+        assert_eq!(std::mem::offset_of!(ApiRoutines, create_function), 45 * slot);
+        assert_eq!(std::mem::offset_of!(ApiRoutines, result_error), 80 * slot);
+        assert_eq!(std::mem::offset_of!(ApiRoutines, result_text), 85 * slot);
+        assert_eq!(std::mem::offset_of!(ApiRoutines, value_bytes), 103 * slot);
+        assert_eq!(std::mem::offset_of!(ApiRoutines, value_text), 109 * slot);
+        assert_eq!(std::mem::offset_of!(ApiRoutines, value_type), 113 * slot);
+    }
+}
@@ -0,0 +1,141 @@
+//! Serial Shipping Container Codes for pallet labels, carried in AI(00)
+
+use std::fmt;
+
+use crate::error::VoiceCodeError;
+use crate::gtin::compute_check_digit;
+
+/// An 18 digit SSCC: extension digit, GS1 company prefix, serial reference and check digit
+///
+/// # Example
+/// ```
+/// use voicecode::sscc::Sscc;
+/// let sscc = Sscc::parse("106141411234567897").unwrap();
+/// assert_eq!(sscc.extension_digit(), 1);
+/// assert_eq!(sscc.check_digit(), 7);
+///
+/// let generated = Sscc::generate(1, "0614141", 123456789).unwrap();
+/// assert_eq!(generated, sscc);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct Sscc {
+    digits: String,
+}
+
+impl Sscc {
+    /// Parse 18 digits and verify the check digit
+    pub fn parse(sscc: &str) -> Result<Self, VoiceCodeError> {
+        if sscc.len() != 18 || !sscc.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(VoiceCodeError::InvalidSscc);
+        }
+        let expected = compute_check_digit(&sscc.as_bytes()[..17]);
+        let found = sscc.as_bytes()[17] - b'0';
+        if expected != found {
+            return Err(VoiceCodeError::InvalidSsccCheckDigit { expected, found });
+        }
+        Ok(Sscc { digits: sscc.to_string() })
+    }
+
+    /// Build an SSCC from an extension digit, a 6 to 12 digit GS1 company prefix and a
+    /// serial reference, the serial is zero padded to fill the 16 digits after the extension
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ VoiceCodeError };
+    /// use voicecode::sscc::Sscc;
+    /// assert_eq!(Sscc::generate(0, "0614141", 1).unwrap().as_str(), "006141410000000012");
+    /// // a 7 digit prefix leaves 9 digits of serial reference
+    /// assert_eq!(Sscc::generate(0, "0614141", 1_000_000_000).unwrap_err(), VoiceCodeError::InvalidSscc);
+    /// ```
+    pub fn generate(extension: u8, company_prefix: &str, serial_reference: u64) -> Result<Self, VoiceCodeError> {
+        let prefix_ok = (6..=12).contains(&company_prefix.len()) && company_prefix.bytes().all(|b| b.is_ascii_digit());
+        if extension > 9 || !prefix_ok {
+            return Err(VoiceCodeError::InvalidSscc);
+        }
+        let serial_len = 16 - company_prefix.len();
+        let serial = format!("{:0width$}", serial_reference, width = serial_len);
+        if serial.len() > serial_len {
+            return Err(VoiceCodeError::InvalidSscc);
+        }
+        let body = format!("{}{}{}", extension, company_prefix, serial);
+        let check = compute_check_digit(body.as_bytes());
+        Ok(Sscc { digits: format!("{}{}", body, check) })
+    }
+
+    /// The 18 digits
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+
+    /// First digit, assigned by the company to extend the serial reference
+    pub fn extension_digit(&self) -> u8 {
+        self.digits.as_bytes()[0] - b'0'
+    }
+
+    /// Last digit
+    pub fn check_digit(&self) -> u8 {
+        self.digits.as_bytes()[17] - b'0'
+    }
+}
+
+impl fmt::Display for Sscc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.digits)
+    }
+}
+
+impl AsRef<str> for Sscc {
+    fn as_ref(&self) -> &str {
+        &self.digits
+    }
+}
+
+impl TryFrom<&str> for Sscc {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Sscc::parse(value)
+    }
+}
+
+impl TryFrom<String> for Sscc {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Sscc::parse(&value)
+    }
+}
+
+impl From<Sscc> for String {
+    fn from(value: Sscc) -> Self {
+        value.digits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert!(Sscc::parse("106141411234567897").is_ok());
+        assert_eq!(Sscc::parse("106141411234567890").unwrap_err(), VoiceCodeError::InvalidSsccCheckDigit { expected: 7, found: 0 });
+        assert_eq!(Sscc::parse("10614141123456789").unwrap_err(), VoiceCodeError::InvalidSscc);
+        assert_eq!(Sscc::parse("10614141123456789X").unwrap_err(), VoiceCodeError::InvalidSscc);
+    }
+
+    #[test]
+    fn test_generate() {
+        for prefix in ["061414", "0614141", "061414123456"] {
+            let sscc = Sscc::generate(3, prefix, 42).unwrap();
+            assert_eq!(sscc.as_str().len(), 18);
+            assert!(sscc.as_str()[1..].starts_with(prefix));
+            assert_eq!(Sscc::parse(sscc.as_str()).unwrap(), sscc);
+        }
+        assert_eq!(Sscc::generate(10, "0614141", 1).unwrap_err(), VoiceCodeError::InvalidSscc);
+        assert_eq!(Sscc::generate(0, "06141", 1).unwrap_err(), VoiceCodeError::InvalidSscc);
+        assert_eq!(Sscc::generate(0, "061414123456", 10_000).unwrap_err(), VoiceCodeError::InvalidSscc);
+    }
+}
@@ -0,0 +1,375 @@
+//! QA label verification station
+//!
+//! The operator scans a case's GS1 barcode and keys the voice code printed on the label.
+//! [`Station::check`] compares the keyed code with the one the barcode's GTIN, LOT and pack
+//! date produce, keeps a running tally and appends every check to a CSV log with columns
+//! `time,gtin,lot,pack_date,expected,keyed,result,error`. `result` is `match`, `mismatch`
+//! or `invalid` when the barcode could not be read as a voice code.
+//!
+//! [`Screen`] is the terminal front end, built on ratatui and crossterm: a fixed layout with
+//! the last result in green or red, the scan and voice code fields and the tally.
+//! `voicecode station --log qa.csv` runs it (`--features cli,tui`).
+
+use std::fmt;
+use std::io;
+
+use ::csv::Writer;
+use chrono::Local;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Alignment, Constraint, Layout, Position};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+
+use crate::scan::parse_scan;
+use crate::verify::VerifyResult;
+use crate::voicecode::HashVoiceCode;
+
+/// One scanned case
+#[derive(Clone, Debug)]
+pub struct Check {
+    /// What the barcode encodes, when it parsed
+    pub voice_code: Option<HashVoiceCode>,
+    /// The code as keyed by the operator, whitespace removed
+    pub keyed: String,
+    pub result: VerifyResult,
+}
+
+impl Check {
+    /// Compare the keyed code with the one the raw scanner output produces
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::station::Check;
+    /// let check = Check::new("]C10110614141007346\x1d1032abcd\x1d13030102", "10 89");
+    /// assert!(check.result.is_match());
+    /// ```
+    pub fn new(scan: &str, keyed: &str) -> Self {
+        let voice_code = parse_scan(scan).and_then(|scan| scan.voice_code());
+        let result = match &voice_code {
+            Ok(voice_code) => VerifyResult::compare(&voice_code.voice_code, keyed),
+            Err(e) => VerifyResult::Invalid(e.clone()),
+        };
+        let keyed = keyed.chars().filter(|ch| !ch.is_whitespace()).collect();
+        Check { voice_code: voice_code.ok(), keyed, result }
+    }
+
+    /// One line for the operator, `MATCH 1089`, `MISMATCH keyed 1098, label should read 1089`
+    /// or `INVALID` and why
+    pub fn message(&self) -> String {
+        match &self.result {
+            VerifyResult::Match => format!("MATCH {}", self.keyed),
+            VerifyResult::Mismatch { expected, printed } => {
+                format!("MISMATCH keyed {}, label should read {}", printed, expected)
+            }
+            VerifyResult::Invalid(e) => format!("INVALID barcode: {}", e),
+        }
+    }
+}
+
+/// Running counts of a session
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub matched: usize,
+    pub mismatched: usize,
+    pub invalid: usize,
+}
+
+impl Tally {
+    pub fn checked(&self) -> usize {
+        self.matched + self.mismatched + self.invalid
+    }
+
+    /// Percentage of checks that matched, `None` before the first check
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.checked() > 0).then(|| self.matched as f64 * 100.0 / self.checked() as f64)
+    }
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checked {}  matched {}  mismatched {}  invalid {}  accuracy ",
+            self.checked(),
+            self.matched,
+            self.mismatched,
+            self.invalid
+        )?;
+        match self.accuracy() {
+            Some(accuracy) => write!(f, "{:.1}%", accuracy),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+/// Verification session logging every check to `log`
+pub struct Station<W: io::Write> {
+    log: Writer<W>,
+    tally: Tally,
+}
+
+impl<W: io::Write> Station<W> {
+    /// Start a session, `append` skips the header for a log that already has one
+    pub fn new(log: W, append: bool) -> io::Result<Self> {
+        let mut log = Writer::from_writer(log);
+        if !append {
+            log.write_record(["time", "gtin", "lot", "pack_date", "expected", "keyed", "result", "error"])?;
+            log.flush()?;
+        }
+        Ok(Station { log, tally: Tally::default() })
+    }
+
+    pub fn tally(&self) -> Tally {
+        self.tally
+    }
+
+    /// Check a scan against the keyed code, count it and log it
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::station::Station;
+    /// let mut log = Vec::new();
+    /// let mut station = Station::new(&mut log, false).unwrap();
+    /// station.check("]C10110614141007346\x1d1032abcd\x1d13030102", "1089").unwrap();
+    /// station.check("]C10110614141007346\x1d1032abcd\x1d13030102", "1098").unwrap();
+    /// assert_eq!(station.tally().accuracy(), Some(50.0));
+    /// ```
+    pub fn check(&mut self, scan: &str, keyed: &str) -> io::Result<Check> {
+        let check = Check::new(scan, keyed);
+        let (result, error) = match &check.result {
+            VerifyResult::Match => {
+                self.tally.matched += 1;
+                ("match", String::new())
+            }
+            VerifyResult::Mismatch { .. } => {
+                self.tally.mismatched += 1;
+                ("mismatch", String::new())
+            }
+            VerifyResult::Invalid(e) => {
+                self.tally.invalid += 1;
+                ("invalid", e.to_string())
+            }
+        };
+        let (gtin, lot, pack_date, expected) = match &check.voice_code {
+            Some(code) => {
+                (code.gtin.to_string(), code.lot.to_string(), code.pack_date().to_string(), code.voice_code.clone())
+            }
+            None => Default::default(),
+        };
+        let time = Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string();
+        self.log.write_record([&time, &gtin, &lot, &pack_date, &expected, &check.keyed, result, &error])?;
+        // flushed per check so the log survives the terminal being closed
+        self.log.flush()?;
+        Ok(check)
+    }
+}
+
+/// Input field the operator is typing into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Scan,
+    VoiceCode,
+}
+
+/// Terminal screen of a station session
+///
+/// # Example
+/// ```
+/// use crossterm::event::{KeyCode, KeyEvent};
+/// use voicecode::station::{Screen, Station};
+/// let mut screen = Screen::new(Station::new(Vec::new(), false).unwrap());
+/// for ch in "]C10110614141007346\x1d1032abcd\x1d13030102\n1089\n".chars() {
+///     let code = if ch == '\n' { KeyCode::Enter } else { KeyCode::Char(ch) };
+///     assert!(screen.key(KeyEvent::from(code)).unwrap());
+/// }
+/// assert!(screen.last().unwrap().result.is_match());
+/// // an empty scan ends the session
+/// assert!(!screen.key(KeyEvent::from(KeyCode::Enter)).unwrap());
+/// ```
+pub struct Screen<W: io::Write> {
+    station: Station<W>,
+    scan: String,
+    keyed: String,
+    field: Field,
+    last: Option<Check>,
+}
+
+impl<W: io::Write> Screen<W> {
+    pub fn new(station: Station<W>) -> Self {
+        Screen { station, scan: String::new(), keyed: String::new(), field: Field::Scan, last: None }
+    }
+
+    pub fn station(&self) -> &Station<W> {
+        &self.station
+    }
+
+    /// Result of the last check, `None` before the first
+    pub fn last(&self) -> Option<&Check> {
+        self.last.as_ref()
+    }
+
+    /// Handle one key press, `Ok(false)` once the operator ended the session with an empty scan,
+    /// Esc or Ctrl+C
+    pub fn key(&mut self, key: KeyEvent) -> io::Result<bool> {
+        if key.kind != KeyEventKind::Press {
+            return Ok(true);
+        }
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let field = match self.field {
+            Field::Scan => &mut self.scan,
+            Field::VoiceCode => &mut self.keyed,
+        };
+        match key.code {
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Char('c') if control => return Ok(false),
+            // keyboard wedge scanners type the GS1 group separator as Ctrl+], which terminals
+            // report as Ctrl+5
+            KeyCode::Char(']' | '5') if control => field.push('\x1d'),
+            KeyCode::Char(ch) if !control => field.push(ch),
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            KeyCode::Enter => match self.field {
+                Field::Scan if self.scan.trim().is_empty() => return Ok(false),
+                Field::Scan => self.field = Field::VoiceCode,
+                Field::VoiceCode => {
+                    self.last = Some(self.station.check(&self.scan, &self.keyed)?);
+                    self.scan.clear();
+                    self.keyed.clear();
+                    self.field = Field::Scan;
+                }
+            },
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let outer = Block::bordered().title(" voicecode station ");
+        let area = outer.inner(frame.area());
+        frame.render_widget(outer, frame.area());
+        let [result, _, scan, keyed, _, tally, _, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+
+        let (message, style) = match &self.last {
+            Some(check) if check.result.is_match() => (check.message(), Style::new().white().on_green().bold()),
+            Some(check) => (check.message(), Style::new().white().on_red().bold()),
+            None => ("Scan a case".to_string(), Style::new().fg(Color::DarkGray)),
+        };
+        let banner = Paragraph::new(vec![Line::default(), Line::from(message), Line::default()])
+            .alignment(Alignment::Center)
+            .style(style);
+        frame.render_widget(banner, result);
+
+        // the group separator is unprintable, show where the scanner sent one
+        let fields = [
+            (Field::Scan, "Scan barcode: ", self.scan.replace('\x1d', "<GS>"), scan),
+            (Field::VoiceCode, "Voice code:   ", self.keyed.clone(), keyed),
+        ];
+        for (field, label, value, area) in fields {
+            let style = if field == self.field { Style::new().bold() } else { Style::new().fg(Color::DarkGray) };
+            let width = label.len() + value.chars().count();
+            frame.render_widget(Paragraph::new(Line::from(vec![label.into(), value.into()])).style(style), area);
+            if field == self.field {
+                frame.set_cursor_position(Position::new(area.x + width as u16, area.y));
+            }
+        }
+
+        frame.render_widget(Paragraph::new(self.station.tally().to_string()), tally);
+        frame.render_widget(Paragraph::new("Enter next field, empty scan or Esc ends the session").dark_gray(), help);
+    }
+
+    /// Take over the terminal until the operator ends the session
+    pub fn run(&mut self) -> io::Result<()> {
+        let mut terminal = ratatui::try_init()?;
+        let mut session = || loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if !self.key(key)? {
+                    return Ok(());
+                }
+            }
+        };
+        let result = session();
+        ratatui::try_restore()?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCAN: &str = "]C10110614141007346\x1d1032abcd\x1d13030102";
+
+    #[test]
+    fn test_session_log() {
+        let mut log = Vec::new();
+        let mut station = Station::new(&mut log, false).unwrap();
+        assert_eq!(station.tally().accuracy(), None);
+        assert!(station.check(SCAN, " 10 89 ").unwrap().result.is_match());
+        assert_eq!(station.check(SCAN, "1098").unwrap().message(), "MISMATCH keyed 1098, label should read 1089");
+        assert!(station.check("]C1019999", "1089").unwrap().message().starts_with("INVALID barcode: "));
+        assert_eq!(station.tally(), Tally { matched: 1, mismatched: 1, invalid: 1 });
+        assert_eq!(station.tally().to_string(), "checked 3  matched 1  mismatched 1  invalid 1  accuracy 33.3%");
+        drop(station);
+
+        let log = String::from_utf8(log).unwrap();
+        let rows: Vec<Vec<&str>> = log.lines().map(|line| line.split(',').skip(1).collect()).collect();
+        assert_eq!(rows[0], ["gtin", "lot", "pack_date", "expected", "keyed", "result", "error"]);
+        assert_eq!(rows[1], ["10614141007346", "32abcd", "030102", "1089", "1089", "match", ""]);
+        assert_eq!(rows[2], ["10614141007346", "32abcd", "030102", "1089", "1098", "mismatch", ""]);
+        assert_eq!(&rows[3][..6], ["", "", "", "", "1089", "invalid"]);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut log = Vec::new();
+        Station::new(&mut log, true).unwrap().check(SCAN, "1089").unwrap();
+        assert_eq!(String::from_utf8(log).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_screen() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut screen = Screen::new(Station::new(Vec::new(), false).unwrap());
+        let mut type_in = |text: &str| {
+            for ch in text.chars() {
+                let key = match ch {
+                    '\n' => KeyEvent::from(KeyCode::Enter),
+                    '\x1d' => KeyEvent::new(KeyCode::Char('5'), KeyModifiers::CONTROL),
+                    ch => KeyEvent::from(KeyCode::Char(ch)),
+                };
+                assert!(screen.key(key).unwrap());
+            }
+        };
+        type_in(&format!("{}\n1098\n", SCAN));
+        type_in("]C1x");
+        type_in("\x1d");
+
+        let mut terminal = Terminal::new(TestBackend::new(72, 12)).unwrap();
+        terminal.draw(|frame| screen.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| (0..72).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(line(2).contains("MISMATCH keyed 1098, label should read 1089"));
+        assert_eq!(buffer[(1, 2)].bg, Color::Red);
+        assert!(line(5).contains("Scan barcode: ]C1x<GS>"));
+        assert!(line(8).contains("checked 1  matched 0  mismatched 1  invalid 0  accuracy 0.0%"));
+        assert_eq!(terminal.get_cursor_position().unwrap(), Position::new(23, 5));
+
+        assert!(!screen.key(KeyEvent::from(KeyCode::Esc)).unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+//! Random valid GTINs, LOTs and cases for load tests and demos
+//!
+//! Each thread draws from its own `testing::Generator` seeded from the clock, call `seed` first
+//! for a repeatable run. Use `testing::Generator` directly when the values must come from a
+//! property testing framework.
+
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::VoiceCodeError;
+use crate::gtin::Gtin;
+use crate::lot::Lot;
+use crate::testing::Generator;
+use crate::voicecode::HashVoiceCode;
+
+pub use crate::testing::ALPHANUMERIC;
+
+thread_local! {
+    static GENERATOR: RefCell<Generator> = RefCell::new(Generator::new(clock_seed()));
+}
+
+fn clock_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Restart this thread's values from `seed`
+///
+/// # Example
+/// ```
+/// use voicecode::testdata::{ random_case, seed };
+/// seed(42);
+/// let first = random_case();
+/// seed(42);
+/// assert_eq!(random_case(), first);
+/// ```
+pub fn seed(seed: u64) {
+    GENERATOR.with(|generator| *generator.borrow_mut() = Generator::new(seed));
+}
+
+/// GTIN-14 starting with `prefix` and a correct check digit, see `Generator::gtin_with_prefix`
+///
+/// # Example
+/// ```
+/// use voicecode::testdata::random_gtin;
+/// let gtin = random_gtin("0614141").unwrap();
+/// assert_eq!(&gtin.as_str()[..7], "0614141");
+/// assert!(gtin.has_valid_check_digit());
+/// ```
+pub fn random_gtin(prefix: &str) -> Result<Gtin, VoiceCodeError> {
+    GENERATOR.with(|generator| generator.borrow_mut().gtin_with_prefix(prefix))
+}
+
+/// LOT of `len` characters from `charset`, see `Generator::lot_from`
+///
+/// # Example
+/// ```
+/// use voicecode::testdata::{ random_lot, ALPHANUMERIC };
+/// assert_eq!(random_lot(6, ALPHANUMERIC).unwrap().as_str().len(), 6);
+/// assert_eq!(random_lot(3, "7").unwrap().as_str(), "777");
+/// ```
+pub fn random_lot(len: usize, charset: &str) -> Result<Lot, VoiceCodeError> {
+    GENERATOR.with(|generator| generator.borrow_mut().lot_from(len, charset))
+}
+
+/// Voice code for a realistic case, see `Generator::case`
+pub fn random_case() -> HashVoiceCode {
+    GENERATOR.with(|generator| generator.borrow_mut().case())
+}
@@ -0,0 +1,285 @@
+//! Generators of valid GTINs, LOTs and pack dates for property tests of code that consumes
+//! voice codes
+//!
+//! `Gtin`, `Lot`, `PackDate` and [`ValidInputs`] implement proptest's, quickcheck's and
+//! arbitrary's `Arbitrary`, so they can be drawn directly, and [`strategy`] gives the proptest
+//! strategy for whole input tuples:
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use voicecode::testing::strategy;
+//!
+//! proptest!(|(inputs in strategy())| {
+//!     prop_assert_eq!(inputs.voice_code().voice_code.len(), 4);
+//! });
+//! ```
+//!
+//! Every impl maps the framework's own `u64` through [`Generator`], which is also usable on its
+//! own. The same seed always gives the same values, so a failing case reproduces from its seed.
+
+use chrono::NaiveDate;
+use proptest::arbitrary::any;
+use proptest::strategy::Strategy;
+
+use crate::error::VoiceCodeError;
+use crate::gtin::{check_digit, Gtin, GtinError};
+use crate::lot::{is_lot_byte, is_lot_char, Lot, LotError, MAX_LOT_LEN};
+use crate::pack_date::{PackDate, YearWindow};
+use crate::voicecode::HashVoiceCode;
+
+/// Upper case letters and digits, the LOT characters that survive every printer, scanner and
+/// ERP system
+pub const ALPHANUMERIC: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// GTIN, LOT and pack date that `HashVoiceCode::new` accepts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidInputs {
+    pub gtin: Gtin,
+    pub lot: Lot,
+    pub pack_date: PackDate,
+}
+
+impl ValidInputs {
+    pub fn voice_code(&self) -> HashVoiceCode {
+        HashVoiceCode::from_parts(self.gtin.clone(), self.lot.clone(), self.pack_date.clone())
+    }
+}
+
+/// Deterministic source of valid values
+///
+/// # Example
+/// ```
+/// use voicecode::HashVoiceCode;
+/// use voicecode::testing::Generator;
+/// let mut generator = Generator::new(7);
+/// for _ in 0..100 {
+///     let inputs = generator.inputs();
+///     let yymmdd = inputs.pack_date.yymmdd();
+///     let voice_code = HashVoiceCode::new(inputs.gtin.as_str(), inputs.lot.as_str(), &yymmdd[..2], &yymmdd[2..4], &yymmdd[4..]).unwrap();
+///     assert_eq!(voice_code, inputs.voice_code());
+/// }
+/// assert_eq!(Generator::new(7).inputs(), Generator::new(7).inputs());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        // xorshift never leaves 0, so a zero seed gets a fixed nonzero state
+        Generator { state: if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed } }
+    }
+
+    /// Next raw value, xorshift64*
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform-ish value in `0..bound`
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// GTIN-8, 12, 13 or 14 with a correct check digit
+    pub fn gtin(&mut self) -> Gtin {
+        let len = [8, 12, 13, 14][self.below(4)];
+        let body: String = (0..len - 1).map(|_| char::from(b'0' + self.below(10) as u8)).collect();
+        let digit = check_digit(&body);
+        Gtin::parse(&format!("{}{}", body, digit)).expect("generated GTIN is valid")
+    }
+
+    /// GTIN-14 starting with `prefix`, such as an indicator digit and GS1 company prefix, with
+    /// random digits after it and a correct check digit
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::testing::Generator;
+    /// let gtin = Generator::new(7).gtin_with_prefix("10614141").unwrap();
+    /// assert!(gtin.as_str().starts_with("10614141"));
+    /// assert!(gtin.has_valid_check_digit());
+    /// assert!(Generator::new(7).gtin_with_prefix("12345678901234").is_err());
+    /// ```
+    pub fn gtin_with_prefix(&mut self, prefix: &str) -> Result<Gtin, VoiceCodeError> {
+        if let Some((offset, ch)) = prefix.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+            return Err(VoiceCodeError::InvalidGtin { reason: GtinError::NonDigit { ch, offset } });
+        }
+        if prefix.len() > 13 {
+            // the check digit makes the GTIN one longer than the prefix
+            return Err(VoiceCodeError::InvalidGtin { reason: GtinError::Length { len: prefix.len() + 1 } });
+        }
+        let mut body = prefix.to_string();
+        body.extend((prefix.len()..13).map(|_| char::from(b'0' + self.below(10) as u8)));
+        let digit = check_digit(&body);
+        Gtin::parse(&format!("{}{}", body, digit))
+    }
+
+    /// LOT of exactly `len` characters drawn from `charset`, every character of which must be
+    /// in the PTI LOT set
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::testing::{ Generator, ALPHANUMERIC };
+    /// let lot = Generator::new(7).lot_from(8, ALPHANUMERIC).unwrap();
+    /// assert_eq!(lot.as_str().len(), 8);
+    /// assert!(Generator::new(7).lot_from(8, "AB C").is_err());
+    /// assert!(Generator::new(7).lot_from(21, ALPHANUMERIC).is_err());
+    /// ```
+    pub fn lot_from(&mut self, len: usize, charset: &str) -> Result<Lot, VoiceCodeError> {
+        if let Some((offset, ch)) = charset.char_indices().find(|(_, ch)| !is_lot_char(*ch)) {
+            return Err(VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch, offset } });
+        }
+        let charset = charset.as_bytes();
+        if charset.is_empty() {
+            return Err(VoiceCodeError::InvalidLot { reason: LotError::Empty });
+        }
+        let lot: String = (0..len).map(|_| char::from(charset[self.below(charset.len())])).collect();
+        Lot::parse(&lot)
+    }
+
+    /// LOT of 1 to `MAX_LOT_LEN` characters from the full LOT character set
+    pub fn lot(&mut self) -> Lot {
+        let charset: Vec<u8> = (b'!'..=b'~').filter(|b| is_lot_byte(*b)).collect();
+        let len = 1 + self.below(MAX_LOT_LEN);
+        let lot: String = (0..len).map(|_| char::from(charset[self.below(charset.len())])).collect();
+        Lot::parse(&lot).expect("generated LOT is valid")
+    }
+
+    /// Calendar date inside the default `YearWindow`, so its YYMMDD reads back as the same date
+    pub fn pack_date(&mut self) -> PackDate {
+        let start = YearWindow::default().start();
+        let first = NaiveDate::from_ymd_opt(start, 1, 1).expect("valid date");
+        let last = NaiveDate::from_ymd_opt(start + 99, 12, 31).expect("valid date");
+        let days = (last - first).num_days() as usize + 1;
+        PackDate::from_naive(first + chrono::Days::new(self.below(days) as u64))
+    }
+
+    pub fn inputs(&mut self) -> ValidInputs {
+        ValidInputs { gtin: self.gtin(), lot: self.lot(), pack_date: self.pack_date() }
+    }
+
+    /// Voice code that looks like a real case label, a GTIN-14 and a 4 to 10 character
+    /// `ALPHANUMERIC` LOT, for demos and load tests rather than edge cases
+    pub fn case(&mut self) -> HashVoiceCode {
+        let gtin = self.gtin_with_prefix("").expect("empty prefix is valid");
+        let len = 4 + self.below(7);
+        let lot = self.lot_from(len, ALPHANUMERIC).expect("ALPHANUMERIC is in the LOT set");
+        HashVoiceCode::from_parts(gtin, lot, self.pack_date())
+    }
+}
+
+/// Proptest strategy for valid input tuples
+pub fn strategy() -> impl Strategy<Value = ValidInputs> {
+    any::<ValidInputs>()
+}
+
+/// `Arbitrary` for each framework, drawing a seed and passing it through `Generator`
+macro_rules! arbitrary_from_seed {
+    ($($ty:ty => $method:ident),* $(,)?) => {$(
+        impl proptest::arbitrary::Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = proptest::strategy::Map<proptest::num::u64::Any, fn(u64) -> Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                any::<u64>().prop_map((|seed| Generator::new(seed).$method()) as fn(u64) -> Self)
+            }
+        }
+
+        impl quickcheck::Arbitrary for $ty {
+            fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+                Generator::new(<u64 as quickcheck::Arbitrary>::arbitrary(g)).$method()
+            }
+        }
+
+        impl<'a> arbitrary::Arbitrary<'a> for $ty {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                Ok(Generator::new(u.arbitrary()?).$method())
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <u64 as arbitrary::Arbitrary>::size_hint(depth)
+            }
+        }
+    )*};
+}
+
+arbitrary_from_seed!(Gtin => gtin, Lot => lot, PackDate => pack_date, ValidInputs => inputs);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_covers_the_input_space() {
+        let mut generator = Generator::new(0);
+        let mut lengths = std::collections::BTreeSet::new();
+        let mut lot_bytes = std::collections::BTreeSet::new();
+        for _ in 0..2000 {
+            let inputs = generator.inputs();
+            lengths.insert(inputs.gtin.len());
+            lot_bytes.extend(inputs.lot.as_str().bytes());
+            assert!(inputs.pack_date.date().is_some());
+            assert_eq!(inputs.voice_code().voice_code.len(), 4);
+        }
+        assert_eq!(lengths.into_iter().collect::<Vec<_>>(), [8, 12, 13, 14]);
+        assert_eq!(lot_bytes.len(), (b'!'..=b'~').filter(|b| is_lot_byte(*b)).count());
+    }
+
+    #[test]
+    fn test_case() {
+        let mut generator = Generator::new(3);
+        for _ in 0..500 {
+            let case = generator.case();
+            assert_eq!(case.gtin.len(), 14);
+            assert!(case.gtin.has_valid_check_digit());
+            assert!((4..=10).contains(&case.lot.as_str().len()));
+            assert!(case.lot.as_str().bytes().all(|b| ALPHANUMERIC.as_bytes().contains(&b)));
+        }
+        assert_eq!(
+            generator.lot_from(4, "AB~").unwrap_err(),
+            VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: '~', offset: 2 } }
+        );
+        assert_eq!(generator.lot_from(4, "").unwrap_err(), VoiceCodeError::InvalidLot { reason: LotError::Empty });
+        assert_eq!(generator.lot_from(0, "A").unwrap_err(), VoiceCodeError::InvalidLot { reason: LotError::Empty });
+        assert_eq!(
+            generator.gtin_with_prefix("0614141x").unwrap_err(),
+            VoiceCodeError::InvalidGtin { reason: GtinError::NonDigit { ch: 'x', offset: 7 } }
+        );
+    }
+
+    #[test]
+    fn test_arbitrary() {
+        let accepted = |inputs: &ValidInputs| {
+            let yymmdd = inputs.pack_date.yymmdd();
+            let parsed = HashVoiceCode::new(inputs.gtin.as_str(), inputs.lot.as_str(), &yymmdd[..2], &yymmdd[2..4], &yymmdd[4..]);
+            parsed == Ok(inputs.voice_code())
+        };
+
+        let mut runner = proptest::test_runner::TestRunner::deterministic();
+        runner
+            .run(&strategy(), |inputs| {
+                proptest::prop_assert!(accepted(&inputs));
+                Ok(())
+            })
+            .unwrap();
+
+        let mut gen = quickcheck::Gen::new(100);
+        for _ in 0..100 {
+            assert!(accepted(&quickcheck::Arbitrary::arbitrary(&mut gen)));
+        }
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut u = arbitrary::Unstructured::new(&bytes);
+        while let Ok(inputs) = u.arbitrary::<ValidInputs>() {
+            assert!(accepted(&inputs));
+            if u.is_empty() {
+                break;
+            }
+        }
+        let gtin: Gtin = arbitrary::Unstructured::new(&[]).arbitrary().unwrap();
+        assert!(gtin.has_valid_check_digit());
+    }
+}
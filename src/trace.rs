@@ -0,0 +1,36 @@
+//! `tracing` events and spans that compile to nothing without the `tracing` feature
+//!
+//! Field values are recorded with their `Display` impl. Without the feature the values are
+//! only borrowed, so variables used just for tracing do not trigger unused warnings.
+
+/// `event!(warn, "message", field = value, …)`
+macro_rules! event {
+    ($level:ident, $message:literal $(, $name:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($name = %$value,)* $message);
+        #[cfg(not(feature = "tracing"))]
+        {
+            $(let _ = &$value;)*
+        }
+    }};
+}
+
+/// `let _span = span!("name", field = value, …);` enters an info span until the guard drops
+#[allow(unused_macros)]
+macro_rules! span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!($name, $($field = %$value),*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let span = {
+            $(let _ = &$value;)*
+            $crate::trace::NoSpan
+        };
+        span
+    }};
+}
+
+/// Guard `span!` returns without the `tracing` feature
+#[cfg(not(feature = "tracing"))]
+#[allow(dead_code)]
+pub(crate) struct NoSpan;
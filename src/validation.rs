@@ -0,0 +1,183 @@
+//! Every problem with a GTIN, LOT and pack date at once, for forms that highlight each bad field
+
+use std::fmt;
+
+use crate::error::{DateComponent, VoiceCodeError};
+use crate::pack_date::parse_component;
+use crate::policy::ValidationPolicy;
+
+/// Input field a violation belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Field {
+    Gtin,
+    Lot,
+    /// YY of the pack date
+    PackDateYy,
+    /// MM of the pack date
+    PackDateMm,
+    /// DD of the pack date
+    PackDateDd,
+    /// The pack date as a whole, for parts that are each valid but not a calendar date
+    PackDate,
+}
+
+impl From<DateComponent> for Field {
+    fn from(value: DateComponent) -> Self {
+        match value {
+            DateComponent::Year => Field::PackDateYy,
+            DateComponent::Month => Field::PackDateMm,
+            DateComponent::Day => Field::PackDateDd,
+        }
+    }
+}
+
+/// One failed rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub field: Field,
+    pub error: VoiceCodeError,
+}
+
+impl Violation {
+    /// Machine readable code, see `VoiceCodeError::code`
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+}
+
+/// Serialized as `{"field":…,"code":…,"message":…}`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Violation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Violation", 3)?;
+        state.serialize_field("field", &self.field)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.error.to_string())?;
+        state.end()
+    }
+}
+
+/// All violations found in one set of inputs, empty when a voice code can be computed
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode };
+/// use voicecode::validation::Field;
+/// let report = HashVoiceCode::validate_all("1234", "LOT 123", "03", "13", "x");
+/// let codes: Vec<_> = report.violations.iter().map(|violation| (violation.field, violation.code())).collect();
+/// assert_eq!(
+///     codes,
+///     [(Field::Gtin, "gtin_format"), (Field::Lot, "lot_invalid_character"), (Field::PackDateDd, "date_dd_format")]
+/// );
+/// assert!(HashVoiceCode::validate_all("00614141007349", "LOT123", "03", "01", "02").is_valid());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Check every field against `policy`, the rules are the same as `new_with_policy`
+    pub fn check(policy: &ValidationPolicy, gtin: &str, lot: &str, yy: &str, mm: &str, dd: &str) -> Self {
+        let mut report = ValidationReport::default();
+        if let Err(error) = policy.gtin(gtin) {
+            report.push(Field::Gtin, error);
+        }
+        if let Err(error) = policy.lot(lot) {
+            report.push(Field::Lot, error);
+        }
+
+        let components = [(yy, DateComponent::Year), (mm, DateComponent::Month), (dd, DateComponent::Day)];
+        let mut parts_valid = true;
+        for (value, which) in components {
+            if let Err(error) = parse_component(value, which) {
+                report.push(which.into(), error);
+                parts_valid = false;
+            }
+        }
+        if parts_valid {
+            if let Err(error) = policy.pack_date(yy, mm, dd) {
+                report.push(Field::PackDate, error);
+            }
+        }
+        report
+    }
+
+    fn push(&mut self, field: Field, error: VoiceCodeError) {
+        self.violations.push(Violation { field, error });
+    }
+
+    /// True when there are no violations
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Violations for one field
+    pub fn for_field(&self, field: Field) -> impl Iterator<Item = &Violation> {
+        self.violations.iter().filter(move |violation| violation.field == field)
+    }
+}
+
+/// One violation per line as `field: message`
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:?}: {}", violation.field, violation.error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashVoiceCode;
+
+    #[test]
+    fn test_agrees_with_new() {
+        let inputs = [
+            ("00614141007349", "LOT123", "03", "01", "02"),
+            ("12345678901244", "LOT123", "03", "01", "02"),
+            ("1234", "LOT123", "03", "01", "02"),
+            ("00614141007349", "", "03", "01", "02"),
+            ("00614141007349", "LOT123", "3x", "01", "02"),
+            ("00614141007349", "LOT123", "03", "99", "99"),
+        ];
+        for (gtin, lot, yy, mm, dd) in inputs {
+            let report = HashVoiceCode::validate_all(gtin, lot, yy, mm, dd);
+            let new = HashVoiceCode::new(gtin, lot, yy, mm, dd);
+            assert_eq!(report.is_valid(), new.is_ok());
+            if let Err(error) = new {
+                assert_eq!(report.violations[0].error, error);
+            }
+        }
+    }
+
+    #[test]
+    fn test_calendar_date_only_when_parts_valid() {
+        let policy = ValidationPolicy { strict_dates: true, ..ValidationPolicy::default() };
+        let report = ValidationReport::check(&policy, "00614141007349", "LOT123", "24", "02", "30");
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.for_field(Field::PackDate).next().unwrap().code(), "date_not_calendar");
+
+        let report = ValidationReport::check(&policy, "00614141007349", "LOT123", "yy", "mm", "30");
+        assert_eq!(report.violations.iter().map(Violation::code).collect::<Vec<_>>(), ["date_yy_format", "date_mm_format"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let report = HashVoiceCode::validate_all("1234", "LOT123", "03", "01", "02");
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            r#"{"violations":[{"field":"gtin","code":"gtin_format","message":"GTIN must be 8, 12, 13 or 14 digits, got 4"}]}"#
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Checking printed voice codes against the GTIN, LOT and pack date they should come from
+
+use crate::batch::BatchRecord;
+use crate::error::VoiceCodeError;
+
+/// Outcome of checking one printed voice code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyResult {
+    /// The printed code is the one the inputs produce
+    Match,
+    /// The printed code differs, `expected` is what should have been printed
+    Mismatch { expected: String, printed: String },
+    /// The GTIN, LOT or pack date failed validation so there is nothing to compare against
+    Invalid(VoiceCodeError),
+}
+
+impl VerifyResult {
+    pub fn is_match(&self) -> bool {
+        matches!(self, VerifyResult::Match)
+    }
+
+    /// Compare a computed code with a printed one, whitespace such as the gap between
+    /// the minor and major digits is ignored
+    pub(crate) fn compare(expected: &str, printed: &str) -> Self {
+        let printed: String = printed.chars().filter(|ch| !ch.is_whitespace()).collect();
+        if printed == expected {
+            VerifyResult::Match
+        } else {
+            VerifyResult::Mismatch { expected: expected.to_string(), printed }
+        }
+    }
+}
+
+impl From<Result<VerifyResult, VoiceCodeError>> for VerifyResult {
+    fn from(value: Result<VerifyResult, VoiceCodeError>) -> Self {
+        value.unwrap_or_else(VerifyResult::Invalid)
+    }
+}
+
+/// Check every `(record, printed code)` pair in order
+///
+/// # Example
+/// ```
+/// use voicecode::batch::BatchRecord;
+/// use voicecode::verify::{ verify_all, VerifyResult };
+/// let results = verify_all(vec![
+///     (BatchRecord::new("12345678901244", "LOT123", "030102"), "69 91"),
+///     (BatchRecord::new("12345678901244", "LOT124", "030102"), "6991"),
+///     (BatchRecord::new("12345678901244", "LOT 123", "030102"), "6991"),
+/// ]);
+/// assert_eq!(results[0], VerifyResult::Match);
+/// assert!(matches!(results[1], VerifyResult::Mismatch { .. }));
+/// assert!(matches!(results[2], VerifyResult::Invalid(_)));
+/// ```
+pub fn verify_all<I, S>(items: I) -> Vec<VerifyResult>
+where
+    I: IntoIterator<Item = (BatchRecord, S)>,
+    S: AsRef<str>,
+{
+    items
+        .into_iter()
+        .map(|(record, printed)| {
+            record.compute().map(|voice_code| VerifyResult::compare(&voice_code.voice_code, printed.as_ref())).into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(VerifyResult::compare("6991", " 69 91\n"), VerifyResult::Match);
+        assert_eq!(
+            VerifyResult::compare("6991", "6919"),
+            VerifyResult::Mismatch { expected: "6991".to_string(), printed: "6919".to_string() }
+        );
+    }
+}
@@ -1,20 +1,28 @@
 #![deny(const_item_mutation)]
-use lazy_static::lazy_static;
-
+#[cfg(feature = "chrono")]
 use chrono::NaiveDate;
-use regex::Regex;
-
-lazy_static! {
-    static ref LOT_REGEX: Regex = Regex::new(r##"^[\!"%&'()\*\+,\-\./0-9:;<=>\?A-Z_a-z]{1,20}$"##).expect("Invalid regex");
-}
 
-/// Generate CRC look up table similar to reference impl on producetraceability.org using 40961 as the polynomial
-use crate::create_crc_lut::create_crc_lut;
-lazy_static! {
-    static ref HASH_VOICE_CHECKSUM_HASH_T: [u16; 256] = create_crc_lut(40961);
-}
+use crate::algorithm::VoiceCodeAlgorithm;
+use crate::builder::{HashVoiceCodeBuilder, PackDateInput};
+use crate::error::VoiceCodeError;
+use crate::explain::Explanation;
+use crate::gs1::Gs1ElementString;
+use crate::gtin::Gtin;
+use crate::lint::{LintConfig, Warning};
+use crate::hash::{crc_u16, hash_u16, NonAsciiPolicy, VoiceCodeHasher, VOICE_CODE_MODULUS};
+use crate::lot::{Lot, LotCase};
+use crate::pack_date::{DateAmbiguity, DatePadding, PackDate, YearWindow};
+use crate::policy::ValidationPolicy;
+use crate::validation::ValidationReport;
+use crate::scan;
+use crate::speech::{self, SpokenOptions, Vocabulary};
+use crate::verify::VerifyResult;
 
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
 
 #[allow(dead_code)]
 /// Represents a voice code hasher for Produce Traceability Initiative (PTI)
@@ -27,15 +35,16 @@ use std::fmt;
 ///
 /// Be aware that the example impl returns case sensitive results so be careful if your Lot Code could be mixed case.
 ///
-/// for GTIN 12345678901244 and Lot LOT123 with pack date 2003-01-02
+/// for GTIN 12345678901244 and Lot LOT123 with pack date 2003-01-02, the GTIN of the PTI
+/// calculator example has a bad check digit so it goes through `Gtin::parse_unchecked`
 ///
 /// # Example
 /// ```
-/// use voicecode::{ HashVoiceCode };
+/// use voicecode::{ HashVoiceCode, Gtin };
 /// let mm = "01";
 /// let dd = "02";
 /// let yy = "03";
-/// let voice_code = HashVoiceCode::new("12345678901244", "LOT123", yy, mm, dd).unwrap();
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", yy, mm, dd).unwrap();
 /// println!("Voice Code: {}", voice_code.voice_code); // expects 6991
 /// println!("Minor: {}", voice_code.voice_code_minor); // expects 69
 /// println!("Major: {}", voice_code.voice_code_major); // expects 91
@@ -45,9 +54,14 @@ use std::fmt;
 #[derive(Clone)]
 pub struct HashVoiceCode {
     pub hash_text: String,
-    pub gtin: String,
-    pub lot: String,
-    pub pack_date: String,
+    pub gtin: Gtin,
+    pub lot: Lot,
+    pack_date: PackDate,
+    padding: DatePadding,
+    lot_case: LotCase,
+    /// `None` for `PtiV1`
+    algorithm: Option<Arc<dyn VoiceCodeAlgorithm>>,
+    code: u16,
     pub voice_code: String,
     pub voice_code_major: String,
     pub voice_code_minor: String,
@@ -62,70 +76,519 @@ impl HashVoiceCode {
     /// hash and not be valid for the PTI label format
     /// ex mm="99" dd="99" yy="99" is valid and is not a real date
     ///
-    /// this method assumes you've provided valid date parts
+    /// this method assumes you've provided valid date parts, use `new_strict` to reject them
+    ///
+    /// yy, mm and dd are padded to two digits before hashing, so "3", "1", "2" hashes the
+    /// same as "03", "01", "02", see `DatePadding` and `HashVoiceCodeBuilder::date_padding`
+    /// for the unpadded text versions before 0.3 hashed
+    ///
+    /// gtin accepts a `&str`, checked like `Gtin::parse` including the check digit, or a
+    /// `Gtin`, pass one from `Gtin::parse_unchecked` for a label already printed with a bad
+    /// check digit
+    ///
+    /// lot accepts a `&str` or an already validated `Lot`
     ///
     /// # Example
     /// ```
-    /// use voicecode::{ HashVoiceCode };
+    /// use voicecode::{ HashVoiceCode, Gtin };
     /// let mm = "01";
     /// let dd = "02";
     /// let yy = "03";
-    /// let voice_code = HashVoiceCode::new("12345678901244", "LOT123", yy, mm, dd).unwrap();
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", yy, mm, dd).unwrap();
     /// println!("Voice Code: {}", voice_code.voice_code); // expects 6991
     /// println!("Minor: {}", voice_code.voice_code_minor); // expects 69
     /// println!("Major: {}", voice_code.voice_code_major); // expects 91
     ///
     /// assert_eq!(voice_code.voice_code, "6991");
+    /// assert!(HashVoiceCode::new("12345678901244", "LOT123", yy, mm, dd).is_err());
     /// ```
-    pub fn new(gtin: &str, lot: &str, pack_date_yy: &str, pack_date_mm: &str, pack_date_dd: &str) -> Result<Self, &'static str> {
-        if !pack_date_yy.chars().all(char::is_numeric) || pack_date_yy.len() > 2 || pack_date_yy.len() < 1 {
-            return Err("Date component YY must be numeric and 1 or 2 digits");
-        }
+    pub fn new<G, L>(gtin: G, lot: L, pack_date_yy: &str, pack_date_mm: &str, pack_date_dd: &str) -> Result<Self, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        L: TryInto<Lot>,
+        VoiceCodeError: From<G::Error> + From<L::Error>,
+    {
+        let pack_date = PackDate::from_parts(pack_date_yy, pack_date_mm, pack_date_dd)?;
 
-        if !pack_date_mm.chars().all(char::is_numeric) || pack_date_mm.len() > 2 || pack_date_mm.len() < 1 {
-            return Err("Date component MM must be numeric and 1 or 2 digits");
-        }
+        // note - gs1 codes use (xx)data to indicate various kinds of data, allowing parens should probably not be allowed
+        let lot: Lot = lot.try_into()?;
+
+        let gtin: Gtin = gtin.try_into()?;
+
+        Ok(Self::from_parts(gtin, lot, pack_date))
+    }
 
-        if !pack_date_dd.chars().all(char::is_numeric) || pack_date_dd.len() > 2 || pack_date_dd.len() < 1 {
-            return Err("Date component DD must be numeric and 1 or 2 digits");
+    /// Same as `new` but rejects date parts that are not a calendar date, such as month 13 or Feb 30
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, VoiceCodeError, Gtin };
+    /// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+    /// assert_eq!(HashVoiceCode::new_strict(&gtin, "LOT123", "03", "01", "02").unwrap().voice_code, "6991");
+    /// assert!(HashVoiceCode::new(&gtin, "LOT123", "24", "02", "30").is_ok());
+    /// assert!(matches!(
+    ///     HashVoiceCode::new_strict(&gtin, "LOT123", "24", "02", "30"),
+    ///     Err(VoiceCodeError::InvalidCalendarDate { .. })
+    /// ));
+    /// ```
+    pub fn new_strict<G, L>(gtin: G, lot: L, pack_date_yy: &str, pack_date_mm: &str, pack_date_dd: &str) -> Result<Self, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        L: TryInto<Lot>,
+        VoiceCodeError: From<G::Error> + From<L::Error>,
+    {
+        let pack_date = PackDate::from_parts(pack_date_yy, pack_date_mm, pack_date_dd)?;
+        if pack_date.calendar_date().is_none() {
+            return Err(VoiceCodeError::InvalidCalendarDate { yymmdd: pack_date.yymmdd() });
         }
 
-        if !Self::validate_lot(lot) {
-            // note - gs1 codes use (xx)data to indicate various kinds of data, allowing parens should probably not be allowed
-            return Err(r##"LOT must be alphanumeric and/or !, ", %, &, ', (, ), *, +, -, ., /, :, ;, <, =, >, ?, _ and comma"##);
+        let lot: Lot = lot.try_into()?;
+        let gtin: Gtin = gtin.try_into()?;
+
+        Ok(Self::from_parts(gtin, lot, pack_date))
+    }
+
+    /// Same as `new` with the GTIN, LOT and date rules taken from `policy`
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, ValidationPolicy, VoiceCodeError };
+    /// let policy = ValidationPolicy { require_check_digit: false, ..ValidationPolicy::default() };
+    /// assert_eq!(
+    ///     HashVoiceCode::new("12345678901244", "LOT123", "03", "01", "02").unwrap_err(),
+    ///     VoiceCodeError::InvalidGtinCheckDigit { expected: 8, found: 4 }
+    /// );
+    /// assert_eq!(HashVoiceCode::new_with_policy("12345678901244", "LOT123", "03", "01", "02", &policy).unwrap().voice_code, "6991");
+    /// ```
+    pub fn new_with_policy(
+        gtin: &str,
+        lot: &str,
+        pack_date_yy: &str,
+        pack_date_mm: &str,
+        pack_date_dd: &str,
+        policy: &ValidationPolicy,
+    ) -> Result<Self, VoiceCodeError> {
+        let pack_date = policy.pack_date(pack_date_yy, pack_date_mm, pack_date_dd)?;
+        let lot = policy.lot(lot)?;
+        let gtin = policy.gtin(gtin)?;
+
+        Ok(Self::from_parts(gtin, lot, pack_date))
+    }
+
+    /// Same as `new` plus the warnings `config` finds in inputs that are valid but suspicious,
+    /// a lint set to `LintLevel::Deny` is returned as `VoiceCodeError::LintDenied`
+    ///
+    /// A bad GTIN check digit is left to `Lint::GtinCheckDigit` rather than rejected.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// use voicecode::lint::{ LintConfig, LintLevel };
+    /// let (voice_code, warnings) = HashVoiceCode::new_with_lints("12345678901244", "030102", "03", "01", "02", &LintConfig::default()).unwrap();
+    /// assert_eq!(voice_code.voice_code, HashVoiceCode::generate_voice_code_hash("12345678901244030102030102"));
+    /// assert_eq!(warnings.len(), 2);
+    /// assert_eq!(warnings[1].to_string(), "LOT 030102 reads as the date 2003-01-02");
+    ///
+    /// let config = LintConfig { lot_looks_like_date: LintLevel::Deny, ..LintConfig::default() };
+    /// let err = HashVoiceCode::new_with_lints("12345678901244", "030102", "03", "01", "02", &config).unwrap_err();
+    /// assert_eq!(err.code(), "lot_looks_like_date");
+    /// ```
+    pub fn new_with_lints(
+        gtin: &str,
+        lot: &str,
+        pack_date_yy: &str,
+        pack_date_mm: &str,
+        pack_date_dd: &str,
+        config: &LintConfig,
+    ) -> Result<(Self, Vec<Warning>), VoiceCodeError> {
+        let voice_code = Self::new(Gtin::parse_unchecked(gtin)?, lot, pack_date_yy, pack_date_mm, pack_date_dd)?;
+        let warnings = config.check(&voice_code)?;
+        Ok((voice_code, warnings))
+    }
+
+    /// Voice code for a label that carries no LOT, the GTIN and pack date are hashed with
+    /// nothing between them
+    ///
+    /// This matches the reference calculator when its LOT field is left blank. `new` still
+    /// rejects an empty LOT, so a missing LOT in a data feed is reported rather than silently
+    /// hashed this way. The resulting `lot` is `Lot::none()` and the GS1 element string omits
+    /// AI(10).
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, VoiceCodeError, Gtin };
+    /// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+    /// let voice_code = HashVoiceCode::new_no_lot(&gtin, ("03", "01", "02")).unwrap();
+    /// assert_eq!(voice_code.hash_text, "12345678901244030102");
+    /// assert_eq!(voice_code.voice_code, HashVoiceCode::generate_voice_code_hash("12345678901244030102"));
+    /// assert!(voice_code.lot.is_empty());
+    /// assert!(matches!(HashVoiceCode::new(&gtin, "", "03", "01", "02"), Err(VoiceCodeError::InvalidLot { .. })));
+    ///
+    /// # #[cfg(feature = "chrono")] {
+    /// let date = voicecode::NaiveDate::from_ymd_opt(2003, 1, 2).unwrap();
+    /// assert_eq!(HashVoiceCode::new_no_lot(&gtin, date).unwrap(), voice_code);
+    /// # }
+    /// ```
+    pub fn new_no_lot<G>(gtin: G, pack_date: impl Into<PackDateInput>) -> Result<Self, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        VoiceCodeError: From<G::Error>,
+    {
+        let pack_date = pack_date.into().resolve(YearWindow::default())?;
+        let gtin: Gtin = gtin.try_into()?;
+
+        Ok(Self::from_parts(gtin, Lot::none(), pack_date))
+    }
+
+    /// Recompute the voice code and compare it with `printed_code`, as read off a label
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// use voicecode::verify::VerifyResult;
+    /// let result = HashVoiceCode::verify("12345678901244", "LOT123", ("03", "01", "02"), "6991");
+    /// assert_eq!(result, VerifyResult::Match);
+    ///
+    /// let result = HashVoiceCode::verify("12345678901244", "LOT123", ("03", "01", "02"), "9169");
+    /// assert_eq!(result, VerifyResult::Mismatch { expected: "6991".to_string(), printed: "9169".to_string() });
+    /// ```
+    pub fn verify(
+        gtin: impl AsRef<str>,
+        lot: impl AsRef<str>,
+        pack_date: impl Into<PackDateInput>,
+        printed_code: &str,
+    ) -> VerifyResult {
+        HashVoiceCode::builder()
+            .gtin(gtin)
+            .lot(lot)
+            .pack_date(pack_date)
+            .build()
+            .map(|voice_code| VerifyResult::compare(&voice_code.voice_code, printed_code))
+            .into()
+    }
+
+    /// Start a `HashVoiceCodeBuilder`
+    pub fn builder() -> HashVoiceCodeBuilder {
+        HashVoiceCodeBuilder::new()
+    }
+
+    /// Create a new HashVoiceCode struct from already validated parts
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin, HashVoiceCode, Lot, PackDate };
+    /// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+    /// let lot = Lot::parse("LOT123").unwrap();
+    /// let pack_date = PackDate::from_parts("03", "01", "02").unwrap();
+    /// let voice_code = HashVoiceCode::from_parts(gtin, lot, pack_date);
+    /// assert_eq!(voice_code.voice_code, "6991");
+    /// ```
+    pub fn from_parts(gtin: Gtin, lot: Lot, pack_date: PackDate) -> Self {
+        Self::from_parts_with_padding(gtin, lot, pack_date, DatePadding::default())
+    }
+
+    /// Same as `from_parts` with a choice of how the date goes into the hashed text
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin, HashVoiceCode, Lot, PackDate };
+    /// use voicecode::pack_date::DatePadding;
+    /// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+    /// let lot = Lot::parse("LOT123").unwrap();
+    /// let pack_date = PackDate::from_parts("3", "1", "2").unwrap();
+    /// let voice_code = HashVoiceCode::from_parts_with_padding(gtin, lot, pack_date, DatePadding::ZeroPad);
+    /// assert_eq!(voice_code.hash_text, "12345678901244LOT123030102");
+    /// assert_eq!(voice_code.voice_code, "6991");
+    /// ```
+    pub fn from_parts_with_padding(gtin: Gtin, lot: Lot, pack_date: PackDate, padding: DatePadding) -> Self {
+        Self::from_parts_hashed_by(gtin, lot, pack_date, padding, |text| hash_u16(text.as_bytes()))
+    }
+
+    /// Same as `from_parts_with_padding` with the code from `algorithm`, which the `with_`
+    /// methods hash again with
+    pub(crate) fn from_parts_with_algorithm(
+        gtin: Gtin,
+        lot: Lot,
+        pack_date: PackDate,
+        padding: DatePadding,
+        algorithm: Arc<dyn VoiceCodeAlgorithm>,
+    ) -> Self {
+        let code = algorithm.code(&gtin, &lot, &pack_date.padded(padding));
+        debug_assert!(code < VOICE_CODE_MODULUS, "{:?} returned {}", algorithm, code);
+        let mut voice_code = Self::from_parts_hashed_by(gtin, lot, pack_date, padding, |_| code);
+        voice_code.algorithm = Some(algorithm);
+        voice_code
+    }
+
+    /// Same as `from_parts_with_padding` with `hash` turning the hashed text into the code,
+    /// reduced modulo 10000 so it always prints as four digits
+    fn from_parts_hashed_by<H>(gtin: Gtin, lot: Lot, pack_date: PackDate, padding: DatePadding, hash: H) -> Self
+    where
+        H: FnOnce(&str) -> u16,
+    {
+        let hash_text = format!("{}{}{}", gtin.as_str(), lot.as_str(), pack_date.hash_text(padding));
+        let code = hash(&hash_text) % VOICE_CODE_MODULUS;
+        let voice_code = format!("{:04}", code);
+
+        let voice_code_major = voice_code[2..].to_string();
+        let voice_code_minor = voice_code[..2].to_string();
+
+        HashVoiceCode {
+            hash_text,
+            gtin,
+            lot,
+            pack_date,
+            padding,
+            lot_case: LotCase::Preserve,
+            algorithm: None,
+            code,
+            voice_code,
+            voice_code_major,
+            voice_code_minor,
         }
+    }
+
+    /// Record the normalization that produced `lot`, the LOT itself is not changed
+    pub(crate) fn recorded_lot_case(mut self, lot_case: LotCase) -> Self {
+        self.lot_case = lot_case;
+        self
+    }
+
+    /// Same GTIN and LOT with another pack date, neither is validated again
+    ///
+    /// The date is hashed with the same `DatePadding` and algorithm this code was built with,
+    /// the same goes for `with_lot` and `with_gtin`.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, PackDate, Gtin };
+    /// let monday = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "01").unwrap();
+    /// let tuesday = monday.with_date(PackDate::from_parts("03", "01", "02").unwrap());
+    /// assert_eq!(tuesday.voice_code, "6991");
+    /// ```
+    pub fn with_date(&self, pack_date: PackDate) -> Self {
+        self.rehashed(self.gtin.clone(), self.lot.clone(), pack_date).recorded_lot_case(self.lot_case)
+    }
+
+    /// Same GTIN and pack date with another LOT, hashed as given so `lot_case` is `Preserve`
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Lot, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT122", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.with_lot(Lot::parse("LOT123").unwrap()).voice_code, "6991");
+    /// ```
+    pub fn with_lot(&self, lot: Lot) -> Self {
+        self.rehashed(self.gtin.clone(), lot, self.pack_date.clone())
+    }
+
+    /// Same LOT and pack date with another GTIN
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ Gtin, HashVoiceCode };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.with_gtin(Gtin::parse_unchecked("12345678901244").unwrap()).voice_code, "6991");
+    /// ```
+    pub fn with_gtin(&self, gtin: Gtin) -> Self {
+        self.rehashed(gtin, self.lot.clone(), self.pack_date.clone()).recorded_lot_case(self.lot_case)
+    }
 
-        if !Self::validate_gtin(gtin) {
-            return Err("GTIN must be numeric 14 digits");
+    /// Hash other parts the way this code was hashed
+    fn rehashed(&self, gtin: Gtin, lot: Lot, pack_date: PackDate) -> Self {
+        match &self.algorithm {
+            Some(algorithm) => Self::from_parts_with_algorithm(gtin, lot, pack_date, self.padding, algorithm.clone()),
+            None => Self::from_parts_with_padding(gtin, lot, pack_date, self.padding),
         }
+    }
 
-        let yy = format!("{:0>2}", pack_date_yy);
-        let mm = format!("{:0>2}", pack_date_mm);
-        let dd = format!("{:0>2}", pack_date_dd);
+    /// Create a new HashVoiceCode struct from a GS1-128 scan carrying AI(01), AI(10) and AI(13)
+    ///
+    /// Accepts the bracketed human readable form or raw scanner data with GS (ASCII 29)
+    /// separators and an optional symbology identifier such as `]C1`
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// let voice_code = HashVoiceCode::from_gs1_scan("(01)10614141007346(13)030102(10)LOT123").unwrap();
+    /// assert_eq!(voice_code.voice_code, "9471");
+    ///
+    /// let voice_code = HashVoiceCode::from_gs1_scan("]C1011061414100734613030102\x1d10LOT123\r\n").unwrap();
+    /// assert_eq!(voice_code.voice_code, "9471");
+    /// ```
+    pub fn from_gs1_scan(scan: &str) -> Result<Self, VoiceCodeError> {
+        scan::parse_scan(scan)?.voice_code()
+    }
+
+    /// Create a new HashVoiceCode struct from a GS1 Digital Link URI carrying AI(01), AI(10) and AI(13)
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// let voice_code = HashVoiceCode::from_digital_link("https://id.gs1.org/01/10614141007346/10/LOT123?13=030102").unwrap();
+    /// assert_eq!(voice_code.voice_code, "9471");
+    /// ```
+    pub fn from_digital_link(uri: &str) -> Result<Self, VoiceCodeError> {
+        Gs1ElementString::from_digital_link(uri)?.voice_code()
+    }
+
+    /// GS1 Digital Link URI for this case on the resolver `base`, such as `https://id.gs1.org`
+    ///
+    /// The path carries the GTIN as hashed, so `from_digital_link` gives back this code.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.to_digital_link("https://id.gs1.org"), "https://id.gs1.org/01/12345678901244/10/LOT123?13=030102");
+    /// ```
+    pub fn to_digital_link(&self, base: &str) -> String {
+        Gs1ElementString::from(self)
+            .to_digital_link(base)
+            .expect("element string built from a voice code always has AI(01)")
+    }
 
-        let hash_text = format!("{}{}{}{}{}", gtin, lot, pack_date_yy, pack_date_mm, pack_date_dd);
-        let voice_code = HashVoiceCode::generate_voice_code_hash(&hash_text);
+    /// Fields that identify a voice code for `Eq`, `Hash` and `Ord`, the pack date zero padded
+    /// so `"3", "1", "2"` and `"03", "01", "02"` are the same date
+    fn key(&self) -> (&Gtin, &Lot, PackDate, &str) {
+        (&self.gtin, &self.lot, self.pack_date.padded(DatePadding::ZeroPad), &self.hash_text)
+    }
 
-        Ok(HashVoiceCode {
-            hash_text,
-            gtin: gtin.to_string(),
-            lot: lot.to_string(),
-            pack_date: format!("{}{}{}", yy, mm, dd),
-            voice_code: voice_code.clone(),
-            voice_code_major: voice_code[2..].to_string(),
-            voice_code_minor: voice_code[..2].to_string(),
-        })
+    /// Pack date the code was computed from
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "1", "2").unwrap();
+    /// assert_eq!(voice_code.pack_date().yymmdd(), "030102");
+    /// assert_eq!(voice_code.pack_date().calendar_date(), Some((2003, 1, 2)));
+    /// ```
+    pub fn pack_date(&self) -> PackDate {
+        self.pack_date.clone()
+    }
+
+    /// The code as a `VoiceCode`, for storing without the inputs
+    pub fn code(&self) -> VoiceCode {
+        VoiceCode(self.code)
+    }
+
+    /// How the date parts went into `hash_text`, `ZeroPad` unless set with
+    /// `HashVoiceCodeBuilder::date_padding` or `from_parts_with_padding`
+    pub fn date_padding(&self) -> DatePadding {
+        self.padding
+    }
+
+    /// Case normalization applied to the LOT before hashing, `Preserve` unless set with
+    /// `HashVoiceCodeBuilder::lot_case`
+    pub fn lot_case(&self) -> LotCase {
+        self.lot_case
+    }
+
+    /// CRC-16/ARC of `hash_text`, the value the voice code is the last four decimal digits of
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.raw_crc() % 10000, voice_code.voice_code_u16());
+    /// ```
+    pub fn raw_crc(&self) -> u16 {
+        crc_u16(self.hash_text.as_bytes())
+    }
+
+    /// Byte by byte trace of how `hash_text` became this code
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// let explanation = voice_code.explain();
+    /// assert_eq!(explanation.crc, voice_code.raw_crc());
+    /// println!("{}", explanation);
+    /// ```
+    pub fn explain(&self) -> Explanation {
+        Explanation::new(&self.hash_text)
+    }
+
+    /// Voice code as a number from 0 to 9999
+    ///
+    /// Kept from when the code was computed, the numeric accessors never reparse the public
+    /// `voice_code` string.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap();
+    /// assert_eq!(voice_code.voice_code, "0065");
+    /// assert_eq!(voice_code.voice_code_u16(), 65);
+    /// ```
+    pub fn voice_code_u16(&self) -> u16 {
+        self.code
+    }
+
+    /// The four digits of the voice code, most significant first
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.digits(), [6, 9, 9, 1]);
+    /// ```
+    pub fn digits(&self) -> [u8; 4] {
+        [1000, 100, 10, 1].map(|place| (self.code / place % 10) as u8)
+    }
+
+    /// Minor and major halves as numbers, the small and large printed digits
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.minor_major(), (69, 91));
+    /// ```
+    pub fn minor_major(&self) -> (u8, u8) {
+        let [a, b, c, d] = self.digits();
+        (a * 10 + b, c * 10 + d)
+    }
+
+    /// Voice code as words for a voice picking prompt, minor pair then major pair
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.voice_code_spoken(), "six nine, nine one");
+    /// ```
+    pub fn voice_code_spoken(&self) -> String {
+        self.voice_code_spoken_with(&SpokenOptions::default())
+    }
+
+    /// Voice code in ICAO phonetic digits, minor pair then major pair
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", "03", "01", "02").unwrap();
+    /// assert_eq!(voice_code.voice_code_phonetic(), "six niner, niner wun");
+    /// ```
+    pub fn voice_code_phonetic(&self) -> String {
+        self.voice_code_spoken_with(&SpokenOptions { vocabulary: Vocabulary::Phonetic, ..SpokenOptions::default() })
+    }
+
+    /// Voice code as words with a choice of vocabulary and grouping, see `speech::spoken`
+    pub fn voice_code_spoken_with(&self, options: &SpokenOptions) -> String {
+        speech::spoken(self.digits(), options)
     }
 
     /// Create a new HashVoiceCode struct with date mm, dd and yy provided from NaiveDate
     ///
     /// # Example
     /// ```
-    /// use voicecode::{ HashVoiceCode };
+    /// use voicecode::{ HashVoiceCode, Gtin };
     /// let pack_date = chrono::NaiveDate::from_ymd_opt(2003, 1, 2);
     /// match pack_date {
     ///    Some(pack_date) => {
-    ///       let voice_code = HashVoiceCode::new_naive("12345678901244", "LOT123", pack_date).unwrap();
+    ///       let voice_code = HashVoiceCode::new_naive(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", pack_date).unwrap();
     ///       println!("Voice Code: {}", voice_code.voice_code); // expects 6991
     ///       println!("Minor: {}", voice_code.voice_code_minor); // expects 69
     ///       println!("Major: {}", voice_code.voice_code_major); // expects 91
@@ -140,15 +603,87 @@ impl HashVoiceCode {
     ///
     /// ```
     #[allow(dead_code)]
-    pub fn new_naive(gtin: &str, lot: &str, pack_date: NaiveDate) -> Result<Self, &'static str> {
-        let date_yy = pack_date.format("%y").to_string();
-        let date_mm = pack_date.format("%m").to_string();
-        let date_dd = pack_date.format("%d").to_string();
+    #[cfg(feature = "chrono")]
+    pub fn new_naive<G, L>(gtin: G, lot: L, pack_date: NaiveDate) -> Result<Self, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        L: TryInto<Lot>,
+        VoiceCodeError: From<G::Error> + From<L::Error>,
+    {
+        let lot: Lot = lot.try_into()?;
+        let gtin: Gtin = gtin.try_into()?;
+
+        Ok(Self::from_parts(gtin, lot, PackDate::from_naive(pack_date)))
+    }
+
+    /// Create a new HashVoiceCode struct with the pack date from a `time` crate date
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let pack_date = time::Date::from_calendar_date(2003, time::Month::January, 2).unwrap();
+    /// let voice_code = HashVoiceCode::new_from_time_date(Gtin::parse_unchecked("12345678901244").unwrap(), "LOT123", pack_date).unwrap();
+    /// assert_eq!(voice_code.voice_code, "6991");
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn new_from_time_date<G, L>(gtin: G, lot: L, pack_date: time::Date) -> Result<Self, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        L: TryInto<Lot>,
+        VoiceCodeError: From<G::Error> + From<L::Error>,
+    {
+        let lot: Lot = lot.try_into()?;
+        let gtin: Gtin = gtin.try_into()?;
 
-        Self::new(gtin, lot, &date_yy, &date_mm, &date_dd)
+        Ok(Self::from_parts(gtin, lot, PackDate::from_time_date(pack_date)))
     }
 
-    /// Validate a LOT string
+    /// Create a new HashVoiceCode struct from a date as text, `mm/dd/yyyy`, `yyyymmdd`,
+    /// ISO 8601 `yyyy-mm-dd` or `yymmdd`
+    ///
+    /// Digits that are a date both year first and month first, such as `010203`, are read
+    /// year first. Use `PackDate::parse_flexible` with another `DateAmbiguity` and the
+    /// builder to read them month first or reject them.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, Gtin };
+    /// let gtin = Gtin::parse_unchecked("12345678901244").unwrap();
+    /// let voice_code = HashVoiceCode::new_parse_date(&gtin, "LOT123", "01/02/2003").unwrap();
+    /// assert_eq!(voice_code.voice_code, "6991");
+    /// assert_eq!(HashVoiceCode::new_parse_date(&gtin, "LOT123", "2003-01-02").unwrap(), voice_code);
+    /// assert_eq!(HashVoiceCode::new_parse_date(&gtin, "LOT123", "030102").unwrap(), voice_code);
+    /// assert!(HashVoiceCode::new_parse_date(&gtin, "LOT123", "Jan 2 2003").is_err());
+    /// ```
+    pub fn new_parse_date<G, L>(gtin: G, lot: L, pack_date: &str) -> Result<Self, VoiceCodeError>
+    where
+        G: TryInto<Gtin>,
+        L: TryInto<Lot>,
+        VoiceCodeError: From<G::Error> + From<L::Error>,
+    {
+        let pack_date = PackDate::parse_flexible(pack_date, DateAmbiguity::default())?;
+        let lot: Lot = lot.try_into()?;
+        let gtin: Gtin = gtin.try_into()?;
+
+        Ok(Self::from_parts(gtin, lot, pack_date))
+    }
+
+    /// Check the same inputs as `new` and report every problem rather than the first
+    ///
+    /// See `ValidationReport::check` to apply a `ValidationPolicy`.
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// let report = HashVoiceCode::validate_all("1234", "", "03", "01", "02");
+    /// assert_eq!(report.violations.len(), 2);
+    /// assert_eq!(report.violations[1].code(), "lot_empty");
+    /// ```
+    pub fn validate_all(gtin: &str, lot: &str, pack_date_yy: &str, pack_date_mm: &str, pack_date_dd: &str) -> ValidationReport {
+        ValidationReport::check(&ValidationPolicy::default(), gtin, lot, pack_date_yy, pack_date_mm, pack_date_dd)
+    }
+
+    /// Validate a LOT string, see `Lot::parse` for the reason a LOT is rejected
     /// # Example
     /// ```
     /// use voicecode::{ HashVoiceCode };
@@ -156,18 +691,18 @@ impl HashVoiceCode {
     /// assert!(HashVoiceCode::validate_lot(lot));
     /// ```
     pub fn validate_lot(lot: &str) -> bool {
-        LOT_REGEX.is_match(lot)
+        Lot::parse(lot).is_ok()
     }
 
-    /// Validate a GTIN string
+    /// Validate a GTIN string the way `new` does, see `Gtin::parse` for the reason a GTIN is rejected
     /// # Example
     /// ```
     /// use voicecode::{ HashVoiceCode };
-    /// let gtin = "12345678901244";
-    /// assert!(HashVoiceCode::validate_gtin(gtin));
+    /// assert!(HashVoiceCode::validate_gtin("00614141007349"));
+    /// assert!(!HashVoiceCode::validate_gtin("00614141007348"));
     /// ```
     pub fn validate_gtin(gtin: &str) -> bool {
-        return gtin.chars().all(char::is_numeric) && (gtin.len() == 8 || gtin.len() == 12 || gtin.len() == 13 || gtin.len() == 14)
+        Gtin::parse(gtin).is_ok()
     }
 
     ///
@@ -204,12 +739,71 @@ impl HashVoiceCode {
     /// println!("Voice Code: {}", voice_code); // expects 6991
     /// assert_eq!(voice_code, "6991");
     /// ```
+    ///
+    /// see `hash_u16` and `hash_into` for variants that do not allocate
+    ///
+    /// non ASCII characters are hashed with `NonAsciiPolicy::RawScalar`, use
+    /// `generate_voice_code_hash_with` to choose
     pub fn generate_voice_code_hash(input: &str) -> String {
-        let mut output: u16 = 0;
-        for ch in input.chars() {
-            output = (output >> 8) ^ HASH_VOICE_CHECKSUM_HASH_T[((output ^ (ch as u16)) % 256) as usize];
+        Self::generate_voice_code_hash_with(input, NonAsciiPolicy::RawScalar).expect("RawScalar accepts any text")
+    }
+
+    /// Generate a voice code hash from a string, handling non ASCII characters as `policy` says
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode, NonAsciiPolicy, VoiceCodeError };
+    /// let voice_code = HashVoiceCode::generate_voice_code_hash_with("12345678901244LOT123030102", NonAsciiPolicy::Reject);
+    /// assert_eq!(voice_code.unwrap(), "6991");
+    ///
+    /// let err = HashVoiceCode::generate_voice_code_hash_with("LOTÉ", NonAsciiPolicy::Reject).unwrap_err();
+    /// assert_eq!(err, VoiceCodeError::NonAscii { position: 3 });
+    /// assert!(HashVoiceCode::generate_voice_code_hash_with("LOTÉ", NonAsciiPolicy::JsCompatUtf16).is_ok());
+    /// ```
+    pub fn generate_voice_code_hash_with(input: &str, policy: NonAsciiPolicy) -> Result<String, VoiceCodeError> {
+        Self::generate_voice_code_crc_with(input, policy).map(|crc| format!("{:04}", crc % VOICE_CODE_MODULUS))
+    }
+
+    /// CRC of a string before the `% 10000` that turns it into the voice code
+    ///
+    /// non ASCII characters are hashed with `NonAsciiPolicy::RawScalar` like
+    /// `generate_voice_code_hash`
+    ///
+    /// # Example
+    /// ```
+    /// use voicecode::{ HashVoiceCode };
+    /// let crc = HashVoiceCode::generate_voice_code_crc("12345678901244LOT123030102");
+    /// assert_eq!(crc % 10000, 6991);
+    /// ```
+    pub fn generate_voice_code_crc(input: &str) -> u16 {
+        Self::generate_voice_code_crc_with(input, NonAsciiPolicy::RawScalar).expect("RawScalar accepts any text")
+    }
+
+    /// CRC of a string before the modulo, handling non ASCII characters as `policy` says
+    pub fn generate_voice_code_crc_with(input: &str, policy: NonAsciiPolicy) -> Result<u16, VoiceCodeError> {
+        if input.is_ascii() {
+            return Ok(crc_u16(input.as_bytes()));
+        }
+
+        // only the low byte of each unit reaches the table, so hashing that byte is the same
+        let mut hasher = VoiceCodeHasher::new();
+        match policy {
+            NonAsciiPolicy::Reject => {
+                let position = input.bytes().position(|b| !b.is_ascii()).unwrap_or_default();
+                return Err(VoiceCodeError::NonAscii { position });
+            }
+            NonAsciiPolicy::JsCompatUtf16 => {
+                for unit in input.encode_utf16() {
+                    hasher.update(&[unit as u8]);
+                }
+            }
+            NonAsciiPolicy::RawScalar => {
+                for ch in input.chars() {
+                    hasher.update(&[ch as u32 as u8]);
+                }
+            }
         }
-        format!("{:04}", output % 10000)
+        Ok(hasher.crc())
     }
 }
 
@@ -218,30 +812,275 @@ impl fmt::Debug for HashVoiceCode {
         f.debug_struct("HashVoiceCode")
             .field("gtin", &self.gtin)
             .field("lot", &self.lot)
-            .field("pack_date", &self.pack_date)
+            .field("pack_date", &self.pack_date.yymmdd())
             .field("voice_code", &self.voice_code)
             .finish()
     }
 }
 
+/// Voice codes are equal when their GTIN, LOT and zero padded pack date are, the hashed
+/// text only tells apart the same inputs hashed with a different `DatePadding`
+impl PartialEq for HashVoiceCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for HashVoiceCode {}
+
+impl Hash for HashVoiceCode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Sorted by GTIN, then LOT, then pack date, the order a manifest lists cases in
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, Gtin };
+/// let mut codes = vec![
+///     HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT2", "03", "01", "02").unwrap(),
+///     HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT1", "03", "01", "03").unwrap(),
+///     HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT1", "03", "01", "02").unwrap(),
+/// ];
+/// codes.sort();
+/// let order: Vec<String> = codes.iter().map(|code| code.to_string()).collect();
+/// assert_eq!(order, ["61414100734933|LOT1|030102", "61414100734933|LOT1|030103", "61414100734933|LOT2|030102"]);
+/// ```
+impl Ord for HashVoiceCode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for HashVoiceCode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Canonical text form `GTIN|LOT|YYMMDD`, for storing a voice code as one string
+///
+/// The pack date is zero padded and `FromStr` verifies the GTIN check digit, so the round
+/// trip only reproduces a voice code built from zero padded date parts and a valid GTIN.
+///
+/// # Example
+/// ```
+/// use voicecode::HashVoiceCode;
+/// let voice_code = HashVoiceCode::new("00614141007349", "LOT123", "03", "01", "02").unwrap();
+/// assert_eq!(voice_code.to_string(), "00614141007349|LOT123|030102");
+/// ```
+impl fmt::Display for HashVoiceCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}|{}", self.gtin, self.lot, self.pack_date)
+    }
+}
+
+/// `Lot::none()` for an empty LOT written by a code from `new_no_lot`, otherwise `Lot::parse`
+fn lot_or_none(lot: &str) -> Result<Lot, VoiceCodeError> {
+    if lot.is_empty() {
+        return Ok(Lot::none());
+    }
+    Lot::parse(lot)
+}
+
+/// Parses the `GTIN|LOT|YYMMDD` form written by `Display` and recomputes the code, the
+/// pack date may also be YYYY-MM-DD, an empty LOT gives a `new_no_lot` code
+///
+/// The GTIN check digit is verified like `new` does.
+///
+/// # Example
+/// ```
+/// use voicecode::{ HashVoiceCode, VoiceCodeError };
+/// let voice_code: HashVoiceCode = "00614141007349|LOT123|030102".parse().unwrap();
+/// assert_eq!(voice_code.voice_code, "6307");
+/// assert_eq!("00614141007349|LOT123".parse::<HashVoiceCode>().unwrap_err(), VoiceCodeError::MissingField { field: "pack_date" });
+/// assert_eq!(
+///     "12345678901244|LOT123|030102".parse::<HashVoiceCode>().unwrap_err(),
+///     VoiceCodeError::InvalidGtinCheckDigit { expected: 8, found: 4 }
+/// );
+/// ```
+impl FromStr for HashVoiceCode {
+    type Err = VoiceCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '|');
+        let mut next = |field: &'static str| parts.next().ok_or(VoiceCodeError::MissingField { field });
+        let gtin = Gtin::parse(next("gtin")?)?;
+        let lot = lot_or_none(next("lot")?)?;
+        let pack_date = next("pack_date")?.parse()?;
+        Ok(HashVoiceCode::from_parts(gtin, lot, pack_date))
+    }
+}
+
+/// Serialized as `gtin`, `lot`, `pack_date` (YYMMDD), `voice_code`, `major` and `minor`
+#[cfg(feature = "serde")]
+impl serde::Serialize for HashVoiceCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("HashVoiceCode", 6)?;
+        state.serialize_field("gtin", &self.gtin)?;
+        state.serialize_field("lot", &self.lot)?;
+        state.serialize_field("pack_date", &self.pack_date)?;
+        state.serialize_field("voice_code", &self.voice_code)?;
+        state.serialize_field("major", &self.voice_code_major)?;
+        state.serialize_field("minor", &self.voice_code_minor)?;
+        state.end()
+    }
+}
+
+/// Deserializing recomputes the code, `voice_code`, `major` and `minor` are optional
+/// and rejected when they disagree with the recomputed values, the GTIN check digit is
+/// verified like `FromStr` does
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HashVoiceCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Record {
+            gtin: Gtin,
+            lot: String,
+            pack_date: PackDate,
+            voice_code: Option<String>,
+            major: Option<String>,
+            minor: Option<String>,
+        }
+
+        let record = Record::deserialize(deserializer)?;
+        let lot = lot_or_none(&record.lot).map_err(serde::de::Error::custom)?;
+        let result = HashVoiceCode::from_parts(record.gtin, lot, record.pack_date);
+        let checks = [
+            ("voice_code", record.voice_code, &result.voice_code),
+            ("major", record.major, &result.voice_code_major),
+            ("minor", record.minor, &result.voice_code_minor),
+        ];
+        for (field, provided, expected) in checks {
+            if let Some(provided) = provided {
+                if &provided != expected {
+                    return Err(serde::de::Error::custom(format!("{} {} does not match computed {}", field, provided, expected)));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The four digit code on its own, for a column or field stored next to the GTIN, LOT and
+/// pack date it was computed from
+///
+/// # Example
+/// ```
+/// use voicecode::{ Gtin, HashVoiceCode, VoiceCode };
+/// let voice_code = HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "LOT123", "03", "01", "10").unwrap();
+/// assert_eq!(voice_code.code(), "0065".parse::<VoiceCode>().unwrap());
+/// assert_eq!(voice_code.code().to_string(), voice_code.voice_code);
+/// assert_eq!((voice_code.code().minor(), voice_code.code().major()), (0, 65));
+/// assert!(VoiceCode::parse("65").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+#[cfg_attr(feature = "diesel", derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
+pub struct VoiceCode(u16);
+
+impl VoiceCode {
+    /// Code from a number from 0 to 9999
+    pub fn new(code: u16) -> Result<Self, VoiceCodeError> {
+        if code >= VOICE_CODE_MODULUS {
+            return Err(VoiceCodeError::InvalidVoiceCode);
+        }
+        Ok(VoiceCode(code))
+    }
+
+    /// Code from exactly four ASCII digits, as printed
+    pub fn parse(code: &str) -> Result<Self, VoiceCodeError> {
+        if code.len() != 4 || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(VoiceCodeError::InvalidVoiceCode);
+        }
+        Ok(VoiceCode(code.bytes().fold(0, |code, b| code * 10 + u16::from(b - b'0'))))
+    }
+
+    /// The code as a number from 0 to 9999
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+
+    /// The four digits, most significant first
+    pub fn digits(&self) -> [u8; 4] {
+        [1000, 100, 10, 1].map(|place| (self.0 / place % 10) as u8)
+    }
+
+    /// First two digits, printed small
+    pub fn minor(&self) -> u8 {
+        (self.0 / 100) as u8
+    }
+
+    /// Last two digits, printed large
+    pub fn major(&self) -> u8 {
+        (self.0 % 100) as u8
+    }
+}
+
+impl fmt::Display for VoiceCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.0)
+    }
+}
+
+impl FromStr for VoiceCode {
+    type Err = VoiceCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VoiceCode::parse(s)
+    }
+}
+
+impl TryFrom<&str> for VoiceCode {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        VoiceCode::parse(value)
+    }
+}
+
+impl TryFrom<String> for VoiceCode {
+    type Error = VoiceCodeError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        VoiceCode::parse(&value)
+    }
+}
+
+impl From<VoiceCode> for String {
+    fn from(value: VoiceCode) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<&HashVoiceCode> for VoiceCode {
+    fn from(value: &HashVoiceCode) -> Self {
+        value.code()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::DateComponent;
+    use crate::gtin::GtinError;
+    use crate::lot::LotError;
 
-    fn parse_date(input: &str) -> Result<NaiveDate, chrono::format::ParseError> {
-        let formats = vec!["%m/%d/%Y", "%m%d%Y", "%Y-%m-%d", "%+"];
-        for format in formats {
-            if let Ok(date) = NaiveDate::parse_from_str(input, format) {
-                return Ok(date);
-            }
-        }
-        NaiveDate::parse_from_str(input, "")
+    /// GTIN of the original test vectors, its check digit should be 1
+    fn gtin() -> Gtin {
+        Gtin::parse_unchecked("61414100734933").unwrap()
     }
 
     #[test]
+    #[cfg(feature = "chrono")]
     fn test_chrono() {
         if let Some(date) = chrono::NaiveDate::from_ymd_opt(2003, 1, 2) {
-            let voice_code = HashVoiceCode::new_naive("12345678901234", "LOT123", date);
+            let voice_code = HashVoiceCode::new_naive(Gtin::parse_unchecked("12345678901234").unwrap(), "LOT123", date);
             match voice_code {
                 Ok(voice_code) => {
                     println!("Voice Code: {}", voice_code.voice_code); // expects 6991
@@ -249,8 +1088,7 @@ mod tests {
                     println!("Major: {}", voice_code.voice_code_major); // expects 91
                 }
                 Err(e) => {
-                    println!("Error: {}", e);
-                    assert!(false);
+                    panic!("Error: {}", e);
                 }
             }
         }
@@ -264,12 +1102,14 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "chrono")]
     fn test_naive_date() {
-        let gtin = "61414100734933";
+        let gtin = &gtin();
         let lot = "32ABCD";
-        let pack_date = parse_date("01/01/2001").unwrap();
+        let pack_date = NaiveDate::from_ymd_opt(2001, 1, 1).unwrap();
 
         let hash_voice_code = HashVoiceCode::new_naive(gtin, lot, pack_date).unwrap();
+        assert_eq!(HashVoiceCode::new_parse_date(gtin, lot, "01/01/2001").unwrap(), hash_voice_code);
 
         assert_eq!(hash_voice_code.voice_code, "1085");
         assert_eq!(hash_voice_code.voice_code_minor, "10");
@@ -280,7 +1120,7 @@ mod tests {
     fn test1() {
         // basic sanity test
 
-        let gtin = "61414100734933";
+        let gtin = &gtin();
         let lot = "32ABCD";
         let pack_date_yy = "01";
         let pack_date_mm = "01";
@@ -297,7 +1137,7 @@ mod tests {
     fn test2() {
         // these are case sensitive
 
-        let gtin = "61414100734933";
+        let gtin = &gtin();
         let lot = "32abcd";
         let pack_date_yy = "03";
         let pack_date_mm = "01";
@@ -314,7 +1154,7 @@ mod tests {
     fn test2_1() {
         // these are case sensitive, so this should not match test2 as lot is ABCD instead of abcd
 
-        let gtin = "61414100734933";
+        let gtin = &gtin();
         let lot = "32ABCD";
         let pack_date_yy = "03";
         let pack_date_mm = "01";
@@ -329,7 +1169,7 @@ mod tests {
 
     #[test]
     fn test3() {
-        let gtin = "61414100734933";
+        let gtin = &gtin();
         let lot = "32abcd";
         let pack_date_yy = "03";
         let pack_date_mm = "01";
@@ -344,20 +1184,246 @@ mod tests {
 
     #[test]
     fn test_invalid_month() {
-        let result = HashVoiceCode::new("61414100734933", "32abcd", "03", "mm", "03");
+        let result = HashVoiceCode::new(gtin(), "32abcd", "03", "mm", "03");
         assert!(result.is_err());
     }
 
     #[test]
     fn test_invalid_day() {
-        let result = HashVoiceCode::new("61414100734933", "32abcd", "03", "02", "dd");
+        let result = HashVoiceCode::new(gtin(), "32abcd", "03", "02", "dd");
         assert!(result.is_err());
     }
 
     #[test]
     fn test_invalid_year() {
-        let result = HashVoiceCode::new("61414100734933", "32abcd", "yy", "01", "02");
+        let result = HashVoiceCode::new(gtin(), "32abcd", "yy", "01", "02");
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_date_padding() {
+        let unpadded = HashVoiceCode::new(gtin(), "32abcd", "3", "1", "2").unwrap();
+        let padded = HashVoiceCode::new(gtin(), "32abcd", "03", "01", "02").unwrap();
+        assert_eq!(unpadded.hash_text, "6141410073493332abcd030102");
+        assert_eq!(unpadded.voice_code, padded.voice_code);
+
+        // the concatenation versions before 0.3 hashed
+        let raw = HashVoiceCode::from_parts_with_padding(
+            unpadded.gtin.clone(),
+            unpadded.lot.clone(),
+            unpadded.pack_date().clone(),
+            DatePadding::Raw,
+        );
+        assert_eq!(raw.hash_text, "6141410073493332abcd312");
+        assert_ne!(raw.voice_code, padded.voice_code);
+    }
+
+    #[test]
+    fn test_non_ascii_policy() {
+        // U+00E9 is one UTF-16 unit, U+1F600 is the surrogate pair D83D DE00
+        for text in ["LOT\u{e9}", "LOT\u{1f600}"] {
+            let err = HashVoiceCode::generate_voice_code_hash_with(text, NonAsciiPolicy::Reject).unwrap_err();
+            assert_eq!(err, VoiceCodeError::NonAscii { position: 3 });
+        }
+        let js = |text| HashVoiceCode::generate_voice_code_hash_with(text, NonAsciiPolicy::JsCompatUtf16).unwrap();
+        let raw = |text| HashVoiceCode::generate_voice_code_hash_with(text, NonAsciiPolicy::RawScalar).unwrap();
+        assert_eq!(js("LOT\u{e9}"), raw("LOT\u{e9}"));
+        assert_eq!(js("LOT\u{1f600}"), HashVoiceCode::generate_voice_code_hash("LOT\u{3d}\u{0}"));
+        assert_eq!(raw("LOT\u{1f600}"), HashVoiceCode::generate_voice_code_hash("LOT\u{0}"));
+        assert_eq!(raw("LOT\u{1f600}"), HashVoiceCode::generate_voice_code_hash("LOT\u{1f600}"));
+
+        assert!(HashVoiceCode::new(gtin(), "32abcd", "03", "\u{0663}", "02").is_err());
+    }
+
+    #[test]
+    fn test_new_strict() {
+        let err = HashVoiceCode::new_strict(gtin(), "32abcd", "99", "99", "99").unwrap_err();
+        assert_eq!(err, VoiceCodeError::InvalidCalendarDate { yymmdd: "999999".to_string() });
+        assert!(HashVoiceCode::new_strict(gtin(), "32abcd", "24", "2", "29").is_ok());
+        assert!(HashVoiceCode::new_strict(gtin(), "32abcd", "23", "2", "29").is_err());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let err = HashVoiceCode::new(gtin(), "32abcd", "03", "123", "02").unwrap_err();
+        assert_eq!(err, VoiceCodeError::InvalidDateComponent { which: DateComponent::Month });
+        assert_eq!(err.to_string(), "Date component MM must be numeric and 1 or 2 digits");
+
+        let err = HashVoiceCode::new(Gtin::parse_unchecked("6141410073493").unwrap(), "", "03", "01", "02").unwrap_err();
+        assert_eq!(err, VoiceCodeError::InvalidLot { reason: LotError::Empty });
+
+        let err = HashVoiceCode::new(Gtin::parse_unchecked("6141410073493").unwrap(), "32#ABCD", "03", "01", "02").unwrap_err();
+        assert_eq!(err, VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: '#', offset: 2 } });
+
+        let err = HashVoiceCode::new("6141410073", "32ABCD", "03", "01", "02").unwrap_err();
+        assert_eq!(err, VoiceCodeError::InvalidGtin { reason: GtinError::Length { len: 10 } });
+        assert_eq!(err.to_string(), "GTIN must be 8, 12, 13 or 14 digits, got 10");
+
+        let err = HashVoiceCode::new("61414100734933", "32ABCD", "03", "01", "02").unwrap_err();
+        assert_eq!(err, VoiceCodeError::InvalidGtinCheckDigit { expected: 1, found: 3 });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let voice_code = HashVoiceCode::new("00614141007349", "LOT123", "03", "01", "02").unwrap();
+        let json = serde_json::to_string(&voice_code).unwrap();
+        assert_eq!(
+            json,
+            r#"{"gtin":"00614141007349","lot":"LOT123","pack_date":"030102","voice_code":"6307","major":"07","minor":"63"}"#
+        );
+        let parsed: HashVoiceCode = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.voice_code, "6307");
+
+        let parsed: HashVoiceCode = serde_json::from_str(r#"{"gtin":"00614141007349","lot":"LOT123","pack_date":"2003-01-02"}"#).unwrap();
+        assert_eq!(parsed.voice_code, "6307");
+
+        assert!(serde_json::from_str::<HashVoiceCode>(r#"{"gtin":"00614141007349","lot":"LOT123","pack_date":"030102","voice_code":"1234"}"#).is_err());
+        assert!(serde_json::from_str::<HashVoiceCode>(r#"{"gtin":"1234","lot":"LOT123","pack_date":"030102"}"#).is_err());
+        assert!(serde_json::from_str::<HashVoiceCode>(r#"{"gtin":"12345678901244","lot":"LOT123","pack_date":"030102"}"#).is_err());
+        assert!(serde_json::from_str::<HashVoiceCode>(r#"{"gtin":"00614141007349","lot":"LOT 123","pack_date":"030102"}"#).is_err());
+    }
+
+    #[test]
+    fn test_typed_gtin() {
+        let gtin = Gtin::parse("00614141007349").unwrap();
+        let from_typed = HashVoiceCode::new(&gtin, "32ABCD", "01", "01", "01").unwrap();
+        let from_str = HashVoiceCode::new("00614141007349", "32ABCD", "01", "01", "01").unwrap();
+        assert_eq!(from_typed.voice_code, from_str.voice_code);
+        assert_eq!(from_typed.gtin, "00614141007349");
+
+        // a bad check digit only hashes through parse_unchecked
+        assert!(HashVoiceCode::new(Gtin::parse_unchecked("61414100734933").unwrap(), "32ABCD", "01", "01", "01").is_ok());
+        assert!(HashVoiceCode::new("61414100734933", "32ABCD", "01", "01", "01").is_err());
+    }
+
+    #[test]
+    fn test_canonical_string() {
+        let voice_code = HashVoiceCode::new("00614141007349", "32abcd", "03", "01", "02").unwrap();
+        let parsed: HashVoiceCode = voice_code.to_string().parse().unwrap();
+        assert_eq!(parsed.hash_text, voice_code.hash_text);
+        assert_eq!(parsed.voice_code, voice_code.voice_code);
+
+        let bad_check_digit = HashVoiceCode::new(gtin(), "32abcd", "03", "01", "02").unwrap();
+        assert_eq!(bad_check_digit.to_string().parse::<HashVoiceCode>().unwrap_err(), VoiceCodeError::InvalidGtinCheckDigit { expected: 1, found: 3 });
+
+        assert_eq!("".parse::<HashVoiceCode>().unwrap_err(), VoiceCodeError::InvalidGtin { reason: GtinError::Length { len: 0 } });
+        assert_eq!("00614141007349".parse::<HashVoiceCode>().unwrap_err(), VoiceCodeError::MissingField { field: "lot" });
+        assert_eq!(
+            "00614141007349|32 abcd|030102".parse::<HashVoiceCode>().unwrap_err(),
+            VoiceCodeError::InvalidLot { reason: LotError::InvalidCharacter { ch: ' ', offset: 2 } }
+        );
+        assert!("00614141007349|32abcd|030102|x".parse::<HashVoiceCode>().is_err());
+    }
+
+    #[test]
+    fn test_no_lot() {
+        let voice_code = HashVoiceCode::new_no_lot(gtin(), ("03", "01", "02")).unwrap();
+        assert_eq!(voice_code.hash_text, "61414100734933030102");
+        assert_eq!(voice_code.voice_code, "7010");
+        assert_eq!(voice_code.lot, "");
+
+        assert_eq!(voice_code.to_string(), "61414100734933||030102");
+        let checked = HashVoiceCode::new_no_lot("00614141007349", ("03", "01", "02")).unwrap();
+        assert_eq!(checked.to_string().parse::<HashVoiceCode>().unwrap(), checked);
+        assert_eq!(Gs1ElementString::from(&voice_code).to_string(), "(01)61414100734933(13)030102");
+
+        assert_eq!(
+            HashVoiceCode::new_no_lot("6141410073", ("03", "01", "02")).unwrap_err(),
+            VoiceCodeError::InvalidGtin { reason: GtinError::Length { len: 10 } }
+        );
+        assert!(matches!(HashVoiceCode::new_no_lot(gtin(), ("03", "1x", "02")), Err(VoiceCodeError::InvalidDateComponent { .. })));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_no_lot_serde() {
+        let voice_code = HashVoiceCode::new_no_lot("00614141007349", ("03", "01", "02")).unwrap();
+        let json = serde_json::to_string(&voice_code).unwrap();
+        assert!(json.contains(r#""lot":"""#));
+        assert_eq!(serde_json::from_str::<HashVoiceCode>(&json).unwrap(), voice_code);
+    }
+
+    #[test]
+    fn test_numeric_accessors() {
+        let mut voice_code = HashVoiceCode::new(gtin(), "LOT123", "03", "01", "10").unwrap();
+        assert_eq!((voice_code.voice_code_u16(), voice_code.digits(), voice_code.minor_major()), (65, [0, 0, 6, 5], (0, 65)));
+        voice_code.voice_code = "é".to_string();
+        assert_eq!(voice_code.digits(), [0, 0, 6, 5]);
+    }
+
+    #[test]
+    fn test_voice_code_newtype() {
+        for (text, code) in [("0000", 0), ("0065", 65), ("6991", 6991), ("9999", 9999)] {
+            let parsed: VoiceCode = text.parse().unwrap();
+            assert_eq!((parsed, parsed.to_string()), (VoiceCode::new(code).unwrap(), text.to_string()));
+        }
+        for text in ["", "065", "00065", "6 91", "+991", "\u{663}991"] {
+            assert_eq!(VoiceCode::parse(text).unwrap_err(), VoiceCodeError::InvalidVoiceCode, "{}", text);
+        }
+        assert_eq!(VoiceCode::new(10000).unwrap_err().code(), "voice_code_format");
+
+        let voice_code = HashVoiceCode::new(gtin(), "LOT123", "03", "01", "02").unwrap();
+        let code = VoiceCode::from(&voice_code);
+        assert_eq!((code.value(), code.digits(), code.minor(), code.major()), (voice_code.voice_code_u16(), voice_code.digits(), 24, 81));
+        assert_eq!(code.to_string(), voice_code.voice_code);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_voice_code_newtype_serde() {
+        let code = VoiceCode::new(65).unwrap();
+        let json = serde_json::to_string(&code).unwrap();
+        assert_eq!(json, r#""0065""#);
+        assert_eq!(serde_json::from_str::<VoiceCode>(&json).unwrap(), code);
+        assert!(serde_json::from_str::<VoiceCode>(r#""065""#).is_err());
+    }
+
+    #[test]
+    fn test_with_keeps_padding() {
+        let padded = HashVoiceCode::new(gtin(), "32abcd", "3", "1", "2").unwrap();
+        let raw = HashVoiceCode::from_parts_with_padding(padded.gtin.clone(), padded.lot.clone(), padded.pack_date(), DatePadding::Raw);
+        let next_day = PackDate::from_parts("3", "1", "3").unwrap();
+
+        assert_eq!(raw.with_date(next_day.clone()).hash_text, "6141410073493332abcd313");
+        assert_eq!(padded.with_date(next_day).hash_text, "6141410073493332abcd030103");
+        let lot = Lot::parse("32abce").unwrap();
+        assert_eq!(padded.with_lot(lot.clone()), HashVoiceCode::from_parts_with_padding(raw.gtin.clone(), lot, raw.pack_date(), DatePadding::ZeroPad));
+
+        // already two digit parts hash the same either way, the padding is still kept
+        let two_digit = HashVoiceCode::new(gtin(), "32abcd", "03", "01", "02").unwrap();
+        assert_eq!((two_digit.date_padding(), raw.date_padding()), (DatePadding::ZeroPad, DatePadding::Raw));
+        assert_eq!(two_digit.with_date(PackDate::from_parts("3", "1", "3").unwrap()).hash_text, "6141410073493332abcd030103");
+        assert_eq!(two_digit.with_gtin(gtin()).date_padding(), DatePadding::ZeroPad);
+    }
+
+    #[test]
+    fn test_dedupe_and_order() {
+        use std::collections::HashSet;
+
+        let a = HashVoiceCode::new(gtin(), "32abcd", "03", "01", "02").unwrap();
+        let b = HashVoiceCode::new_parse_date(gtin(), "32abcd", "2003-01-02").unwrap();
+        let set: HashSet<HashVoiceCode> = [a.clone(), b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+
+        // same inputs, different hashed text
+        let padded = HashVoiceCode::new(gtin(), "32abcd", "3", "1", "2").unwrap();
+        let raw = HashVoiceCode::from_parts_with_padding(padded.gtin.clone(), padded.lot.clone(), padded.pack_date(), DatePadding::Raw);
+        assert_ne!(raw, padded);
+
+        // one digit parts are the same date once padded, equal, hashed alike and round tripped
+        let short = HashVoiceCode::new(gtin(), "32abcd", "3", "1", "2").unwrap();
+        assert_eq!(short, a);
+        let set: HashSet<HashVoiceCode> = [short.clone(), a.clone()].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert_eq!(short.cmp(&a), Ordering::Equal);
+        let valid = HashVoiceCode::new("00614141007349", "32abcd", "3", "1", "2").unwrap();
+        assert_eq!(valid.to_string().parse::<HashVoiceCode>().unwrap(), valid);
+
+        let earlier = HashVoiceCode::new(gtin(), "32abcd", "99", "12", "31").unwrap();
+        let legacy = HashVoiceCode::new(gtin(), "32abcd", "99", "99", "99").unwrap();
+        let mut codes = vec![a.clone(), earlier.clone(), legacy.clone()];
+        codes.sort();
+        assert_eq!(codes, vec![legacy, earlier, a]);
+    }
 }
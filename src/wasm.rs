@@ -0,0 +1,100 @@
+//! WebAssembly bindings, a drop in replacement for the producetraceability.org voicecodewidget.js
+//!
+//! Build with
+//! `cargo rustc --lib --release --features wasm --target wasm32-unknown-unknown --crate-type cdylib`
+//! then run `wasm-bindgen` over the resulting `.wasm` to get the JavaScript glue.
+//!
+//! ```js
+//! import init, { compute_voice_code } from "./voicecode.js";
+//! await init();
+//! const result = compute_voice_code("12345678901244", "LOT123", "03", "01", "02");
+//! result.voice_code; // "6991"
+//! result.major; // "91"
+//! result.minor; // "69"
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::gtin::Gtin;
+use crate::voicecode::HashVoiceCode;
+
+/// Voice code and the inputs it was computed from, as seen from JavaScript
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct VoiceCodeResult {
+    voice_code: HashVoiceCode,
+}
+
+#[wasm_bindgen]
+impl VoiceCodeResult {
+    /// GTIN as provided
+    #[wasm_bindgen(getter)]
+    pub fn gtin(&self) -> String {
+        self.voice_code.gtin.to_string()
+    }
+
+    /// LOT as provided
+    #[wasm_bindgen(getter)]
+    pub fn lot(&self) -> String {
+        self.voice_code.lot.to_string()
+    }
+
+    /// Zero padded YYMMDD pack date
+    #[wasm_bindgen(getter)]
+    pub fn pack_date(&self) -> String {
+        self.voice_code.pack_date().yymmdd()
+    }
+
+    /// Text that was hashed
+    #[wasm_bindgen(getter)]
+    pub fn hash_text(&self) -> String {
+        self.voice_code.hash_text.clone()
+    }
+
+    /// All four digits
+    #[wasm_bindgen(getter)]
+    pub fn voice_code(&self) -> String {
+        self.voice_code.voice_code.clone()
+    }
+
+    /// Last two digits, printed large on the label
+    #[wasm_bindgen(getter)]
+    pub fn major(&self) -> String {
+        self.voice_code.voice_code_major.clone()
+    }
+
+    /// First two digits, printed small on the label
+    #[wasm_bindgen(getter)]
+    pub fn minor(&self) -> String {
+        self.voice_code.voice_code_minor.clone()
+    }
+}
+
+impl From<HashVoiceCode> for VoiceCodeResult {
+    fn from(voice_code: HashVoiceCode) -> Self {
+        VoiceCodeResult { voice_code }
+    }
+}
+
+/// Compute a voice code with the same arguments as the widget, throws an `Error` with the
+/// validation message when an input is rejected, like the widget the GTIN check digit is
+/// not verified
+#[wasm_bindgen]
+pub fn compute_voice_code(gtin: &str, lot: &str, yy: &str, mm: &str, dd: &str) -> Result<VoiceCodeResult, JsError> {
+    Ok(HashVoiceCode::new(Gtin::parse_unchecked(gtin)?, lot, yy, mm, dd)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let result = compute_voice_code("12345678901244", "LOT123", "03", "01", "02").unwrap();
+        assert_eq!(result.voice_code(), "6991");
+        assert_eq!(result.major(), "91");
+        assert_eq!(result.minor(), "69");
+        assert_eq!(result.pack_date(), "030102");
+        assert_eq!(result.hash_text(), "12345678901244LOT123030102");
+    }
+}